@@ -2,6 +2,7 @@
 //!
 //! This module centralizes the construction of libfyaml configuration structures.
 
+use crate::error::{Error, Result};
 use fyaml_sys::*;
 use std::ptr;
 
@@ -9,16 +10,23 @@ use std::ptr;
 ///
 /// Enables:
 /// - `FYPCF_QUIET`: Suppress stderr output
-/// - `FYPCF_KEEP_COMMENTS`: Preserve comments for roundtrip
+/// - `FYPCF_KEEP_COMMENTS`: Preserve comments for roundtrip (if `options.parse_comments`)
 ///
 /// The diag pointer allows capturing parse errors with location information.
+/// libfyaml stores this configuration in the document and reuses it when the
+/// editor builds nodes from YAML snippets, so inserted nodes are parsed with
+/// the same comment handling as the original input.
 #[inline]
-pub fn document_parse_cfg_with_diag(diag: *mut fy_diag) -> fy_parse_cfg {
+pub fn document_parse_cfg_with_diag(diag: *mut fy_diag, options: &ParseOptions) -> fy_parse_cfg {
+    let mut flags = FYPCF_QUIET;
+    if options.parse_comments {
+        flags |= FYPCF_KEEP_COMMENTS;
+    }
     fy_parse_cfg {
         search_path: ptr::null_mut(),
         userdata: ptr::null_mut(),
         diag,
-        flags: FYPCF_QUIET | FYPCF_KEEP_COMMENTS,
+        flags,
     }
 }
 
@@ -28,18 +36,22 @@ pub fn document_parse_cfg_with_diag(diag: *mut fy_diag) -> fy_parse_cfg {
 /// - `FYPCF_QUIET`: Suppress stderr output (always enabled for no-stderr guarantee)
 /// - `FYPCF_DISABLE_BUFFERING`: Don't buffer input
 /// - `FYPCF_RESOLVE_DOCUMENT`: Resolve document after parsing
-/// - `FYPCF_KEEP_COMMENTS`: Preserve comments for roundtrip
+/// - `FYPCF_KEEP_COMMENTS`: Preserve comments for roundtrip (if `options.parse_comments`)
 ///
 /// The diag pointer allows capturing parse errors with location information.
 /// FYPCF_QUIET is always enabled to guarantee no stderr output, regardless of
 /// whether a custom diag is provided.
 #[inline]
-pub fn stream_parse_cfg_with_diag(diag: *mut fy_diag) -> fy_parse_cfg {
+pub fn stream_parse_cfg_with_diag(diag: *mut fy_diag, options: &ParseOptions) -> fy_parse_cfg {
+    let mut flags = FYPCF_QUIET | FYPCF_DISABLE_BUFFERING | FYPCF_RESOLVE_DOCUMENT;
+    if options.parse_comments {
+        flags |= FYPCF_KEEP_COMMENTS;
+    }
     fy_parse_cfg {
         search_path: ptr::null_mut(),
         userdata: ptr::null_mut(),
         diag,
-        flags: FYPCF_QUIET | FYPCF_DISABLE_BUFFERING | FYPCF_RESOLVE_DOCUMENT | FYPCF_KEEP_COMMENTS,
+        flags,
     }
 }
 
@@ -75,3 +87,319 @@ pub fn stream_parse_cfg_with_diag(diag: *mut fy_diag) -> fy_parse_cfg {
 pub fn emit_flags() -> u32 {
     FYECF_MODE_ORIGINAL | FYECF_OUTPUT_COMMENTS | FYECF_WIDTH_INF
 }
+
+/// Returns the pinned emitter flags behind `Document::emit_stable`.
+///
+/// Deliberately spelled out rather than derived from [`emit_flags`]: golden
+/// files produced with `emit_stable` must not change when the default flags
+/// do (e.g. once the `FYECF_WIDTH_INF` workaround above is dropped).
+/// Original mode keeps source styles and infinite width avoids re-wrapping.
+/// Unlike the defaults, the indent is fixed at 2 instead of libfyaml's
+/// default, and the document markers no longer follow the source: `---` is
+/// always written and `...` never is, so inputs differing only in explicit
+/// markers emit the same text.
+#[inline]
+pub fn stable_emit_flags() -> u32 {
+    FYECF_MODE_ORIGINAL
+        | FYECF_OUTPUT_COMMENTS
+        | FYECF_WIDTH_INF
+        | FYECF_INDENT_2
+        | FYECF_DOC_START_MARK_ON
+        | FYECF_DOC_END_MARK_OFF
+}
+
+// =============================================================================
+// Parse Options
+// =============================================================================
+
+/// Options controlling how YAML input is parsed.
+///
+/// The default options match [`Document::parse_str`](crate::Document::parse_str)
+/// and [`FyParser::from_string`](crate::FyParser::from_string): comments are
+/// kept so that emitting a parsed document reproduces them, and no depth or
+/// size limits apply.
+///
+/// # Limits
+///
+/// `max_depth` and `max_nodes` bound the documents accepted from untrusted
+/// input; a document over either limit is rejected with
+/// [`Error::LimitExceeded`](crate::Error::LimitExceeded). libfyaml has no
+/// such limits of its own, so they are checked *after* each document is
+/// built, before it is returned. Building is iterative and its memory is
+/// proportional to the input size, so deep or large input cannot overflow
+/// the stack, but it is fully allocated before being rejected: bound the
+/// input length as well when that matters.
+///
+/// `max_depth` and `max_nodes` count aliases as single nodes. A document
+/// can stay small while its aliases expand to billions of nodes for
+/// consumers that resolve them; `max_alias_expansion` bounds that size,
+/// computed without expanding anything, and also rejects aliases that
+/// would expand forever (see [`Document::check_aliases`](crate::Document::check_aliases)).
+///
+/// # Example
+///
+/// ```
+/// use fyaml::{FyParser, ParseOptions};
+///
+/// let opts = ParseOptions {
+///     parse_comments: false,
+///     ..ParseOptions::default()
+/// };
+/// let parser = FyParser::from_string_with("a: 1 # note\n", &opts).unwrap();
+/// let doc = parser.doc_iter().next().unwrap().unwrap();
+/// assert!(!doc.emit().unwrap().contains("# note"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Keep comments attached to nodes so that emission reproduces them.
+    pub parse_comments: bool,
+    /// Maximum nesting depth of a document, as measured by
+    /// [`NodeRef::depth`](crate::NodeRef::depth): a scalar document has
+    /// depth 0, `{a: [1]}` depth 2.
+    pub max_depth: Option<usize>,
+    /// Maximum number of nodes in a document, as counted by
+    /// [`NodeRef::node_count`](crate::NodeRef::node_count), keys included.
+    pub max_nodes: Option<usize>,
+    /// Maximum number of nodes in a document with its aliases expanded, as
+    /// counted by [`Document::expanded_node_count`](crate::Document::expanded_node_count).
+    /// When set, a document with an alias cycle fails with
+    /// [`Error::AliasCycle`](crate::Error::AliasCycle).
+    pub max_alias_expansion: Option<usize>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            parse_comments: true,
+            max_depth: None,
+            max_nodes: None,
+            max_alias_expansion: None,
+        }
+    }
+}
+
+// =============================================================================
+// Emit Options
+// =============================================================================
+
+/// Options controlling how a document is emitted.
+///
+/// The default options reproduce [`Document::emit`](crate::Document::emit):
+/// original formatting and comments are preserved, indentation is 2 spaces,
+/// lines are never wrapped, and non-ASCII characters are written as raw
+/// UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::{Document, EmitOptions};
+///
+/// let doc = Document::parse_str("city: 東京").unwrap();
+///
+/// let raw = doc.emit_with(&EmitOptions::default()).unwrap();
+/// assert!(raw.contains("東京"));
+///
+/// let opts = EmitOptions {
+///     escape_unicode: true,
+///     ..EmitOptions::default()
+/// };
+/// let escaped = doc.emit_with(&opts).unwrap();
+/// assert!(escaped.contains("\\u6771\\u4EAC"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmitOptions {
+    /// Escape non-ASCII characters instead of writing them as raw UTF-8.
+    ///
+    /// libfyaml has no emitter flag for this: it always writes printable
+    /// non-ASCII characters verbatim. When enabled, scalars containing
+    /// non-ASCII characters are switched to double-quoted style and each such
+    /// character is written as `\uXXXX` (or `\UXXXXXXXX` outside the Basic
+    /// Multilingual Plane). Only double-quoted scalars are escaped: anchors,
+    /// tags and comments have no escape syntax and are written verbatim.
+    pub escape_unicode: bool,
+
+    /// Number of spaces to prefix every emitted line with.
+    ///
+    /// Useful when embedding the output inside another document's block.
+    /// Empty lines are left empty. Defaults to `0`.
+    pub base_indent: usize,
+
+    /// Spaces per block nesting level, from 1 to 9. Defaults to `2`.
+    ///
+    /// `0` is rejected with [`Error::InvalidOption`] rather than producing
+    /// YAML whose nesting cannot be read back.
+    pub indent: usize,
+
+    /// Column at which the emitter wraps long lines. Defaults to `None`,
+    /// which never wraps.
+    ///
+    /// A width below `indent + 1` is raised to that, since no content would
+    /// fit otherwise; widths of 255 and above mean no wrapping. Note that
+    /// libfyaml wraps single-quoted scalars with a `\` continuation that
+    /// does not round-trip (see [`emit_flags`]), so only set this for output
+    /// that is not parsed back.
+    pub width: Option<usize>,
+
+    /// Nesting depth from which collections are written in flow style
+    /// (`{a: 1}`, `[x, y]`). Defaults to `None`, keeping each collection's
+    /// own style.
+    ///
+    /// The emitted node is depth `0`, so `Some(0)` writes everything on one
+    /// line and `Some(1)` keeps the top level in block style.
+    pub flow_level: Option<usize>,
+
+    /// Nesting depth from which mapping keys are sorted. Defaults to `None`,
+    /// keeping the authored order everywhere.
+    ///
+    /// Depth counts like [`flow_level`](Self::flow_level): `Some(0)` sorts
+    /// every mapping, `Some(1)` keeps the top-level order and sorts the
+    /// mappings nested below it. Scalar keys are ordered by their text,
+    /// byte by byte; complex keys go after them. Keys that compare equal
+    /// keep their authored order.
+    pub sort_keys_from_depth: Option<usize>,
+
+    /// Sort the keys of every mapping, nested ones and those inside
+    /// sequences included. Defaults to `false`.
+    ///
+    /// Shorthand for `sort_keys_from_depth: Some(0)`, and takes precedence
+    /// over it.
+    pub sort_keys: bool,
+
+    /// Double-quote string scalars that would read back as null, a boolean
+    /// or a number if written plain (`yes`, `~`, `0x1F`, ...). Defaults to
+    /// `false`.
+    ///
+    /// Plain, untagged scalars that YAML 1.1 and 1.2 readers disagree on are
+    /// double-quoted too: the `yes`/`no`/`on`/`off` booleans and integers
+    /// with a leading zero such as `012`. They read back as strings; other
+    /// plain scalars like `true` or `3` keep their resolved type.
+    /// [`Value`](crate::Value) strings like these are always single-quoted,
+    /// so for them this only switches the quote style.
+    pub quote_ambiguous_strings: bool,
+}
+
+impl Default for EmitOptions {
+    fn default() -> Self {
+        EmitOptions {
+            escape_unicode: false,
+            base_indent: 0,
+            indent: 2,
+            width: None,
+            flow_level: None,
+            sort_keys_from_depth: None,
+            sort_keys: false,
+            quote_ambiguous_strings: false,
+        }
+    }
+}
+
+impl EmitOptions {
+    /// Maps `indent` and `width` onto emitter flags, on top of the
+    /// [`emit_flags`] defaults.
+    pub(crate) fn emit_flags(&self) -> Result<u32> {
+        if self.indent == 0 {
+            return Err(Error::InvalidOption("indent must be at least 1"));
+        }
+        if self.indent > 9 {
+            return Err(Error::InvalidOption("indent must be at most 9"));
+        }
+        let width = match self.width {
+            Some(w) if w < FYECF_WIDTH_MASK as usize => w.max(self.indent + 1) as u32,
+            _ => FYECF_WIDTH_MASK,
+        };
+        Ok((emit_flags() & !(FYECF_WIDTH_MASK << FYECF_WIDTH_SHIFT))
+            | ((self.indent as u32 & FYECF_INDENT_MASK) << FYECF_INDENT_SHIFT)
+            | ((width & FYECF_WIDTH_MASK) << FYECF_WIDTH_SHIFT))
+    }
+
+    /// Nesting depth from which mapping keys are sorted, if any.
+    pub(crate) fn sort_depth(&self) -> Option<usize> {
+        if self.sort_keys {
+            Some(0)
+        } else {
+            self.sort_keys_from_depth
+        }
+    }
+
+    /// Returns `true` if emitting needs a restyled copy of the nodes.
+    pub(crate) fn restyles(&self) -> bool {
+        self.escape_unicode
+            || self.quote_ambiguous_strings
+            || self.flow_level.is_some()
+            || self.sort_depth().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_emit_flags_match_emit() {
+        let flags = EmitOptions::default().emit_flags().unwrap();
+        // Same mode and width as `emit_flags()`; indent 2 is libfyaml's default
+        assert_eq!(
+            flags & !(FYECF_INDENT_MASK << FYECF_INDENT_SHIFT),
+            emit_flags()
+        );
+    }
+
+    #[test]
+    fn test_stable_emit_flags_pin_indent_and_markers() {
+        let flags = stable_emit_flags();
+        assert_ne!(flags, emit_flags());
+        assert_eq!((flags >> FYECF_INDENT_SHIFT) & FYECF_INDENT_MASK, 2);
+        assert_eq!(flags & FYECF_DOC_START_MARK_ON, FYECF_DOC_START_MARK_ON);
+        assert_eq!(flags & FYECF_DOC_END_MARK_OFF, FYECF_DOC_END_MARK_OFF);
+    }
+
+    #[test]
+    fn test_indent_bounds() {
+        for indent in [0, 10] {
+            let opts = EmitOptions {
+                indent,
+                ..EmitOptions::default()
+            };
+            assert!(matches!(opts.emit_flags(), Err(Error::InvalidOption(_))));
+        }
+    }
+
+    #[test]
+    fn test_sort_keys_needs_restyle() {
+        assert!(!EmitOptions::default().restyles());
+        let opts = EmitOptions {
+            sort_keys_from_depth: Some(1),
+            ..EmitOptions::default()
+        };
+        assert!(opts.restyles());
+
+        let opts = EmitOptions {
+            sort_keys: true,
+            sort_keys_from_depth: Some(3),
+            ..EmitOptions::default()
+        };
+        assert!(opts.restyles());
+        assert_eq!(opts.sort_depth(), Some(0));
+    }
+
+    #[test]
+    fn test_width_clamps_to_indent() {
+        let opts = EmitOptions {
+            indent: 4,
+            width: Some(1),
+            ..EmitOptions::default()
+        };
+        let flags = opts.emit_flags().unwrap();
+        assert_eq!((flags >> FYECF_WIDTH_SHIFT) & FYECF_WIDTH_MASK, 5);
+
+        let opts = EmitOptions {
+            width: Some(1000),
+            ..EmitOptions::default()
+        };
+        let flags = opts.emit_flags().unwrap();
+        assert_eq!(
+            flags & (FYECF_WIDTH_MASK << FYECF_WIDTH_SHIFT),
+            FYECF_WIDTH_INF
+        );
+    }
+}
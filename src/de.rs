@@ -0,0 +1,259 @@
+//! Deserialization of Rust types straight from a parsed document.
+//!
+//! [`from_str`], [`from_slice`] and [`from_reader`] drive serde over the
+//! libfyaml DOM by walking [`NodeRef`]s, without building an intermediate
+//! [`Value`](crate::Value). Scalars are interpreted with the same rules as
+//! [`ValueRef`](crate::ValueRef): quoted and block scalars stay strings,
+//! plain ones are inferred as null, bool, integer (including `0x`, `0o` and
+//! `0b` forms), float or string. Errors carry the path of the offending node
+//! like [`from_value`](crate::from_value) does.
+
+use crate::document::Document;
+use crate::error::{Error, Result};
+use crate::node::NodeType;
+use crate::node_ref::NodeRef;
+use crate::scalar_parse;
+use crate::value::{
+    child_path, visit_number, with_path, EnumDeserializer, MapDeserializer, PathNode,
+    SeqDeserializer,
+};
+use serde::de::value::UnitDeserializer;
+use serde::de::{self, DeserializeOwned, Deserializer, Unexpected, Visitor};
+use std::io::Read;
+
+/// Deserializes a Rust type from a YAML string.
+///
+/// The input must hold a single document; an empty document deserializes
+/// as null. Errors carry the path of the offending node, for example
+/// `Deserialize error at /database/port: invalid type: string "abc", expected u16`.
+/// Missing fields are reported at the path of the mapping that lacks them,
+/// unknown fields (with `#[serde(deny_unknown_fields)]`) at the path of the
+/// unexpected key.
+///
+/// A `!!str` tag keeps a plain scalar a string (`!!str 42` is `"42"`).
+/// Other tags are transparent, except when deserializing an enum, where the
+/// tag (without its leading `!`) names the variant. Enums can also be written
+/// as a plain string (unit variants) or a single-entry mapping.
+///
+/// # Errors
+///
+/// Returns [`Error::ParseError`] if the YAML is invalid and
+/// [`Error::Deserialize`] if it does not match `T`.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+///     mask: u8,
+/// }
+///
+/// let config: Config = fyaml::from_str("name: '80'\nport: 8080\nmask: 0xFF").unwrap();
+/// assert_eq!(config.name, "80");
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(config.mask, 255);
+/// ```
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T> {
+    from_document(&Document::parse_str(s)?)
+}
+
+/// Deserializes a Rust type from YAML bytes.
+///
+/// See [`from_str`] for the rules and errors.
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    from_document(&Document::from_bytes(bytes.to_vec())?)
+}
+
+/// Deserializes a Rust type from YAML read out of `reader`.
+///
+/// The whole input is read before parsing. See [`from_str`] for the rules.
+///
+/// # Errors
+///
+/// Returns [`Error::IoError`] if reading fails, and the errors of
+/// [`from_str`] otherwise.
+pub fn from_reader<R: Read, T: DeserializeOwned>(reader: R) -> Result<T> {
+    from_document(&Document::from_reader(reader)?)
+}
+
+fn from_document<T: DeserializeOwned>(doc: &Document) -> Result<T> {
+    match doc.root() {
+        Some(root) => T::deserialize(NodeDeserializer {
+            node: root,
+            path: String::new(),
+        }),
+        None => T::deserialize(UnitDeserializer::<Error>::new()),
+    }
+}
+
+/// Returns `true` for plain scalars that read as null.
+fn is_null(node: NodeRef<'_>) -> bool {
+    node.is_scalar()
+        && !node.is_non_plain()
+        && matches!(node.scalar_str(), Ok(s) if scalar_parse::is_null(s))
+}
+
+/// Returns `true` for the core schema `!!str` tag, short or resolved.
+fn is_str_tag(tag: &str) -> bool {
+    matches!(tag, "!!str" | "tag:yaml.org,2002:str")
+}
+
+fn unexpected<'a>(node: NodeRef<'a>) -> Unexpected<'a> {
+    match node.kind() {
+        NodeType::Sequence => Unexpected::Seq,
+        NodeType::Mapping => Unexpected::Map,
+        NodeType::Scalar if is_null(node) => Unexpected::Unit,
+        NodeType::Scalar => Unexpected::Str(node.scalar_str().unwrap_or("")),
+    }
+}
+
+/// Serde deserializer over a document node, tracking the node path.
+pub(crate) struct NodeDeserializer<'de> {
+    node: NodeRef<'de>,
+    path: String,
+}
+
+impl<'de> NodeDeserializer<'de> {
+    /// Visits a scalar, inferring its type the way `ValueRef` does.
+    fn visit_scalar<V>(&self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let s = self.node.scalar_str()?;
+        if self.node.is_non_plain() || matches!(self.node.tag_str()?, Some(tag) if is_str_tag(tag))
+        {
+            return visitor.visit_borrowed_str(s);
+        }
+        if scalar_parse::is_null(s) {
+            return visitor.visit_unit();
+        }
+        if let Some(b) = scalar_parse::parse_bool(s) {
+            return visitor.visit_bool(b);
+        }
+        match scalar_parse::parse_number(s) {
+            Some(n) => visit_number(&n, visitor),
+            None => visitor.visit_borrowed_str(s),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.node.kind() {
+            NodeType::Scalar => self.visit_scalar(visitor),
+            NodeType::Sequence => {
+                let len = self.node.seq_len().unwrap_or(0);
+                let mut access = SeqDeserializer::new(self.node.seq_iter(), len, &self.path);
+                visitor.visit_seq(&mut access).and_then(|v| access.end(v))
+            }
+            NodeType::Mapping => {
+                let len = self.node.map_len().unwrap_or(0);
+                visitor.visit_map(MapDeserializer::new(self.node.map_iter(), len, &self.path))
+            }
+        };
+        result.map_err(|e| with_path(e, &self.path))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if is_null(self.node) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let access = if let Some(tag) = self.node.tag_str()? {
+            EnumDeserializer::new(
+                tag.trim_start_matches('!'),
+                Some(self.node),
+                self.path.clone(),
+            )
+        } else if self.node.is_scalar() {
+            EnumDeserializer::new(self.node.scalar_str()?, None, self.path.clone())
+        } else if self.node.map_len().ok() == Some(1) {
+            let (key, value) = self.node.map_iter().next().expect("mapping has one entry");
+            if !key.is_scalar() {
+                return Err(with_path(
+                    de::Error::invalid_type(unexpected(key), &"a string variant name"),
+                    &self.path,
+                ));
+            }
+            let variant = key.scalar_str()?;
+            EnumDeserializer::new(variant, Some(value), child_path(&self.path, variant))
+        } else {
+            return Err(with_path(
+                de::Error::invalid_type(unexpected(self.node), &"an enum"),
+                &self.path,
+            ));
+        };
+        visitor
+            .visit_enum(access)
+            .map_err(|e| with_path(e, &self.path))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> PathNode<'de> for NodeRef<'de> {
+    type Deserializer = NodeDeserializer<'de>;
+
+    fn deserializer(self, path: String) -> NodeDeserializer<'de> {
+        NodeDeserializer { node: self, path }
+    }
+
+    fn key_segment(self) -> String {
+        if self.is_scalar() {
+            self.scalar_str().unwrap_or("?").to_owned()
+        } else {
+            "?".to_owned()
+        }
+    }
+
+    fn is_null(self) -> bool {
+        is_null(self)
+    }
+
+    fn is_sequence(self) -> bool {
+        NodeRef::is_sequence(&self)
+    }
+
+    fn is_mapping(self) -> bool {
+        NodeRef::is_mapping(&self)
+    }
+
+    fn unexpected(self) -> Unexpected<'de> {
+        unexpected(self)
+    }
+}
@@ -97,7 +97,6 @@ impl Diag {
     /// Collects all errors into a vector of ParseError.
     ///
     /// Use [`first_error()`](Self::first_error) if you only need the first error.
-    #[allow(dead_code)]
     pub fn collect_errors(&self) -> Vec<ParseError> {
         let mut errors = Vec::new();
         let mut prev: *mut std::ffi::c_void = ptr::null_mut();
@@ -1,17 +1,27 @@
 //! Document type that owns parsed YAML data.
 
-use crate::config;
+use crate::config::{self, EmitOptions, ParseOptions};
 use crate::diag::{diag_error, Diag};
 use crate::editor::Editor;
-use crate::error::{Error, Result};
-use crate::ffi_util::{malloc_copy, take_c_string};
+use crate::encoding;
+use crate::error::{Error, ParseError, Result};
+use crate::ffi_util::{emit_to_writer, take_c_string};
+use crate::line_merge;
+use crate::node::{NodeStyle, NodeType};
 use crate::node_ref::NodeRef;
+use crate::scalar_parse;
+use crate::value::{walk_leaves, Children, FlatNode, Step, Value};
 use crate::value_ref::ValueRef;
 use fyaml_sys::*;
-use libc::c_void;
+use libc::{c_int, c_void, size_t};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::ptr::{self, NonNull};
+use std::rc::Rc;
 use std::str::FromStr;
 
 // =============================================================================
@@ -28,18 +38,21 @@ pub(crate) use crate::parser::ParserInner;
 /// memory owned by this input source.
 #[allow(dead_code)]
 pub enum InputOwnership {
-    /// libfyaml owns the input via malloc'd buffer.
-    /// Used for string parsing with `fy_document_build_from_malloc_string`.
-    LibfyamlOwned,
     /// Document owns the input string directly.
     /// Used with `fy_document_build_from_string` for zero-extra-copy parsing.
-    OwnedString(String),
+    /// Shared with copies made by [`Document::deep_copy`].
+    OwnedString(Rc<String>),
     /// Document owns the input bytes directly.
     /// Used with `fy_document_build_from_string` for zero-extra-copy parsing of raw bytes.
-    OwnedBytes(Vec<u8>),
+    /// Shared with copies made by [`Document::deep_copy`].
+    OwnedBytes(Rc<Vec<u8>>),
     /// Parser owns the input buffer (stream parsing).
     /// The parser must outlive the document to prevent use-after-free.
-    Parser(std::rc::Rc<ParserInner>),
+    Parser(Rc<ParserInner>),
+    /// Copy of a stream-parsed document: its nodes may reference the
+    /// parser's input, but the document itself was not created by the
+    /// parser and is destroyed with `fy_document_destroy`.
+    ParserInput(Rc<ParserInner>),
     /// Empty document or constructed document (no external input).
     None,
 }
@@ -79,6 +92,9 @@ pub struct Document {
     /// Keeps input buffer alive if needed.
     #[allow(dead_code)]
     input: InputOwnership,
+    /// Set once an alias is built by hand, so emitting checks that every
+    /// alias has an anchor to refer to.
+    pub(crate) unchecked_aliases: bool,
     /// Marker to ensure !Send + !Sync
     _marker: PhantomData<*mut ()>,
 }
@@ -96,10 +112,50 @@ impl Document {
         Document {
             doc_ptr,
             input,
+            unchecked_aliases: false,
             _marker: PhantomData,
         }
     }
 
+    /// Returns the input text if the document keeps its own copy.
+    pub(crate) fn source(&self) -> Option<&[u8]> {
+        match &self.input {
+            InputOwnership::OwnedString(s) => Some(s.as_bytes()),
+            InputOwnership::OwnedBytes(bytes) => Some(bytes.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the `len` bytes at `ptr` lie in the parsed input,
+    /// as opposed to memory libfyaml allocated for processed text.
+    pub(crate) fn input_contains(&self, ptr: *const u8, len: usize) -> bool {
+        let span = match &self.input {
+            InputOwnership::OwnedString(s) => s.as_ptr() as usize..s.as_ptr() as usize + s.len(),
+            InputOwnership::OwnedBytes(bytes) => {
+                bytes.as_ptr() as usize..bytes.as_ptr() as usize + bytes.len()
+            }
+            InputOwnership::Parser(inner) | InputOwnership::ParserInput(inner) => {
+                return inner.input_contains(ptr, len);
+            }
+            InputOwnership::None => return false,
+        };
+        let start = ptr as usize;
+        start >= span.start && start + len <= span.end
+    }
+
+    /// Returns an ownership handle that keeps this document's input alive,
+    /// for a copy whose nodes may still reference it.
+    fn share_input(&self) -> InputOwnership {
+        match &self.input {
+            InputOwnership::OwnedString(s) => InputOwnership::OwnedString(Rc::clone(s)),
+            InputOwnership::OwnedBytes(bytes) => InputOwnership::OwnedBytes(Rc::clone(bytes)),
+            InputOwnership::Parser(inner) | InputOwnership::ParserInput(inner) => {
+                InputOwnership::ParserInput(Rc::clone(inner))
+            }
+            InputOwnership::None => InputOwnership::None,
+        }
+    }
+
     /// Creates a new empty YAML document.
     ///
     /// Use [`edit()`](Self::edit) to add content to the document.
@@ -118,6 +174,7 @@ impl Document {
         Ok(Document {
             doc_ptr: nn,
             input: InputOwnership::None,
+            unchecked_aliases: false,
             _marker: PhantomData,
         })
     }
@@ -149,9 +206,9 @@ impl Document {
     ///
     /// # Memory Safety
     ///
-    /// The input string is copied to a malloc'd buffer that libfyaml takes
-    /// ownership of. This ensures zero-copy node access is safe even after
-    /// the original string is dropped.
+    /// The input string is copied into a buffer the document keeps alive.
+    /// This ensures zero-copy node access is safe even after the original
+    /// string is dropped.
     ///
     /// # Errors
     ///
@@ -167,34 +224,165 @@ impl Document {
     /// assert!(root.is_mapping());
     /// ```
     pub fn parse_str(s: &str) -> Result<Self> {
+        Self::parse_str_with(s, &ParseOptions::default())
+    }
+
+    /// Parses a YAML string into a Document using the given options.
+    ///
+    /// With `parse_comments` enabled (the default, as used by
+    /// [`parse_str`](Self::parse_str)), comments are attached to nodes and
+    /// [`emit`](Self::emit) reproduces them, including after edits. Snippets
+    /// inserted through the [`Editor`] are parsed with the same options, so
+    /// their comments are handled the same way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] with line and column information if parsing fails,
+    /// and [`Error::LimitExceeded`] if the document is deeper or larger than
+    /// `max_depth`, `max_nodes` or `max_alias_expansion` allow (see
+    /// [`ParseOptions`] for when these are checked).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, ParseOptions};
+    ///
+    /// let yaml = "# chart values\nreplicas: 1  # scale here\n";
+    ///
+    /// let mut doc = Document::parse_str_with(yaml, &ParseOptions::default()).unwrap();
+    /// doc.edit().set_yaml_at("/replicas", "3").unwrap();
+    /// let out = doc.emit().unwrap();
+    /// assert!(out.contains("# chart values"));
+    /// assert!(out.contains("replicas: 3"));
+    ///
+    /// let opts = ParseOptions {
+    ///     parse_comments: false,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let doc = Document::parse_str_with(yaml, &opts).unwrap();
+    /// assert!(!doc.emit().unwrap().contains('#'));
+    ///
+    /// let opts = ParseOptions {
+    ///     max_depth: Some(2),
+    ///     ..ParseOptions::default()
+    /// };
+    /// assert!(Document::parse_str_with("a: [1, 2]", &opts).is_ok());
+    /// assert!(Document::parse_str_with("a: [[1], 2]", &opts).is_err());
+    /// ```
+    pub fn parse_str_with(s: &str, options: &ParseOptions) -> Result<Self> {
         if s.is_empty() {
             return Err(Error::Parse("empty input"));
         }
 
-        // Allocate buffer and copy input - libfyaml takes ownership
-        let buf = unsafe { malloc_copy(s.as_bytes())? };
-
         // Create diagnostic handler to capture errors
         let diag = Diag::new();
-        let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
+        let doc = match Self::build_from_str(s, diag.as_ref(), options)? {
+            Some(doc) => doc,
+            None => return Err(diag_error(diag, "fy_document_build_from_string failed")),
+        };
+        doc.check_limits(options)?;
+        Ok(doc)
+    }
+
+    /// Parses a YAML string, keeping whatever leading part is valid.
+    ///
+    /// This is meant for editor tooling working on half-written files.
+    /// Instead of failing, it returns every error libfyaml reported for the
+    /// full input together with the largest valid prefix it could build:
+    /// libfyaml does not produce partial documents, so on error the input
+    /// is cut before the offending line and re-parsed, repeating until a
+    /// prefix parses.
+    ///
+    /// Returns `(Some(doc), [])` for valid input, `(Some(prefix_doc), errors)`
+    /// when a leading part is usable, and `(None, errors)` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let (doc, errors) = Document::parse_str_lenient("name: app\nport: 80\nbad: [1, 2\n");
+    /// assert!(!errors.is_empty());
+    /// let doc = doc.unwrap();
+    /// assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "app");
+    /// ```
+    pub fn parse_str_lenient(s: &str) -> (Option<Self>, Vec<ParseError>) {
+        let errors = match Self::try_parse_collecting(s) {
+            Ok(doc) => return (Some(doc), Vec::new()),
+            Err(errors) => errors,
+        };
 
-        // libfyaml takes ownership of buf on success
-        let cfg = config::document_parse_cfg_with_diag(diag_ptr);
-        let doc_ptr = unsafe { fy_document_build_from_malloc_string(&cfg, buf, s.len()) };
+        let mut prefix = s;
+        let mut bad_line = errors.iter().filter_map(ParseError::line).min();
+        loop {
+            let lines: Vec<&str> = prefix.split_inclusive('\n').collect();
+            // Keep the lines before the error, always dropping at least one
+            let max_keep = lines.len().saturating_sub(1);
+            let keep = bad_line.map_or(max_keep, |line| {
+                (line as usize).saturating_sub(1).min(max_keep)
+            });
+            let len: usize = lines[..keep].iter().map(|l| l.len()).sum();
+            prefix = &prefix[..len];
+            if prefix.trim().is_empty() {
+                return (None, errors);
+            }
+            match Self::try_parse_collecting(prefix) {
+                Ok(doc) => return (Some(doc), errors),
+                Err(retry) => bad_line = retry.iter().filter_map(ParseError::line).min(),
+            }
+        }
+    }
+
+    /// Parses `s`, returning all collected errors on failure.
+    fn try_parse_collecting(s: &str) -> std::result::Result<Self, Vec<ParseError>> {
+        if s.is_empty() {
+            return Err(vec![ParseError::new("empty input")]);
+        }
+        let diag = Diag::new();
+        match Self::build_from_str(s, diag.as_ref(), &ParseOptions::default()) {
+            Ok(Some(doc)) => Ok(doc),
+            Ok(None) => {
+                let errors = diag.map(|d| d.collect_errors()).unwrap_or_default();
+                if errors.is_empty() {
+                    Err(vec![ParseError::new(
+                        "fy_document_build_from_string failed",
+                    )])
+                } else {
+                    Err(errors)
+                }
+            }
+            Err(e) => Err(vec![ParseError::new(e.to_string())]),
+        }
+    }
+
+    /// Builds a document from a copy of `s`, reporting errors to `diag`.
+    ///
+    /// Returns `Ok(None)` if libfyaml rejects the input; the details are
+    /// then available from `diag`.
+    fn build_from_str(
+        s: &str,
+        diag: Option<&Diag>,
+        options: &ParseOptions,
+    ) -> Result<Option<Self>> {
+        let input = Rc::new(s.to_owned());
+        let diag_ptr = diag.map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
+
+        let cfg = config::document_parse_cfg_with_diag(diag_ptr, options);
+        // SAFETY: fy_document_build_from_string borrows the input - the copy
+        // stays valid for the document's lifetime in InputOwnership::OwnedString.
+        let doc_ptr = unsafe {
+            fy_document_build_from_string(&cfg, input.as_ptr() as *const i8, input.len())
+        };
         if doc_ptr.is_null() {
-            // On failure, libfyaml does NOT free the buffer
-            unsafe { libc::free(buf as *mut c_void) };
-            return Err(diag_error(
-                diag,
-                "fy_document_build_from_malloc_string failed",
-            ));
+            return Ok(None);
         }
 
-        Ok(Document {
+        Ok(Some(Document {
             doc_ptr: NonNull::new(doc_ptr).unwrap(),
-            input: InputOwnership::LibfyamlOwned,
+            input: InputOwnership::OwnedString(input),
+            unchecked_aliases: false,
             _marker: PhantomData,
-        })
+        }))
     }
 
     /// Parses an owned YAML string into a Document (zero extra copy).
@@ -228,7 +416,7 @@ impl Document {
         let diag = Diag::new();
         let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
 
-        let cfg = config::document_parse_cfg_with_diag(diag_ptr);
+        let cfg = config::document_parse_cfg_with_diag(diag_ptr, &ParseOptions::default());
         // SAFETY: fy_document_build_from_string borrows the input - the String must
         // remain valid for the document's lifetime. We keep it in InputOwnership::OwnedString.
         let doc_ptr =
@@ -239,7 +427,8 @@ impl Document {
 
         Ok(Document {
             doc_ptr: NonNull::new(doc_ptr).unwrap(),
-            input: InputOwnership::OwnedString(s),
+            input: InputOwnership::OwnedString(Rc::new(s)),
+            unchecked_aliases: false,
             _marker: PhantomData,
         })
     }
@@ -277,6 +466,37 @@ impl Document {
     /// assert_eq!(root.at_path("/name").unwrap().scalar_str().unwrap(), "Alice");
     /// ```
     pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_bytes_with(bytes, &ParseOptions::default())
+    }
+
+    /// Parses owned bytes into a Document using the given options.
+    ///
+    /// This is [`from_bytes`](Self::from_bytes) with the options of
+    /// [`parse_str_with`](Self::parse_str_with), for services that read
+    /// untrusted YAML as bytes and want to bound its depth and size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] with line and column information if parsing fails,
+    /// and [`Error::LimitExceeded`] if the document exceeds `max_depth`,
+    /// `max_nodes` or `max_alias_expansion`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Error, ParseOptions};
+    ///
+    /// let opts = ParseOptions {
+    ///     max_nodes: Some(5),
+    ///     ..ParseOptions::default()
+    /// };
+    /// assert!(Document::from_bytes_with(b"a: 1\nb: 2".to_vec(), &opts).is_ok());
+    /// assert_eq!(
+    ///     Document::from_bytes_with(b"[1, 2, 3, 4, 5]".to_vec(), &opts).unwrap_err(),
+    ///     Error::LimitExceeded { limit: 5, kind: "nodes" }
+    /// );
+    /// ```
+    pub fn from_bytes_with(bytes: Vec<u8>, options: &ParseOptions) -> Result<Self> {
         if bytes.is_empty() {
             return Err(Error::Parse("empty input"));
         }
@@ -285,7 +505,7 @@ impl Document {
         let diag = Diag::new();
         let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
 
-        let cfg = config::document_parse_cfg_with_diag(diag_ptr);
+        let cfg = config::document_parse_cfg_with_diag(diag_ptr, options);
         // SAFETY: fy_document_build_from_string borrows the input - the Vec must
         // remain valid for the document's lifetime. We keep it in InputOwnership::OwnedBytes.
         let doc_ptr = unsafe {
@@ -295,11 +515,75 @@ impl Document {
             return Err(diag_error(diag, "fy_document_build_from_string failed"));
         }
 
-        Ok(Document {
+        let doc = Document {
             doc_ptr: NonNull::new(doc_ptr).unwrap(),
-            input: InputOwnership::OwnedBytes(bytes),
+            input: InputOwnership::OwnedBytes(Rc::new(bytes)),
+            unchecked_aliases: false,
             _marker: PhantomData,
-        })
+        };
+        doc.check_limits(options)?;
+        Ok(doc)
+    }
+
+    /// Reads all of `reader` and parses it into a Document.
+    ///
+    /// The input is buffered into an owned `Vec<u8>` and parsed as with
+    /// [`from_bytes`](Self::from_bytes), so scalars stay zero-copy views
+    /// into that buffer. Use this for sockets, decompressors or any other
+    /// [`Read`](std::io::Read) source; for multi-document streams see
+    /// [`FyParser::from_reader`](crate::FyParser::from_reader).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IoError`] with the reader's error if reading fails,
+    /// and the same errors as `from_bytes` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let input: &[u8] = b"name: Alice\nage: 30";
+    /// let doc = Document::from_reader(input).unwrap();
+    /// assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "Alice");
+    /// ```
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Parses owned bytes into a Document after detecting their encoding.
+    ///
+    /// libfyaml only reads UTF-8, so UTF-16 input given to
+    /// [`from_bytes`](Self::from_bytes) produces confusing parse errors.
+    /// This constructor looks at the first bytes first:
+    ///
+    /// - a UTF-8 byte order mark is stripped, and the bytes are parsed
+    ///   without copying
+    /// - UTF-16 (LE or BE, with or without BOM) is transcoded to UTF-8
+    /// - UTF-32 is rejected
+    ///
+    /// Detection without a BOM follows the YAML 1.2 rules, which rely on the
+    /// stream starting with an ASCII character.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedEncoding`] for UTF-32 or malformed UTF-16,
+    /// and the same errors as `from_bytes` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut bytes = vec![0xFF, 0xFE]; // UTF-16LE BOM
+    /// bytes.extend("name: Zo\u{eb}".encode_utf16().flat_map(u16::to_le_bytes));
+    /// let doc = Document::from_bytes_detect(bytes).unwrap();
+    /// assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "Zo\u{eb}");
+    /// ```
+    pub fn from_bytes_detect(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_bytes(encoding::to_utf8(bytes)?)
     }
 
     /// Returns the root node of this document, if any.
@@ -340,6 +624,132 @@ impl Document {
         self.root()?.at_path(path)
     }
 
+    /// Returns the node that defines the anchor `name`.
+    ///
+    /// `name` is the anchor label without the leading `&`. Returns `None`
+    /// if no node in the document defines that anchor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("base: &defaults {a: 1}").unwrap();
+    /// let node = doc.node_by_anchor("defaults").unwrap();
+    /// assert_eq!(node.at_path("/a").unwrap().scalar_str().unwrap(), "1");
+    /// assert!(doc.node_by_anchor("missing").is_none());
+    /// ```
+    pub fn node_by_anchor(&self, name: &str) -> Option<NodeRef<'_>> {
+        let anchor = unsafe {
+            fy_document_lookup_anchor(
+                self.doc_ptr.as_ptr(),
+                name.as_ptr() as *const libc::c_char,
+                name.len(),
+            )
+        };
+        if anchor.is_null() {
+            return None;
+        }
+        let node_ptr = unsafe { fy_anchor_node(anchor) };
+        NonNull::new(node_ptr).map(|nn| NodeRef::new(nn, self))
+    }
+
+    /// Checks that every alias can be expanded.
+    ///
+    /// Each alias refers to the closest anchor of that name defined before
+    /// it in document order. An alias inside the node its anchor is defined
+    /// on (`a: &a [*a]`) would expand forever; libfyaml builds such
+    /// documents without complaint since it keeps aliases as nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AliasCycle`] for the first alias whose expansion
+    /// contains itself, and [`Error::UndefinedAlias`] for an alias to an
+    /// anchor that no earlier node defines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Error};
+    ///
+    /// let doc = Document::parse_str("base: &b {x: 1}\nuse: *b").unwrap();
+    /// assert!(doc.check_aliases().is_ok());
+    ///
+    /// let doc = Document::parse_str("a: &a [*a, *a]").unwrap();
+    /// assert_eq!(doc.check_aliases(), Err(Error::AliasCycle("a".into())));
+    /// ```
+    pub fn check_aliases(&self) -> Result<()> {
+        self.expanded_node_count().map(|_| ())
+    }
+
+    /// Returns the number of nodes the document would have with every alias
+    /// replaced by a copy of its target.
+    ///
+    /// Nodes are counted as in [`NodeRef::node_count`], which this equals
+    /// for documents without aliases. Nested aliases multiply, so a small
+    /// document can expand to billions of nodes ("billion laughs"); the
+    /// count is computed without expanding anything, in time linear in the
+    /// document size, and saturates at `usize::MAX`. An empty document has
+    /// no nodes.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`check_aliases`](Self::check_aliases) when an alias
+    /// cannot be expanded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("a: &x [1, 2]\nb: *x").unwrap();
+    /// assert_eq!(doc.root().unwrap().node_count(), 7);
+    /// // the alias stands for the three nodes of [1, 2]
+    /// assert_eq!(doc.expanded_node_count().unwrap(), 9);
+    /// ```
+    pub fn expanded_node_count(&self) -> Result<usize> {
+        match self.root() {
+            Some(root) => expanded_size(root, &alias_targets(root)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns `true` if the source began the document with an explicit
+    /// `---` marker.
+    ///
+    /// Documents created with [`Document::new`] report `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// assert!(Document::parse_str("---\na: 1").unwrap().has_explicit_start());
+    /// assert!(!Document::parse_str("a: 1").unwrap().has_explicit_start());
+    /// ```
+    pub fn has_explicit_start(&self) -> bool {
+        let state = unsafe { fy_document_get_document_state(self.doc_ptr.as_ptr()) };
+        !state.is_null() && !unsafe { fy_document_state_start_implicit(state) }
+    }
+
+    /// Returns `true` if the source ended the document with an explicit
+    /// `...` marker.
+    ///
+    /// Documents created with [`Document::new`] report `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// assert!(Document::parse_str("a: 1\n...\n").unwrap().has_explicit_end());
+    /// assert!(!Document::parse_str("---\na: 1").unwrap().has_explicit_end());
+    /// ```
+    pub fn has_explicit_end(&self) -> bool {
+        let state = unsafe { fy_document_get_document_state(self.doc_ptr.as_ptr()) };
+        !state.is_null() && !unsafe { fy_document_state_end_implicit(state) }
+    }
+
     /// Returns the root node as a typed [`ValueRef`].
     ///
     /// `ValueRef` provides typed accessors (`as_str()`, `as_i64()`, `as_bool()`, etc.)
@@ -365,6 +775,166 @@ impl Document {
         self.root().map(ValueRef::new)
     }
 
+    /// Returns every scalar value with its JSON Pointer path, in document order.
+    ///
+    /// Only values are reported, not mapping keys. Path segments escape `~`
+    /// as `~0` and `/` as `~1`; a scalar root has the empty path `""`.
+    /// Non-scalar keys appear in paths as their flow-emitted YAML. Aliases
+    /// are skipped, so anchored content is reported once, where the anchor
+    /// defines it. Intended for auditing, e.g. scanning a config for secrets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("db:\n  user: app\n  hosts: [a, b]").unwrap();
+    /// assert_eq!(
+    ///     doc.scalar_leaves(),
+    ///     vec![
+    ///         ("/db/user".to_string(), "app"),
+    ///         ("/db/hosts/0".to_string(), "a"),
+    ///         ("/db/hosts/1".to_string(), "b"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn scalar_leaves(&self) -> Vec<(String, &str)> {
+        let mut out = Vec::new();
+        if let Some(root) = self.root() {
+            let mut path = String::new();
+            collect_scalar_leaves(root, &mut path, &mut out);
+        }
+        out
+    }
+
+    /// Renders the document as flat `key=value` lines for `.properties` or
+    /// env-style files.
+    ///
+    /// Nested mapping keys are joined with `separator`, and sequence items
+    /// use their index as the key segment, so `db: {hosts: [a]}` becomes
+    /// `db.hosts.0=a` with a `.` separator. Scalars are written as their
+    /// text, without quotes; empty collections are written with an empty
+    /// value so their key is kept. Lines are in document order and each
+    /// ends with `\n`.
+    ///
+    /// Escaping follows the Java `.properties` format: in keys, `\`, `=`,
+    /// `:`, spaces, `#` and `!` are backslash-escaped; in values, `\` and
+    /// leading spaces are. Newlines, carriage returns, tabs and form feeds
+    /// are written as `\n`, `\r`, `\t` and `\f` in both.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if the root is a scalar, which has
+    /// no key to write it under. An empty document yields an empty string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("db:\n  host: localhost\n  ports: [5432, 5433]").unwrap();
+    /// assert_eq!(
+    ///     doc.to_properties_string(".").unwrap(),
+    ///     "db.host=localhost\ndb.ports.0=5432\ndb.ports.1=5433\n"
+    /// );
+    /// ```
+    pub fn to_properties_string(&self, separator: &str) -> Result<String> {
+        let root = match self.root() {
+            Some(root) => root,
+            None => return Ok(String::new()),
+        };
+        if root.is_scalar() {
+            return Err(Error::TypeMismatch {
+                expected: "mapping or sequence",
+                got: "scalar",
+            });
+        }
+        let push = |key: &mut String, step: &Step<'_>| {
+            if !key.is_empty() {
+                push_properties_escaped(key, separator, true);
+            }
+            match step {
+                Step::Key(segment) => push_properties_escaped(key, segment, true),
+                Step::Index(index) => key.push_str(&index.to_string()),
+            }
+        };
+        let mut out = String::new();
+        walk_leaves(root, &mut String::new(), &push, &mut |key, node| {
+            out.push_str(key);
+            out.push('=');
+            if node.is_scalar() {
+                push_properties_escaped(&mut out, node.scalar_str()?, false);
+            }
+            out.push('\n');
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// Compares this document structurally against a YAML string.
+    ///
+    /// `yaml` is parsed and both documents are converted to [`Value`] before
+    /// comparing, so formatting differences (quoting style, flow vs block,
+    /// indentation, comments, mapping key order) are ignored while content
+    /// and tags must match. This is intended for golden-file assertions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `yaml` fails to parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("a: 1\nb: [x, y]").unwrap();
+    /// assert!(doc.structurally_equal_to_str("{b: [x, y], a: 1}").unwrap());
+    /// assert!(!doc.structurally_equal_to_str("{a: 2, b: [x, y]}").unwrap());
+    /// ```
+    pub fn structurally_equal_to_str(&self, yaml: &str) -> Result<bool> {
+        let other = Document::parse_str(yaml)?;
+        let lhs = self.root().map(Value::from_node_ref).transpose()?;
+        let rhs = other.root().map(Value::from_node_ref).transpose()?;
+        Ok(lhs == rhs)
+    }
+
+    /// Returns an independent copy of this document.
+    ///
+    /// The copy is a new libfyaml document holding a node-by-node copy of
+    /// the tree, so styles, tags and anchors survive, as do the
+    /// document's directives. No text is emitted or re-parsed, which makes
+    /// this a cheap way to stamp out edited variants of a cached template.
+    /// Editing or dropping either document leaves the other untouched.
+    ///
+    /// Copied scalars may still point into this document's input buffer;
+    /// the copy shares ownership of that buffer, so it stays valid after
+    /// this document is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if libfyaml fails to clone the document.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let template = Document::parse_str("name: app\nreplicas: 1").unwrap();
+    /// let mut copy = template.deep_copy().unwrap();
+    /// copy.edit().set_yaml_at("/replicas", "3").unwrap();
+    ///
+    /// assert_eq!(template.at_path("/replicas").unwrap().scalar_str().unwrap(), "1");
+    /// assert_eq!(copy.at_path("/replicas").unwrap().scalar_str().unwrap(), "3");
+    /// ```
+    pub fn deep_copy(&self) -> Result<Document> {
+        let clone_ptr = unsafe { fy_document_clone(self.as_ptr()) };
+        let clone_ptr =
+            NonNull::new(clone_ptr).ok_or(Error::Ffi("fy_document_clone returned null"))?;
+        let mut copy = Document::from_raw_ptr(clone_ptr, self.share_input());
+        copy.unchecked_aliases = self.unchecked_aliases;
+        Ok(copy)
+    }
+
     /// Returns an exclusive editor for modifying this document.
     ///
     /// While the editor exists, no [`NodeRef`] can be held (enforced by borrow checker).
@@ -402,9 +972,92 @@ impl Document {
     /// If the emitted YAML contains invalid UTF-8 (rare), invalid bytes are
     /// replaced with the Unicode replacement character (U+FFFD). YAML is
     /// expected to be valid UTF-8 per the specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UndefinedAlias`] if an alias built with
+    /// [`Editor::build_alias`] refers to an anchor no earlier node defines.
     pub fn emit(&self) -> Result<String> {
-        let ptr =
-            unsafe { fy_emit_document_to_string(self.doc_ptr.as_ptr(), config::emit_flags()) };
+        self.emit_with(&EmitOptions::default())
+    }
+
+    /// Emits the document into `writer` without building a `String`.
+    ///
+    /// Produces the same bytes as [`emit`](Self::emit), handed to the writer
+    /// chunk by chunk as libfyaml generates them, which keeps memory flat
+    /// for large documents. The writer is flushed at the end. Wrap
+    /// unbuffered sinks such as `File` in a `BufWriter`, since chunks can be
+    /// as small as a single indicator character.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IoError`] with the writer's error if a write or the
+    /// final flush fails; output may have been partially written by then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("name: app\nports: [80, 443]").unwrap();
+    /// let mut out = Vec::new();
+    /// doc.emit_to_writer(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), doc.emit().unwrap());
+    /// ```
+    pub fn emit_to_writer<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        self.check_alias_targets()?;
+        let flags = EmitOptions::default().emit_flags()?;
+        emit_to_writer(writer, flags, |emitter| unsafe {
+            fy_emit_document(emitter, self.as_ptr())
+        })
+    }
+
+    /// Emits the document with pinned, deterministic emitter settings.
+    ///
+    /// The output is a fixed point of parse-and-emit: parsing it and calling
+    /// `emit_stable` again yields byte-identical text. Scalar and collection
+    /// styles from the source are kept, as are comments. The settings are
+    /// pinned independently of [`emit`](Self::emit), so output only changes
+    /// if libfyaml itself changes, which makes it suitable for golden files.
+    ///
+    /// The first emission may still differ from the original input
+    /// (indentation and spacing are normalized, `---` is always written and
+    /// `...` never is); stability holds from there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("a:   1\nlist: [x, 'y']  # note\n").unwrap();
+    /// let once = doc.emit_stable().unwrap();
+    /// let twice = Document::parse_str(&once).unwrap().emit_stable().unwrap();
+    /// assert_eq!(once, twice);
+    /// ```
+    pub fn emit_stable(&self) -> Result<String> {
+        self.check_alias_targets()?;
+        self.emit_with_flags(config::stable_emit_flags())
+    }
+
+    /// Fails if an alias built with
+    /// [`Editor::build_alias`](crate::Editor::build_alias) has no anchor
+    /// defined before it, which libfyaml would emit as unparseable YAML.
+    pub(crate) fn check_alias_targets(&self) -> Result<()> {
+        if !self.unchecked_aliases {
+            return Ok(());
+        }
+        let mut anchors = HashSet::new();
+        match self.root() {
+            Some(root) => match find_undefined_alias(root, &mut anchors) {
+                Some(name) => Err(Error::UndefinedAlias(name.to_owned())),
+                None => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn emit_with_flags(&self, flags: u32) -> Result<String> {
+        let ptr = unsafe { fy_emit_document_to_string(self.doc_ptr.as_ptr(), flags) };
         if ptr.is_null() {
             return Err(Error::Ffi("fy_emit_document_to_string returned null"));
         }
@@ -412,6 +1065,166 @@ impl Document {
         Ok(unsafe { take_c_string(ptr) })
     }
 
+    /// Emits the document as a YAML string using the given options.
+    ///
+    /// With [`EmitOptions::default()`] this is identical to [`emit`](Self::emit).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOption`] if `options.indent` is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, EmitOptions};
+    ///
+    /// let doc = Document::parse_str("greeting: こんにちは").unwrap();
+    /// let opts = EmitOptions {
+    ///     escape_unicode: true,
+    ///     ..EmitOptions::default()
+    /// };
+    /// let yaml = doc.emit_with(&opts).unwrap();
+    /// assert!(yaml.is_ascii());
+    /// ```
+    pub fn emit_with(&self, options: &EmitOptions) -> Result<String> {
+        let flags = options.emit_flags()?;
+        self.check_alias_targets()?;
+        if !options.restyles() {
+            return finish_emit(self.emit_with_flags(flags)?, options);
+        }
+        // Restyle a private clone so the caller's document is left untouched
+        let clone_ptr = unsafe { fy_document_clone(self.as_ptr()) };
+        let clone = Document::from_raw_ptr(
+            NonNull::new(clone_ptr).ok_or(Error::Ffi("fy_document_clone returned null"))?,
+            InputOwnership::None,
+        );
+        if let Some(root) = clone.root() {
+            restyle_for_emit(root, options);
+        }
+        finish_emit(clone.emit_with_flags(flags)?, options)
+    }
+
+    /// Emits the document, copying untouched lines verbatim from `original`.
+    ///
+    /// `original` should be the text this document was parsed from. Both it
+    /// and the edited document are emitted, and every line that the edits
+    /// did not change is taken byte-for-byte from `original` instead of from
+    /// the emitter. This keeps indentation, spacing before comments, and
+    /// blank lines that [`emit`](Self::emit) would otherwise normalize, so
+    /// the textual diff against `original` only covers edited lines.
+    ///
+    /// This is best effort: changed lines use libfyaml's formatting, and
+    /// when nothing was edited `original` is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `original` fails to parse or emission fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let original = "# settings\nport:    8080   # http\nhost: localhost\n";
+    /// let mut doc = Document::parse_str(original).unwrap();
+    /// doc.edit().set_yaml_at("/host", "example.com").unwrap();
+    ///
+    /// let out = doc.emit_preserving_unchanged(original).unwrap();
+    /// assert!(out.starts_with("# settings\nport:    8080   # http\n"));
+    /// assert!(out.contains("host: example.com"));
+    /// ```
+    pub fn emit_preserving_unchanged(&self, original: &str) -> Result<String> {
+        let baseline = Document::parse_str(original)?.emit()?;
+        let edited = self.emit()?;
+        Ok(line_merge::merge_unchanged(original, &baseline, &edited))
+    }
+
+    /// Emits the document, copying untouched lines verbatim from the text
+    /// it was parsed from.
+    ///
+    /// Same as [`emit_preserving_unchanged`](Self::emit_preserving_unchanged)
+    /// with the document's own input, which [`parse_str`](Self::parse_str),
+    /// [`from_string`](Self::from_string), [`from_bytes`](Self::from_bytes)
+    /// and [`from_reader`](Self::from_reader) keep. If nothing was edited,
+    /// the input is returned byte for byte, blank lines, comment spacing
+    /// and all.
+    ///
+    /// After edits, the output is assembled line by line:
+    ///
+    /// - lines the edits did not change are copied exactly, including their
+    ///   indentation and the spacing before trailing comments;
+    /// - blank and comment-only lines are kept, in order; one that sat
+    ///   right before a changed line may come out right after it;
+    /// - changed or inserted lines are written by the emitter, with its
+    ///   indentation and spacing, and only those lines differ from the
+    ///   input (a restyled node, e.g. a scalar switched to quoted, counts as
+    ///   changed);
+    /// - a deleted node takes its lines with it, but comment lines above it
+    ///   stay.
+    ///
+    /// Documents without kept input, such as those built with
+    /// [`new`](Self::new) or read from a stream parser, are emitted as with
+    /// [`emit`](Self::emit).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let original = "a:   1    # one\n\n\nb:  [x,y]\n";
+    /// let mut doc = Document::parse_str(original).unwrap();
+    /// assert_eq!(doc.emit_preserving().unwrap(), original);
+    ///
+    /// doc.edit().set_yaml_at("/a", "2").unwrap();
+    /// let out = doc.emit_preserving().unwrap();
+    /// assert!(out.ends_with("\n\n\nb:  [x,y]\n"));
+    /// ```
+    pub fn emit_preserving(&self) -> Result<String> {
+        match self.input_text() {
+            Some(original) => self.emit_preserving_unchanged(original),
+            None => self.emit(),
+        }
+    }
+
+    /// Returns the text this document was parsed from, if it keeps it.
+    fn input_text(&self) -> Option<&str> {
+        std::str::from_utf8(self.source()?).ok()
+    }
+
+    /// Fails if the document is deeper or has more nodes, before or after
+    /// alias expansion, than `options` allow.
+    pub(crate) fn check_limits(&self, options: &ParseOptions) -> Result<()> {
+        let root = match self.root() {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+        if let Some(limit) = options.max_depth {
+            if root.depth() > limit {
+                return Err(Error::LimitExceeded {
+                    limit,
+                    kind: "depth",
+                });
+            }
+        }
+        if let Some(limit) = options.max_nodes {
+            if root.node_count() > limit {
+                return Err(Error::LimitExceeded {
+                    limit,
+                    kind: "nodes",
+                });
+            }
+        }
+        if let Some(limit) = options.max_alias_expansion {
+            if self.expanded_node_count()? > limit {
+                return Err(Error::LimitExceeded {
+                    limit,
+                    kind: "alias expansion",
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the raw document pointer.
     ///
     /// # Safety
@@ -482,6 +1295,475 @@ impl fmt::Debug for Document {
 // Document is !Send and !Sync due to PhantomData<*mut ()>.
 // This is intentional - libfyaml is not thread-safe.
 
+// =============================================================================
+// Emit Helpers
+// =============================================================================
+
+/// Applies the style changes `options` call for to the tree under `root`.
+///
+/// `root` must belong to a scratch document: its nodes are modified in
+/// place.
+pub(crate) fn restyle_for_emit(root: NodeRef<'_>, options: &EmitOptions) {
+    let mut flow = Vec::new();
+    if let Some(level) = options.flow_level {
+        collect_collections_from(root, level, &mut flow);
+    }
+    for node_ptr in flow {
+        unsafe { fy_node_set_style(node_ptr, FYNS_FLOW) };
+    }
+    if let Some(depth) = options.sort_depth() {
+        let mut collections = Vec::new();
+        collect_collections_from(root, depth, &mut collections);
+        for node_ptr in collections {
+            if unsafe { fy_node_get_type(node_ptr) } == FYNT_MAPPING {
+                sort_mapping_keys(node_ptr);
+            }
+        }
+    }
+    let mut targets = Vec::new();
+    collect_scalars(root, &mut targets, &|node| {
+        (options.escape_unicode && matches!(node.scalar_bytes(), Ok(b) if !b.is_ascii()))
+            || (options.quote_ambiguous_strings && is_ambiguous_scalar(node))
+    });
+    for node_ptr in targets {
+        unsafe { fy_node_set_style(node_ptr, FYNS_DOUBLE_QUOTED) };
+    }
+}
+
+/// Checks if `node` is a string that reads back as another type when plain,
+/// or a plain, untagged scalar that YAML 1.1 and 1.2 readers disagree on.
+fn is_ambiguous_scalar(node: &NodeRef<'_>) -> bool {
+    let text = match node.scalar_str() {
+        Ok(text) => text,
+        Err(_) => return false,
+    };
+    if node.is_non_plain() {
+        scalar_parse::resolves_to_non_string(text)
+    } else {
+        matches!(node.tag_str(), Ok(None)) && scalar_parse::is_version_dependent(text)
+    }
+}
+
+/// Sorts the pairs of `map` by key, keeping the authored order of equal keys.
+fn sort_mapping_keys(map: *mut fy_node) {
+    // libfyaml's sort is not guaranteed to be stable, so ties are broken on
+    // the original position.
+    let mut positions: HashMap<*const fy_node_pair, usize> = HashMap::new();
+    let mut iter: *mut c_void = ptr::null_mut();
+    loop {
+        let pair = unsafe { fy_node_mapping_iterate(map, &mut iter) };
+        if pair.is_null() {
+            break;
+        }
+        let position = positions.len();
+        positions.insert(pair as *const fy_node_pair, position);
+    }
+    let arg = &mut positions as *mut HashMap<*const fy_node_pair, usize> as *mut c_void;
+    unsafe { fy_node_mapping_sort(map, Some(compare_pair_keys), arg) };
+}
+
+/// Orders mapping pairs by key text; pairs with non-scalar keys go last.
+///
+/// `arg` points to the pairs' original positions, used to break ties.
+unsafe extern "C" fn compare_pair_keys(
+    a: *const fy_node_pair,
+    b: *const fy_node_pair,
+    arg: *mut c_void,
+) -> c_int {
+    let ordering = match (pair_key_bytes(a), pair_key_bytes(b)) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+    let positions = &*(arg as *const HashMap<*const fy_node_pair, usize>);
+    ordering.then_with(|| positions.get(&a).cmp(&positions.get(&b))) as c_int
+}
+
+/// Returns the text of a pair's key, or `None` if the key is not a scalar.
+unsafe fn pair_key_bytes<'a>(pair: *const fy_node_pair) -> Option<&'a [u8]> {
+    let key = fy_node_pair_key(pair as *mut fy_node_pair);
+    if key.is_null() || fy_node_get_type(key) != FYNT_SCALAR {
+        return None;
+    }
+    let mut len: size_t = 0;
+    let text = fy_node_get_scalar(key, &mut len);
+    if text.is_null() {
+        return Some(&[]);
+    }
+    Some(std::slice::from_raw_parts(text as *const u8, len))
+}
+
+/// Applies the text-level options to emitter output.
+pub(crate) fn finish_emit(yaml: String, options: &EmitOptions) -> Result<String> {
+    let yaml = if options.escape_unicode {
+        escape_double_quoted(&yaml)?
+    } else {
+        yaml
+    };
+    Ok(indent_lines(&yaml, options.base_indent))
+}
+
+/// Escapes the non-ASCII characters inside the double-quoted scalars of
+/// `yaml`.
+///
+/// The text is parsed again to locate the scalars, so anchors, tags,
+/// comments and scalars in other styles are left as they are.
+fn escape_double_quoted(yaml: &str) -> Result<String> {
+    if yaml.is_ascii() {
+        return Ok(yaml.to_owned());
+    }
+    let doc = Document::parse_str(yaml)?;
+    let mut quoted = Vec::new();
+    if let Some(root) = doc.root() {
+        collect_scalars(root, &mut quoted, &|node| {
+            node.style() == NodeStyle::DoubleQuoted
+        });
+    }
+    let mut spans: Vec<Range<usize>> = quoted
+        .into_iter()
+        .filter_map(|ptr| NodeRef::new(NonNull::new(ptr)?, &doc).span())
+        .collect();
+    spans.sort_by_key(|span| span.start);
+
+    let mut out = String::with_capacity(yaml.len());
+    let mut pos = 0;
+    for span in spans {
+        let text = match yaml.get(span.clone()) {
+            Some(text) if span.start >= pos => text,
+            _ => continue,
+        };
+        out.push_str(&yaml[pos..span.start]);
+        out.push_str(&escape_non_ascii(text));
+        pos = span.end;
+    }
+    out.push_str(&yaml[pos..]);
+    Ok(out)
+}
+
+/// Collects the collections at `depth` levels or more below `node`.
+fn collect_collections_from(node: NodeRef<'_>, depth: usize, out: &mut Vec<*mut fy_node>) {
+    let is_collection = node.kind() != NodeType::Scalar;
+    if is_collection && depth == 0 {
+        out.push(node.as_ptr());
+    }
+    let depth = depth.saturating_sub(1);
+    match node.kind() {
+        NodeType::Scalar => {}
+        NodeType::Sequence => {
+            for item in node.seq_iter() {
+                collect_collections_from(item, depth, out);
+            }
+        }
+        NodeType::Mapping => {
+            for (key, value) in node.map_iter() {
+                collect_collections_from(key, depth, out);
+                collect_collections_from(value, depth, out);
+            }
+        }
+    }
+}
+
+/// Collects scalar nodes (keys included) that `pred` accepts.
+///
+/// Alias nodes are skipped: they are emitted as `*anchor`, not as content.
+fn collect_scalars(
+    node: NodeRef<'_>,
+    out: &mut Vec<*mut fy_node>,
+    pred: &dyn Fn(&NodeRef<'_>) -> bool,
+) {
+    match node.kind() {
+        NodeType::Scalar => {
+            if node.style() != NodeStyle::Alias && pred(&node) {
+                out.push(node.as_ptr());
+            }
+        }
+        NodeType::Sequence => {
+            for item in node.seq_iter() {
+                collect_scalars(item, out, pred);
+            }
+        }
+        NodeType::Mapping => {
+            for (key, value) in node.map_iter() {
+                collect_scalars(key, out, pred);
+                collect_scalars(value, out, pred);
+            }
+        }
+    }
+}
+
+/// Returns the first alias below `node`, in emit order, whose anchor is not
+/// defined by an earlier node. `anchors` collects the anchors seen so far.
+fn find_undefined_alias<'doc>(
+    node: NodeRef<'doc>,
+    anchors: &mut HashSet<&'doc str>,
+) -> Option<&'doc str> {
+    if let Some(anchor) = node.anchor() {
+        anchors.insert(anchor);
+    }
+    match node.kind() {
+        NodeType::Scalar => {
+            if node.style() != NodeStyle::Alias {
+                return None;
+            }
+            let name = node.scalar_str().unwrap_or("");
+            if anchors.contains(name) {
+                None
+            } else {
+                Some(name)
+            }
+        }
+        NodeType::Sequence => node
+            .seq_iter()
+            .find_map(|item| find_undefined_alias(item, anchors)),
+        NodeType::Mapping => node.map_iter().find_map(|(key, value)| {
+            find_undefined_alias(key, anchors).or_else(|| find_undefined_alias(value, anchors))
+        }),
+    }
+}
+
+/// Maps each alias node below `root` to the node it refers to: the last
+/// node before it in emit order with that anchor, which may be one of its
+/// ancestors.
+fn alias_targets<'doc>(root: NodeRef<'doc>) -> Result<HashMap<*mut fy_node, NodeRef<'doc>>> {
+    let mut anchors = HashMap::new();
+    let mut targets = HashMap::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if let Some(anchor) = node.anchor() {
+            anchors.insert(anchor, node);
+        }
+        let start = stack.len();
+        match node.kind() {
+            NodeType::Scalar => {
+                if node.style() == NodeStyle::Alias {
+                    let name = node.scalar_str().unwrap_or("");
+                    match anchors.get(name) {
+                        Some(target) => targets.insert(node.as_ptr(), *target),
+                        None => return Err(Error::UndefinedAlias(name.to_owned())),
+                    };
+                }
+            }
+            NodeType::Sequence => stack.extend(node.seq_iter()),
+            NodeType::Mapping => {
+                for (key, value) in node.map_iter() {
+                    stack.push(key);
+                    stack.push(value);
+                }
+            }
+        }
+        // Pop children in emit order
+        stack[start..].reverse();
+    }
+    Ok(targets)
+}
+
+/// Counts the nodes below `root` with aliases expanded through `targets`.
+///
+/// Each node's size is computed once, children first, on an explicit stack.
+/// Reaching an alias whose target is still being sized means the target
+/// contains the alias.
+fn expanded_size(root: NodeRef<'_>, targets: &HashMap<*mut fy_node, NodeRef<'_>>) -> Result<usize> {
+    let mut sizes: HashMap<*mut fy_node, usize> = HashMap::new();
+    let mut pending = HashSet::new();
+    // (node, whether its children have been sized)
+    let mut stack = vec![(root, false)];
+    while let Some((node, ready)) = stack.pop() {
+        let ptr = node.as_ptr();
+        if ready {
+            let size = match targets.get(&ptr) {
+                Some(target) => sizes[&target.as_ptr()],
+                None => children(node).fold(1usize, |acc, child| {
+                    acc.saturating_add(sizes[&child.as_ptr()])
+                }),
+            };
+            pending.remove(&ptr);
+            sizes.insert(ptr, size);
+            continue;
+        }
+        if sizes.contains_key(&ptr) {
+            continue;
+        }
+        pending.insert(ptr);
+        stack.push((node, true));
+        match targets.get(&ptr) {
+            Some(target) if pending.contains(&target.as_ptr()) => {
+                return Err(Error::AliasCycle(
+                    node.scalar_str().unwrap_or("").to_owned(),
+                ));
+            }
+            Some(target) => stack.push((*target, false)),
+            None => {
+                let start = stack.len();
+                stack.extend(children(node).map(|child| (child, false)));
+                // Pop children in emit order, so the first cycle is reported
+                stack[start..].reverse();
+            }
+        }
+    }
+    Ok(sizes[&root.as_ptr()])
+}
+
+/// Iterates over a node's keys, values and items.
+fn children<'doc>(node: NodeRef<'doc>) -> Box<dyn Iterator<Item = NodeRef<'doc>> + 'doc> {
+    match node.kind() {
+        NodeType::Scalar => Box::new(std::iter::empty()),
+        NodeType::Sequence => Box::new(node.seq_iter()),
+        NodeType::Mapping => Box::new(node.map_iter().flat_map(|(key, value)| [key, value])),
+    }
+}
+
+/// Appends `(path, value)` for every non-alias scalar below `node`.
+fn collect_scalar_leaves<'doc>(
+    node: NodeRef<'doc>,
+    path: &mut String,
+    out: &mut Vec<(String, &'doc str)>,
+) {
+    match node.kind() {
+        NodeType::Scalar => {
+            if node.style() == NodeStyle::Alias {
+                return;
+            }
+            if let Ok(s) = node.scalar_str() {
+                out.push((path.clone(), s));
+            }
+        }
+        NodeType::Sequence => {
+            for (i, item) in node.seq_iter().enumerate() {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&i.to_string());
+                collect_scalar_leaves(item, path, out);
+                path.truncate(len);
+            }
+        }
+        NodeType::Mapping => {
+            for (key, value) in node.map_iter() {
+                let len = path.len();
+                path.push('/');
+                match key.scalar_str() {
+                    Ok(k) => push_pointer_token(path, k),
+                    Err(_) => {
+                        let text = key.emit().unwrap_or_default();
+                        push_pointer_token(path, text.trim_end());
+                    }
+                }
+                collect_scalar_leaves(value, path, out);
+                path.truncate(len);
+            }
+        }
+    }
+}
+
+/// Lets [`walk_leaves`] descend into nodes. Keys that are not scalars are
+/// named by their emitted YAML.
+impl<'doc> FlatNode<'doc> for NodeRef<'doc> {
+    type Error = Error;
+
+    fn flat_children(self) -> Result<Children<'doc, Self>> {
+        let mut children = Vec::new();
+        if self.is_sequence() {
+            for (i, item) in self.seq_iter().enumerate() {
+                children.push((Step::Index(i), item));
+            }
+        } else if self.is_mapping() {
+            for (key, value) in self.map_iter() {
+                let segment = match key.scalar_str() {
+                    Ok(text) => Cow::Borrowed(text),
+                    Err(_) => Cow::Owned(key.emit()?.trim_end().to_owned()),
+                };
+                children.push((Step::Key(segment), value));
+            }
+        }
+        Ok(if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        })
+    }
+}
+
+/// Appends `text` escaped for a `.properties` key or value.
+fn push_properties_escaped(out: &mut String, text: &str, is_key: bool) {
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{c}' => out.push_str("\\f"),
+            '=' | ':' | ' ' | '#' | '!' if is_key => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if i == 0 => out.push_str("\\ "),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Appends a JSON Pointer reference token, escaping `~` and `/`.
+pub(crate) fn push_pointer_token(path: &mut String, token: &str) {
+    for c in token.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+/// Prefixes every non-empty line with `indent` spaces.
+///
+/// Empty lines stay empty so no trailing whitespace is introduced, and the
+/// `---` and `...` document markers stay at column 0 where YAML requires
+/// them. Block scalar indentation is relative, so the shifted output stays
+/// valid.
+fn indent_lines(s: &str, indent: usize) -> String {
+    if indent == 0 {
+        return s.to_owned();
+    }
+    let pad = " ".repeat(indent);
+    let mut out = String::with_capacity(s.len() + indent * (s.lines().count() + 1));
+    for line in s.split_inclusive('\n') {
+        if line != "\n" && !is_document_marker(line) {
+            out.push_str(&pad);
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Returns true if `line` starts with a `---` or `...` document marker.
+fn is_document_marker(line: &str) -> bool {
+    let rest = match line.get(..3) {
+        Some("---") | Some("...") => &line[3..],
+        _ => return false,
+    };
+    matches!(rest.chars().next(), None | Some(' ' | '\t' | '\r' | '\n'))
+}
+
+/// Replaces every non-ASCII character with a YAML double-quoted escape.
+///
+/// Characters in the Basic Multilingual Plane become `\uXXXX`, the others
+/// `\UXXXXXXXX`.
+fn escape_non_ascii(s: &str) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        let code = c as u32;
+        if code < 0x80 {
+            out.push(c);
+        } else if code <= 0xFFFF {
+            let _ = write!(out, "\\u{:04X}", code);
+        } else {
+            let _ = write!(out, "\\U{:08X}", code);
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -536,4 +1818,186 @@ mod tests {
             "comment fused onto block scalar content:\n{emitted}"
         );
     }
+
+    #[test]
+    fn test_structurally_equal_ignores_formatting() {
+        let doc = Document::parse_str("# golden\nname: 'Alice'\ntags:\n  - a\n  - b\n").unwrap();
+        assert!(doc
+            .structurally_equal_to_str("{tags: [a, b], name: \"Alice\"}")
+            .unwrap());
+    }
+
+    #[test]
+    fn test_structurally_equal_detects_content_change() {
+        let doc = Document::parse_str("name: Alice\ntags: [a, b]").unwrap();
+        assert!(!doc
+            .structurally_equal_to_str("name: Alice\ntags: [a, c]")
+            .unwrap());
+        assert!(!doc
+            .structurally_equal_to_str("name: Bob\ntags: [a, b]")
+            .unwrap());
+    }
+
+    #[test]
+    fn test_structurally_equal_invalid_yaml_errors() {
+        let doc = Document::parse_str("a: 1").unwrap();
+        assert!(doc.structurally_equal_to_str("[unclosed").is_err());
+    }
+
+    #[test]
+    fn test_parse_str_lenient_valid_input() {
+        let (doc, errors) = Document::parse_str_lenient("a: 1\nb: 2");
+        assert!(errors.is_empty());
+        assert_eq!(doc.unwrap().root().unwrap().map_len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_parse_str_lenient_broken_tail() {
+        let yaml = "name: service\nports:\n  - 80\n  - 443\nbroken: [1, 2\n  oops: {\n";
+        assert!(Document::parse_str(yaml).is_err());
+
+        let (doc, errors) = Document::parse_str_lenient(yaml);
+        assert!(!errors.is_empty());
+        assert!(errors[0].line().is_some());
+
+        let doc = doc.expect("valid head should be recovered");
+        assert_eq!(
+            doc.at_path("/name").unwrap().scalar_str().unwrap(),
+            "service"
+        );
+        assert_eq!(doc.at_path("/ports").unwrap().seq_len().unwrap(), 2);
+        assert!(doc.at_path("/broken").is_none());
+    }
+
+    #[test]
+    fn test_parse_str_lenient_nothing_valid() {
+        let (doc, errors) = Document::parse_str_lenient("[unclosed");
+        assert!(doc.is_none());
+        assert!(!errors.is_empty());
+
+        let (doc, errors) = Document::parse_str_lenient("");
+        assert!(doc.is_none());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_indent_lines() {
+        assert_eq!(indent_lines("a: 1\n\nb: 2\n", 2), "  a: 1\n\n  b: 2\n");
+        assert_eq!(indent_lines("a: 1", 3), "   a: 1");
+        assert_eq!(indent_lines("a: 1\n", 0), "a: 1\n");
+        assert_eq!(
+            indent_lines("--- !t\na: 1\n...\n---\nb: ---x\n", 2),
+            "--- !t\n  a: 1\n...\n---\n  b: ---x\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_non_ascii() {
+        assert_eq!(escape_non_ascii("plain ascii"), "plain ascii");
+        assert_eq!(escape_non_ascii("café"), "caf\\u00E9");
+        assert_eq!(escape_non_ascii("😀"), "\\U0001F600");
+    }
+
+    #[test]
+    fn test_emit_with_default_matches_emit() {
+        let doc = Document::parse_str("name: 名前 # comment").unwrap();
+        assert_eq!(
+            doc.emit_with(&EmitOptions::default()).unwrap(),
+            doc.emit().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_properties_string_nested() {
+        let doc = Document::parse_str(
+            "app:\n  name: web\n  db:\n    hosts: [a, b]\n    url: \"x=1\\ny\"\n  empty: []\nkey with space: ' lead'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            doc.to_properties_string(".").unwrap(),
+            "app.name=web\n\
+             app.db.hosts.0=a\n\
+             app.db.hosts.1=b\n\
+             app.db.url=x=1\\ny\n\
+             app.empty=\n\
+             key\\ with\\ space=\\ lead\n"
+        );
+        assert_eq!(
+            doc.to_properties_string("_").unwrap().lines().next(),
+            Some("app_name=web")
+        );
+    }
+
+    #[test]
+    fn test_to_properties_string_scalar_root() {
+        let doc = Document::parse_str("just text").unwrap();
+        assert!(matches!(
+            doc.to_properties_string("."),
+            Err(Error::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scalar_leaves_nested() {
+        let doc = Document::parse_str(
+            "name: app\ndb:\n  user: admin\n  password: hunter2\n  replicas:\n    - host: r1\n      port: 5432\n    - host: r2\nempty: {}\nflags: [on, off]\n",
+        )
+        .unwrap();
+        let leaves = doc.scalar_leaves();
+        let expected: Vec<(String, &str)> = vec![
+            ("/name".into(), "app"),
+            ("/db/user".into(), "admin"),
+            ("/db/password".into(), "hunter2"),
+            ("/db/replicas/0/host".into(), "r1"),
+            ("/db/replicas/0/port".into(), "5432"),
+            ("/db/replicas/1/host".into(), "r2"),
+            ("/flags/0".into(), "on"),
+            ("/flags/1".into(), "off"),
+        ];
+        assert_eq!(leaves, expected);
+    }
+
+    #[test]
+    fn test_scalar_leaves_escapes_tokens() {
+        let doc = Document::parse_str("a/b: 1\nc~d: 2\n").unwrap();
+        assert_eq!(
+            doc.scalar_leaves(),
+            vec![("/a~1b".to_string(), "1"), ("/c~0d".to_string(), "2")]
+        );
+
+        let doc = Document::parse_str("just text").unwrap();
+        assert_eq!(doc.scalar_leaves(), vec![(String::new(), "just text")]);
+    }
+
+    #[test]
+    fn test_scalar_leaves_skips_aliases() {
+        let doc = Document::parse_str("key: &k secret\ncopy: *k\nlist: [*k, x]\n").unwrap();
+        assert_eq!(
+            doc.scalar_leaves(),
+            vec![("/key".to_string(), "secret"), ("/list/1".to_string(), "x")]
+        );
+    }
+
+    #[test]
+    fn test_explicit_document_markers() {
+        let doc = Document::parse_str("a: 1\n").unwrap();
+        assert!(!doc.has_explicit_start());
+        assert!(!doc.has_explicit_end());
+
+        let doc = Document::parse_str("---\na: 1\n").unwrap();
+        assert!(doc.has_explicit_start());
+        assert!(!doc.has_explicit_end());
+
+        let doc = Document::parse_str("a: 1\n...\n").unwrap();
+        assert!(!doc.has_explicit_start());
+        assert!(doc.has_explicit_end());
+
+        let doc = Document::parse_str("--- !!map\na: 1\n...\n").unwrap();
+        assert!(doc.has_explicit_start());
+        assert!(doc.has_explicit_end());
+
+        let doc = Document::new().unwrap();
+        assert!(!doc.has_explicit_start());
+        assert!(!doc.has_explicit_end());
+    }
 }
@@ -3,10 +3,14 @@
 use crate::diag::{diag_error, Diag};
 use crate::document::Document;
 use crate::error::{Error, Result};
-use crate::ffi_util::malloc_copy;
+use crate::ffi_util::{malloc_copy, take_c_string};
+use crate::node::{BlockKind, Chomp, NodeStyle};
 use crate::node_ref::NodeRef;
+use crate::value::Value;
 use fyaml_sys::*;
+use libc::size_t;
 
+use std::ffi::{CStr, CString};
 use std::ptr::{self, NonNull};
 
 // =============================================================================
@@ -134,6 +138,129 @@ fn split_path(path: &str) -> (&str, &str) {
     }
 }
 
+/// Rejects anchor names the emitter could not write back as `&name`.
+fn check_anchor_name(name: &str) -> Result<()> {
+    let bad = |c: char| c.is_whitespace() || matches!(c, '[' | ']' | '{' | '}' | ',');
+    if name.is_empty() || name.contains(bad) {
+        return Err(Error::InvalidOption("invalid anchor name"));
+    }
+    Ok(())
+}
+
+/// Returns libfyaml's path for an attached node (e.g. `/items/2`).
+fn node_path(node: *mut fy_node) -> Result<String> {
+    let ptr = unsafe { fy_node_get_path(node) };
+    if ptr.is_null() {
+        return Err(Error::Ffi("fy_node_get_path failed"));
+    }
+    Ok(unsafe { take_c_string(ptr) })
+}
+
+// =============================================================================
+// Node Slots
+// =============================================================================
+
+/// Where a node sits in the tree, so it can be replaced in place.
+#[derive(Clone, Copy)]
+enum Slot {
+    Root,
+    PairValue(*mut fy_node_pair),
+    SeqItem {
+        seq: *mut fy_node,
+        item: *mut fy_node,
+    },
+}
+
+/// Walks the values below `node` (mapping keys are not visited) and collects
+/// the slot of every scalar for which `select` returns `Some`.
+fn collect_scalar_slots<T, F>(
+    node: NodeRef<'_>,
+    slot: Slot,
+    select: &mut F,
+    out: &mut Vec<(Slot, T)>,
+) where
+    F: FnMut(NodeRef<'_>) -> Option<T>,
+{
+    if node.is_scalar() {
+        if let Some(found) = select(node) {
+            out.push((slot, found));
+        }
+    } else if node.is_sequence() {
+        for item in node.seq_iter() {
+            let slot = Slot::SeqItem {
+                seq: node.as_ptr(),
+                item: item.as_ptr(),
+            };
+            collect_scalar_slots(item, slot, select, out);
+        }
+    } else if node.is_mapping() {
+        let mut iter_ptr = ptr::null_mut();
+        loop {
+            let pair_ptr = unsafe { fy_node_mapping_iterate(node.as_ptr(), &mut iter_ptr) };
+            if pair_ptr.is_null() {
+                break;
+            }
+            let value_ptr = unsafe { fy_node_pair_value(pair_ptr) };
+            if let Some(nn) = NonNull::new(value_ptr) {
+                let value = NodeRef::new(nn, node.document());
+                collect_scalar_slots(value, Slot::PairValue(pair_ptr), select, out);
+            }
+        }
+    }
+}
+
+/// Returns the slot `node` occupies in its parent.
+fn slot_of(node: *mut fy_node) -> Result<Slot> {
+    let parent = unsafe { fy_node_get_parent(node) };
+    if parent.is_null() {
+        return Ok(Slot::Root);
+    }
+    if unsafe { fy_node_get_type(parent) } == FYNT_SEQUENCE {
+        return Ok(Slot::SeqItem {
+            seq: parent,
+            item: node,
+        });
+    }
+    let mut iter_ptr = ptr::null_mut();
+    loop {
+        let pair_ptr = unsafe { fy_node_mapping_iterate(parent, &mut iter_ptr) };
+        if pair_ptr.is_null() {
+            return Err(Error::Ffi("node is not a value of its parent mapping"));
+        }
+        if unsafe { fy_node_pair_value(pair_ptr) } == node {
+            return Ok(Slot::PairValue(pair_ptr));
+        }
+    }
+}
+
+/// Detaches the node at `slot` from the tree without freeing it.
+///
+/// The returned handle owns the node: it is freed on drop unless inserted
+/// again. A mapping value takes its pair along; the key is freed.
+fn detach(slot: Slot) -> Result<RawNodeHandle> {
+    let node_ptr = match slot {
+        Slot::Root => return Err(Error::Ffi("cannot detach the root")),
+        Slot::SeqItem { seq, item } => unsafe { fy_node_sequence_remove(seq, item) },
+        Slot::PairValue(pair_ptr) => unsafe {
+            let map = fy_node_get_parent(fy_node_pair_value(pair_ptr));
+            fy_node_mapping_remove_by_key(map, fy_node_pair_key(pair_ptr))
+        },
+    };
+    RawNodeHandle::try_from_ptr(node_ptr, "failed to detach node")
+}
+
+/// Returns whether `node` is `ancestor` or lies below it.
+fn is_within(node: *mut fy_node, ancestor: *mut fy_node) -> bool {
+    let mut current = node;
+    while !current.is_null() {
+        if current == ancestor {
+            return true;
+        }
+        current = unsafe { fy_node_get_parent(current) };
+    }
+    false
+}
+
 // =============================================================================
 // Editor
 // =============================================================================
@@ -176,13 +303,18 @@ fn split_path(path: &str) -> (&str, &str) {
 /// ```
 pub struct Editor<'doc> {
     doc: &'doc mut Document,
+    /// Subtrees refused to mutations, by path and root node.
+    locked: Vec<(String, *mut fy_node)>,
 }
 
 impl<'doc> Editor<'doc> {
     /// Creates a new editor for the document.
     #[inline]
     pub(crate) fn new(doc: &'doc mut Document) -> Self {
-        Editor { doc }
+        Editor {
+            doc,
+            locked: Vec::new(),
+        }
     }
 
     /// Returns the raw document pointer.
@@ -211,6 +343,120 @@ impl<'doc> Editor<'doc> {
         self.root()?.at_path(path)
     }
 
+    // ==================== Subtree Locks ====================
+
+    /// Locks the subtree at `path` against edits for the rest of this
+    /// editing session.
+    ///
+    /// Afterwards, any mutation that would change the locked node or
+    /// anything below it fails with [`Error::Locked`]: setting, replacing
+    /// or deleting it or one of its descendants, and replacing or deleting
+    /// an ancestor that contains it (including the root). Edits elsewhere,
+    /// such as adding a sibling key, are unaffected. Bulk rewrites like
+    /// [`replace_scalar_value`](Self::replace_scalar_value) skip locked
+    /// subtrees.
+    ///
+    /// The lock follows the node, not the path, so it stays on the same
+    /// subtree if sequence items before it are removed. Locks are per
+    /// editor and end when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing exists at `path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Error};
+    ///
+    /// let mut doc = Document::parse_str("metadata: {name: app}\nspec: {replicas: 1}").unwrap();
+    /// let mut ed = doc.edit();
+    /// ed.lock_subtree("/metadata").unwrap();
+    ///
+    /// let err = ed.set_yaml_at("/metadata/name", "other").unwrap_err();
+    /// assert_eq!(err, Error::Locked { path: "/metadata".into() });
+    /// ed.set_yaml_at("/spec/replicas", "3").unwrap();
+    /// ```
+    pub fn lock_subtree(&mut self, path: &str) -> Result<()> {
+        let node_ptr = self.get_node_ptr_at(path)?;
+        if !self.locked.iter().any(|(_, p)| *p == node_ptr) {
+            self.locked.push((path.to_owned(), node_ptr));
+        }
+        Ok(())
+    }
+
+    /// Returns the lock on `node_ptr` or one of its ancestors.
+    fn lock_above(&self, node_ptr: *mut fy_node) -> Option<&str> {
+        let mut current = node_ptr;
+        while !current.is_null() {
+            if let Some((path, _)) = self.locked.iter().find(|(_, p)| *p == current) {
+                return Some(path);
+            }
+            current = unsafe { fy_node_get_parent(current) };
+        }
+        None
+    }
+
+    /// Returns a lock that replacing or removing `node_ptr` would break:
+    /// one on the node, above it, or anywhere in its subtree.
+    fn lock_touching(&self, node_ptr: *mut fy_node) -> Option<&str> {
+        self.lock_above(node_ptr).or_else(|| {
+            self.locked
+                .iter()
+                .find(|(_, locked)| {
+                    let mut current = *locked;
+                    while !current.is_null() {
+                        if current == node_ptr {
+                            return true;
+                        }
+                        current = unsafe { fy_node_get_parent(current) };
+                    }
+                    false
+                })
+                .map(|(path, _)| path.as_str())
+        })
+    }
+
+    /// Fails if replacing or removing whatever is at `path` would modify a
+    /// locked subtree. A missing `path` is checked as a new child of its
+    /// parent; if the parent is missing too, the edit fails on its own.
+    fn check_unlocked(&self, path: &str) -> Result<()> {
+        if self.locked.is_empty() {
+            return Ok(());
+        }
+        let lock = match self.get_node_ptr_at(path) {
+            Ok(node_ptr) => self.lock_touching(node_ptr),
+            Err(_) => match self.resolve_parent(split_path(path).0) {
+                Ok(parent_ptr) => self.lock_above(parent_ptr),
+                Err(_) => None,
+            },
+        };
+        match lock {
+            Some(locked) => Err(Error::Locked {
+                path: locked.to_owned(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails if the node at `path` is locked itself or lies in a locked
+    /// subtree. For edits that change the node but keep its descendants.
+    fn check_node_unlocked(&self, path: &str) -> Result<()> {
+        if self.locked.is_empty() {
+            return Ok(());
+        }
+        match self
+            .get_node_ptr_at(path)
+            .ok()
+            .and_then(|p| self.lock_above(p))
+        {
+            Some(locked) => Err(Error::Locked {
+                path: locked.to_owned(),
+            }),
+            None => Ok(()),
+        }
+    }
+
     // ==================== Internal Helpers ====================
 
     /// Resolves a parent path to a node pointer.
@@ -237,7 +483,7 @@ impl<'doc> Editor<'doc> {
                 )
             };
             if parent_ptr.is_null() {
-                return Err(Error::Ffi("parent path not found"));
+                return Err(Error::PathNotFound(parent_path.to_owned()));
             }
             Ok(parent_ptr)
         }
@@ -289,9 +535,177 @@ impl<'doc> Editor<'doc> {
     /// assert_eq!(doc.at_path("/items/2").unwrap().scalar_str().unwrap(), "last");
     /// ```
     pub fn set_yaml_at(&mut self, path: &str, yaml: &str) -> Result<()> {
-        // Build the new node
+        let new_node = self.build_from_yaml(yaml)?;
+        self.set_node_at(path, new_node)
+    }
+
+    /// Like [`set_yaml_at`](Self::set_yaml_at), returning the canonical path
+    /// of the inserted node.
+    ///
+    /// The returned path has negative sequence indices resolved, and can be
+    /// passed to [`at_path`](Self::at_path) or back to other editor methods
+    /// to keep working on the new node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("items: [a, b, c]").unwrap();
+    /// let mut ed = doc.edit();
+    /// let path = ed.set_yaml_at_located("/items/-1", "{name: z}").unwrap();
+    /// assert_eq!(path, "/items/2");
+    /// assert!(ed.at_path(&path).unwrap().is_mapping());
+    /// ```
+    pub fn set_yaml_at_located(&mut self, path: &str, yaml: &str) -> Result<String> {
+        let new_node = self.build_from_yaml(yaml)?;
+        let node_ptr = new_node.as_ptr();
+        self.set_node_at(path, new_node)?;
+        node_path(node_ptr)
+    }
+
+    /// Sets a value at the given path from a YAML snippet, keeping the
+    /// replaced node's tag.
+    ///
+    /// Behaves like [`set_yaml_at`](Self::set_yaml_at), except that if the
+    /// existing node at `path` has a tag and the snippet does not specify
+    /// one, the old tag is applied to the replacement. A tag in the snippet
+    /// always wins. If nothing exists at `path` yet, this is equivalent to
+    /// `set_yaml_at`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("password: !secret abc").unwrap();
+    /// {
+    ///     let mut ed = doc.edit();
+    ///     ed.set_yaml_at_keeping_tag("/password", "xyz").unwrap();
+    /// }
+    /// let node = doc.at_path("/password").unwrap();
+    /// assert_eq!(node.tag_str().unwrap(), Some("!secret"));
+    /// assert_eq!(node.scalar_str().unwrap(), "xyz");
+    /// ```
+    pub fn set_yaml_at_keeping_tag(&mut self, path: &str, yaml: &str) -> Result<()> {
+        // Copy the tag out before the old node is freed by the replacement
+        let old_tag = match self.at_path(path) {
+            Some(node) => node.tag_str()?.map(str::to_owned),
+            None => None,
+        };
+
         let mut new_node = self.build_from_yaml(yaml)?;
 
+        if let Some(tag) = old_tag {
+            let mut len: size_t = 0;
+            let new_tag = unsafe { fy_node_get_tag(new_node.as_ptr(), &mut len) };
+            if new_tag.is_null() {
+                self.set_tag(&mut new_node, &tag)?;
+            }
+        }
+
+        self.set_node_at(path, new_node)
+    }
+
+    /// Replaces the existing value at the given path from a YAML snippet.
+    ///
+    /// Unlike [`set_yaml_at`](Self::set_yaml_at), this never creates a new
+    /// key: the path must already exist. The replacement keeps the position
+    /// of the old value, so a mapping key stays where it was (its pair is
+    /// updated in place rather than removed and re-appended) and a sequence
+    /// item keeps its index, even when the new value has a different type.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PathNotFound`] if nothing exists at `path`, or an
+    /// error if the snippet fails to parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Error};
+    ///
+    /// let mut doc = Document::parse_str("a: 1\nb: 2\nc: 3").unwrap();
+    /// doc.edit().replace_value_at("/b", "[x, y]").unwrap();
+    ///
+    /// let keys: Vec<&str> = doc
+    ///     .root()
+    ///     .unwrap()
+    ///     .map_iter()
+    ///     .map(|(k, _)| k.scalar_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(keys, ["a", "b", "c"]);
+    /// let err = doc.edit().replace_value_at("/missing", "1").unwrap_err();
+    /// assert!(matches!(err, Error::PathNotFound(p) if p == "/missing"));
+    /// ```
+    pub fn replace_value_at(&mut self, path: &str, yaml: &str) -> Result<()> {
+        if self.at_path(path).is_none() {
+            return Err(Error::PathNotFound(path.to_owned()));
+        }
+        let new_node = self.build_from_yaml(yaml)?;
+        self.set_node_at(path, new_node)
+    }
+
+    /// Sets the value at `path` to a block scalar (`|` or `>`).
+    ///
+    /// `chomp` decides the trailing line breaks of the stored value: `Strip`
+    /// removes them, `Clip` leaves exactly one, and `Keep` stores `content`
+    /// unchanged. The emitter then writes the matching indicator, e.g. `|-`
+    /// for a stripped literal or `>+` for a folded scalar ending in several
+    /// line breaks. For folded scalars, libfyaml inserts the extra line
+    /// breaks needed for `content` to read back unchanged.
+    ///
+    /// Path rules are the same as for [`set_yaml_at`](Self::set_yaml_at).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path cannot be set, or if libfyaml refuses
+    /// the block style for this content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{BlockKind, Chomp, Document};
+    ///
+    /// let mut doc = Document::parse_str("script: x").unwrap();
+    /// doc.edit()
+    ///     .set_block_scalar_at("/script", "make\nmake install\n", BlockKind::Literal, Chomp::Strip)
+    ///     .unwrap();
+    /// assert!(doc.emit().unwrap().starts_with("script: |-\n"));
+    /// let script = doc.at_path("/script").unwrap().scalar_str().unwrap();
+    /// assert_eq!(script, "make\nmake install");
+    /// ```
+    pub fn set_block_scalar_at(
+        &mut self,
+        path: &str,
+        content: &str,
+        kind: BlockKind,
+        chomp: Chomp,
+    ) -> Result<()> {
+        let body = content.trim_end_matches('\n');
+        let value = match chomp {
+            Chomp::Strip => body.to_owned(),
+            Chomp::Clip => format!("{}\n", body),
+            Chomp::Keep => content.to_owned(),
+        };
+        let style = match kind {
+            BlockKind::Literal => NodeStyle::Literal,
+            BlockKind::Folded => NodeStyle::Folded,
+        };
+        let mut node = self.build_scalar(&value)?;
+        if self.set_style(&mut node, style) != style {
+            return Err(Error::Ffi("fy_node_set_style rejected block style"));
+        }
+        self.set_node_at(path, node)
+    }
+
+    /// Inserts a detached node at the given path, replacing any existing value.
+    ///
+    /// Shared implementation of the `set_yaml_at*` family; see
+    /// [`set_yaml_at`](Self::set_yaml_at) for the path rules.
+    fn set_node_at(&mut self, path: &str, mut new_node: RawNodeHandle) -> Result<()> {
+        self.check_unlocked(path)?;
+
         // Find the parent path and key
         if path.is_empty() || path == "/" {
             // Setting the root
@@ -422,6 +836,7 @@ impl<'doc> Editor<'doc> {
             // Can't delete root this way
             return Err(Error::Ffi("cannot delete root via delete_at"));
         }
+        self.check_unlocked(path)?;
 
         // Find parent and key using helper
         let (parent_path, key) = split_path(path);
@@ -480,98 +895,479 @@ impl<'doc> Editor<'doc> {
         }
     }
 
-    // ==================== Node Building ====================
-
-    /// Builds a node from a YAML snippet.
+    /// Places full-line `#` comments directly above the mapping key at `path`.
     ///
-    /// The node is created but not inserted into the document tree.
-    /// Use [`set_root`](Self::set_root) or other methods to insert it.
+    /// Each entry of `lines` becomes one `# line` comment (an entry holding
+    /// newlines yields several). The comment block replaces any comment
+    /// already above the key; an empty `lines` removes it. The key keeps its
+    /// position, quoting and value.
     ///
-    /// Original formatting (including quotes) is preserved.
+    /// This works by rebuilding the key node from a snippet that carries the
+    /// comment, so it requires a scalar key.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::ParseError`] with line and column information if parsing fails.
-    pub fn build_from_yaml(&mut self, yaml: &str) -> Result<RawNodeHandle> {
-        let buffer = unsafe { malloc_copy(yaml.as_bytes())? };
-
-        // Create diagnostic handler to capture errors
-        let diag = Diag::new();
-        let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
-
-        // Save original diag and set our capture diag with RAII guard for restoration
-        let original_diag = unsafe { fy_document_get_diag(self.doc_ptr()) };
-        let _guard = DiagGuard::new(self.doc_ptr(), original_diag);
-        if !diag_ptr.is_null() {
-            unsafe { fy_document_set_diag(self.doc_ptr(), diag_ptr) };
+    /// Returns an error if `path` is the root, its parent is not a mapping,
+    /// the key does not exist, or the key is not a scalar, and
+    /// [`Error::InvalidOption`] if the document does not keep comments: it
+    /// was parsed with `parse_comments: false` or built in code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("name: app\nport: 80\n").unwrap();
+    /// doc.edit()
+    ///     .set_comment_before_at("/port", &["Listening port", "change with care"])
+    ///     .unwrap();
+    /// let yaml = doc.emit().unwrap();
+    /// assert!(yaml.contains("# Listening port\n# change with care\nport: 80"));
+    /// ```
+    pub fn set_comment_before_at(&mut self, path: &str, lines: &[&str]) -> Result<()> {
+        if path.is_empty() || path == "/" {
+            return Err(Error::Ffi(
+                "cannot comment the root via set_comment_before_at",
+            ));
+        }
+        // Without KEEP_COMMENTS the snippet's comment would be dropped
+        let cfg = unsafe { fy_document_get_cfg(self.doc_ptr()) };
+        // SAFETY: a non-null config lives inside the document
+        if cfg.is_null() || unsafe { (*cfg).flags } & FYPCF_KEEP_COMMENTS == 0 {
+            return Err(Error::InvalidOption("document does not keep comments"));
+        }
+        self.check_node_unlocked(path)?;
+        let (parent_path, key) = split_path(path);
+        let parent_ptr = self.resolve_parent(parent_path)?;
+        if unsafe { fy_node_get_type(parent_ptr) } != FYNT_MAPPING {
+            return Err(Error::TypeMismatch {
+                expected: "mapping",
+                got: "non-mapping",
+            });
+        }
+        let pair_ptr = unsafe {
+            fy_node_mapping_lookup_pair_by_string(parent_ptr, key.as_ptr() as *const i8, key.len())
+        };
+        if pair_ptr.is_null() {
+            return Err(Error::PathNotFound(path.to_owned()));
+        }
+        let key_ptr = unsafe { fy_node_pair_key(pair_ptr) };
+        let old_key = match NonNull::new(key_ptr) {
+            Some(nn) => NodeRef::new(nn, &*self.doc),
+            None => return Err(Error::Ffi("fy_node_pair_key returned null")),
+        };
+        if !old_key.is_scalar() {
+            return Err(Error::TypeMismatch {
+                expected: "scalar key",
+                got: "complex key",
+            });
         }
 
-        let node_ptr =
-            unsafe { fy_node_build_from_malloc_string(self.doc_ptr(), buffer, yaml.len()) };
-
-        // Guard will restore original diag on drop (including early returns and panics)
+        // Emitting keeps the key's quoting; drop any comment already on it
+        let mut snippet = String::new();
+        for line in lines.iter().flat_map(|l| l.lines()) {
+            snippet.push('#');
+            if !line.is_empty() {
+                snippet.push(' ');
+                snippet.push_str(line.trim_end());
+            }
+            snippet.push('\n');
+        }
+        for line in old_key.emit()?.lines() {
+            if !line.trim_start().starts_with('#') {
+                snippet.push_str(line);
+                snippet.push('\n');
+            }
+        }
 
-        if node_ptr.is_null() {
-            // Note: fy_node_build_from_malloc_string creates an internal parser that takes
-            // ownership of the buffer via fy_parser_set_malloc_string. Once registered,
-            // the buffer is freed by fy_parse_cleanup when the internal parser is destroyed,
-            // regardless of whether parsing succeeded or failed.
-            //
-            // The docs for fy_parser_set_malloc_string say "In case of an error the string
-            // is not freed" - but this refers to errors in the registration call itself,
-            // NOT to parse errors later. In practice, registration rarely fails (only on
-            // allocation errors), so for parse failures the buffer is already registered
-            // and WILL be freed by libfyaml.
-            //
-            // VERIFIED: Freeing here causes double-free (detected in tests).
-            return Err(diag_error(diag, "fy_node_build_from_malloc_string failed"));
+        let mut new_key = self.build_from_yaml(&snippet)?;
+        let ret = unsafe { fy_node_pair_set_key(pair_ptr, new_key.as_ptr()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_pair_set_key failed"));
         }
-        // On success, libfyaml takes ownership of buffer (freed when document is destroyed).
-        // node_ptr is non-null here (null case handled above).
-        Ok(RawNodeHandle {
-            node_ptr: NonNull::new(node_ptr).unwrap(),
-            inserted: false,
-        })
+        new_key.mark_inserted();
+        Ok(())
     }
 
-    /// Creates a scalar node from raw pointer and length.
+    /// Renames the key `old_key` of the mapping at `parent_path` to
+    /// `new_key`.
     ///
-    /// Pass `(ptr::null(), 0)` for YAML null (distinct from empty string `("", 0)`).
-    fn build_scalar_raw(&mut self, ptr: *const i8, len: usize) -> Result<RawNodeHandle> {
-        let node_ptr = unsafe { fy_node_create_scalar_copy(self.doc_ptr(), ptr, len) };
-        RawNodeHandle::try_from_ptr(node_ptr, "fy_node_create_scalar_copy failed")
-    }
-
-    /// Builds a plain scalar node.
+    /// Only the key node is swapped: the pair keeps its position and the
+    /// value node is left untouched, style, tag and comments included. The
+    /// new key is quoted if it would otherwise read as another type.
+    /// Renaming a key to itself is a no-op that returns `Ok(true)`.
     ///
-    /// The scalar style is automatically determined based on content.
-    /// Use [`build_from_yaml`](Self::build_from_yaml) for explicit quoting.
-    pub fn build_scalar(&mut self, value: &str) -> Result<RawNodeHandle> {
-        self.build_scalar_raw(value.as_ptr() as *const i8, value.len())
-    }
+    /// Returns `Ok(false)` if the mapping has no key `old_key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `parent_path` does not exist or is not a mapping,
+    /// [`Error::DuplicateKey`] if `new_key` is already present, or
+    /// [`Error::Locked`] if the value lies in a locked subtree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("server:\n  hostname: 'web'\n  port: 80\n").unwrap();
+    /// assert!(doc.edit().rename_key("/server", "hostname", "host").unwrap());
+    /// assert!(!doc.edit().rename_key("/server", "missing", "other").unwrap());
+    /// let yaml = doc.emit().unwrap();
+    /// assert!(yaml.contains("host: 'web'\n  port: 80"), "got:\n{}", yaml);
+    /// ```
+    pub fn rename_key(&mut self, parent_path: &str, old_key: &str, new_key: &str) -> Result<bool> {
+        let parent_ptr = self.resolve_parent(parent_path)?;
+        if unsafe { fy_node_get_type(parent_ptr) } != FYNT_MAPPING {
+            return Err(Error::TypeMismatch {
+                expected: "mapping",
+                got: "non-mapping",
+            });
+        }
+        let pair_ptr = unsafe {
+            fy_node_mapping_lookup_pair_by_string(
+                parent_ptr,
+                old_key.as_ptr() as *const i8,
+                old_key.len(),
+            )
+        };
+        if pair_ptr.is_null() {
+            return Ok(false);
+        }
+        if old_key == new_key {
+            return Ok(true);
+        }
+        if let Some(locked) = self.lock_above(unsafe { fy_node_pair_value(pair_ptr) }) {
+            return Err(Error::Locked {
+                path: locked.to_owned(),
+            });
+        }
+        let existing = unsafe {
+            fy_node_mapping_lookup_pair_by_string(
+                parent_ptr,
+                new_key.as_ptr() as *const i8,
+                new_key.len(),
+            )
+        };
+        if !existing.is_null() {
+            return Err(Error::DuplicateKey(new_key.to_owned()));
+        }
 
-    /// Builds an empty sequence node.
-    pub fn build_sequence(&mut self) -> Result<RawNodeHandle> {
-        let ptr = unsafe { fy_node_create_sequence(self.doc_ptr()) };
-        RawNodeHandle::try_from_ptr(ptr, "fy_node_create_sequence failed")
+        let mut key = Value::from(new_key).build_node(self)?;
+        let ret = unsafe { fy_node_pair_set_key(pair_ptr, key.as_ptr()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_pair_set_key failed"));
+        }
+        key.mark_inserted();
+        Ok(true)
     }
 
-    /// Builds an empty mapping node.
-    pub fn build_mapping(&mut self) -> Result<RawNodeHandle> {
-        let ptr = unsafe { fy_node_create_mapping(self.doc_ptr()) };
-        RawNodeHandle::try_from_ptr(ptr, "fy_node_create_mapping failed")
-    }
+    // ==================== Tags and Directives ====================
 
-    /// Sets the document root to the given node.
+    /// Adds a `%TAG` directive mapping `handle` to `prefix`.
     ///
-    /// The node handle is consumed and the document takes ownership.
+    /// Once registered, tags written with the shorthand (e.g. `!e!foo` for
+    /// handle `!e!`) can be set with [`set_tag_at`](Self::set_tag_at) or
+    /// [`set_tag`](Self::set_tag), and the directive is emitted ahead of
+    /// the document so the shorthand resolves when read back.
     ///
-    /// # Warning
+    /// # Errors
     ///
-    /// If the document already has a root, it will be replaced and freed.
-    pub fn set_root(&mut self, mut node: RawNodeHandle) -> Result<()> {
-        let ret = unsafe { fy_document_set_root(self.doc_ptr(), node.as_ptr()) };
-        if ret != 0 {
+    /// Returns an error if either argument contains a NUL byte, or if
+    /// libfyaml rejects the directive (a malformed handle, or a handle
+    /// that is already registered).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("shape: circle").unwrap();
+    /// {
+    ///     let mut ed = doc.edit();
+    ///     ed.register_tag_directive("!e!", "tag:example.com,2000:").unwrap();
+    ///     ed.set_tag_at("/shape", "!e!circle").unwrap();
+    /// }
+    /// let yaml = doc.emit().unwrap();
+    /// assert!(yaml.contains("%TAG !e! tag:example.com,2000:"));
+    /// assert!(yaml.contains("!e!circle circle"));
+    /// ```
+    pub fn register_tag_directive(&mut self, handle: &str, prefix: &str) -> Result<()> {
+        let c_handle =
+            CString::new(handle).map_err(|_| Error::Ffi("tag handle contains a NUL byte"))?;
+        let c_prefix =
+            CString::new(prefix).map_err(|_| Error::Ffi("tag prefix contains a NUL byte"))?;
+        let ret = unsafe {
+            fy_document_tag_directive_add(self.doc_ptr(), c_handle.as_ptr(), c_prefix.as_ptr())
+        };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_document_tag_directive_add failed"));
+        }
+        Ok(())
+    }
+
+    /// Sets the YAML tag of the node at `path`, keeping its content and
+    /// style.
+    ///
+    /// A shorthand tag such as `!e!foo` needs its handle registered first
+    /// with [`register_tag_directive`](Self::register_tag_directive);
+    /// `!local` and `!!str` style tags work without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path does not exist or libfyaml rejects the
+    /// tag, e.g. because its handle is unknown.
+    pub fn set_tag_at(&mut self, path: &str, tag: &str) -> Result<()> {
+        self.check_node_unlocked(path)?;
+        let node_ptr = self.get_node_ptr_at(path)?;
+        let ret = unsafe { fy_node_set_tag(node_ptr, tag.as_ptr() as *const i8, tag.len()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_set_tag failed"));
+        }
+        Ok(())
+    }
+
+    /// Defines the anchor `&name` on the node at `path`.
+    ///
+    /// Aliases built with [`build_alias`](Self::build_alias) can then refer
+    /// to it. Setting the name the node already has is a no-op; any other
+    /// anchor the node had is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DuplicateAnchor`] if another node already defines
+    /// `name`, [`Error::InvalidOption`] if `name` is not a valid anchor
+    /// (empty, or containing whitespace or `[]{},`), and an error if the path
+    /// does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("defaults: {retries: 3}\njobs: []").unwrap();
+    /// {
+    ///     let mut ed = doc.edit();
+    ///     ed.set_anchor("/defaults", "defaults").unwrap();
+    ///     let alias = ed.build_alias("defaults").unwrap();
+    ///     ed.seq_append_at("/jobs", alias).unwrap();
+    /// }
+    /// let yaml = doc.emit().unwrap();
+    /// assert!(yaml.contains("defaults: &defaults"));
+    /// assert!(yaml.contains("*defaults"));
+    /// ```
+    pub fn set_anchor(&mut self, path: &str, name: &str) -> Result<()> {
+        check_anchor_name(name)?;
+        self.check_node_unlocked(path)?;
+        let node_ptr = self.get_node_ptr_at(path)?;
+        if let Some(owner) = self.doc.node_by_anchor(name) {
+            if owner.as_ptr() == node_ptr {
+                return Ok(());
+            }
+            return Err(Error::DuplicateAnchor(name.to_owned()));
+        }
+        let ret =
+            unsafe { fy_node_set_anchor_copy(node_ptr, name.as_ptr() as *const i8, name.len()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_set_anchor_copy failed"));
+        }
+        Ok(())
+    }
+
+    // ==================== Root Restructuring ====================
+
+    /// Replaces a root mapping holding the single key `key` with that key's
+    /// value.
+    ///
+    /// Useful for configs that wrap everything under one top-level key. The
+    /// value keeps its formatting. Inverse of [`wrap_root`](Self::wrap_root).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no root, the root is not a
+    /// mapping, or it has any key other than `key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("app:\n  name: web\n  port: 80").unwrap();
+    /// doc.edit().unwrap_root_key("app").unwrap();
+    /// assert_eq!(doc.at_path("/port").unwrap().scalar_str().unwrap(), "80");
+    ///
+    /// doc.edit().wrap_root("app").unwrap();
+    /// assert_eq!(doc.at_path("/app/name").unwrap().scalar_str().unwrap(), "web");
+    /// ```
+    pub fn unwrap_root_key(&mut self, key: &str) -> Result<()> {
+        self.check_unlocked("")?;
+        let root_ptr = self.resolve_parent("")?;
+        if unsafe { fy_node_get_type(root_ptr) } != FYNT_MAPPING {
+            return Err(Error::TypeMismatch {
+                expected: "mapping",
+                got: "non-mapping",
+            });
+        }
+        if unsafe { fy_node_mapping_item_count(root_ptr) } != 1 {
+            return Err(Error::Ffi("root mapping must have exactly one key"));
+        }
+        let value_ptr = unsafe {
+            fy_node_mapping_lookup_by_string(root_ptr, key.as_ptr() as *const i8, key.len())
+        };
+        if value_ptr.is_null() {
+            return Err(Error::Ffi("root key not found"));
+        }
+
+        // Copy first: setting the root frees the old tree, value included
+        let ptr = unsafe { fy_node_copy(self.doc_ptr(), value_ptr) };
+        let value = RawNodeHandle::try_from_ptr(ptr, "fy_node_copy failed")?;
+        self.set_root(value)
+    }
+
+    /// Replaces the root with a mapping holding the old root under `key`.
+    ///
+    /// Inverse of [`unwrap_root_key`](Self::unwrap_root_key). The key is
+    /// quoted if it would otherwise read as another type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no root.
+    pub fn wrap_root(&mut self, key: &str) -> Result<()> {
+        self.check_unlocked("")?;
+        let root_ptr = self.resolve_parent("")?;
+        let ptr = unsafe { fy_node_copy(self.doc_ptr(), root_ptr) };
+        let value = RawNodeHandle::try_from_ptr(ptr, "fy_node_copy failed")?;
+
+        let key = Value::from(key).build_node(self)?;
+        let mut map = self.build_mapping()?;
+        self.map_insert(&mut map, key, value)?;
+        self.set_root(map)
+    }
+
+    // ==================== Node Building ====================
+
+    /// Builds a node from a YAML snippet.
+    ///
+    /// The node is created but not inserted into the document tree.
+    /// Use [`set_root`](Self::set_root) or other methods to insert it.
+    ///
+    /// Original formatting (including quotes) is preserved. The snippet is
+    /// parsed with the document's own parse configuration, so its comments
+    /// are kept exactly when the document was parsed with
+    /// [`ParseOptions::parse_comments`](crate::ParseOptions::parse_comments).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] with line and column information if parsing fails.
+    pub fn build_from_yaml(&mut self, yaml: &str) -> Result<RawNodeHandle> {
+        let buffer = unsafe { malloc_copy(yaml.as_bytes())? };
+
+        // Create diagnostic handler to capture errors
+        let diag = Diag::new();
+        let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
+
+        // Save original diag and set our capture diag with RAII guard for restoration
+        let original_diag = unsafe { fy_document_get_diag(self.doc_ptr()) };
+        let _guard = DiagGuard::new(self.doc_ptr(), original_diag);
+        if !diag_ptr.is_null() {
+            unsafe { fy_document_set_diag(self.doc_ptr(), diag_ptr) };
+        }
+
+        let node_ptr =
+            unsafe { fy_node_build_from_malloc_string(self.doc_ptr(), buffer, yaml.len()) };
+
+        // Guard will restore original diag on drop (including early returns and panics)
+
+        if node_ptr.is_null() {
+            // Note: fy_node_build_from_malloc_string creates an internal parser that takes
+            // ownership of the buffer via fy_parser_set_malloc_string. Once registered,
+            // the buffer is freed by fy_parse_cleanup when the internal parser is destroyed,
+            // regardless of whether parsing succeeded or failed.
+            //
+            // The docs for fy_parser_set_malloc_string say "In case of an error the string
+            // is not freed" - but this refers to errors in the registration call itself,
+            // NOT to parse errors later. In practice, registration rarely fails (only on
+            // allocation errors), so for parse failures the buffer is already registered
+            // and WILL be freed by libfyaml.
+            //
+            // VERIFIED: Freeing here causes double-free (detected in tests).
+            return Err(diag_error(diag, "fy_node_build_from_malloc_string failed"));
+        }
+        // On success, libfyaml takes ownership of buffer (freed when document is destroyed).
+        // node_ptr is non-null here (null case handled above).
+        Ok(RawNodeHandle {
+            node_ptr: NonNull::new(node_ptr).unwrap(),
+            inserted: false,
+        })
+    }
+
+    /// Creates a scalar node from raw pointer and length.
+    ///
+    /// Pass `(ptr::null(), 0)` for YAML null (distinct from empty string `("", 0)`).
+    fn build_scalar_raw(&mut self, ptr: *const i8, len: usize) -> Result<RawNodeHandle> {
+        let node_ptr = unsafe { fy_node_create_scalar_copy(self.doc_ptr(), ptr, len) };
+        RawNodeHandle::try_from_ptr(node_ptr, "fy_node_create_scalar_copy failed")
+    }
+
+    /// Builds a plain scalar node.
+    ///
+    /// The scalar style is automatically determined based on content.
+    /// Use [`build_from_yaml`](Self::build_from_yaml) for explicit quoting.
+    pub fn build_scalar(&mut self, value: &str) -> Result<RawNodeHandle> {
+        self.build_scalar_raw(value.as_ptr() as *const i8, value.len())
+    }
+
+    /// Builds an empty sequence node.
+    pub fn build_sequence(&mut self) -> Result<RawNodeHandle> {
+        let ptr = unsafe { fy_node_create_sequence(self.doc_ptr()) };
+        RawNodeHandle::try_from_ptr(ptr, "fy_node_create_sequence failed")
+    }
+
+    /// Builds an empty mapping node.
+    pub fn build_mapping(&mut self) -> Result<RawNodeHandle> {
+        let ptr = unsafe { fy_node_create_mapping(self.doc_ptr()) };
+        RawNodeHandle::try_from_ptr(ptr, "fy_node_create_mapping failed")
+    }
+
+    /// Builds an alias node (`*anchor_name`).
+    ///
+    /// The anchor does not have to exist yet: define it with
+    /// [`set_anchor`](Self::set_anchor) before or after inserting the alias.
+    /// Emitting fails with [`Error::UndefinedAlias`] if, by then, no node
+    /// before the alias defines the anchor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOption`] if `anchor_name` is not a valid
+    /// anchor name.
+    pub fn build_alias(&mut self, anchor_name: &str) -> Result<RawNodeHandle> {
+        check_anchor_name(anchor_name)?;
+        let ptr = unsafe {
+            fy_node_create_alias_copy(
+                self.doc_ptr(),
+                anchor_name.as_ptr() as *const i8,
+                anchor_name.len(),
+            )
+        };
+        let handle = RawNodeHandle::try_from_ptr(ptr, "fy_node_create_alias_copy failed")?;
+        self.doc.unchecked_aliases = true;
+        Ok(handle)
+    }
+
+    /// Sets the document root to the given node.
+    ///
+    /// The node handle is consumed and the document takes ownership.
+    ///
+    /// # Warning
+    ///
+    /// If the document already has a root, it will be replaced and freed.
+    pub fn set_root(&mut self, mut node: RawNodeHandle) -> Result<()> {
+        // Every lock is somewhere under the current root
+        if let Some((path, _)) = self.locked.first() {
+            return Err(Error::Locked { path: path.clone() });
+        }
+        let ret = unsafe { fy_document_set_root(self.doc_ptr(), node.as_ptr()) };
+        if ret != 0 {
             return Err(Error::Ffi("fy_document_set_root failed"));
         }
         // Mark as inserted so Drop doesn't free it
@@ -630,85 +1426,674 @@ impl<'doc> Editor<'doc> {
         if ret != 0 {
             return Err(Error::Ffi("fy_node_mapping_append failed"));
         }
-        key.mark_inserted();
-        value.mark_inserted();
-        Ok(())
+        key.mark_inserted();
+        value.mark_inserted();
+        Ok(())
+    }
+
+    /// Sets the style of a detached node handle.
+    ///
+    /// libfyaml validates the requested style against the node content.
+    /// If the style is not valid for this content (e.g., plain for a scalar
+    /// that would be ambiguous), libfyaml may keep the current style.
+    ///
+    /// Returns the style that was actually set.
+    pub fn set_style(
+        &mut self,
+        node: &mut RawNodeHandle,
+        style: crate::node::NodeStyle,
+    ) -> crate::node::NodeStyle {
+        let raw_style = match style {
+            crate::node::NodeStyle::Any => FYNS_ANY,
+            crate::node::NodeStyle::Flow => FYNS_FLOW,
+            crate::node::NodeStyle::Block => FYNS_BLOCK,
+            crate::node::NodeStyle::Plain => FYNS_PLAIN,
+            crate::node::NodeStyle::SingleQuoted => FYNS_SINGLE_QUOTED,
+            crate::node::NodeStyle::DoubleQuoted => FYNS_DOUBLE_QUOTED,
+            crate::node::NodeStyle::Literal => FYNS_LITERAL,
+            crate::node::NodeStyle::Folded => FYNS_FOLDED,
+            crate::node::NodeStyle::Alias => FYNS_ALIAS,
+        };
+        let result = unsafe { fy_node_set_style(node.as_ptr(), raw_style) };
+        crate::node::NodeStyle::from(result)
+    }
+
+    /// Sets a YAML tag on a detached node handle.
+    ///
+    /// For example, `set_tag(&mut node, "!custom")` produces `!custom value`.
+    pub fn set_tag(&mut self, node: &mut RawNodeHandle, tag: &str) -> Result<()> {
+        let ret = unsafe { fy_node_set_tag(node.as_ptr(), tag.as_ptr() as *const i8, tag.len()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_set_tag failed"));
+        }
+        Ok(())
+    }
+
+    /// Builds a null scalar node.
+    ///
+    /// Uses `build_from_yaml` internally because libfyaml's
+    /// `fy_node_create_scalar_copy(doc, NULL, 0)` produces an empty scalar
+    /// without the `is_null` flag that the parser sets. Going through the
+    /// parser ensures `fy_node_is_null()` returns true and the emitter
+    /// produces `null` instead of empty string.
+    pub fn build_null(&mut self) -> Result<RawNodeHandle> {
+        self.build_from_yaml("null")
+    }
+
+    // ==================== Path-Based Sequence Operations ====================
+
+    /// Appends a node to a sequence at the given path.
+    ///
+    /// The node handle is consumed and the document takes ownership.
+    pub fn seq_append_at(&mut self, path: &str, mut item: RawNodeHandle) -> Result<()> {
+        self.check_node_unlocked(path)?;
+        let seq_ptr = self.get_node_ptr_at(path)?;
+        let seq_type = unsafe { fy_node_get_type(seq_ptr) };
+        if seq_type != FYNT_SEQUENCE {
+            return Err(Error::TypeMismatch {
+                expected: "sequence",
+                got: "non-sequence",
+            });
+        }
+        let ret = unsafe { fy_node_sequence_append(seq_ptr, item.as_ptr()) };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_node_sequence_append failed"));
+        }
+        // Mark as inserted so Drop doesn't free it
+        item.mark_inserted();
+        Ok(())
+    }
+
+    /// Like [`seq_append_at`](Self::seq_append_at), returning the canonical
+    /// path of the appended item, such as `/items/3`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("items: [a, b]").unwrap();
+    /// let mut ed = doc.edit();
+    /// let item = ed.build_from_yaml("c").unwrap();
+    /// let path = ed.seq_append_at_located("/items", item).unwrap();
+    /// assert_eq!(path, "/items/2");
+    /// assert_eq!(ed.at_path(&path).unwrap().scalar_str().unwrap(), "c");
+    /// ```
+    pub fn seq_append_at_located(&mut self, path: &str, item: RawNodeHandle) -> Result<String> {
+        let node_ptr = item.as_ptr();
+        self.seq_append_at(path, item)?;
+        node_path(node_ptr)
+    }
+
+    /// Inserts a node parsed from `yaml` into the sequence at `path`, so
+    /// that it ends up at `index` and later items shift back by one.
+    ///
+    /// An `index` equal to the length appends. Negative indices count from
+    /// the end of the resulting sequence, as in
+    /// [`move_node`](Self::move_node): `-1` appends, `-2` inserts before the
+    /// last item. The snippet's formatting is preserved as in
+    /// [`set_yaml_at`](Self::set_yaml_at).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist or is not a sequence, if
+    /// `index` is out of range, or if `yaml` fails to parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("steps: [build, deploy]").unwrap();
+    /// {
+    ///     let mut ed = doc.edit();
+    ///     ed.insert_at("/steps", 1, "test").unwrap();
+    ///     ed.insert_at("/steps", 0, "'lint'").unwrap();
+    ///     ed.insert_at("/steps", 4, "notify").unwrap();
+    ///     ed.insert_at("/steps", -1, "cleanup").unwrap();
+    /// }
+    /// let steps: Vec<&str> = doc
+    ///     .at_path("/steps")
+    ///     .unwrap()
+    ///     .seq_iter()
+    ///     .map(|n| n.scalar_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(steps, ["lint", "build", "test", "deploy", "notify", "cleanup"]);
+    /// ```
+    pub fn insert_at(&mut self, path: &str, index: i32, yaml: &str) -> Result<()> {
+        self.check_node_unlocked(path)?;
+        let seq_ptr = self.get_node_ptr_at(path)?;
+        if unsafe { fy_node_get_type(seq_ptr) } != FYNT_SEQUENCE {
+            return Err(Error::TypeMismatch {
+                expected: "sequence",
+                got: "non-sequence",
+            });
+        }
+
+        let count = unsafe { fy_node_sequence_item_count(seq_ptr) };
+        let resolved_index = if index < 0 { count + 1 + index } else { index };
+        if resolved_index < 0 || resolved_index > count {
+            return Err(Error::Ffi("sequence index out of bounds"));
+        }
+
+        let mut new_node = self.build_from_yaml(yaml)?;
+        if resolved_index == count {
+            let ret = unsafe { fy_node_sequence_append(seq_ptr, new_node.as_ptr()) };
+            if ret != 0 {
+                return Err(Error::Ffi("fy_node_sequence_append failed"));
+            }
+        } else {
+            let next_item = unsafe { fy_node_sequence_get_by_index(seq_ptr, resolved_index) };
+            if next_item.is_null() {
+                return Err(Error::Ffi("sequence element not found"));
+            }
+            let ret =
+                unsafe { fy_node_sequence_insert_before(seq_ptr, next_item, new_node.as_ptr()) };
+            if ret != 0 {
+                return Err(Error::Ffi("fy_node_sequence_insert_before failed"));
+            }
+        }
+        new_node.mark_inserted();
+        Ok(())
+    }
+
+    /// Sets the value at `path` to a new sequence built from `items`.
+    ///
+    /// Any existing value at `path` is replaced wholesale; a missing key in
+    /// an existing mapping is created, following the same path rules as
+    /// [`set_yaml_at`](Self::set_yaml_at). Each item is converted with
+    /// `Into<Value>` and emitted the way [`Value`] emits it, so strings that
+    /// would otherwise read as another type are quoted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("name: app\ntags: [old]").unwrap();
+    /// doc.edit().set_sequence_at("/tags", &["a", "b", "c"]).unwrap();
+    /// assert_eq!(doc.at_path("/tags").unwrap().seq_len().unwrap(), 3);
+    /// assert_eq!(doc.at_path("/tags/2").unwrap().scalar_str().unwrap(), "c");
+    /// ```
+    pub fn set_sequence_at<T>(&mut self, path: &str, items: &[T]) -> Result<()>
+    where
+        T: Into<Value> + Clone,
+    {
+        let mut seq = self.build_sequence()?;
+        for item in items {
+            let value: Value = item.clone().into();
+            let node = value.build_node(self)?;
+            self.seq_append(&mut seq, node)?;
+        }
+        self.set_node_at(path, seq)
+    }
+
+    /// Replaces every item of the sequence at `path` for which `matcher`
+    /// returns `true` with a node parsed from `new_yaml`.
+    ///
+    /// Each replacement keeps the position of the item it replaces. Returns
+    /// the number of items replaced; if nothing matches, the document is
+    /// left unchanged. Applying the same call twice is therefore idempotent
+    /// as long as the replacement no longer matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist or is not a sequence, or
+    /// if `new_yaml` fails to parse. The snippet is parsed before any item
+    /// is touched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("servers:\n  - {name: web, port: 80}\n  - {name: db, port: 5432}").unwrap();
+    /// let replaced = doc
+    ///     .edit()
+    ///     .replace_in_sequence_where(
+    ///         "/servers",
+    ///         |item| item.at_path("/name").and_then(|n| n.scalar_str().ok()) == Some("web"),
+    ///         "{name: web, port: 8080}",
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(replaced, 1);
+    /// assert_eq!(doc.at_path("/servers/0/port").unwrap().scalar_str().unwrap(), "8080");
+    /// ```
+    pub fn replace_in_sequence_where<M>(
+        &mut self,
+        path: &str,
+        matcher: M,
+        new_yaml: &str,
+    ) -> Result<usize>
+    where
+        M: Fn(NodeRef<'_>) -> bool,
+    {
+        self.check_unlocked(path)?;
+        let seq_ptr = self.get_node_ptr_at(path)?;
+        let seq_type = unsafe { fy_node_get_type(seq_ptr) };
+        if seq_type != FYNT_SEQUENCE {
+            return Err(Error::TypeMismatch {
+                expected: "sequence",
+                got: "non-sequence",
+            });
+        }
+
+        // Parse up front so a bad snippet fails before anything is modified
+        let mut first = Some(self.build_from_yaml(new_yaml)?);
+
+        let targets: Vec<*mut fy_node> = match self.at_path(path) {
+            Some(seq) => seq
+                .seq_iter()
+                .filter(|item| matcher(*item))
+                .map(|item| item.as_ptr())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        for old_item in &targets {
+            let mut new_node = match first.take() {
+                Some(node) => node,
+                None => self.build_from_yaml(new_yaml)?,
+            };
+            let ret =
+                unsafe { fy_node_sequence_insert_before(seq_ptr, *old_item, new_node.as_ptr()) };
+            if ret != 0 {
+                return Err(Error::Ffi("fy_node_sequence_insert_before failed"));
+            }
+            new_node.mark_inserted();
+
+            let removed = unsafe { fy_node_sequence_remove(seq_ptr, *old_item) };
+            if removed.is_null() {
+                return Err(Error::Ffi("fy_node_sequence_remove failed"));
+            }
+            // Free the detached node to avoid memory leak
+            unsafe { fy_node_free(removed) };
+        }
+        Ok(targets.len())
+    }
+
+    // ==================== Moving Nodes ====================
+
+    /// Moves the node at `from` to `to`.
+    ///
+    /// The node is detached and re-attached, not copied, so it keeps its
+    /// content, styles, tags, anchors and comments. Both paths are
+    /// resolved against the document as it is before the move:
+    ///
+    /// - If the parent of `to` is a mapping, the node becomes the value of
+    ///   that key: an existing value is replaced, a missing key is appended.
+    /// - If the parent of `to` is a sequence, the node is inserted so that
+    ///   it ends up at that index, shifting later items back. An index equal
+    ///   to the length appends; negative indices count from the end of the
+    ///   resulting sequence, as in [`insert_at`](Self::insert_at), so `-1`
+    ///   moves the node last. Moving within one
+    ///   sequence accounts for the removal: `/items/0` to `/items/2` turns
+    ///   `[a, b, c]` into `[b, c, a]`.
+    ///
+    /// Moving a node onto its own path is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOption`] if either path is the root, `to`
+    /// lies inside `from`, or the sequence index in `to` is not a number,
+    /// and [`Error::IndexOutOfBounds`] if that index is out of range. Also
+    /// fails if `from` does not exist, the parent of `to` does not exist or
+    /// is a scalar, or the move would modify a locked subtree. All checks
+    /// run before the node is detached, so the document is unchanged when
+    /// an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("old: {port: 80}\nnew: {}\nsteps: [a, b, c]").unwrap();
+    /// {
+    ///     let mut ed = doc.edit();
+    ///     ed.move_node("/old/port", "/new/port").unwrap();
+    ///     ed.move_node("/steps/0", "/steps/-1").unwrap();
+    /// }
+    /// assert!(doc.at_path("/old/port").is_none());
+    /// assert_eq!(doc.at_path("/new/port").unwrap().scalar_str().unwrap(), "80");
+    /// assert_eq!(doc.at_path("/steps/2").unwrap().scalar_str().unwrap(), "a");
+    /// ```
+    pub fn move_node(&mut self, from: &str, to: &str) -> Result<()> {
+        if to.is_empty() || to == "/" {
+            return Err(Error::InvalidOption("cannot move a node to the root"));
+        }
+        let node_ptr = self.get_node_ptr_at(from)?;
+        let from_slot = slot_of(node_ptr)?;
+        if let Slot::Root = from_slot {
+            return Err(Error::InvalidOption("cannot move the root"));
+        }
+        if self.get_node_ptr_at(to).ok() == Some(node_ptr) {
+            return Ok(());
+        }
+        let (parent_path, key) = split_path(to);
+        let parent_ptr = self.resolve_parent(parent_path)?;
+        if is_within(parent_ptr, node_ptr) {
+            return Err(Error::InvalidOption("cannot move a node inside itself"));
+        }
+        self.check_unlocked(from)?;
+
+        // Validate the target and build the new key before detaching
+        // anything, so that no error can leave the node orphaned
+        let parent_type = unsafe { fy_node_get_type(parent_ptr) };
+        let mut seq_index = None;
+        let mut pair_ptr = ptr::null_mut();
+        let mut new_key = None;
+        if parent_type == FYNT_MAPPING {
+            self.check_unlocked(to)?;
+            pair_ptr = unsafe {
+                fy_node_mapping_lookup_pair_by_string(
+                    parent_ptr,
+                    key.as_ptr() as *const i8,
+                    key.len(),
+                )
+            };
+            if pair_ptr.is_null() {
+                new_key = Some(Value::from(key).build_node(self)?);
+            }
+        } else if parent_type == FYNT_SEQUENCE {
+            if let Some(locked) = self.lock_above(parent_ptr) {
+                return Err(Error::Locked {
+                    path: locked.to_owned(),
+                });
+            }
+            let index: i32 = key
+                .parse()
+                .map_err(|_| Error::InvalidOption("invalid sequence index"))?;
+            // Length once the node is gone, if it comes from this sequence
+            let mut count = unsafe { fy_node_sequence_item_count(parent_ptr) };
+            if unsafe { fy_node_get_parent(node_ptr) } == parent_ptr {
+                count -= 1;
+            }
+            let resolved_index = if index < 0 { count + 1 + index } else { index };
+            if resolved_index < 0 || resolved_index > count {
+                return Err(Error::IndexOutOfBounds {
+                    index: index.unsigned_abs() as usize,
+                    len: count as usize,
+                });
+            }
+            seq_index = Some(resolved_index);
+        } else {
+            return Err(Error::TypeMismatch {
+                expected: "mapping or sequence",
+                got: "scalar",
+            });
+        }
+
+        let mut node = detach(from_slot)?;
+        match seq_index {
+            None => {
+                if let Some(mut key) = new_key {
+                    let ret =
+                        unsafe { fy_node_mapping_append(parent_ptr, key.as_ptr(), node.as_ptr()) };
+                    if ret != 0 {
+                        return Err(Error::Ffi("fy_node_mapping_append failed"));
+                    }
+                    key.mark_inserted();
+                } else {
+                    let ret = unsafe { fy_node_pair_set_value(pair_ptr, node.as_ptr()) };
+                    if ret != 0 {
+                        return Err(Error::Ffi("fy_node_pair_set_value failed"));
+                    }
+                }
+            }
+            Some(index) => {
+                let next_item = unsafe { fy_node_sequence_get_by_index(parent_ptr, index) };
+                if next_item.is_null() {
+                    let ret = unsafe { fy_node_sequence_append(parent_ptr, node.as_ptr()) };
+                    if ret != 0 {
+                        return Err(Error::Ffi("fy_node_sequence_append failed"));
+                    }
+                } else {
+                    let ret = unsafe {
+                        fy_node_sequence_insert_before(parent_ptr, next_item, node.as_ptr())
+                    };
+                    if ret != 0 {
+                        return Err(Error::Ffi("fy_node_sequence_insert_before failed"));
+                    }
+                }
+            }
+        }
+        node.mark_inserted();
+        Ok(())
+    }
+
+    /// Exchanges the nodes at `a` and `b`.
+    ///
+    /// Each node takes the other's place: mapping values stay under their
+    /// keys and sequence items keep their indices. libfyaml cannot detach a
+    /// mapping value without dropping its key, so both nodes are swapped as
+    /// deep copies; content, styles, tags and anchors carry over.
+    ///
+    /// Swapping a node with itself is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either path does not exist, one node contains the
+    /// other (including the root), or either node lies in or contains a
+    /// locked subtree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("primary: db1\nreplicas: [db2, db3]").unwrap();
+    /// doc.edit().swap("/primary", "/replicas/0").unwrap();
+    /// assert_eq!(doc.at_path("/primary").unwrap().scalar_str().unwrap(), "db2");
+    /// assert_eq!(doc.at_path("/replicas/0").unwrap().scalar_str().unwrap(), "db1");
+    /// ```
+    pub fn swap(&mut self, a: &str, b: &str) -> Result<()> {
+        let a_ptr = self.get_node_ptr_at(a)?;
+        let b_ptr = self.get_node_ptr_at(b)?;
+        if a_ptr == b_ptr {
+            return Ok(());
+        }
+        if is_within(a_ptr, b_ptr) || is_within(b_ptr, a_ptr) {
+            return Err(Error::Ffi("cannot swap a node with one that contains it"));
+        }
+        self.check_unlocked(a)?;
+        self.check_unlocked(b)?;
+        let a_slot = slot_of(a_ptr)?;
+        let b_slot = slot_of(b_ptr)?;
+
+        let a_copy = unsafe { fy_node_copy(self.doc_ptr(), a_ptr) };
+        let a_copy = RawNodeHandle::try_from_ptr(a_copy, "fy_node_copy failed")?;
+        let b_copy = unsafe { fy_node_copy(self.doc_ptr(), b_ptr) };
+        let b_copy = RawNodeHandle::try_from_ptr(b_copy, "fy_node_copy failed")?;
+        // Neither node contains the other, so replacing one leaves the
+        // other's slot intact
+        self.replace_slot(a_slot, b_copy)?;
+        self.replace_slot(b_slot, a_copy)
+    }
+
+    // ==================== Bulk Rewrites ====================
+
+    /// Rewrites every plain boolean scalar to a canonical spelling.
+    ///
+    /// YAML 1.1 accepts `yes`/`on`/`True`/... as booleans; this replaces
+    /// each plain, untagged boolean value with `true_form` or `false_form`
+    /// and returns how many scalars changed. Quoted and tagged scalars are
+    /// strings and are left alone, as are mapping keys (rewriting `on:` in
+    /// a CI config would change its meaning). A rewritten scalar keeps its
+    /// anchor and comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if `true_form` or `false_form` is not
+    /// itself a YAML boolean of the right value. Nothing is modified then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("a: yes\nb: Off\nc: 'on'").unwrap();
+    /// let changed = doc.edit().canonicalize_booleans("true", "false").unwrap();
+    /// assert_eq!(changed, 2);
+    /// assert_eq!(doc.at_path("/a").unwrap().scalar_str().unwrap(), "true");
+    /// assert_eq!(doc.at_path("/b").unwrap().scalar_str().unwrap(), "false");
+    /// assert_eq!(doc.at_path("/c").unwrap().scalar_str().unwrap(), "on");
+    /// ```
+    pub fn canonicalize_booleans(&mut self, true_form: &str, false_form: &str) -> Result<usize> {
+        if crate::scalar_parse::parse_bool(true_form) != Some(true)
+            || crate::scalar_parse::parse_bool(false_form) != Some(false)
+        {
+            return Err(Error::TypeMismatch {
+                expected: "YAML boolean",
+                got: "non-boolean canonical form",
+            });
+        }
+
+        // Plain, untagged booleans not already spelled canonically
+        let mut select = |node: NodeRef<'_>| {
+            if node.style() != NodeStyle::Plain || !matches!(node.tag_str(), Ok(None)) {
+                return None;
+            }
+            if self.lock_above(node.as_ptr()).is_some() {
+                return None;
+            }
+            let text = node.scalar_str().ok()?;
+            let b = crate::scalar_parse::parse_bool(text)?;
+            let canonical = if b { true_form } else { false_form };
+            if text == canonical {
+                None
+            } else {
+                Some((node.as_ptr(), b))
+            }
+        };
+        let mut targets = Vec::new();
+        if let Some(root) = self.root() {
+            collect_scalar_slots(root, Slot::Root, &mut select, &mut targets);
+        }
+
+        for &(slot, (old, b)) in &targets {
+            let form = if b { true_form } else { false_form };
+            let mut node = self.build_scalar(form)?;
+            self.carry_scalar_meta(old, &mut node)?;
+            self.replace_slot(slot, node)?;
+        }
+        Ok(targets.len())
     }
 
-    /// Sets the style of a detached node handle.
+    /// Rewrites every scalar value whose content is exactly `from` to `to`.
     ///
-    /// libfyaml validates the requested style against the node content.
-    /// If the style is not valid for this content (e.g., plain for a scalar
-    /// that would be ambiguous), libfyaml may keep the current style.
+    /// A find-and-replace over the whole document, e.g. turning every
+    /// `staging` into `production`. Each replaced scalar keeps its style,
+    /// tag, anchor and comments: a quoted `'staging'` becomes
+    /// `'production'`. Returns the number of scalars changed.
     ///
-    /// Returns the style that was actually set.
-    pub fn set_style(
-        &mut self,
-        node: &mut RawNodeHandle,
-        style: crate::node::NodeStyle,
-    ) -> crate::node::NodeStyle {
-        let raw_style = match style {
-            crate::node::NodeStyle::Any => FYNS_ANY,
-            crate::node::NodeStyle::Flow => FYNS_FLOW,
-            crate::node::NodeStyle::Block => FYNS_BLOCK,
-            crate::node::NodeStyle::Plain => FYNS_PLAIN,
-            crate::node::NodeStyle::SingleQuoted => FYNS_SINGLE_QUOTED,
-            crate::node::NodeStyle::DoubleQuoted => FYNS_DOUBLE_QUOTED,
-            crate::node::NodeStyle::Literal => FYNS_LITERAL,
-            crate::node::NodeStyle::Folded => FYNS_FOLDED,
-            crate::node::NodeStyle::Alias => FYNS_ALIAS,
+    /// Only values are rewritten, in mappings and sequences at any depth;
+    /// mapping keys are never touched, so a key named `staging` stays.
+    /// Matching is on the raw content, so `'1'` and `1` both match `"1"`.
+    /// Note that a plain result is re-read with the usual type inference:
+    /// replacing with `"123"` in a plain scalar yields a number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let mut doc = Document::parse_str("env: staging\nhosts: [staging, qa]").unwrap();
+    /// let n = doc.edit().replace_scalar_value("staging", "production").unwrap();
+    /// assert_eq!(n, 2);
+    /// assert_eq!(doc.at_path("/hosts/0").unwrap().scalar_str().unwrap(), "production");
+    /// ```
+    pub fn replace_scalar_value(&mut self, from: &str, to: &str) -> Result<usize> {
+        let mut select = |node: NodeRef<'_>| {
+            if node.style() == NodeStyle::Alias || node.scalar_str().ok()? != from {
+                return None;
+            }
+            if self.lock_above(node.as_ptr()).is_some() {
+                return None;
+            }
+            let tag = node.tag_str().ok()?.map(str::to_owned);
+            Some((node.as_ptr(), node.style(), tag))
         };
-        let result = unsafe { fy_node_set_style(node.as_ptr(), raw_style) };
-        crate::node::NodeStyle::from(result)
-    }
+        let mut targets = Vec::new();
+        if let Some(root) = self.root() {
+            collect_scalar_slots(root, Slot::Root, &mut select, &mut targets);
+        }
 
-    /// Sets a YAML tag on a detached node handle.
-    ///
-    /// For example, `set_tag(&mut node, "!custom")` produces `!custom value`.
-    pub fn set_tag(&mut self, node: &mut RawNodeHandle, tag: &str) -> Result<()> {
-        let ret = unsafe { fy_node_set_tag(node.as_ptr(), tag.as_ptr() as *const i8, tag.len()) };
-        if ret != 0 {
-            return Err(Error::Ffi("fy_node_set_tag failed"));
+        for (slot, (old, style, tag)) in targets.iter() {
+            let mut node = self.build_scalar(to)?;
+            self.set_style(&mut node, *style);
+            if let Some(tag) = tag {
+                self.set_tag(&mut node, tag)?;
+            }
+            self.carry_scalar_meta(*old, &mut node)?;
+            self.replace_slot(*slot, node)?;
         }
-        Ok(())
+        Ok(targets.len())
     }
 
-    /// Builds a null scalar node.
-    ///
-    /// Uses `build_from_yaml` internally because libfyaml's
-    /// `fy_node_create_scalar_copy(doc, NULL, 0)` produces an empty scalar
-    /// without the `is_null` flag that the parser sets. Going through the
-    /// parser ensures `fy_node_is_null()` returns true and the emitter
-    /// produces `null` instead of empty string.
-    pub fn build_null(&mut self) -> Result<RawNodeHandle> {
-        self.build_from_yaml("null")
+    // ==================== Internal Helpers ====================
+
+    /// Puts `new_node` in place of the node at `slot`, freeing the old one.
+    fn replace_slot(&mut self, slot: Slot, mut new_node: RawNodeHandle) -> Result<()> {
+        match slot {
+            Slot::Root => return self.set_root(new_node),
+            Slot::PairValue(pair_ptr) => {
+                let ret = unsafe { fy_node_pair_set_value(pair_ptr, new_node.as_ptr()) };
+                if ret != 0 {
+                    return Err(Error::Ffi("fy_node_pair_set_value failed"));
+                }
+            }
+            Slot::SeqItem { seq, item } => {
+                let ret = unsafe { fy_node_sequence_insert_before(seq, item, new_node.as_ptr()) };
+                if ret != 0 {
+                    return Err(Error::Ffi("fy_node_sequence_insert_before failed"));
+                }
+                new_node.mark_inserted();
+                let removed = unsafe { fy_node_sequence_remove(seq, item) };
+                if removed.is_null() {
+                    return Err(Error::Ffi("fy_node_sequence_remove failed"));
+                }
+                unsafe { fy_node_free(removed) };
+                return Ok(());
+            }
+        }
+        new_node.mark_inserted();
+        Ok(())
     }
 
-    // ==================== Path-Based Sequence Operations ====================
+    /// Copies the anchor and comments of the scalar `old` onto `new_node`,
+    /// so that aliases to a rewritten scalar still resolve.
+    fn carry_scalar_meta(&mut self, old: *mut fy_node, new_node: &mut RawNodeHandle) -> Result<()> {
+        let old_token = unsafe { fy_node_get_scalar_token(old) };
+        let new_token = unsafe { fy_node_get_scalar_token(new_node.as_ptr()) };
+        for which in [fycp_top, fycp_right, fycp_bottom] {
+            let text = unsafe { fy_token_get_comment(old_token, which) };
+            if text.is_null() {
+                continue;
+            }
+            // SAFETY: the comment text is NUL-terminated and owned by `old_token`
+            let len = unsafe { CStr::from_ptr(text) }.to_bytes().len();
+            if unsafe { fy_token_set_comment(new_token, which, text, len) } != 0 {
+                return Err(Error::Ffi("fy_token_set_comment failed"));
+            }
+        }
 
-    /// Appends a node to a sequence at the given path.
-    ///
-    /// The node handle is consumed and the document takes ownership.
-    pub fn seq_append_at(&mut self, path: &str, mut item: RawNodeHandle) -> Result<()> {
-        let seq_ptr = self.get_node_ptr_at(path)?;
-        let seq_type = unsafe { fy_node_get_type(seq_ptr) };
-        if seq_type != FYNT_SEQUENCE {
-            return Err(Error::TypeMismatch {
-                expected: "sequence",
-                got: "non-sequence",
-            });
+        let anchor = unsafe { fy_node_get_anchor(old) };
+        if anchor.is_null() {
+            return Ok(());
         }
-        let ret = unsafe { fy_node_sequence_append(seq_ptr, item.as_ptr()) };
+        let mut len: size_t = 0;
+        let text = unsafe { fy_anchor_get_text(anchor, &mut len) };
+        if text.is_null() {
+            return Err(Error::Ffi("fy_anchor_get_text failed"));
+        }
+        // Both nodes hold the name until `old` is freed by the replacement
+        let ret = unsafe { fy_node_set_anchor_copy(new_node.as_ptr(), text, len) };
         if ret != 0 {
-            return Err(Error::Ffi("fy_node_sequence_append failed"));
+            return Err(Error::Ffi("fy_node_set_anchor_copy failed"));
         }
-        // Mark as inserted so Drop doesn't free it
-        item.mark_inserted();
         Ok(())
     }
 
-    // ==================== Internal Helpers ====================
-
     fn get_node_ptr_at(&self, path: &str) -> Result<*mut fy_node> {
         let root_ptr = unsafe { fy_document_root(self.doc_ptr()) };
         if root_ptr.is_null() {
@@ -720,7 +2105,7 @@ impl<'doc> Editor<'doc> {
         let node_ptr =
             unsafe { fy_node_by_path(root_ptr, path.as_ptr() as *const i8, path.len(), 0) };
         if node_ptr.is_null() {
-            return Err(Error::Ffi("path not found"));
+            return Err(Error::PathNotFound(path.to_owned()));
         }
         Ok(node_ptr)
     }
@@ -728,7 +2113,7 @@ impl<'doc> Editor<'doc> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Document;
+    use crate::{Document, NodeRef};
 
     #[test]
     fn test_set_yaml_at_replace() {
@@ -980,6 +2365,270 @@ mod tests {
         assert_eq!(root.scalar_str().unwrap(), "42");
     }
 
+    #[test]
+    fn test_set_yaml_at_keeping_tag() {
+        let mut doc = Document::parse_str("user: admin\npassword: !secret abc").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.set_yaml_at_keeping_tag("/password", "s3cr3t").unwrap();
+        }
+        let node = doc.at_path("/password").unwrap();
+        assert_eq!(node.tag_str().unwrap(), Some("!secret"));
+        assert_eq!(node.scalar_str().unwrap(), "s3cr3t");
+        assert!(doc.emit().unwrap().contains("!secret s3cr3t"));
+    }
+
+    #[test]
+    fn test_set_yaml_at_keeping_tag_snippet_tag_wins() {
+        let mut doc = Document::parse_str("password: !secret abc").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.set_yaml_at_keeping_tag("/password", "!vault xyz")
+                .unwrap();
+        }
+        let node = doc.at_path("/password").unwrap();
+        assert_eq!(node.tag_str().unwrap(), Some("!vault"));
+        assert_eq!(node.scalar_str().unwrap(), "xyz");
+    }
+
+    #[test]
+    fn test_set_yaml_at_keeping_tag_untagged_and_new() {
+        let mut doc = Document::parse_str("name: Alice").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.set_yaml_at_keeping_tag("/name", "Bob").unwrap();
+            ed.set_yaml_at_keeping_tag("/age", "30").unwrap();
+        }
+        assert_eq!(doc.at_path("/name").unwrap().tag_str().unwrap(), None);
+        assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "Bob");
+        assert_eq!(doc.at_path("/age").unwrap().scalar_str().unwrap(), "30");
+    }
+
+    #[test]
+    fn test_replace_value_at_keeps_position() {
+        let mut doc = Document::parse_str("first: 1\nmiddle: 2\nlast: 3").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.replace_value_at("/middle", "{nested: true}").unwrap();
+        }
+        let keys: Vec<&str> = doc
+            .root()
+            .unwrap()
+            .map_iter()
+            .map(|(k, _)| k.scalar_str().unwrap())
+            .collect();
+        assert_eq!(keys, ["first", "middle", "last"]);
+        assert!(doc.at_path("/middle").unwrap().is_mapping());
+    }
+
+    #[test]
+    fn test_replace_value_at_missing_path() {
+        let mut doc = Document::parse_str("a: 1").unwrap();
+        {
+            let mut ed = doc.edit();
+            assert!(ed.replace_value_at("/b", "2").is_err());
+        }
+        assert!(doc.at_path("/b").is_none());
+    }
+
+    #[test]
+    fn test_replace_in_sequence_where() {
+        let yaml = "servers:\n  - name: web\n    port: 80\n  - name: db\n    port: 5432\n  - name: web\n    port: 81\n";
+        let mut doc = Document::parse_str(yaml).unwrap();
+        let is_web = |item: NodeRef<'_>| {
+            item.at_path("/name").and_then(|n| n.scalar_str().ok()) == Some("web")
+        };
+        {
+            let mut ed = doc.edit();
+            let count = ed
+                .replace_in_sequence_where("/servers", is_web, "{name: web, port: 8080}")
+                .unwrap();
+            assert_eq!(count, 2);
+        }
+        let servers = doc.at_path("/servers").unwrap();
+        assert_eq!(servers.seq_len().unwrap(), 3);
+        assert_eq!(
+            doc.at_path("/servers/0/port")
+                .unwrap()
+                .scalar_str()
+                .unwrap(),
+            "8080"
+        );
+        assert_eq!(
+            doc.at_path("/servers/1/name")
+                .unwrap()
+                .scalar_str()
+                .unwrap(),
+            "db"
+        );
+        assert_eq!(
+            doc.at_path("/servers/2/port")
+                .unwrap()
+                .scalar_str()
+                .unwrap(),
+            "8080"
+        );
+    }
+
+    #[test]
+    fn test_replace_in_sequence_where_errors() {
+        let mut doc = Document::parse_str("items: [a, b]\nmap: {k: v}").unwrap();
+        {
+            let mut ed = doc.edit();
+            assert_eq!(
+                ed.replace_in_sequence_where("/items", |_| false, "c")
+                    .unwrap(),
+                0
+            );
+            assert!(ed.replace_in_sequence_where("/map", |_| true, "c").is_err());
+            assert!(ed
+                .replace_in_sequence_where("/missing", |_| true, "c")
+                .is_err());
+            assert!(ed
+                .replace_in_sequence_where("/items", |_| true, "[bad")
+                .is_err());
+        }
+        assert_eq!(doc.at_path("/items/0").unwrap().scalar_str().unwrap(), "a");
+    }
+
+    #[test]
+    fn test_set_sequence_at_replaces_existing() {
+        let mut doc = Document::parse_str("name: app\ntags: [x]\nport: 80").unwrap();
+        doc.edit()
+            .set_sequence_at("/tags", &["a", "b", "c"])
+            .unwrap();
+
+        let tags: Vec<&str> = doc
+            .at_path("/tags")
+            .unwrap()
+            .seq_iter()
+            .map(|n| n.scalar_str().unwrap())
+            .collect();
+        assert_eq!(tags, ["a", "b", "c"]);
+        assert_eq!(doc.at_path("/port").unwrap().scalar_str().unwrap(), "80");
+    }
+
+    #[test]
+    fn test_set_sequence_at_creates_key() {
+        let mut doc = Document::parse_str("name: app").unwrap();
+        doc.edit().set_sequence_at("/ports", &[80i64, 443]).unwrap();
+        doc.edit().set_sequence_at::<&str>("/empty", &[]).unwrap();
+
+        let ports = doc.at_path("/ports").unwrap();
+        assert!(ports.is_sequence());
+        assert_eq!(ports.seq_get(1).unwrap().scalar_str().unwrap(), "443");
+        assert_eq!(doc.at_path("/empty").unwrap().seq_len().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_set_sequence_at_quotes_ambiguous_strings() {
+        let mut doc = Document::parse_str("a: 1").unwrap();
+        doc.edit()
+            .set_sequence_at("/flags", &["true", "plain"])
+            .unwrap();
+        assert!(doc.at_path("/flags/0").unwrap().is_quoted());
+        assert!(!doc.at_path("/flags/1").unwrap().is_quoted());
+    }
+
+    #[test]
+    fn test_set_comment_before_at() {
+        let mut doc = Document::parse_str("name: app\nport: 80\nhost: x\n").unwrap();
+        doc.edit()
+            .set_comment_before_at("/port", &["Listening port", "change with care"])
+            .unwrap();
+
+        let yaml = doc.emit().unwrap();
+        assert!(
+            yaml.contains("name: app\n# Listening port\n# change with care\nport: 80\nhost: x"),
+            "got:\n{}",
+            yaml
+        );
+        assert_eq!(doc.at_path("/port").unwrap().scalar_str().unwrap(), "80");
+    }
+
+    #[test]
+    fn test_set_comment_before_at_nested_quoted_key_replaces_comment() {
+        let mut doc = Document::parse_str("db:\n  # old\n  'the key': 1\n").unwrap();
+        doc.edit()
+            .set_comment_before_at("/db/the key", &["new"])
+            .unwrap();
+
+        let yaml = doc.emit().unwrap();
+        assert!(yaml.contains("# new"), "got:\n{}", yaml);
+        assert!(!yaml.contains("# old"), "got:\n{}", yaml);
+        assert!(yaml.contains("'the key': 1"), "got:\n{}", yaml);
+    }
+
+    #[test]
+    fn test_set_comment_before_at_errors() {
+        let mut doc = Document::parse_str("list: [a, b]\n").unwrap();
+        let mut ed = doc.edit();
+        assert!(ed.set_comment_before_at("", &["x"]).is_err());
+        assert!(ed.set_comment_before_at("/missing", &["x"]).is_err());
+        assert!(ed.set_comment_before_at("/list/0", &["x"]).is_err());
+    }
+
+    #[test]
+    fn test_set_comment_before_at_requires_kept_comments() {
+        let opts = crate::ParseOptions {
+            parse_comments: false,
+            ..crate::ParseOptions::default()
+        };
+        let mut doc = Document::parse_str_with("port: 80\n", &opts).unwrap();
+        assert_eq!(
+            doc.edit().set_comment_before_at("/port", &["x"]),
+            Err(crate::Error::InvalidOption(
+                "document does not keep comments"
+            ))
+        );
+        assert!(!doc.emit().unwrap().contains('#'));
+    }
+
+    #[test]
+    fn test_unwrap_and_wrap_root() {
+        let yaml = "app:\n  name: web\n  ports: [80, 443]\n";
+        let mut doc = Document::parse_str(yaml).unwrap();
+        let inner_before = crate::Value::from_node_ref(doc.at_path("/app").unwrap()).unwrap();
+
+        doc.edit().unwrap_root_key("app").unwrap();
+        assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "web");
+        assert_eq!(
+            doc.at_path("/ports/1").unwrap().scalar_str().unwrap(),
+            "443"
+        );
+        assert!(doc.at_path("/app").is_none());
+        let unwrapped = crate::Value::from_node_ref(doc.root().unwrap()).unwrap();
+        assert_eq!(unwrapped, inner_before);
+
+        doc.edit().wrap_root("app").unwrap();
+        assert!(doc.structurally_equal_to_str(yaml).unwrap());
+    }
+
+    #[test]
+    fn test_unwrap_root_key_errors() {
+        let mut doc = Document::parse_str("a: 1\nb: 2").unwrap();
+        assert!(doc.edit().unwrap_root_key("a").is_err());
+        assert_eq!(doc.at_path("/b").unwrap().scalar_str().unwrap(), "2");
+
+        let mut doc = Document::parse_str("a: 1").unwrap();
+        assert!(doc.edit().unwrap_root_key("b").is_err());
+
+        let mut doc = Document::parse_str("[a]").unwrap();
+        assert!(doc.edit().unwrap_root_key("a").is_err());
+
+        let mut doc = Document::new().unwrap();
+        assert!(doc.edit().wrap_root("a").is_err());
+    }
+
+    #[test]
+    fn test_wrap_root_quotes_ambiguous_key() {
+        let mut doc = Document::parse_str("x: 1").unwrap();
+        doc.edit().wrap_root("true").unwrap();
+        let (key, _) = doc.root().unwrap().map_iter().next().unwrap();
+        assert!(key.is_quoted());
+        assert_eq!(doc.at_path("/true/x").unwrap().scalar_str().unwrap(), "1");
+    }
+
     #[test]
     fn test_build_null() {
         // Note: build_null() creates a zero-length scalar via NULL ptr.
@@ -996,4 +2645,288 @@ mod tests {
         let emitted = root.emit().unwrap();
         assert!(emitted.is_empty() || emitted == "null");
     }
+
+    #[test]
+    fn test_canonicalize_booleans() {
+        let mut doc = Document::parse_str(
+            "a: yes\nb: True\nc: off\nd: 'yes'\ne: [On, no, true]\nf: !!str yes\ng: 1\n",
+        )
+        .unwrap();
+        let changed = doc.edit().canonicalize_booleans("true", "false").unwrap();
+        assert_eq!(changed, 5);
+
+        let text = |path: &str| doc.at_path(path).unwrap().scalar_str().unwrap();
+        assert_eq!(text("/a"), "true");
+        assert_eq!(text("/b"), "true");
+        assert_eq!(text("/c"), "false");
+        assert_eq!(text("/e/0"), "true");
+        assert_eq!(text("/e/1"), "false");
+        assert_eq!(text("/e/2"), "true");
+        assert_eq!(text("/g"), "1");
+        // Quoted and tagged strings are untouched
+        assert_eq!(text("/d"), "yes");
+        assert!(doc.at_path("/d").unwrap().is_quoted());
+        assert_eq!(text("/f"), "yes");
+        assert!(doc.emit().unwrap().contains("d: 'yes'"));
+
+        // Already canonical: nothing to do
+        assert_eq!(
+            doc.edit().canonicalize_booleans("true", "false").unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_booleans_keeps_keys_and_rejects_bad_forms() {
+        let mut doc = Document::parse_str("on: [push]\nenabled: Yes").unwrap();
+        assert_eq!(doc.edit().canonicalize_booleans("yes", "no").unwrap(), 1);
+        assert_eq!(
+            doc.at_path("/enabled").unwrap().scalar_str().unwrap(),
+            "yes"
+        );
+        assert!(doc.at_path("/on").is_some());
+
+        assert!(doc.edit().canonicalize_booleans("1", "0").is_err());
+        assert!(doc.edit().canonicalize_booleans("false", "true").is_err());
+    }
+
+    #[test]
+    fn test_canonicalize_booleans_keeps_anchor_and_comment() {
+        let mut doc = Document::parse_str("a: &flag yes # toggled\nb: *flag\n").unwrap();
+        let changed = doc.edit().canonicalize_booleans("true", "false").unwrap();
+        assert_eq!(changed, 1);
+
+        let yaml = doc.emit().unwrap();
+        assert!(yaml.contains("a: &flag true"), "{}", yaml);
+        assert!(yaml.contains("# toggled"), "{}", yaml);
+        assert!(yaml.contains("b: *flag"), "{}", yaml);
+        assert_eq!(doc.at_path("/a").unwrap().anchor(), Some("flag"));
+    }
+
+    #[test]
+    fn test_set_block_scalar_at_combinations() {
+        use crate::{BlockKind, Chomp};
+
+        let cases = [
+            (
+                BlockKind::Literal,
+                Chomp::Clip,
+                "one\ntwo",
+                "|\n",
+                "one\ntwo\n",
+            ),
+            (
+                BlockKind::Literal,
+                Chomp::Strip,
+                "one\ntwo\n",
+                "|-\n",
+                "one\ntwo",
+            ),
+            (
+                BlockKind::Literal,
+                Chomp::Keep,
+                "one\ntwo\n\n",
+                "|+\n",
+                "one\ntwo\n\n",
+            ),
+            (
+                BlockKind::Folded,
+                Chomp::Clip,
+                "long text",
+                ">\n",
+                "long text\n",
+            ),
+            (
+                BlockKind::Folded,
+                Chomp::Strip,
+                "long text\n",
+                ">-\n",
+                "long text",
+            ),
+            (
+                BlockKind::Folded,
+                Chomp::Keep,
+                "long text\n\n",
+                ">+\n",
+                "long text\n\n",
+            ),
+        ];
+        for (kind, chomp, content, header, expected) in cases {
+            let mut doc = Document::parse_str("name: app\ntext: x\n").unwrap();
+            doc.edit()
+                .set_block_scalar_at("/text", content, kind, chomp)
+                .unwrap();
+            let yaml = doc.emit().unwrap();
+            assert!(
+                yaml.contains(&format!("text: {}", header)),
+                "{:?}/{:?} emitted:\n{}",
+                kind,
+                chomp,
+                yaml
+            );
+
+            let reparsed = Document::parse_str(&yaml).unwrap();
+            let value = reparsed.at_path("/text").unwrap();
+            assert_eq!(
+                value.scalar_str().unwrap(),
+                expected,
+                "{:?}/{:?}",
+                kind,
+                chomp
+            );
+            let expected_style = match kind {
+                BlockKind::Literal => crate::NodeStyle::Literal,
+                BlockKind::Folded => crate::NodeStyle::Folded,
+            };
+            assert_eq!(value.style(), expected_style);
+        }
+    }
+
+    #[test]
+    fn test_set_block_scalar_at_folded_keeps_line_breaks() {
+        use crate::{BlockKind, Chomp};
+
+        let mut doc = Document::parse_str("text: x").unwrap();
+        doc.edit()
+            .set_block_scalar_at("/text", "first\nsecond\n", BlockKind::Folded, Chomp::Clip)
+            .unwrap();
+        let yaml = doc.emit().unwrap();
+        let reparsed = Document::parse_str(&yaml).unwrap();
+        assert_eq!(
+            reparsed.at_path("/text").unwrap().scalar_str().unwrap(),
+            "first\nsecond\n",
+            "emitted:\n{}",
+            yaml
+        );
+    }
+
+    #[test]
+    fn test_replace_scalar_value() {
+        let mut doc = Document::parse_str(
+            "env: staging\nstaging: keep-key\nquoted: 'staging'\nnot: staging-2\ndeploy:\n  targets: [dev, staging]\n  stages:\n    - name: staging\n      tag: !env staging\n",
+        )
+        .unwrap();
+        let count = doc
+            .edit()
+            .replace_scalar_value("staging", "production")
+            .unwrap();
+        assert_eq!(count, 5);
+
+        let text = |path: &str| doc.at_path(path).unwrap().scalar_str().unwrap();
+        assert_eq!(text("/env"), "production");
+        assert_eq!(text("/quoted"), "production");
+        assert_eq!(text("/not"), "staging-2");
+        assert_eq!(text("/deploy/targets/1"), "production");
+        assert_eq!(text("/deploy/stages/0/name"), "production");
+        assert_eq!(text("/deploy/stages/0/tag"), "production");
+
+        // Style and tag preserved, keys untouched
+        assert!(doc.at_path("/quoted").unwrap().is_quoted());
+        let tag = doc
+            .at_path("/deploy/stages/0/tag")
+            .unwrap()
+            .tag_str()
+            .unwrap();
+        assert_eq!(tag, Some("!env"));
+        assert_eq!(text("/staging"), "keep-key");
+        assert!(doc.emit().unwrap().contains("quoted: 'production'"));
+    }
+
+    #[test]
+    fn test_replace_scalar_value_no_match() {
+        let mut doc = Document::parse_str("a: b").unwrap();
+        assert_eq!(doc.edit().replace_scalar_value("zzz", "y").unwrap(), 0);
+        assert_eq!(doc.emit().unwrap(), "a: b\n");
+    }
+
+    #[test]
+    fn test_replace_scalar_value_keeps_anchor_and_comment() {
+        let mut doc =
+            Document::parse_str("env: &env staging # target\nhosts: [*env, qa]\n").unwrap();
+        let count = doc
+            .edit()
+            .replace_scalar_value("staging", "production")
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let yaml = doc.emit().unwrap();
+        assert!(yaml.contains("env: &env production"), "{}", yaml);
+        assert!(yaml.contains("# target"), "{}", yaml);
+        assert!(yaml.contains("*env"), "{}", yaml);
+        assert_eq!(doc.at_path("/env").unwrap().anchor(), Some("env"));
+    }
+
+    #[test]
+    fn test_register_tag_directive_and_shorthand() {
+        let mut doc = Document::parse_str("item: widget\nother: x\n").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.register_tag_directive("!e!", "tag:example.com,2000:")
+                .unwrap();
+            ed.set_tag_at("/item", "!e!foo").unwrap();
+        }
+        let yaml = doc.emit().unwrap();
+        assert!(
+            yaml.contains("%TAG !e! tag:example.com,2000:"),
+            "emitted:\n{}",
+            yaml
+        );
+        assert!(yaml.contains("item: !e!foo widget"), "emitted:\n{}", yaml);
+
+        // The emitted text is self-contained and reads back with the tag
+        let reparsed = Document::parse_str(&yaml).unwrap();
+        let item = reparsed.at_path("/item").unwrap();
+        assert_eq!(item.scalar_str().unwrap(), "widget");
+        assert!(item.tag_str().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_set_tag_at_unknown_handle_fails() {
+        let mut doc = Document::parse_str("item: widget").unwrap();
+        assert!(doc.edit().set_tag_at("/item", "!nope!foo").is_err());
+        assert!(doc.edit().set_tag_at("/missing", "!local").is_err());
+    }
+
+    #[test]
+    fn test_lock_subtree_refuses_edits_inside() {
+        let mut doc = Document::parse_str("a:\n  b: 1\n  list: [x]\nc: 2\n").unwrap();
+        {
+            let mut ed = doc.edit();
+            ed.lock_subtree("/a").unwrap();
+            let locked = crate::Error::Locked { path: "/a".into() };
+
+            assert_eq!(ed.set_yaml_at("/a/b", "9").unwrap_err(), locked);
+            assert_eq!(ed.set_yaml_at("/a/new", "9").unwrap_err(), locked);
+            assert_eq!(ed.set_yaml_at("/a", "{}").unwrap_err(), locked);
+            assert_eq!(ed.delete_at("/a/b").unwrap_err(), locked);
+            assert_eq!(ed.delete_at("/a").unwrap_err(), locked);
+            let item = ed.build_scalar("y").unwrap();
+            assert_eq!(ed.seq_append_at("/a/list", item).unwrap_err(), locked);
+            assert_eq!(ed.set_yaml_at("", "replaced").unwrap_err(), locked);
+            assert_eq!(ed.wrap_root("top").unwrap_err(), locked);
+
+            // Outside the lock, edits still work
+            ed.set_yaml_at("/c", "3").unwrap();
+            ed.set_yaml_at("/d", "4").unwrap();
+            assert_eq!(ed.replace_scalar_value("1", "one").unwrap(), 0);
+        }
+        assert_eq!(doc.at_path("/a/b").unwrap().scalar_str().unwrap(), "1");
+        assert_eq!(doc.at_path("/c").unwrap().scalar_str().unwrap(), "3");
+        assert_eq!(doc.at_path("/d").unwrap().scalar_str().unwrap(), "4");
+
+        // Locks end with the editor
+        doc.edit().set_yaml_at("/a/b", "9").unwrap();
+    }
+
+    #[test]
+    fn test_lock_subtree_follows_node() {
+        let mut doc = Document::parse_str("items: [first, {keep: me}, last]").unwrap();
+        let mut ed = doc.edit();
+        ed.lock_subtree("/items/1").unwrap();
+        assert!(ed.lock_subtree("/missing").is_err());
+        assert!(ed.delete_at("/items/0").unwrap());
+        // The locked mapping is now at index 0
+        assert!(ed.set_yaml_at("/items/0/keep", "x").is_err());
+        ed.set_yaml_at("/items/1", "changed").unwrap();
+    }
 }
@@ -0,0 +1,137 @@
+//! Input encoding detection.
+//!
+//! libfyaml only reads UTF-8. YAML streams may also be UTF-16 or UTF-32,
+//! marked by a byte order mark or, per YAML 1.2 section 5.2, recognizable
+//! from the null bytes around a leading ASCII character.
+
+use crate::error::{Error, Result};
+
+/// Encoding detected from the first bytes of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+/// Detects the encoding of `bytes`, returning it with the BOM length.
+fn detect(bytes: &[u8]) -> (Encoding, usize) {
+    match bytes {
+        [0x00, 0x00, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, 4),
+        [0xFF, 0xFE, 0x00, 0x00, ..] => (Encoding::Utf32Le, 4),
+        [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+        [0x00, 0x00, 0x00, _, ..] => (Encoding::Utf32Be, 0),
+        [_, 0x00, 0x00, 0x00, ..] => (Encoding::Utf32Le, 0),
+        [0x00, _, ..] => (Encoding::Utf16Be, 0),
+        [_, 0x00, ..] => (Encoding::Utf16Le, 0),
+        _ => (Encoding::Utf8, 0),
+    }
+}
+
+/// Converts `bytes` to UTF-8, stripping any byte order mark.
+///
+/// UTF-8 input is returned without copying (apart from dropping a BOM).
+/// UTF-16 input is transcoded. UTF-32 is rejected with
+/// [`Error::UnsupportedEncoding`], as is malformed UTF-16.
+pub(crate) fn to_utf8(mut bytes: Vec<u8>) -> Result<Vec<u8>> {
+    let (encoding, bom_len) = detect(&bytes);
+    let units = match encoding {
+        Encoding::Utf8 => {
+            bytes.drain(..bom_len);
+            return Ok(bytes);
+        }
+        Encoding::Utf32Le | Encoding::Utf32Be => {
+            return Err(Error::UnsupportedEncoding("UTF-32"));
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let body = &bytes[bom_len..];
+            if body.len() % 2 != 0 {
+                return Err(Error::UnsupportedEncoding("truncated UTF-16"));
+            }
+            body.chunks_exact(2).map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if encoding == Encoding::Utf16Le {
+                    u16::from_le_bytes(pair)
+                } else {
+                    u16::from_be_bytes(pair)
+                }
+            })
+        }
+    };
+
+    let mut out = String::with_capacity(bytes.len());
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => out.push(c),
+            Err(_) => return Err(Error::UnsupportedEncoding("malformed UTF-16")),
+        }
+    }
+    Ok(out.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(s: &str, bom: bool) -> Vec<u8> {
+        let mut out = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        out.extend(s.encode_utf16().flat_map(u16::to_le_bytes));
+        out
+    }
+
+    fn utf16be(s: &str, bom: bool) -> Vec<u8> {
+        let mut out = if bom { vec![0xFE, 0xFF] } else { Vec::new() };
+        out.extend(s.encode_utf16().flat_map(u16::to_be_bytes));
+        out
+    }
+
+    #[test]
+    fn test_utf8_passthrough() {
+        assert_eq!(to_utf8(b"a: 1".to_vec()).unwrap(), b"a: 1");
+        assert_eq!(to_utf8(b"\xEF\xBB\xBFa: 1".to_vec()).unwrap(), b"a: 1");
+        assert_eq!(to_utf8(Vec::new()).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_utf16_transcoded() {
+        let text = "k: \u{e9}t\u{e9} \u{1F600}";
+        for bytes in [
+            utf16le(text, true),
+            utf16le(text, false),
+            utf16be(text, true),
+            utf16be(text, false),
+        ] {
+            assert_eq!(to_utf8(bytes).unwrap(), text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_utf16_malformed() {
+        // Lone high surrogate
+        let bytes = vec![0xFF, 0xFE, b'a', 0x00, 0x00, 0xD8];
+        assert_eq!(
+            to_utf8(bytes),
+            Err(Error::UnsupportedEncoding("malformed UTF-16"))
+        );
+        let odd = vec![0xFF, 0xFE, b'a', 0x00, b'b'];
+        assert_eq!(
+            to_utf8(odd),
+            Err(Error::UnsupportedEncoding("truncated UTF-16"))
+        );
+    }
+
+    #[test]
+    fn test_utf32_rejected() {
+        for bytes in [
+            vec![0x00, 0x00, 0xFE, 0xFF, 0, 0, 0, b'a'],
+            vec![0xFF, 0xFE, 0x00, 0x00, b'a', 0, 0, 0],
+            vec![b'a', 0, 0, 0],
+        ] {
+            assert_eq!(to_utf8(bytes), Err(Error::UnsupportedEncoding("UTF-32")));
+        }
+    }
+}
@@ -110,6 +110,14 @@ pub enum Error {
     /// I/O operation failed.
     Io(&'static str),
 
+    /// A `std::io` reader or writer returned an error.
+    ///
+    /// Keeps the error's kind and message so `Error` stays `Clone + Eq`.
+    IoError {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+
     /// Memory allocation failed.
     Alloc(&'static str),
 
@@ -130,6 +138,78 @@ pub enum Error {
 
     /// Scalar length exceeds sanity limit.
     ScalarTooLarge(usize),
+
+    /// A JSON pointer (RFC 6901) was malformed.
+    InvalidPointer(&'static str),
+
+    /// Sequence index is past the end of the sequence.
+    IndexOutOfBounds { index: usize, len: usize },
+
+    /// Deserializing a Rust type from a [`Value`](crate::value::Value) failed.
+    ///
+    /// `path` locates the offending node (e.g. `/database/port`); it is empty
+    /// when the error concerns the root.
+    Deserialize { path: String, message: String },
+
+    /// Input is in an encoding libfyaml cannot read (e.g. UTF-32), or is
+    /// malformed for its detected encoding.
+    UnsupportedEncoding(&'static str),
+
+    /// An options struct holds a value that cannot be honored.
+    InvalidOption(&'static str),
+
+    /// An edit would modify a subtree locked with
+    /// [`Editor::lock_subtree`](crate::Editor::lock_subtree).
+    Locked { path: String },
+
+    /// Nothing exists at the given JSON pointer.
+    PathNotFound(String),
+
+    /// The value at a JSON pointer has the wrong type.
+    TypeMismatchAt {
+        path: String,
+        expected: &'static str,
+        got: &'static str,
+    },
+
+    /// A mapping was built with the same key twice.
+    ///
+    /// Holds the key rendered as YAML.
+    DuplicateKey(String),
+
+    /// A table row does not have one cell per header.
+    RowLengthMismatch {
+        row: usize,
+        expected: usize,
+        got: usize,
+    },
+
+    /// A value has no faithful form in the target format, such as a NaN
+    /// float converted to JSON.
+    ///
+    /// `path` locates the offending node; it is empty for the root.
+    Unrepresentable { path: String, message: String },
+
+    /// An anchor name is already defined on another node.
+    DuplicateAnchor(String),
+
+    /// An alias refers to an anchor that no earlier node defines.
+    ///
+    /// Holds the anchor name, without the `*`.
+    UndefinedAlias(String),
+
+    /// An alias refers to an anchor defined on a node that contains the
+    /// alias, so expanding it never ends.
+    ///
+    /// Holds the anchor name, without the `*`.
+    AliasCycle(String),
+
+    /// A parsed document exceeds a limit set in
+    /// [`ParseOptions`](crate::ParseOptions).
+    ///
+    /// `kind` is `"depth"`, `"nodes"` or `"alias expansion"`; `limit` is the
+    /// configured bound.
+    LimitExceeded { limit: usize, kind: &'static str },
 }
 
 impl Error {
@@ -149,6 +229,7 @@ impl fmt::Display for Error {
             Error::Parse(msg) => write!(f, "Parse error: {}", msg),
             Error::ParseError(e) => write!(f, "Parse error {}", e),
             Error::Io(msg) => write!(f, "I/O error: {}", msg),
+            Error::IoError { message, .. } => write!(f, "I/O error: {}", message),
             Error::Alloc(msg) => write!(f, "Allocation error: {}", msg),
             Error::Utf8(e) => write!(f, "UTF-8 error: {}", e),
             Error::MutationWhileIterating => {
@@ -163,6 +244,55 @@ impl fmt::Display for Error {
             Error::ScalarTooLarge(len) => {
                 write!(f, "Scalar length {} exceeds sanity limit", len)
             }
+            Error::InvalidPointer(msg) => write!(f, "Invalid pointer: {}", msg),
+            Error::IndexOutOfBounds { index, len } => {
+                write!(
+                    f,
+                    "Index {} out of bounds for sequence of length {}",
+                    index, len
+                )
+            }
+            Error::Deserialize { path, message } if path.is_empty() => {
+                write!(f, "Deserialize error: {}", message)
+            }
+            Error::Deserialize { path, message } => {
+                write!(f, "Deserialize error at {}: {}", path, message)
+            }
+            Error::UnsupportedEncoding(msg) => write!(f, "Unsupported encoding: {}", msg),
+            Error::InvalidOption(msg) => write!(f, "Invalid option: {}", msg),
+            Error::Locked { path } => write!(f, "Subtree is locked: {}", path),
+            Error::PathNotFound(path) => write!(f, "Path not found: {}", path),
+            Error::TypeMismatchAt {
+                path,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Type mismatch at {}: expected {}, got {}",
+                path, expected, got
+            ),
+            Error::DuplicateKey(key) => write!(f, "Duplicate mapping key: {}", key),
+            Error::RowLengthMismatch { row, expected, got } => write!(
+                f,
+                "Row {} has {} cells, expected {} (one per header)",
+                row, got, expected
+            ),
+            Error::Unrepresentable { path, message } if path.is_empty() => {
+                write!(f, "Unrepresentable value: {}", message)
+            }
+            Error::Unrepresentable { path, message } => {
+                write!(f, "Unrepresentable value at {}: {}", path, message)
+            }
+            Error::DuplicateAnchor(name) => write!(f, "Duplicate anchor: &{}", name),
+            Error::UndefinedAlias(name) => {
+                write!(f, "Alias *{} refers to an undefined anchor", name)
+            }
+            Error::AliasCycle(name) => {
+                write!(f, "Alias *{} refers to an anchor that contains it", name)
+            }
+            Error::LimitExceeded { limit, kind } => {
+                write!(f, "Document exceeds the {} limit of {}", kind, limit)
+            }
         }
     }
 }
@@ -183,6 +313,21 @@ impl From<std::str::Utf8Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IoError {
+            kind: e.kind(),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<std::convert::Infallible> for Error {
+    fn from(e: std::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
 impl From<ParseError> for Error {
     fn from(e: ParseError) -> Self {
         Error::ParseError(e)
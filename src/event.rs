@@ -0,0 +1,194 @@
+//! Pull-parser events.
+//!
+//! [`FyParser::events`](crate::FyParser::events) walks a YAML stream as a
+//! flat sequence of [`Event`]s without building documents, so memory use
+//! stays flat however large the input is.
+
+use crate::error::{Error, Result};
+use crate::node::NodeStyle;
+use crate::parser::ParserInner;
+use fyaml_sys::*;
+use libc::size_t;
+use std::borrow::Cow;
+use std::slice;
+
+/// A parse event, as produced by [`FyParser::events`](crate::FyParser::events).
+///
+/// Text borrows from the parser's input where libfyaml hands it out
+/// unchanged, and is copied otherwise (escapes, folding, tags resolved
+/// through a `%TAG` directive, or input read from stdin). Either way it
+/// stays valid after later events are parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<'p> {
+    /// Start of the stream; always the first event.
+    StreamStart,
+    /// End of the stream; always the last event.
+    StreamEnd,
+    /// Start of a document.
+    DocumentStart {
+        /// Whether the `---` marker was omitted.
+        implicit: bool,
+    },
+    /// End of a document.
+    DocumentEnd {
+        /// Whether the `...` marker was omitted.
+        implicit: bool,
+    },
+    /// Start of a mapping; its keys and values follow, alternating.
+    MappingStart {
+        /// Anchor defined on the mapping.
+        anchor: Option<Cow<'p, str>>,
+        /// Explicit tag, as [`NodeRef::tag_str`](crate::NodeRef::tag_str) reports it.
+        tag: Option<Cow<'p, str>>,
+        /// [`NodeStyle::Flow`] or [`NodeStyle::Block`].
+        style: NodeStyle,
+    },
+    /// End of the innermost open mapping.
+    MappingEnd,
+    /// Start of a sequence; its items follow.
+    SequenceStart {
+        /// Anchor defined on the sequence.
+        anchor: Option<Cow<'p, str>>,
+        /// Explicit tag, as [`NodeRef::tag_str`](crate::NodeRef::tag_str) reports it.
+        tag: Option<Cow<'p, str>>,
+        /// [`NodeStyle::Flow`] or [`NodeStyle::Block`].
+        style: NodeStyle,
+    },
+    /// End of the innermost open sequence.
+    SequenceEnd,
+    /// A scalar, key or value.
+    Scalar {
+        /// Content, with quotes and escapes processed.
+        value: Cow<'p, str>,
+        /// How the scalar was written (plain, quoted, literal, folded).
+        style: NodeStyle,
+        /// Explicit tag, as [`NodeRef::tag_str`](crate::NodeRef::tag_str) reports it.
+        tag: Option<Cow<'p, str>>,
+        /// Anchor defined on the scalar.
+        anchor: Option<Cow<'p, str>>,
+    },
+    /// An alias (`*name`) to an earlier anchor.
+    Alias {
+        /// The anchor referred to, without the `*`.
+        anchor: Cow<'p, str>,
+    },
+}
+
+/// Iterator over the [`Event`]s of a parser's stream.
+///
+/// Created by [`FyParser::events`](crate::FyParser::events). Yields an error
+/// and stops at the first parse error.
+pub struct EventIter<'p> {
+    inner: &'p ParserInner,
+    done: bool,
+}
+
+impl<'p> EventIter<'p> {
+    pub(crate) fn new(inner: &'p ParserInner) -> Self {
+        EventIter { inner, done: false }
+    }
+}
+
+/// Frees a parsed event on drop.
+struct EventGuard<'a> {
+    inner: &'a ParserInner,
+    event: *mut fy_event,
+}
+
+impl Drop for EventGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { fy_parser_event_free(self.inner.as_ptr(), self.event) };
+    }
+}
+
+impl<'p> Iterator for EventIter<'p> {
+    type Item = Result<Event<'p>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event_ptr = unsafe { fy_parser_parse(self.inner.as_ptr()) };
+        if event_ptr.is_null() {
+            self.done = true;
+            let has_error = unsafe { fy_parser_get_stream_error(self.inner.as_ptr()) };
+            if has_error {
+                return Some(Err(self.inner.first_error_or("stream parse error")));
+            }
+            return None;
+        }
+        let guard = EventGuard {
+            inner: self.inner,
+            event: event_ptr,
+        };
+        let event = unsafe { convert_event(self.inner, guard.event) };
+        if event.is_err() {
+            self.done = true;
+        }
+        Some(event)
+    }
+}
+
+/// Builds the safe form of `event`, copying any text the event owns.
+unsafe fn convert_event<'p>(inner: &'p ParserInner, event: *mut fy_event) -> Result<Event<'p>> {
+    let anchor = || token_text(inner, fy_event_get_anchor_token(event));
+    let tag = || token_text(inner, fy_event_get_tag_token(event));
+    let style = || NodeStyle::from(fy_event_get_node_style(event));
+    let event = match (*event).type_ {
+        t if t == FYET_STREAM_START => Event::StreamStart,
+        t if t == FYET_STREAM_END => Event::StreamEnd,
+        t if t == FYET_DOCUMENT_START => Event::DocumentStart {
+            implicit: fy_document_event_is_implicit(event),
+        },
+        t if t == FYET_DOCUMENT_END => Event::DocumentEnd {
+            implicit: fy_document_event_is_implicit(event),
+        },
+        t if t == FYET_MAPPING_START => Event::MappingStart {
+            anchor: anchor()?,
+            tag: tag()?,
+            style: style(),
+        },
+        t if t == FYET_MAPPING_END => Event::MappingEnd,
+        t if t == FYET_SEQUENCE_START => Event::SequenceStart {
+            anchor: anchor()?,
+            tag: tag()?,
+            style: style(),
+        },
+        t if t == FYET_SEQUENCE_END => Event::SequenceEnd,
+        t if t == FYET_SCALAR => Event::Scalar {
+            value: token_text(inner, fy_event_get_token(event))?.unwrap_or(Cow::Borrowed("")),
+            style: style(),
+            tag: tag()?,
+            anchor: anchor()?,
+        },
+        t if t == FYET_ALIAS => Event::Alias {
+            anchor: token_text(inner, fy_event_get_token(event))?.unwrap_or(Cow::Borrowed("")),
+        },
+        _ => return Err(Error::Ffi("unknown parser event type")),
+    };
+    Ok(event)
+}
+
+/// Returns the text of `token`, borrowed if it lies in the parser's input.
+unsafe fn token_text<'p>(
+    inner: &'p ParserInner,
+    token: *mut fy_token,
+) -> Result<Option<Cow<'p, str>>> {
+    if token.is_null() {
+        return Ok(None);
+    }
+    let mut len: size_t = 0;
+    let text_ptr = fy_token_get_text(token, &mut len);
+    if text_ptr.is_null() || len == 0 {
+        return Ok(Some(Cow::Borrowed("")));
+    }
+    if inner.input_contains(text_ptr as *const u8, len) {
+        // SAFETY: the input buffer lives as long as the parser, which `'p`
+        // borrows
+        let bytes: &'p [u8] = slice::from_raw_parts(text_ptr as *const u8, len);
+        return Ok(Some(Cow::Borrowed(std::str::from_utf8(bytes)?)));
+    }
+    // Text owned by the token goes away with the event: copy it
+    let bytes = slice::from_raw_parts(text_ptr as *const u8, len);
+    Ok(Some(Cow::Owned(std::str::from_utf8(bytes)?.to_owned())))
+}
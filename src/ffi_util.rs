@@ -4,8 +4,14 @@
 //! libfyaml's C API, ensuring consistent memory allocation and error handling.
 
 use crate::error::{Error, Result};
-use libc::{c_char, c_void};
+use fyaml_sys::*;
+use libc::{c_char, c_int, c_void};
+use std::any::Any;
 use std::ffi::CStr;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
 
 /// Allocates a buffer via libc malloc and copies bytes into it.
 ///
@@ -70,6 +76,94 @@ pub(crate) unsafe fn take_c_string(ptr: *mut c_char) -> String {
     s
 }
 
+/// Destination of an emitter created by [`emit_to_writer`].
+struct WriterSink<'w> {
+    writer: &'w mut dyn Write,
+    /// First write error; later output is refused.
+    error: Option<io::Error>,
+    /// Panic raised by the writer, resumed once back in Rust.
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+/// Emitter output callback forwarding each chunk to a [`WriterSink`].
+///
+/// Returns `len` when the whole chunk was written. Anything else makes
+/// libfyaml mark the emission as failed, so on error `-1` is returned and
+/// the error kept for the caller. `write_all` retries partial writes, so a
+/// chunk is either written completely or reported as an error.
+unsafe extern "C" fn write_to_sink(
+    _emit: *mut fy_emitter,
+    _write_type: fy_emitter_write_type,
+    data: *const c_char,
+    len: c_int,
+    userdata: *mut c_void,
+) -> c_int {
+    // SAFETY: userdata is the WriterSink set up by emit_to_writer, which
+    // outlives the emitter
+    let sink = &mut *(userdata as *mut WriterSink<'_>);
+    if sink.error.is_some() || sink.panic.is_some() {
+        return -1;
+    }
+    if len <= 0 || data.is_null() {
+        return 0;
+    }
+    let bytes = slice::from_raw_parts(data as *const u8, len as usize);
+    // A panic must not unwind through libfyaml's C frames
+    match panic::catch_unwind(AssertUnwindSafe(|| sink.writer.write_all(bytes))) {
+        Ok(Ok(())) => len,
+        Ok(Err(e)) => {
+            sink.error = Some(e);
+            -1
+        }
+        Err(payload) => {
+            sink.panic = Some(payload);
+            -1
+        }
+    }
+}
+
+/// Runs `emit` against an emitter whose output goes straight to `writer`.
+///
+/// Output is streamed in the chunks libfyaml produces, without building
+/// the whole text in memory. The writer is flushed on success. A write
+/// error is returned as [`Error::IoError`] in preference to the emitter's
+/// own failure code, and a panic in the writer is resumed here.
+pub(crate) fn emit_to_writer<W, F>(writer: &mut W, flags: u32, emit: F) -> Result<()>
+where
+    W: Write,
+    F: FnOnce(*mut fy_emitter) -> c_int,
+{
+    let mut sink = WriterSink {
+        writer,
+        error: None,
+        panic: None,
+    };
+    let cfg = fy_emitter_cfg {
+        flags,
+        output: Some(write_to_sink),
+        userdata: &mut sink as *mut WriterSink<'_> as *mut c_void,
+        diag: ptr::null_mut(),
+    };
+    let emitter = unsafe { fy_emitter_create(&cfg) };
+    if emitter.is_null() {
+        return Err(Error::Ffi("fy_emitter_create returned null"));
+    }
+    let ret = emit(emitter);
+    unsafe { fy_emitter_destroy(emitter) };
+
+    if let Some(payload) = sink.panic {
+        panic::resume_unwind(payload);
+    }
+    if let Some(e) = sink.error {
+        return Err(e.into());
+    }
+    if ret != 0 {
+        return Err(Error::Ffi("emitter failed"));
+    }
+    sink.writer.flush()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,99 @@
+//! Hash index over the keys of a mapping node.
+
+use crate::node_ref::NodeRef;
+use std::collections::HashMap;
+
+/// A precomputed key → value index over a mapping node.
+///
+/// [`NodeRef::map_get`] scans the mapping on every call, which is O(n).
+/// Building a `KeyIndex` costs one O(n) pass, after which each lookup is
+/// O(1). Worth it when checking many keys against a large mapping.
+///
+/// Only scalar keys are indexed; complex keys such as `? [a, b]` are
+/// skipped.
+///
+/// Created by [`NodeRef::build_key_index`]. Borrows the document, so it
+/// cannot outlive it or survive an edit.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::Document;
+///
+/// let doc = Document::parse_str("a: 1\nb: 2\nc: 3").unwrap();
+/// let index = doc.root().unwrap().build_key_index();
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(index.get("b").unwrap().scalar_str().unwrap(), "2");
+/// assert!(index.get("z").is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyIndex<'doc> {
+    map: HashMap<&'doc str, NodeRef<'doc>>,
+}
+
+impl<'doc> KeyIndex<'doc> {
+    pub(crate) fn new(mapping: NodeRef<'doc>) -> Self {
+        let mut map = HashMap::with_capacity(mapping.map_len().unwrap_or(0));
+        for (key, value) in mapping.map_iter() {
+            if let Ok(k) = key.scalar_str() {
+                map.entry(k).or_insert(value);
+            }
+        }
+        KeyIndex { map }
+    }
+
+    /// Looks up the value for `key`.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<NodeRef<'doc>> {
+        self.map.get(key).copied()
+    }
+
+    /// Returns `true` if `key` is present.
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the number of indexed keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no keys were indexed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Document;
+
+    #[test]
+    fn test_lookup() {
+        let doc = Document::parse_str("name: app\nport: 80\nnested: {a: 1}").unwrap();
+        let index = doc.root().unwrap().build_key_index();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get("name").unwrap().scalar_str().unwrap(), "app");
+        assert_eq!(index.get("port").unwrap().scalar_str().unwrap(), "80");
+        assert!(index.get("nested").unwrap().is_mapping());
+        assert!(index.contains_key("port"));
+        assert!(!index.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_skips_complex_keys() {
+        let doc = Document::parse_str("? [a, b]\n: x\nplain: y").unwrap();
+        let index = doc.root().unwrap().build_key_index();
+        assert_eq!(index.len(), 1);
+        assert!(index.get("plain").is_some());
+    }
+
+    #[test]
+    fn test_non_mapping_is_empty() {
+        let doc = Document::parse_str("[a, b]").unwrap();
+        assert!(doc.root().unwrap().build_key_index().is_empty());
+    }
+}
@@ -1,33 +1,57 @@
 #![doc = include_str!(concat!(env!("OUT_DIR"), "/README.md"))]
 
 mod config;
+mod de;
 mod diag;
+mod encoding;
 pub mod error;
 mod ffi_util;
+mod line_merge;
+mod lint;
+mod macros;
+mod maybe;
 mod node;
 mod scalar_parse;
+mod timestamp;
 pub mod value;
 
 // Core modules (formerly v2)
 mod document;
 mod editor;
+mod event;
 mod iter;
+mod key_index;
 mod node_ref;
 mod parser;
+mod stream;
 mod value_ref;
 
 // Re-export main API
+pub use config::{EmitOptions, ParseOptions};
+pub use de::{from_reader, from_slice, from_str};
 pub use document::Document;
 pub use editor::{Editor, RawNodeHandle};
+pub use event::{Event, EventIter};
 pub use iter::{MapIter, SeqIter};
-pub use node::{NodeStyle, NodeType};
+pub use key_index::KeyIndex;
+pub use lint::{LintFinding, LintKind};
+pub use maybe::Maybe;
+pub use node::{BlockKind, Chomp, Location, NodeStyle, NodeType, ScalarType};
 pub use node_ref::NodeRef;
 pub use parser::{DocumentIterator, FyParser};
+pub use scalar_parse::{
+    needs_quoting, parse_bool, parse_f64, parse_i128, parse_i64, parse_u128, parse_u64,
+};
+pub use stream::{emit_documents, emit_documents_to_writer};
+pub use timestamp::Timestamp;
 pub use value_ref::ValueRef;
 
 // Re-export error and value types
 pub use error::{Error, ParseError, Result};
-pub use value::{Number, TaggedValue, Value};
+pub use value::{
+    from_value, Change, ChangeKind, CompactOptions, MergeStrategy, Number, PathSegment,
+    TaggedValue, Value,
+};
 
 /// Returns the version string of the underlying libfyaml C library.
 pub fn get_c_version() -> Result<String> {
@@ -0,0 +1,236 @@
+//! Line-level merging of re-emitted YAML with its original text.
+//!
+//! libfyaml preserves styles and comments, but not the exact whitespace of
+//! the input. To keep textual diffs minimal after an edit, the original text
+//! is re-parsed and re-emitted as a *baseline*; lines that the edit left
+//! identical to the baseline are then copied back from the original.
+//!
+//! Alignment uses a longest common subsequence over lines. Common prefixes
+//! and suffixes are stripped first, so the quadratic part only covers the
+//! region around the edits; it runs in linear memory (Hirschberg's
+//! algorithm), so large inputs that differ throughout cost time, not a
+//! table of every pair of lines.
+
+/// Merges `edited` (the emitted, modified document) back onto `original`.
+///
+/// `baseline` must be the emission of `original` with no edits applied.
+/// Every line of `edited` that is unchanged relative to `baseline` is
+/// replaced by the matching original line. Blank and comment-only lines the
+/// emitter dropped are carried over in place.
+pub(crate) fn merge_unchanged(original: &str, baseline: &str, edited: &str) -> String {
+    if baseline == edited {
+        return original.to_owned();
+    }
+
+    let o: Vec<&str> = original.lines().collect();
+    let a: Vec<&str> = baseline.lines().collect();
+    let b: Vec<&str> = edited.lines().collect();
+
+    // Original <-> baseline: the emitter may respace lines, so compare tokens
+    let mut a_to_o = vec![None; a.len()];
+    let mut o_matched = vec![false; o.len()];
+    for (oi, ai) in lcs_pairs(&o, &a, |x, y| x.split_whitespace().eq(y.split_whitespace())) {
+        a_to_o[ai] = Some(oi);
+        o_matched[oi] = true;
+    }
+
+    // Baseline <-> edited: both come from the same emitter, compare exactly
+    let mut b_to_a = vec![None; b.len()];
+    for (ai, bi) in lcs_pairs(&a, &b, |x, y| x == y) {
+        b_to_a[bi] = Some(ai);
+    }
+
+    let mut out: Vec<&str> = Vec::with_capacity(b.len());
+    let mut next_o = 0;
+    for (bi, line) in b.iter().enumerate() {
+        match b_to_a[bi].and_then(|ai| a_to_o[ai]) {
+            Some(oi) => {
+                push_trivia(&mut out, &o[next_o..oi], &o_matched[next_o..oi]);
+                out.push(o[oi]);
+                next_o = oi + 1;
+            }
+            None => out.push(line),
+        }
+    }
+    push_trivia(&mut out, &o[next_o..], &o_matched[next_o..]);
+
+    let mut result = out.join("\n");
+    if original.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Copies unmatched blank and comment-only lines from the original.
+///
+/// Unmatched content lines are not copied: their edited form (if any) has
+/// already been emitted from the new document.
+fn push_trivia<'a>(out: &mut Vec<&'a str>, lines: &[&'a str], matched: &[bool]) {
+    for (line, &is_matched) in lines.iter().zip(matched) {
+        let trimmed = line.trim_start();
+        if !is_matched && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            out.push(line);
+        }
+    }
+}
+
+/// Returns the index pairs `(i, j)` of a longest common subsequence of `x`
+/// and `y` under `eq`, in increasing order.
+fn lcs_pairs<F>(x: &[&str], y: &[&str], eq: F) -> Vec<(usize, usize)>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let mut prefix = 0;
+    while prefix < x.len() && prefix < y.len() && eq(x[prefix], y[prefix]) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < x.len() - prefix
+        && suffix < y.len() - prefix
+        && eq(x[x.len() - 1 - suffix], y[y.len() - 1 - suffix])
+    {
+        suffix += 1;
+    }
+
+    let xm = &x[prefix..x.len() - suffix];
+    let ym = &y[prefix..y.len() - suffix];
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|k| (k, k)).collect();
+    hirschberg(xm, ym, (prefix, prefix), &eq, &mut pairs);
+    pairs.extend((0..suffix).map(|k| (x.len() - suffix + k, y.len() - suffix + k)));
+    pairs
+}
+
+/// Appends the pairs of a longest common subsequence of `x` and `y`,
+/// offset by `at`, keeping only two rows of lengths at a time.
+///
+/// `x` is split in half; the split point in `y` is where the LCS lengths of
+/// the first half against a prefix of `y` and of the second half against
+/// the rest add up to the most. Each half is then solved on its own.
+fn hirschberg<F>(
+    x: &[&str],
+    y: &[&str],
+    at: (usize, usize),
+    eq: &F,
+    pairs: &mut Vec<(usize, usize)>,
+) where
+    F: Fn(&str, &str) -> bool,
+{
+    if x.is_empty() || y.is_empty() {
+        return;
+    }
+    if x.len() == 1 {
+        if let Some(j) = y.iter().position(|line| eq(x[0], line)) {
+            pairs.push((at.0, at.1 + j));
+        }
+        return;
+    }
+
+    let mid = x.len() / 2;
+    let head = lcs_lengths(&x[..mid], y, false, eq);
+    let tail = lcs_lengths(&x[mid..], y, true, eq);
+    // head[k] + tail[m - k]: LCS through the split between y[..k] and y[k..]
+    let m = y.len();
+    let mut split = 0;
+    for k in 1..=m {
+        if head[k] + tail[m - k] > head[split] + tail[m - split] {
+            split = k;
+        }
+    }
+
+    hirschberg(&x[..mid], &y[..split], at, eq, pairs);
+    hirschberg(
+        &x[mid..],
+        &y[split..],
+        (at.0 + mid, at.1 + split),
+        eq,
+        pairs,
+    );
+}
+
+/// Returns `row` with `row[k]` the LCS length of all of `x` and the first
+/// `k` lines of `y`, or with both read backwards if `reverse` is set.
+fn lcs_lengths<F>(x: &[&str], y: &[&str], reverse: bool, eq: &F) -> Vec<u32>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let (n, m) = (x.len(), y.len());
+    let mut prev = vec![0u32; m + 1];
+    let mut row = vec![0u32; m + 1];
+    for i in 0..n {
+        let xi = if reverse { x[n - 1 - i] } else { x[i] };
+        for j in 0..m {
+            let yj = if reverse { y[m - 1 - j] } else { y[j] };
+            row[j + 1] = if eq(xi, yj) {
+                prev[j] + 1
+            } else {
+                prev[j + 1].max(row[j])
+            };
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcs_pairs() {
+        let x = ["a", "b", "c", "d"];
+        let y = ["a", "c", "x", "d"];
+        assert_eq!(lcs_pairs(&x, &y, |p, q| p == q), [(0, 0), (2, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn test_lcs_pairs_matches_exhaustive_length() {
+        let x = ["a", "b", "c", "a", "b", "b", "a", "x"];
+        let y = ["c", "b", "a", "b", "a", "c", "y"];
+        let pairs = lcs_pairs(&x, &y, |p, q| p == q);
+        // "baba" / "cbba" / ... all have length 4
+        assert_eq!(pairs.len(), 4);
+        assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+        assert!(pairs.iter().all(|&(i, j)| x[i] == y[j]));
+    }
+
+    #[test]
+    fn test_lcs_pairs_large_disjoint_inputs() {
+        let x: Vec<String> = (0..2_000).map(|i| format!("x{}", i)).collect();
+        let y: Vec<String> = (0..3_000).map(|i| format!("y{}", i)).collect();
+        let x: Vec<&str> = x.iter().map(String::as_str).collect();
+        let y: Vec<&str> = y.iter().map(String::as_str).collect();
+        assert!(lcs_pairs(&x, &y, |p, q| p == q).is_empty());
+    }
+
+    #[test]
+    fn test_merge_unchanged_returns_original() {
+        let original = "a:   1   # spaced\n";
+        assert_eq!(
+            merge_unchanged(original, "a: 1 # spaced\n", "a: 1 # spaced\n"),
+            original
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_original_spacing_and_trivia() {
+        let original = "# head\n\na:    1\nb: 2\n\n# tail\n";
+        let baseline = "# head\na: 1\nb: 2\n# tail\n";
+        let edited = "# head\na: 1\nb: 3\n# tail\n";
+        assert_eq!(
+            merge_unchanged(original, baseline, edited),
+            "# head\n\na:    1\nb: 3\n\n# tail\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_handles_insert_and_delete() {
+        let original = "a:  1\nb:  2\nc:  3\n";
+        let baseline = "a: 1\nb: 2\nc: 3\n";
+        let edited = "a: 1\nc: 3\nd: 4\n";
+        assert_eq!(
+            merge_unchanged(original, baseline, edited),
+            "a:  1\nc:  3\nd: 4\n"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Style checks over a parsed document.
+//!
+//! [`Document::lint`] walks the node tree and its source marks looking for
+//! layout and spelling issues that parse fine but make a file harder to
+//! maintain. Nothing here rejects valid YAML; the findings are advisory.
+
+use crate::document::{push_pointer_token, Document};
+use crate::node::{Location, NodeStyle, NodeType, ScalarType};
+use crate::node_ref::NodeRef;
+use std::fmt;
+
+/// The kind of issue a [`LintFinding`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// A block collection is indented by a different width than the first
+    /// nested block collection of the document.
+    InconsistentIndent,
+    /// A line ends in spaces or tabs.
+    TrailingWhitespace,
+    /// A collection holds both flow and block collections.
+    MixedFlowBlock,
+    /// A plain boolean is spelled other than `true` or `false`.
+    NonCanonicalBool,
+}
+
+/// An issue reported by [`Document::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub(crate) kind: LintKind,
+    pub(crate) path: String,
+    pub(crate) message: String,
+    pub(crate) line: Option<u32>,
+}
+
+impl LintFinding {
+    /// Returns the kind of issue.
+    pub fn kind(&self) -> LintKind {
+        self.kind
+    }
+
+    /// Returns the path of the offending node, `""` for the root.
+    ///
+    /// Uses the same `/`-separated form as [`Document::at_path`].
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns a human-readable description of the issue.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the line number (1-based), if available.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        match self.line {
+            Some(line) => write!(f, "line {} ({}): {}", line, path, self.message),
+            None => write!(f, "{}: {}", path, self.message),
+        }
+    }
+}
+
+impl Document {
+    /// Reports style issues in the document.
+    ///
+    /// The checks are:
+    ///
+    /// - [`InconsistentIndent`](LintKind::InconsistentIndent): the first
+    ///   block collection nested under a mapping key sets the indentation
+    ///   width; nested block collections indented differently are reported.
+    ///   Sequences written at the same column as their key are accepted.
+    /// - [`MixedFlowBlock`](LintKind::MixedFlowBlock): a collection whose
+    ///   entries include both flow and block collections. Empty `[]` and
+    ///   `{}` are ignored, since they can only be written in flow style.
+    /// - [`NonCanonicalBool`](LintKind::NonCanonicalBool): plain, untagged
+    ///   scalars such as `yes`, `Off` or `TRUE`.
+    /// - [`TrailingWhitespace`](LintKind::TrailingWhitespace): lines ending
+    ///   in spaces or tabs, reported against the last node starting before
+    ///   the whitespace. This needs the source text, which parsed documents
+    ///   keep; for documents built in code or read from a stream parser the
+    ///   check is skipped.
+    ///
+    /// Tree findings come first, in document order, followed by trailing
+    /// whitespace findings in line order. A clean document yields an empty
+    /// vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, LintKind};
+    ///
+    /// let doc = Document::parse_str("debug: yes\nverbose: false").unwrap();
+    /// let findings = doc.lint();
+    /// assert_eq!(findings.len(), 1);
+    /// assert_eq!(findings[0].kind(), LintKind::NonCanonicalBool);
+    /// assert_eq!(findings[0].path(), "/debug");
+    /// ```
+    pub fn lint(&self) -> Vec<LintFinding> {
+        let mut linter = Linter {
+            findings: Vec::new(),
+            indent: None,
+            starts: Vec::new(),
+        };
+        if let Some(root) = self.root() {
+            let mut path = String::new();
+            linter.visit(root, &mut path);
+        }
+        if let Some(source) = self.source() {
+            linter.check_trailing_whitespace(source);
+        }
+        linter.findings
+    }
+}
+
+struct Linter {
+    findings: Vec<LintFinding>,
+    /// Indentation width set by the first nested block collection.
+    indent: Option<usize>,
+    /// Start offset and path of every node, for locating source lines.
+    starts: Vec<(usize, String)>,
+}
+
+impl Linter {
+    fn report(&mut self, kind: LintKind, path: &str, message: String, line: Option<u32>) {
+        self.findings.push(LintFinding {
+            kind,
+            path: path.to_string(),
+            message,
+            line,
+        });
+    }
+
+    fn visit(&mut self, node: NodeRef<'_>, path: &mut String) {
+        let mark = node.location();
+        if let Some(mark) = mark {
+            self.starts.push((mark.offset, path.clone()));
+        }
+        match node.kind() {
+            NodeType::Scalar => self.check_bool(node, path, mark),
+            NodeType::Sequence => {
+                self.check_mixed(node.seq_iter(), path, mark);
+                for (i, item) in node.seq_iter().enumerate() {
+                    let len = path.len();
+                    path.push('/');
+                    path.push_str(&i.to_string());
+                    self.visit(item, path);
+                    path.truncate(len);
+                }
+            }
+            NodeType::Mapping => {
+                self.check_mixed(node.map_iter().map(|(_, value)| value), path, mark);
+                for (key, value) in node.map_iter() {
+                    let len = path.len();
+                    path.push('/');
+                    match key.scalar_str() {
+                        Ok(k) => push_pointer_token(path, k),
+                        Err(_) => {
+                            let text = key.emit().unwrap_or_default();
+                            push_pointer_token(path, text.trim_end());
+                        }
+                    }
+                    if node.style() != NodeStyle::Flow {
+                        self.check_indent(key, value, path);
+                    }
+                    self.visit(value, path);
+                    path.truncate(len);
+                }
+            }
+        }
+    }
+
+    fn check_bool(&mut self, node: NodeRef<'_>, path: &str, mark: Option<Location>) {
+        if node.inferred_scalar_type() != Some(ScalarType::Bool) {
+            return;
+        }
+        if matches!(node.tag_str(), Ok(Some(_))) {
+            return;
+        }
+        let text = match node.scalar_str() {
+            Ok(text) => text,
+            Err(_) => return,
+        };
+        if text == "true" || text == "false" {
+            return;
+        }
+        let canonical = if crate::scalar_parse::parse_bool(text) == Some(true) {
+            "true"
+        } else {
+            "false"
+        };
+        let message = format!("non-canonical boolean `{}`, use `{}`", text, canonical);
+        self.report(
+            LintKind::NonCanonicalBool,
+            path,
+            message,
+            mark.map(|m| m.line as u32),
+        );
+    }
+
+    fn check_mixed<'doc>(
+        &mut self,
+        children: impl Iterator<Item = NodeRef<'doc>>,
+        path: &str,
+        mark: Option<Location>,
+    ) {
+        let (mut flow, mut block) = (false, false);
+        for child in children {
+            if is_empty_collection(child) {
+                continue;
+            }
+            match (child.kind(), child.style()) {
+                (NodeType::Scalar, _) => {}
+                (_, NodeStyle::Flow) => flow = true,
+                _ => block = true,
+            }
+        }
+        if flow && block {
+            let message = "mixes flow and block collections at the same level".to_string();
+            self.report(
+                LintKind::MixedFlowBlock,
+                path,
+                message,
+                mark.map(|m| m.line as u32),
+            );
+        }
+    }
+
+    fn check_indent(&mut self, key: NodeRef<'_>, value: NodeRef<'_>, path: &str) {
+        if value.kind() == NodeType::Scalar
+            || value.style() == NodeStyle::Flow
+            || is_empty_collection(value)
+        {
+            return;
+        }
+        let (key_mark, value_mark) = match (key.location(), value.location()) {
+            (Some(k), Some(v)) => (k, v),
+            _ => return,
+        };
+        // Only collections opened on a line of their own are indented
+        if value_mark.line <= key_mark.line || value_mark.column <= key_mark.column {
+            return;
+        }
+        let width = value_mark.column - key_mark.column;
+        match self.indent {
+            None => self.indent = Some(width),
+            Some(expected) if expected != width => {
+                let message = format!(
+                    "indented by {} spaces, expected {} like the rest of the document",
+                    width, expected
+                );
+                let line = Some(value_mark.line as u32);
+                self.report(LintKind::InconsistentIndent, path, message, line);
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn check_trailing_whitespace(&mut self, source: &[u8]) {
+        self.starts.sort_by_key(|(pos, _)| *pos);
+        let mut line_start = 0;
+        for (index, line) in source.split(|&b| b == b'\n').enumerate() {
+            let content = line.strip_suffix(b"\r").unwrap_or(line);
+            let trimmed = content
+                .iter()
+                .rposition(|&b| b != b' ' && b != b'\t')
+                .map_or(0, |i| i + 1);
+            if trimmed < content.len() {
+                let pos = line_start + trimmed;
+                let at = self.starts.partition_point(|(start, _)| *start <= pos);
+                let path = match at {
+                    0 => String::new(),
+                    at => self.starts[at - 1].1.clone(),
+                };
+                self.report(
+                    LintKind::TrailingWhitespace,
+                    &path,
+                    "trailing whitespace".to_string(),
+                    Some(index as u32 + 1),
+                );
+            }
+            line_start += line.len() + 1;
+        }
+    }
+}
+
+fn is_empty_collection(node: NodeRef<'_>) -> bool {
+    match node.kind() {
+        NodeType::Scalar => false,
+        NodeType::Sequence => matches!(node.seq_len(), Ok(0)),
+        NodeType::Mapping => matches!(node.map_len(), Ok(0)),
+    }
+}
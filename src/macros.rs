@@ -0,0 +1,212 @@
+//! The [`yaml!`] macro for building [`Value`](crate::Value) literals.
+
+/// Builds a [`Value`](crate::Value) from a JSON-like literal.
+///
+/// Mappings are written `{ key: value, ... }` and sequences `[a, b, ...]`;
+/// both nest freely and accept a trailing comma. `null`, `true` and `false`
+/// are literals. Anything else is a Rust expression converted with
+/// [`Value::from`](crate::Value), so variables, numbers, strings and
+/// existing `Value`s can be interpolated. Keys are expressions too; wrap a
+/// key in parentheses if it contains a `:`.
+///
+/// Mapping entries keep their written order. A repeated key keeps its first
+/// position and takes the last value.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::{yaml, Value};
+///
+/// let age: i64 = 30;
+/// let value = yaml!({
+///     "name": "Alice",
+///     "tags": ["a", "b"],
+///     "age": age,
+///     "manager": null,
+/// });
+///
+/// let expected: Value = "{name: Alice, tags: [a, b], age: 30, manager: ~}".parse().unwrap();
+/// assert_eq!(value, expected);
+/// ```
+#[macro_export]
+macro_rules! yaml {
+    ($($yaml:tt)+) => {
+        $crate::__yaml_internal!($($yaml)+)
+    };
+}
+
+// Token muncher behind `yaml!`. Sequence elements are accumulated as
+// expressions in `@seq [...]`; mapping entries are inserted one at a time,
+// munching the key token by token until the `:`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __yaml_internal {
+    // Sequences. Done, with or without a trailing comma.
+    (@seq [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+    (@seq [$($elems:expr),*]) => {
+        ::std::vec![$($elems),*]
+    };
+
+    // Next element is a literal or nested collection.
+    (@seq [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::__yaml_internal!(@seq [$($elems,)* $crate::__yaml_internal!(null)] $($rest)*)
+    };
+    (@seq [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::__yaml_internal!(@seq [$($elems,)* $crate::__yaml_internal!(true)] $($rest)*)
+    };
+    (@seq [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::__yaml_internal!(@seq [$($elems,)* $crate::__yaml_internal!(false)] $($rest)*)
+    };
+    (@seq [$($elems:expr,)*] [$($seq:tt)*] $($rest:tt)*) => {
+        $crate::__yaml_internal!(
+            @seq [$($elems,)* $crate::__yaml_internal!([$($seq)*])] $($rest)*
+        )
+    };
+    (@seq [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::__yaml_internal!(
+            @seq [$($elems,)* $crate::__yaml_internal!({$($map)*})] $($rest)*
+        )
+    };
+
+    // Next element is an expression, followed by a comma or the end.
+    (@seq [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::__yaml_internal!(@seq [$($elems,)* $crate::__yaml_internal!($next),] $($rest)*)
+    };
+    (@seq [$($elems:expr,)*] $last:expr) => {
+        $crate::__yaml_internal!(@seq [$($elems,)* $crate::__yaml_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@seq [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::__yaml_internal!(@seq [$($elems,)*] $($rest)*)
+    };
+
+    // Unexpected token after the most recent element.
+    (@seq [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::__yaml_unexpected!($unexpected)
+    };
+
+    // Mappings. Done.
+    (@map $map:ident () () ()) => {};
+
+    // Insert the current entry, followed by a comma or the end.
+    (@map $map:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $map.insert($crate::Value::from($($key)+), $value);
+        $crate::__yaml_internal!(@map $map () ($($rest)*) ($($rest)*));
+    };
+    (@map $map:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $map.insert($crate::Value::from($($key)+), $value);
+    };
+
+    // Current entry followed by an unexpected token.
+    (@map $map:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::__yaml_unexpected!($unexpected);
+    };
+
+    // Next value is a literal or nested collection.
+    (@map $map:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map [$($key)+] ($crate::__yaml_internal!(null)) $($rest)*);
+    };
+    (@map $map:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map [$($key)+] ($crate::__yaml_internal!(true)) $($rest)*);
+    };
+    (@map $map:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map [$($key)+] ($crate::__yaml_internal!(false)) $($rest)*);
+    };
+    (@map $map:ident ($($key:tt)+) (: [$($seq:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(
+            @map $map [$($key)+] ($crate::__yaml_internal!([$($seq)*])) $($rest)*
+        );
+    };
+    (@map $map:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(
+            @map $map [$($key)+] ($crate::__yaml_internal!({$($inner)*})) $($rest)*
+        );
+    };
+
+    // Next value is an expression, followed by a comma or the end.
+    (@map $map:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(
+            @map $map [$($key)+] ($crate::__yaml_internal!($value)) , $($rest)*
+        );
+    };
+    (@map $map:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map [$($key)+] ($crate::__yaml_internal!($value)));
+    };
+
+    // Missing value, or missing colon and value, for the last entry.
+    (@map $map:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::__yaml_internal!();
+    };
+    (@map $map:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::__yaml_internal!();
+    };
+
+    // Colon with no key, or comma inside a key.
+    (@map $map:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::__yaml_unexpected!($colon);
+    };
+    (@map $map:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::__yaml_unexpected!($comma);
+    };
+
+    // Parenthesized key: take it whole so it may contain a colon.
+    (@map $map:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // A colon the key munching would absorb: report what follows instead.
+    (@map $map:ident ($($key:tt)*) (: $($unexpected:tt)+) $copy:tt) => {
+        $crate::__yaml_expect_expr_comma!($($unexpected)+);
+    };
+
+    // Munch one token into the current key.
+    (@map $map:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::__yaml_internal!(@map $map ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    // Entry points.
+    (null) => {
+        $crate::Value::Null
+    };
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+    ([]) => {
+        $crate::Value::Sequence(::std::vec::Vec::new())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Sequence($crate::__yaml_internal!(@seq [] $($tt)+))
+    };
+    ({}) => {
+        $crate::Value::Mapping(::std::default::Default::default())
+    };
+    ({ $($tt:tt)+ }) => {{
+        let mut value = $crate::Value::Mapping(::std::default::Default::default());
+        if let $crate::Value::Mapping(map) = &mut value {
+            $crate::__yaml_internal!(@map map () ($($tt)+) ($($tt)+));
+        }
+        value
+    }};
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+// Matches nothing, so the compiler points its error at the offending token.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __yaml_unexpected {
+    () => {};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __yaml_expect_expr_comma {
+    ($e:expr , $($tt:tt)*) => {};
+}
@@ -0,0 +1,184 @@
+//! Tri-state optional value for serde: missing, null, or present.
+
+use serde::de::{Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+
+/// A struct field that tells a missing key apart from an explicit `null`.
+///
+/// `Option<T>` maps both to `None`. `Maybe<T>` keeps the distinction, which
+/// matters for merge or patch semantics where `null` means "clear" and a
+/// missing key means "leave alone".
+///
+/// Fields must be marked `#[serde(default)]`: serde reports a missing key
+/// and a `null` value the same way to the field type, so the `Missing`
+/// state comes from [`Default`]. When serializing, add
+/// `#[serde(skip_serializing_if = "Maybe::is_missing")]` to omit missing
+/// fields; otherwise they serialize as `null`.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::{from_value, Maybe, Value};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Patch {
+///     #[serde(default)]
+///     name: Maybe<String>,
+///     #[serde(default)]
+///     email: Maybe<String>,
+/// }
+///
+/// let value: Value = "email: ~".parse().unwrap();
+/// let patch: Patch = from_value(&value).unwrap();
+/// assert_eq!(patch.name, Maybe::Missing);
+/// assert_eq!(patch.email, Maybe::Null);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Maybe<T> {
+    /// The key was absent.
+    Missing,
+    /// The key was present with a `null` value.
+    Null,
+    /// The key was present with a value.
+    Present(T),
+}
+
+impl<T> Maybe<T> {
+    /// Returns `true` if the key was absent.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Maybe::Missing)
+    }
+
+    /// Returns `true` if the key was explicitly `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Maybe::Null)
+    }
+
+    /// Returns `true` if the key held a value.
+    pub fn is_present(&self) -> bool {
+        matches!(self, Maybe::Present(_))
+    }
+
+    /// Converts from `&Maybe<T>` to `Maybe<&T>`.
+    pub fn as_ref(&self) -> Maybe<&T> {
+        match self {
+            Maybe::Missing => Maybe::Missing,
+            Maybe::Null => Maybe::Null,
+            Maybe::Present(v) => Maybe::Present(v),
+        }
+    }
+
+    /// Returns the value, collapsing `Missing` and `Null` to `None`.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Maybe::Present(v) => Some(v),
+            Maybe::Missing | Maybe::Null => None,
+        }
+    }
+}
+
+impl<T> Default for Maybe<T> {
+    fn default() -> Self {
+        Maybe::Missing
+    }
+}
+
+impl<T> From<Option<T>> for Maybe<T> {
+    /// `None` becomes `Null`, since an `Option` has no notion of absence.
+    fn from(opt: Option<T>) -> Self {
+        match opt {
+            Some(v) => Maybe::Present(v),
+            None => Maybe::Null,
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Maybe<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Maybe::from)
+    }
+}
+
+impl<T> Serialize for Maybe<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Maybe::Present(v) => serializer.serialize_some(v),
+            Maybe::Missing | Maybe::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::{from_value, Value};
+
+    #[derive(Debug, serde::Deserialize, serde::Serialize)]
+    struct Settings {
+        #[serde(default, skip_serializing_if = "Maybe::is_missing")]
+        absent: Maybe<u32>,
+        #[serde(default, skip_serializing_if = "Maybe::is_missing")]
+        cleared: Maybe<u32>,
+        #[serde(default, skip_serializing_if = "Maybe::is_missing")]
+        set: Maybe<u32>,
+    }
+
+    #[test]
+    fn test_deserialize_three_states() {
+        let value: Value = "cleared: null\nset: 8080".parse().unwrap();
+        let settings: Settings = from_value(&value).unwrap();
+        assert_eq!(settings.absent, Maybe::Missing);
+        assert_eq!(settings.cleared, Maybe::Null);
+        assert_eq!(settings.set, Maybe::Present(8080));
+    }
+
+    #[test]
+    fn test_tilde_and_empty_are_null() {
+        let value: Value = "absent: ~\ncleared:\n".parse().unwrap();
+        let settings: Settings = from_value(&value).unwrap();
+        assert!(settings.absent.is_null());
+        assert!(settings.cleared.is_null());
+        assert!(settings.set.is_missing());
+    }
+
+    #[test]
+    fn test_wrong_type_still_errors() {
+        let value: Value = "set: abc".parse().unwrap();
+        let err = from_value::<Settings>(&value).unwrap_err();
+        assert!(err.to_string().contains("at /set:"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_serialize_skips_missing() {
+        let settings = Settings {
+            absent: Maybe::Missing,
+            cleared: Maybe::Null,
+            set: Maybe::Present(1),
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(json, r#"{"cleared":null,"set":1}"#);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Maybe::from(Some(3)), Maybe::Present(3));
+        assert_eq!(Maybe::<u8>::from(None), Maybe::Null);
+        assert_eq!(Maybe::Present(3).into_option(), Some(3));
+        assert_eq!(Maybe::<u8>::Missing.into_option(), None);
+        let owned = Maybe::Present(String::from("x"));
+        assert_eq!(owned.as_ref().into_option().map(String::len), Some(1));
+    }
+}
@@ -38,6 +38,59 @@ pub enum NodeStyle {
     Alias,
 }
 
+/// The type a plain scalar is inferred as.
+///
+/// This mirrors the inference [`Value::from_node_ref`](crate::Value::from_node_ref)
+/// performs, see [`NodeRef::inferred_scalar_type`](crate::NodeRef::inferred_scalar_type).
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ScalarType {
+    /// `null`, `~`, or empty.
+    Null,
+    /// A YAML 1.1 boolean (`true`, `yes`, `off`, ...).
+    Bool,
+    /// An integer (decimal, hex, octal, or binary).
+    Int,
+    /// A float, including `.inf` and `.nan`.
+    Float,
+    /// Anything else, and every non-plain scalar.
+    Str,
+}
+
+/// The block scalar indicator used by [`Editor::set_block_scalar_at`](crate::Editor::set_block_scalar_at).
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum BlockKind {
+    /// Literal block (`|`): line breaks are kept as written.
+    Literal,
+    /// Folded block (`>`): single line breaks read back as spaces.
+    Folded,
+}
+
+/// How trailing line breaks of a block scalar are handled.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Chomp {
+    /// Keep a single final line break (no indicator).
+    Clip,
+    /// Drop all final line breaks (`-`).
+    Strip,
+    /// Keep every final line break (`+`).
+    Keep,
+}
+
+/// Where a node starts in the parsed input.
+///
+/// Returned by [`NodeRef::location`](crate::NodeRef::location). `line` and
+/// `column` are 1-based, like [`ParseError`](crate::ParseError) locations;
+/// `offset` is the 0-based byte offset into the input.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Location {
+    /// Line number (1-based).
+    pub line: usize,
+    /// Column number (1-based).
+    pub column: usize,
+    /// Byte offset from the start of the input (0-based).
+    pub offset: usize,
+}
+
 impl From<i32> for NodeStyle {
     fn from(value: i32) -> Self {
         match value {
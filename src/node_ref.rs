@@ -1,14 +1,19 @@
 //! Zero-copy node reference type.
 
-use crate::config;
-use crate::document::Document;
+use crate::config::EmitOptions;
+use crate::document::{finish_emit, restyle_for_emit, Document};
 use crate::error::{Error, Result};
-use crate::ffi_util::take_c_string;
+use crate::ffi_util::{emit_to_writer, take_c_string};
 use crate::iter::{MapIter, SeqIter};
-use crate::node::{NodeStyle, NodeType};
+use crate::key_index::KeyIndex;
+use crate::node::{Location, NodeStyle, NodeType, ScalarType};
+use crate::scalar_parse;
+use crate::value::Value;
 use fyaml_sys::*;
 use libc::size_t;
+use std::collections::HashSet;
 use std::fmt;
+use std::ops::Range;
 use std::ptr::NonNull;
 use std::slice;
 
@@ -148,6 +153,36 @@ impl<'doc> NodeRef<'doc> {
             || style == FYNS_FOLDED
     }
 
+    /// Returns the type a scalar would be inferred as when converted to a `Value`.
+    ///
+    /// Plain scalars go through the same inference as
+    /// [`Value::from_node_ref`](crate::Value::from_node_ref): null, then
+    /// boolean, then number, otherwise string. Non-plain scalars (quoted,
+    /// literal, folded) are always [`ScalarType::Str`].
+    ///
+    /// Returns `None` for sequences and mappings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, ScalarType};
+    ///
+    /// let doc = Document::parse_str("a: 42\nb: '42'").unwrap();
+    /// assert_eq!(doc.at_path("/a").unwrap().inferred_scalar_type(), Some(ScalarType::Int));
+    /// assert_eq!(doc.at_path("/b").unwrap().inferred_scalar_type(), Some(ScalarType::Str));
+    /// assert_eq!(doc.root().unwrap().inferred_scalar_type(), None);
+    /// ```
+    pub fn inferred_scalar_type(&self) -> Option<ScalarType> {
+        if !self.is_scalar() {
+            return None;
+        }
+        if self.is_non_plain() {
+            return Some(ScalarType::Str);
+        }
+        let s = self.scalar_str().ok()?;
+        Some(scalar_parse::scalar_type(s))
+    }
+
     // ==================== Zero-Copy Scalar Access ====================
 
     /// Returns the scalar value as a byte slice (zero-copy).
@@ -155,6 +190,23 @@ impl<'doc> NodeRef<'doc> {
     /// The returned slice points directly into libfyaml's internal buffer.
     /// It is valid for the lifetime `'doc` of the document.
     ///
+    /// The value is the scalar's content, never its source text:
+    ///
+    /// - [`Plain`](NodeStyle::Plain): the text, with a multi-line scalar's
+    ///   line breaks folded into spaces.
+    /// - [`SingleQuoted`](NodeStyle::SingleQuoted): without the quotes,
+    ///   `''` unescaped to `'`, lines folded.
+    /// - [`DoubleQuoted`](NodeStyle::DoubleQuoted): without the quotes,
+    ///   escapes such as `\t` and `\u00e9` decoded, lines folded.
+    /// - [`Literal`](NodeStyle::Literal): without the indicator and
+    ///   indentation, line breaks kept, chomping applied.
+    /// - [`Folded`](NodeStyle::Folded): like `Literal`, with lines folded.
+    ///
+    /// Content that needed none of this points into the parsed input;
+    /// anything else was decoded into memory owned by the document.
+    /// [`ValueRef::as_cow_str`](crate::ValueRef::as_cow_str) tells the two
+    /// apart.
+    ///
     /// # Errors
     ///
     /// Returns an error if this is not a scalar node.
@@ -208,6 +260,19 @@ impl<'doc> NodeRef<'doc> {
         std::str::from_utf8(bytes).map_err(Error::from)
     }
 
+    /// Returns `true` if this is a scalar whose content matches `re`.
+    ///
+    /// The raw scalar content is matched, so quoting does not matter. As
+    /// with [`Regex::is_match`](regex::Regex::is_match), the pattern may
+    /// match anywhere; anchor it with `^...$` to check the whole value.
+    /// Returns `false` for non-scalars and non-UTF-8 content.
+    ///
+    /// Requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub fn scalar_matches(&self, re: &regex::Regex) -> bool {
+        matches!(self.scalar_str(), Ok(s) if re.is_match(s))
+    }
+
     // ==================== Zero-Copy Tag Access ====================
 
     /// Returns the YAML tag as a byte slice (zero-copy).
@@ -237,6 +302,34 @@ impl<'doc> NodeRef<'doc> {
         }
     }
 
+    /// Returns the anchor label this node defines (zero-copy).
+    ///
+    /// The label is returned without the leading `&`. Returns `None` if the
+    /// node has no anchor, or if the label is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("base: &defaults {a: 1}\nother: 2").unwrap();
+    /// assert_eq!(doc.at_path("/base").unwrap().anchor(), Some("defaults"));
+    /// assert_eq!(doc.at_path("/other").unwrap().anchor(), None);
+    /// ```
+    pub fn anchor(&self) -> Option<&'doc str> {
+        let anchor = unsafe { fy_node_get_anchor(self.as_ptr()) };
+        if anchor.is_null() {
+            return None;
+        }
+        let mut len: size_t = 0;
+        let text = unsafe { fy_anchor_get_text(anchor, &mut len) };
+        if text.is_null() || len > isize::MAX as usize {
+            return None;
+        }
+        let bytes = unsafe { slice::from_raw_parts(text as *const u8, len) };
+        std::str::from_utf8(bytes).ok()
+    }
+
     // ==================== Navigation ====================
 
     /// Navigates to a child node by path.
@@ -264,6 +357,159 @@ impl<'doc> NodeRef<'doc> {
         NonNull::new(node_ptr).map(|nn| NodeRef::new(nn, self.doc))
     }
 
+    /// Returns every node matching a path expression with wildcards.
+    ///
+    /// The expression uses `/`-separated segments like
+    /// [`at_path`](Self::at_path), plus:
+    /// - `*` - every child (sequence items, mapping values)
+    /// - `**` - this node and all its descendants, at any depth
+    ///
+    /// Other segments match a mapping key, or an index in a sequence.
+    /// Matches are returned in document order, each node at most once. An
+    /// empty expression matches this node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str(
+    ///     "servers:\n  - {host: a, port: 80}\n  - {host: b, port: 81}",
+    /// )
+    /// .unwrap();
+    /// let root = doc.root().unwrap();
+    ///
+    /// let hosts: Vec<&str> = root
+    ///     .query("/servers/*/host")
+    ///     .iter()
+    ///     .map(|n| n.scalar_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(hosts, ["a", "b"]);
+    /// assert_eq!(root.query("/**/port").len(), 2);
+    /// ```
+    pub fn query(&self, expr: &str) -> Vec<NodeRef<'doc>> {
+        let mut current = vec![*self];
+        for segment in expr.split('/').filter(|s| !s.is_empty()) {
+            let mut next = Vec::new();
+            for node in &current {
+                match segment {
+                    "*" => node.push_children(&mut next),
+                    "**" => node.push_descendants(&mut next),
+                    key => next.extend(node.child(key)),
+                }
+            }
+            // `**` can reach the same node along several routes
+            let mut seen = std::collections::HashSet::new();
+            next.retain(|n| seen.insert(n.as_ptr()));
+            current = next;
+        }
+        current
+    }
+
+    /// Returns every node matching a glob-style path pattern.
+    ///
+    /// An alias for [`query`](Self::query), for callers who think of the
+    /// pattern as a selector: `*` matches any single mapping key or
+    /// sequence index, `**` descends recursively, and other segments match
+    /// literally. Matching walks the tree directly (libfyaml's path lookup
+    /// has no globs), and no match gives an empty `Vec`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str(
+    ///     "services:\n  web: {host: w, port: 80}\n  db: {host: d, port: 5432}",
+    /// )
+    /// .unwrap();
+    /// let root = doc.root().unwrap();
+    /// assert_eq!(root.select("/services/*/host").len(), 2);
+    /// assert!(root.select("/services/*/user").is_empty());
+    /// ```
+    #[inline]
+    pub fn select(&self, pattern: &str) -> Vec<NodeRef<'doc>> {
+        self.query(pattern)
+    }
+
+    /// Returns the collection that contains this node.
+    ///
+    /// Returns `None` for the document root. Both the key and the value of
+    /// a mapping pair have the mapping as their parent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("server: {ports: [80, 443]}").unwrap();
+    /// let port = doc.at_path("/server/ports/1").unwrap();
+    /// let ports = port.parent().unwrap();
+    /// assert_eq!(ports.seq_len().unwrap(), 2);
+    /// assert!(ports.parent().unwrap().parent().unwrap().parent().is_none());
+    /// ```
+    pub fn parent(&self) -> Option<NodeRef<'doc>> {
+        let parent_ptr = unsafe { fy_node_get_parent(self.as_ptr()) };
+        NonNull::new(parent_ptr).map(|nn| NodeRef::new(nn, self.doc))
+    }
+
+    /// Returns the key this node is stored under, if it is a mapping value.
+    ///
+    /// Returns `None` for the root, sequence items and key nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("db: {port: 5432}\nhosts: [a]").unwrap();
+    /// let port = doc.at_path("/db/port").unwrap();
+    /// assert_eq!(port.key().unwrap().scalar_str().unwrap(), "port");
+    /// assert!(doc.at_path("/hosts/0").unwrap().key().is_none());
+    /// ```
+    pub fn key(&self) -> Option<NodeRef<'doc>> {
+        let parent = self.parent()?;
+        if !parent.is_mapping() {
+            return None;
+        }
+        parent
+            .map_iter()
+            .find(|(_, value)| value.as_ptr() == self.as_ptr())
+            .map(|(key, _)| key)
+    }
+
+    /// Looks up one child by mapping key or sequence index.
+    fn child(&self, segment: &str) -> Option<NodeRef<'doc>> {
+        match self.kind() {
+            NodeType::Mapping => self.map_get(segment),
+            NodeType::Sequence => segment
+                .parse::<i32>()
+                .ok()
+                .filter(|i| *i >= 0)
+                .and_then(|i| self.seq_get(i)),
+            NodeType::Scalar => None,
+        }
+    }
+
+    fn push_children(&self, out: &mut Vec<NodeRef<'doc>>) {
+        match self.kind() {
+            NodeType::Mapping => out.extend(self.map_iter().map(|(_, v)| v)),
+            NodeType::Sequence => out.extend(self.seq_iter()),
+            NodeType::Scalar => {}
+        }
+    }
+
+    /// Pushes this node, then its descendants in pre-order.
+    fn push_descendants(&self, out: &mut Vec<NodeRef<'doc>>) {
+        out.push(*self);
+        let start = out.len();
+        self.push_children(out);
+        let children: Vec<NodeRef<'doc>> = out.drain(start..).collect();
+        for child in children {
+            child.push_descendants(out);
+        }
+    }
+
     // ==================== Length Operations ====================
 
     /// Returns the number of items in a sequence node.
@@ -298,6 +544,84 @@ impl<'doc> NodeRef<'doc> {
         Ok(len as usize)
     }
 
+    /// Returns how many levels of collections this subtree spans.
+    ///
+    /// A scalar has depth 0 and a collection one more than its deepest
+    /// child, so `[]` and `{a: 1}` have depth 1 and `{a: [1]}` depth 2.
+    /// Mapping keys count like values. Aliases are not followed: `*name`
+    /// is a scalar at its own position.
+    ///
+    /// The walk uses a heap-allocated stack, so it is safe to call on
+    /// untrusted input however deeply it nests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("a: {b: [1, 2]}\nc: 3").unwrap();
+    /// assert_eq!(doc.root().unwrap().depth(), 3);
+    /// assert_eq!(doc.at_path("/c").unwrap().depth(), 0);
+    /// ```
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        self.walk_subtree(|node, level| {
+            let spans = if node.is_scalar() { level } else { level + 1 };
+            depth = depth.max(spans);
+        });
+        depth
+    }
+
+    /// Returns the number of nodes in this subtree, this node, mapping
+    /// keys and alias nodes included.
+    ///
+    /// Like [`depth`](Self::depth), aliases are counted once where they
+    /// appear rather than expanded, and the walk does not recurse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("a: &x [1, 2]\nb: *x").unwrap();
+    /// // root, keys a and b, the sequence, its two items, the alias
+    /// assert_eq!(doc.root().unwrap().node_count(), 7);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        self.walk_subtree(|_, _| count += 1);
+        count
+    }
+
+    /// Visits each node of the subtree once, keys included, in pre-order,
+    /// with its nesting level below this node.
+    ///
+    /// Iterative, and a node reached a second time is skipped, so neither
+    /// deep nesting nor shared nodes can make the walk run away.
+    fn walk_subtree<F: FnMut(NodeRef<'doc>, usize)>(&self, mut visit: F) {
+        let mut seen = HashSet::new();
+        let mut stack = vec![(*self, 0)];
+        while let Some((node, level)) = stack.pop() {
+            if !seen.insert(node.as_ptr()) {
+                continue;
+            }
+            visit(node, level);
+            let start = stack.len();
+            match node.kind() {
+                NodeType::Mapping => {
+                    for (key, value) in node.map_iter() {
+                        stack.push((key, level + 1));
+                        stack.push((value, level + 1));
+                    }
+                }
+                NodeType::Sequence => stack.extend(node.seq_iter().map(|item| (item, level + 1))),
+                NodeType::Scalar => {}
+            }
+            // Pop children in document order
+            stack[start..].reverse();
+        }
+    }
+
     // ==================== Sequence Access ====================
 
     /// Gets a sequence item by index.
@@ -334,11 +658,37 @@ impl<'doc> NodeRef<'doc> {
         SeqIter::new(*self)
     }
 
+    /// Returns an iterator over `(index, item)` pairs in a sequence node.
+    ///
+    /// Indices match [`seq_get`](Self::seq_get) positions. If this is not a
+    /// sequence, the iterator will be empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("[a, b]").unwrap();
+    /// let root = doc.root().unwrap();
+    ///
+    /// for (i, item) in root.seq_iter_indexed() {
+    ///     assert_eq!(root.seq_get(i as i32).unwrap().scalar_str(), item.scalar_str());
+    /// }
+    /// ```
+    #[inline]
+    pub fn seq_iter_indexed(&self) -> impl Iterator<Item = (usize, NodeRef<'doc>)> {
+        self.seq_iter().enumerate()
+    }
+
     // ==================== Mapping Access ====================
 
     /// Looks up a value in this mapping by string key.
     ///
     /// Returns `None` if the key is not found or this is not a mapping.
+    ///
+    /// libfyaml scans the pairs for each call, so this is O(n) in the
+    /// mapping size. For many lookups against a large mapping, build a
+    /// [`KeyIndex`] once with [`build_key_index`](Self::build_key_index).
     pub fn map_get(&self, key: &str) -> Option<NodeRef<'doc>> {
         if !self.is_mapping() {
             return None;
@@ -349,6 +699,13 @@ impl<'doc> NodeRef<'doc> {
         NonNull::new(node_ptr).map(|nn| NodeRef::new(nn, self.doc))
     }
 
+    /// Builds a hash index of this mapping's keys for O(1) lookups.
+    ///
+    /// If this is not a mapping, the index is empty. See [`KeyIndex`].
+    pub fn build_key_index(&self) -> KeyIndex<'doc> {
+        KeyIndex::new(*self)
+    }
+
     /// Returns an iterator over key-value pairs in a mapping node.
     ///
     /// If this is not a mapping, the iterator will be empty.
@@ -370,6 +727,173 @@ impl<'doc> NodeRef<'doc> {
         MapIter::new(*self)
     }
 
+    /// Returns an iterator over the mapping pairs whose key satisfies `pred`.
+    ///
+    /// The predicate receives the key's string. Only string keys are
+    /// considered, as [`inferred_scalar_type`](Self::inferred_scalar_type)
+    /// reports them: keys such as `42`, `true` or `~`, alias keys, keys
+    /// that are not scalars and keys that are not valid UTF-8 are skipped.
+    /// Quote a key (`'42'`) to have it matched. If this is not a mapping,
+    /// the iterator will be empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("x_a: 1\nname: n\nx_b: 2").unwrap();
+    /// let root = doc.root().unwrap();
+    ///
+    /// let keys: Vec<&str> = root
+    ///     .map_iter_matching(|k| k.starts_with("x_"))
+    ///     .map(|(k, _)| k.scalar_str().unwrap())
+    ///     .collect();
+    /// assert_eq!(keys, ["x_a", "x_b"]);
+    /// ```
+    pub fn map_iter_matching<F>(
+        &self,
+        pred: F,
+    ) -> impl Iterator<Item = (NodeRef<'doc>, NodeRef<'doc>)>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.map_iter().filter(move |(key, _)| {
+            key.style() != NodeStyle::Alias
+                && key.inferred_scalar_type() == Some(ScalarType::Str)
+                && matches!(key.scalar_str(), Ok(k) if pred(k))
+        })
+    }
+
+    // ==================== Owned Conversion ====================
+
+    /// Converts this node and everything below it into an owned [`Value`].
+    ///
+    /// Shorthand for [`Value::from_node_ref`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Value};
+    ///
+    /// let doc = Document::parse_str("db: {host: a, port: 5432}").unwrap();
+    /// let db = doc.at_path("/db").unwrap().to_value().unwrap();
+    /// drop(doc);
+    /// assert_eq!(db["port"], Value::from(5432));
+    /// ```
+    pub fn to_value(&self) -> Result<Value> {
+        Value::from_node_ref(*self)
+    }
+
+    /// Copies the pairs of a mapping node into owned `(key, value)` pairs.
+    ///
+    /// Keys are taken as their scalar text and values are converted like
+    /// [`to_value`](Self::to_value). Pairs keep their document order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this is not a mapping, or if a key
+    /// is not a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Value};
+    ///
+    /// let doc = Document::parse_str("name: app\nport: 80\ntags: [a]").unwrap();
+    /// let pairs = doc.root().unwrap().to_owned_pairs().unwrap();
+    /// assert_eq!(pairs[0], ("name".to_string(), Value::from("app")));
+    /// assert_eq!(pairs[1].0, "port");
+    /// assert!(doc.at_path("/tags").unwrap().to_owned_pairs().is_err());
+    /// ```
+    pub fn to_owned_pairs(&self) -> Result<Vec<(String, Value)>> {
+        let mut pairs = Vec::with_capacity(self.map_len()?);
+        for (key, value) in self.map_iter() {
+            if !key.is_scalar() {
+                return Err(Error::TypeMismatch {
+                    expected: "scalar key",
+                    got: "non-scalar key",
+                });
+            }
+            pairs.push((key.scalar_str()?.to_owned(), value.to_value()?));
+        }
+        Ok(pairs)
+    }
+
+    // ==================== Source Location ====================
+
+    /// Returns the byte range of the original input covered by this node.
+    ///
+    /// The range goes from the node's start mark to its end mark, so
+    /// `&input[span]` is the node's source text: quotes included for quoted
+    /// scalars, brackets included for flow collections. Offsets are relative
+    /// to the whole input, which for multi-document streams means the
+    /// stream, not the current document.
+    ///
+    /// Returns `None` if libfyaml has no marks for the node. Nodes inserted
+    /// through the [`Editor`](crate::Editor) report offsets into the snippet
+    /// they were built from, not into the original input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let input = "name: app\nports: [80, 443]\n";
+    /// let doc = Document::parse_str(input).unwrap();
+    /// let span = doc.at_path("/ports").unwrap().span().unwrap();
+    /// assert_eq!(&input[span], "[80, 443]");
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        let start = unsafe { fy_node_get_start_mark(self.as_ptr()) };
+        let end = unsafe { fy_node_get_end_mark(self.as_ptr()) };
+        if start.is_null() || end.is_null() {
+            return None;
+        }
+        // SAFETY: non-null marks point into tokens owned by the document
+        let (start, end) = unsafe { ((*start).input_pos, (*end).input_pos) };
+        if end < start {
+            return None;
+        }
+        Some(start..end)
+    }
+
+    /// Returns where this node starts in the original input.
+    ///
+    /// The location comes from the node's start mark: for a mapping value
+    /// that is the value itself, not its key, and for a block collection it
+    /// is its first entry. Lines and columns are 1-based. Offsets follow the
+    /// same rules as [`span`](Self::span), including for multi-document
+    /// streams.
+    ///
+    /// Returns `None` if libfyaml has no mark for the node, as for nodes
+    /// the [`Editor`](crate::Editor) builds without parsing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("name: app\nserver:\n  port: 80\n").unwrap();
+    /// let loc = doc.at_path("/server/port").unwrap().location().unwrap();
+    /// assert_eq!((loc.line, loc.column, loc.offset), (3, 9, 26));
+    /// ```
+    pub fn location(&self) -> Option<Location> {
+        let mark = unsafe { fy_node_get_start_mark(self.as_ptr()) };
+        if mark.is_null() {
+            return None;
+        }
+        // SAFETY: non-null marks point into tokens owned by the document
+        let mark = unsafe { &*mark };
+        // libfyaml counts lines and columns from 0
+        let line = usize::try_from(mark.line).ok()?;
+        let column = usize::try_from(mark.column).ok()?;
+        Some(Location {
+            line: line + 1,
+            column: column + 1,
+            offset: mark.input_pos,
+        })
+    }
+
     // ==================== Emission ====================
 
     /// Emits this node as a YAML string.
@@ -379,14 +903,157 @@ impl<'doc> NodeRef<'doc> {
     ///
     /// This always allocates a new string. If the emitted content contains
     /// invalid UTF-8 (rare), invalid bytes are replaced with U+FFFD.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UndefinedAlias`] if the document has an alias built
+    /// with [`Editor::build_alias`](crate::Editor::build_alias) that refers to
+    /// an anchor no earlier node defines.
     pub fn emit(&self) -> Result<String> {
-        let ptr = unsafe { fy_emit_node_to_string(self.as_ptr(), config::emit_flags()) };
-        if ptr.is_null() {
-            return Err(Error::Ffi("fy_emit_node_to_string returned null"));
+        self.emit_with(&EmitOptions::default())
+    }
+
+    /// Emits this node as a YAML string using the given options.
+    ///
+    /// With [`EmitOptions::default()`] this is identical to
+    /// [`emit`](Self::emit). Options that restyle or reorder nodes
+    /// (`escape_unicode`, `quote_ambiguous_strings`, `flow_level`,
+    /// `sort_keys`, `sort_keys_from_depth`) are applied to a scratch copy, so
+    /// the document is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOption`] if `options.indent` is out of range,
+    /// and [`Error::UndefinedAlias`] as for [`emit`](Self::emit).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, EmitOptions};
+    ///
+    /// let doc = Document::parse_str("db:\n  host: h\n  ports: [1, 2]").unwrap();
+    /// let opts = EmitOptions {
+    ///     indent: 4,
+    ///     ..EmitOptions::default()
+    /// };
+    /// let yaml = doc.at_path("/db").unwrap().emit_with(&opts).unwrap();
+    /// assert!(yaml.starts_with("host: h\n"));
+    /// ```
+    pub fn emit_with(&self, options: &EmitOptions) -> Result<String> {
+        self.doc.check_alias_targets()?;
+        let flags = options.emit_flags()?;
+        if !options.restyles() {
+            return finish_emit(emit_node(self.as_ptr(), flags)?, options);
+        }
+        let mut scratch = Document::new()?;
+        {
+            let mut ed = scratch.edit();
+            let node = ed.copy_node(*self)?;
+            ed.set_root(node)?;
+        }
+        let root = scratch
+            .root()
+            .ok_or(Error::Ffi("fy_document_set_root failed"))?;
+        restyle_for_emit(root, options);
+        finish_emit(emit_node(root.as_ptr(), flags)?, options)
+    }
+
+    /// Emits this node into `writer` without building a `String`.
+    ///
+    /// The streaming counterpart of [`emit`](Self::emit). The node is
+    /// written as the root of its document's framing: output ends with a
+    /// newline, and if the document has explicit directives or a `---`
+    /// marker, those are written first. See [`Document::emit_to_writer`]
+    /// for buffering advice.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UndefinedAlias`] as for [`emit`](Self::emit), and
+    /// [`Error::IoError`] with the writer's error if a write or the final
+    /// flush fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("db:\n  host: h\n  port: 5432").unwrap();
+    /// let mut out = Vec::new();
+    /// doc.at_path("/db").unwrap().emit_to_writer(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "host: h\nport: 5432\n");
+    /// ```
+    pub fn emit_to_writer<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        self.doc.check_alias_targets()?;
+        let flags = EmitOptions::default().emit_flags()?;
+        let doc_ptr = self.doc.as_ptr();
+        let node_ptr = self.as_ptr();
+        emit_to_writer(writer, flags, |emitter| unsafe {
+            // Start/end set up the document state (tag directives) that
+            // the node's tags are written against
+            let ret = fy_emit_document_start(emitter, doc_ptr, node_ptr);
+            if ret != 0 {
+                return ret;
+            }
+            let ret = fy_emit_root_node(emitter, node_ptr);
+            if ret != 0 {
+                return ret;
+            }
+            fy_emit_document_end(emitter)
+        })
+    }
+
+    /// Emits this scalar on its own, forcing the given style.
+    ///
+    /// The scalar is copied into a scratch document, so this node is left
+    /// untouched. Useful for generating code or config fragments that need
+    /// a specific quoting regardless of how the source was written.
+    ///
+    /// libfyaml validates the style against the content: a style that cannot
+    /// represent it (e.g. [`NodeStyle::Plain`] for `"a: b"`) falls back to
+    /// one that can.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TypeMismatch`] if this node is not a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, NodeStyle};
+    ///
+    /// let doc = Document::parse_str("name: app").unwrap();
+    /// let node = doc.at_path("/name").unwrap();
+    /// assert_eq!(node.emit_scalar_styled(NodeStyle::DoubleQuoted).unwrap(), "\"app\"");
+    /// assert_eq!(node.emit_scalar_styled(NodeStyle::SingleQuoted).unwrap(), "'app'");
+    /// ```
+    pub fn emit_scalar_styled(&self, style: NodeStyle) -> Result<String> {
+        if !self.is_scalar() {
+            return Err(Error::TypeMismatch {
+                expected: "scalar",
+                got: "non-scalar",
+            });
         }
-        // SAFETY: ptr is a valid malloc'd C string from libfyaml
-        Ok(unsafe { take_c_string(ptr) })
+        let mut scratch = Document::new()?;
+        {
+            let mut ed = scratch.edit();
+            let mut node = ed.copy_node(*self)?;
+            ed.set_style(&mut node, style);
+            ed.set_root(node)?;
+        }
+        match scratch.root() {
+            Some(root) => root.emit(),
+            None => Err(Error::Ffi("fy_document_set_root failed")),
+        }
+    }
+}
+
+fn emit_node(node_ptr: *mut fy_node, flags: u32) -> Result<String> {
+    let ptr = unsafe { fy_emit_node_to_string(node_ptr, flags) };
+    if ptr.is_null() {
+        return Err(Error::Ffi("fy_emit_node_to_string returned null"));
     }
+    // SAFETY: ptr is a valid malloc'd C string from libfyaml
+    Ok(unsafe { take_c_string(ptr) })
 }
 
 impl fmt::Display for NodeRef<'_> {
@@ -419,6 +1086,28 @@ mod tests {
         assert_eq!(node.scalar_str().unwrap(), "value");
     }
 
+    #[test]
+    fn test_span_slices_source_text() {
+        let input = "name: app\nquoted: \"hi there\"\nnested:\n  key: [1, 2]\nlast: x\n";
+        let doc = Document::parse_str(input).unwrap();
+
+        let text = |path: &str| &input[doc.at_path(path).unwrap().span().unwrap()];
+        assert_eq!(text("/name"), "app");
+        assert_eq!(text("/quoted"), "\"hi there\"");
+        assert_eq!(text("/nested/key"), "[1, 2]");
+        assert_eq!(text("/nested/key/1"), "2");
+        assert_eq!(text("/last"), "x");
+    }
+
+    #[test]
+    fn test_span_of_key_node() {
+        let input = "alpha: 1\nbeta: 2";
+        let doc = Document::parse_str(input).unwrap();
+        let (key, value) = doc.root().unwrap().map_iter().nth(1).unwrap();
+        assert_eq!(key.span(), Some(9..13));
+        assert_eq!(value.span(), Some(15..16));
+    }
+
     #[test]
     fn test_is_quoted() {
         let doc = Document::parse_str("plain: value\nquoted: 'value'").unwrap();
@@ -441,9 +1130,58 @@ mod tests {
         assert_eq!(doc.root().unwrap().seq_len().unwrap(), 3);
     }
 
+    #[test]
+    fn test_inferred_scalar_type() {
+        let doc = Document::parse_str("t: true\ni: 42\nf: 3.14\nn: ~\nq: '42'\ns: text\nm: {a: 1}")
+            .unwrap();
+        let ty = |path: &str| doc.at_path(path).unwrap().inferred_scalar_type();
+        assert_eq!(ty("/t"), Some(ScalarType::Bool));
+        assert_eq!(ty("/i"), Some(ScalarType::Int));
+        assert_eq!(ty("/f"), Some(ScalarType::Float));
+        assert_eq!(ty("/n"), Some(ScalarType::Null));
+        assert_eq!(ty("/q"), Some(ScalarType::Str));
+        assert_eq!(ty("/s"), Some(ScalarType::Str));
+        assert_eq!(ty("/m"), None);
+    }
+
     #[test]
     fn test_map_len() {
         let doc = Document::parse_str("a: 1\nb: 2").unwrap();
         assert_eq!(doc.root().unwrap().map_len().unwrap(), 2);
     }
+
+    #[test]
+    fn test_emit_scalar_styled_double_quoted() {
+        let doc = Document::parse_str("name: plain text").unwrap();
+        let node = doc.at_path("/name").unwrap();
+        let out = node.emit_scalar_styled(NodeStyle::DoubleQuoted).unwrap();
+        assert_eq!(out, "\"plain text\"");
+        // Source node keeps its style
+        assert_eq!(node.style(), NodeStyle::Plain);
+    }
+
+    #[test]
+    fn test_emit_scalar_styled_literal() {
+        let doc = Document::parse_str("script: \"echo hi\\nexit 0\\n\"").unwrap();
+        let node = doc.at_path("/script").unwrap();
+        let out = node.emit_scalar_styled(NodeStyle::Literal).unwrap();
+        assert!(out.starts_with('|'), "got: {:?}", out);
+        assert!(out.contains("\n  echo hi\n  exit 0"), "got: {:?}", out);
+
+        let reparsed = Document::parse_str(&out).unwrap();
+        assert_eq!(
+            reparsed.root().unwrap().scalar_str().unwrap(),
+            "echo hi\nexit 0\n"
+        );
+    }
+
+    #[test]
+    fn test_emit_scalar_styled_rejects_collections() {
+        let doc = Document::parse_str("list: [1, 2]").unwrap();
+        let node = doc.at_path("/list").unwrap();
+        assert!(matches!(
+            node.emit_scalar_styled(NodeStyle::DoubleQuoted),
+            Err(Error::TypeMismatch { .. })
+        ));
+    }
 }
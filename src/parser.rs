@@ -8,14 +8,17 @@
 //! input buffer. The [`InputOwnership::Parser`] variant ensures the parser
 //! outlives its documents, preventing use-after-free.
 
-use crate::config;
+use crate::config::{self, ParseOptions};
 use crate::diag::Diag;
 use crate::document::{Document, InputOwnership};
 use crate::error::{Error, Result};
+use crate::event::EventIter;
 use crate::ffi_util::malloc_copy;
 use fyaml_sys::*;
 use libc::{c_void, setvbuf, _IOLBF};
+use std::io::Read;
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::os::fd::AsRawFd;
 use std::ptr::{self, NonNull};
 use std::rc::Rc;
@@ -32,17 +35,23 @@ pub(crate) struct ParserInner {
     parser_ptr: *mut fy_parser,
     /// Diagnostic handler that captures errors silently (must outlive parser)
     diag: Option<Diag>,
+    /// Input buffer borrowed by libfyaml (must outlive parser)
+    _input: Option<Vec<u8>>,
+    /// Address range of the in-memory input, freed with the parser
+    input_span: Option<Range<usize>>,
+    /// Options the parser was created with, for the per-document limits
+    options: ParseOptions,
     /// Marker to ensure !Send + !Sync
     _marker: PhantomData<*mut ()>,
 }
 
 impl ParserInner {
-    fn new() -> Result<Self> {
+    fn new(options: &ParseOptions) -> Result<Self> {
         // Create diagnostic handler to suppress stderr output and capture errors
         let diag = Diag::new();
         let diag_ptr = diag.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null_mut());
 
-        let cfg = config::stream_parse_cfg_with_diag(diag_ptr);
+        let cfg = config::stream_parse_cfg_with_diag(diag_ptr, options);
         let parser_ptr = unsafe { fy_parser_create(&cfg) };
         if parser_ptr.is_null() {
             return Err(Error::Ffi("fy_parser_create returned null"));
@@ -50,6 +59,9 @@ impl ParserInner {
         Ok(ParserInner {
             parser_ptr,
             diag,
+            _input: None,
+            input_span: None,
+            options: options.clone(),
             _marker: PhantomData,
         })
     }
@@ -59,6 +71,13 @@ impl ParserInner {
         self.parser_ptr
     }
 
+    /// Returns `true` if the `len` bytes at `ptr` lie in the input buffer,
+    /// which stays valid for as long as the parser.
+    pub(crate) fn input_contains(&self, ptr: *const u8, len: usize) -> bool {
+        let start = ptr as usize;
+        matches!(&self.input_span, Some(span) if start >= span.start && start + len <= span.end)
+    }
+
     /// Returns the first collected error as an Error, or a fallback if no errors collected.
     pub(crate) fn first_error_or(&self, fallback_msg: &'static str) -> Error {
         self.diag
@@ -108,13 +127,27 @@ impl Drop for ParserInner {
 /// ```
 pub struct FyParser {
     inner: Rc<ParserInner>,
+    /// Input kept by recovering parsers to restart after a broken document.
+    recovery: Option<Rc<Recovery>>,
+}
+
+/// What a recovering parser needs to restart past a parse error.
+struct Recovery {
+    input: String,
+    options: ParseOptions,
 }
 
 impl FyParser {
     /// Creates a new YAML parser with default configuration.
     fn new() -> Result<Self> {
+        Self::with_options(&ParseOptions::default())
+    }
+
+    /// Creates a new YAML parser with the given options.
+    fn with_options(options: &ParseOptions) -> Result<Self> {
         Ok(FyParser {
-            inner: Rc::new(ParserInner::new()?),
+            inner: Rc::new(ParserInner::new(options)?),
+            recovery: None,
         })
     }
 
@@ -135,15 +168,125 @@ impl FyParser {
     /// assert_eq!(docs.len(), 2);
     /// ```
     pub fn from_string(yaml: &str) -> Result<Self> {
-        let parser = FyParser::new()?;
+        Self::from_string_with(yaml, &ParseOptions::default())
+    }
+
+    /// Creates a parser for the given YAML string using the given options.
+    ///
+    /// The options apply to every document in the stream. With
+    /// `parse_comments` enabled (the default), each produced [`Document`]
+    /// keeps its comments and [`Document::emit`] reproduces them. A document
+    /// over `max_depth` or `max_nodes` is yielded as
+    /// [`Error::LimitExceeded`] and iteration continues with the next one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{FyParser, ParseOptions};
+    ///
+    /// let yaml = "# first\na: 1\n---\n# second\nb: 2\n";
+    /// let parser = FyParser::from_string_with(yaml, &ParseOptions::default()).unwrap();
+    /// let emitted: Vec<String> = parser
+    ///     .doc_iter()
+    ///     .map(|doc| doc.unwrap().emit().unwrap())
+    ///     .collect();
+    /// assert!(emitted[0].contains("# first"));
+    /// assert!(emitted[1].contains("# second"));
+    /// ```
+    pub fn from_string_with(yaml: &str, options: &ParseOptions) -> Result<Self> {
+        let mut inner = ParserInner::new(options)?;
 
         let buf = unsafe { malloc_copy(yaml.as_bytes())? };
-        let ret = unsafe { fy_parser_set_malloc_string(parser.inner.as_ptr(), buf, yaml.len()) };
+        let ret = unsafe { fy_parser_set_malloc_string(inner.as_ptr(), buf, yaml.len()) };
         if ret != 0 {
             unsafe { libc::free(buf as *mut c_void) };
             return Err(Error::Ffi("fy_parser_set_malloc_string failed"));
         }
+        // The parser now owns `buf` and frees it when destroyed
+        inner.input_span = Some(buf as usize..buf as usize + yaml.len());
 
+        Ok(FyParser {
+            inner: Rc::new(inner),
+            recovery: None,
+        })
+    }
+
+    /// Creates a parser for a multi-document stream read from `reader`.
+    ///
+    /// The whole input is read into an owned buffer first, which the parser
+    /// keeps alive for as long as any document parsed from it, so no
+    /// further copy is made. See [`Document::from_reader`] for single
+    /// documents.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IoError`] with the reader's error if reading fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::FyParser;
+    ///
+    /// let input: &[u8] = b"a: 1\n---\nb: 2\n";
+    /// let parser = FyParser::from_reader(input).unwrap();
+    /// assert_eq!(parser.doc_iter().count(), 2);
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut inner = ParserInner::new(&ParseOptions::default())?;
+        // SAFETY: fy_parser_set_string borrows the input; the Vec's heap
+        // buffer does not move when the Vec is stored in `inner`, which
+        // drops it only after destroying the parser.
+        let ret = unsafe {
+            fy_parser_set_string(inner.as_ptr(), bytes.as_ptr() as *const i8, bytes.len())
+        };
+        if ret != 0 {
+            return Err(Error::Ffi("fy_parser_set_string failed"));
+        }
+        let start = bytes.as_ptr() as usize;
+        inner.input_span = Some(start..start + bytes.len());
+        inner._input = Some(bytes);
+
+        Ok(FyParser {
+            inner: Rc::new(inner),
+            recovery: None,
+        })
+    }
+
+    /// Creates a parser for the given YAML string that skips broken documents.
+    ///
+    /// With a regular parser, the stream ends at the first parse error. A
+    /// recovering parser's [`doc_iter`](Self::doc_iter) instead yields the
+    /// error, skips the broken document up to the next `---` document marker,
+    /// and resumes parsing from there. Line numbers in the
+    /// yielded errors are relative to the whole input.
+    ///
+    /// Documents yielded after a recovery come from a fresh internal parser;
+    /// their [`span`](crate::NodeRef::span) offsets are relative to the point
+    /// where parsing resumed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::FyParser;
+    ///
+    /// let yaml = "a: 1\n---\nb: [1, 2\n---\nc: 3\n";
+    /// let parser = FyParser::from_string_recovering(yaml).unwrap();
+    /// let results: Vec<_> = parser.doc_iter().collect();
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results[0].is_ok());
+    /// assert!(results[1].is_err());
+    /// assert!(results[2].is_ok());
+    /// ```
+    pub fn from_string_recovering(yaml: &str) -> Result<Self> {
+        let options = ParseOptions::default();
+        let mut parser = FyParser::from_string_with(yaml, &options)?;
+        parser.recovery = Some(Rc::new(Recovery {
+            input: yaml.to_owned(),
+            options,
+        }));
         Ok(parser)
     }
 
@@ -210,8 +353,109 @@ impl FyParser {
         DocumentIterator {
             inner: Rc::clone(&self.inner),
             done: false,
+            recovery: self.recovery.as_ref().map(|r| RecoveryState {
+                recovery: Rc::clone(r),
+                chunk_start: 0,
+                line_offset: 0,
+                loaded: 0,
+            }),
         }
     }
+
+    /// Returns an iterator over the stream's parse events.
+    ///
+    /// Events are produced one at a time and no document is built, so this
+    /// handles inputs too large to load. Scalars borrow their text from the
+    /// parser's input where possible, which the `'_` lifetime keeps alive.
+    ///
+    /// Events and documents are read from the same position in the stream:
+    /// do not mix this with [`doc_iter`](Self::doc_iter) on one parser.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Event, FyParser};
+    ///
+    /// let parser = FyParser::from_string("a: [1, 'two']").unwrap();
+    /// let scalars: Vec<String> = parser
+    ///     .events()
+    ///     .filter_map(|event| match event.unwrap() {
+    ///         Event::Scalar { value, .. } => Some(value.into_owned()),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(scalars, ["a", "1", "two"]);
+    /// ```
+    pub fn events(&self) -> EventIter<'_> {
+        EventIter::new(&self.inner)
+    }
+
+    /// Counts the documents in the stream, consuming the parser.
+    ///
+    /// Each document is loaded and dropped before the next, so memory use
+    /// stays bounded by the largest document rather than the whole stream.
+    /// Useful for preallocating before a second pass with [`doc_iter`](Self::doc_iter)
+    /// on a fresh parser.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first parse error in the stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::FyParser;
+    ///
+    /// let parser = FyParser::from_string("---\na: 1\n---\nb: 2\n").unwrap();
+    /// assert_eq!(parser.count_documents().unwrap(), 2);
+    /// ```
+    pub fn count_documents(self) -> Result<usize> {
+        let mut count = 0;
+        for doc in self.doc_iter() {
+            doc?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Returns `true` if `line` is a `---` document start marker.
+fn is_document_start(line: &str) -> bool {
+    match line.strip_prefix("---") {
+        Some(rest) => rest.is_empty() || rest.starts_with(&[' ', '\t', '\r'][..]),
+        None => false,
+    }
+}
+
+/// Returns the byte offset where the document after the first `skip`
+/// documents of `text` starts, i.e. the position of its `---` marker.
+///
+/// A `---` line cannot occur inside content, so marker lines delimit
+/// documents even when a document fails to parse. Content before the first
+/// marker forms an implicit first document.
+fn nth_document_start(text: &str, skip: usize) -> Option<usize> {
+    let mut implicit_first = false;
+    let mut markers = Vec::new();
+    let mut pos = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(&['\n', '\r'][..]);
+        if is_document_start(trimmed) {
+            markers.push(pos);
+        } else if markers.is_empty() {
+            let content = trimmed.trim_start();
+            let is_content =
+                !(content.is_empty() || content.starts_with(&['#', '%'][..]) || content == "...");
+            implicit_first |= is_content;
+        }
+        pos += line.len();
+    }
+    if !implicit_first {
+        return markers.get(skip).copied();
+    }
+    match skip.checked_sub(1) {
+        Some(index) => markers.get(index).copied(),
+        None => Some(0),
+    }
 }
 
 // =============================================================================
@@ -233,6 +477,56 @@ impl FyParser {
 pub struct DocumentIterator {
     inner: Rc<ParserInner>,
     done: bool,
+    /// Set for iterators of recovering parsers.
+    recovery: Option<RecoveryState>,
+}
+
+/// Position of the current internal parser within a recovering parser's input.
+struct RecoveryState {
+    recovery: Rc<Recovery>,
+    /// Byte offset where the current internal parser's input starts.
+    chunk_start: usize,
+    /// Number of input lines before `chunk_start`.
+    line_offset: u32,
+    /// Documents loaded by the current internal parser.
+    loaded: usize,
+}
+
+impl DocumentIterator {
+    /// Rebases the error's line onto the whole input and, for recovering
+    /// iterators, restarts parsing at the document after the broken one.
+    fn recover(&mut self, err: Error) -> Error {
+        let state = match self.recovery.as_mut() {
+            Some(state) => state,
+            None => return err,
+        };
+
+        let mut err = err;
+        if let Error::ParseError(pe) = &mut err {
+            pe.line = pe.line.map(|l| l + state.line_offset);
+        }
+
+        let input = &state.recovery.input;
+        let chunk = &input[state.chunk_start..];
+        // The broken document is the one after those already loaded
+        let restart = match nth_document_start(chunk, state.loaded + 1) {
+            Some(offset) => state.chunk_start + offset,
+            None => return err,
+        };
+
+        match FyParser::from_string_with(&input[restart..], &state.recovery.options) {
+            Ok(parser) => {
+                let skipped = &input[state.chunk_start..restart];
+                state.line_offset += skipped.matches('\n').count() as u32;
+                state.chunk_start = restart;
+                state.loaded = 0;
+                self.inner = parser.inner;
+                self.done = false;
+            }
+            Err(e) => log::warn!("could not restart parser after error: {}", e),
+        }
+        err
+    }
 }
 
 impl Iterator for DocumentIterator {
@@ -252,20 +546,25 @@ impl Iterator for DocumentIterator {
             let has_error = unsafe { fy_parser_get_stream_error(self.inner.as_ptr()) };
             if has_error {
                 // Return rich error with line/column info from diagnostic
-                return Some(Err(self.inner.first_error_or("stream parse error")));
+                let err = self.inner.first_error_or("stream parse error");
+                return Some(Err(self.recover(err)));
             }
             return None;
         }
 
         log::trace!("  got next document !");
+        if let Some(state) = self.recovery.as_mut() {
+            state.loaded += 1;
+        }
 
         // Document keeps parser alive via Rc to ensure input buffer validity.
         // This is critical for memory safety: scalar data may reference
         // the parser's input buffer, so the parser must outlive the document.
-        Some(Ok(Document::from_raw_ptr(
+        let doc = Document::from_raw_ptr(
             NonNull::new(doc_ptr).unwrap(),
             InputOwnership::Parser(Rc::clone(&self.inner)),
-        )))
+        );
+        Some(doc.check_limits(&self.inner.options).map(|()| doc))
     }
 }
 
@@ -302,6 +601,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_string_with_keeps_comments_per_document() {
+        let yaml = "# header one\nname: a # inline one\n---\n# header two\nname: b # inline two\n";
+        let opts = ParseOptions {
+            parse_comments: true,
+            ..ParseOptions::default()
+        };
+        let parser = FyParser::from_string_with(yaml, &opts).unwrap();
+        let docs: Vec<_> = parser.doc_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(docs.len(), 2);
+
+        let first = docs[0].emit().unwrap();
+        assert!(first.contains("# header one"), "got:\n{}", first);
+        assert!(first.contains("# inline one"), "got:\n{}", first);
+        assert!(!first.contains("two"), "got:\n{}", first);
+
+        let second = docs[1].emit().unwrap();
+        assert!(second.contains("# header two"), "got:\n{}", second);
+        assert!(second.contains("# inline two"), "got:\n{}", second);
+    }
+
+    #[test]
+    fn test_from_string_with_comments_disabled() {
+        let opts = ParseOptions {
+            parse_comments: false,
+            ..ParseOptions::default()
+        };
+        let yaml = "a: 1 # note\n---\nb: 2 # other\n";
+        let parser = FyParser::from_string_with(yaml, &opts).unwrap();
+        for doc in parser.doc_iter() {
+            assert!(!doc.unwrap().emit().unwrap().contains('#'));
+        }
+    }
+
+    #[test]
+    fn test_from_string_with_limits_skip_oversized_documents() {
+        let opts = ParseOptions {
+            max_depth: Some(1),
+            ..ParseOptions::default()
+        };
+        let yaml = "a: 1\n---\na: {b: 1}\n---\n[1, 2]\n";
+        let parser = FyParser::from_string_with(yaml, &opts).unwrap();
+        let results: Vec<_> = parser.doc_iter().collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &Error::LimitExceeded {
+                limit: 1,
+                kind: "depth"
+            }
+        );
+        assert!(results[2].is_ok());
+    }
+
     #[test]
     fn test_parse_empty_stream() {
         let parser = FyParser::from_string("").unwrap();
@@ -345,6 +699,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_recovering_skips_broken_document() {
+        let yaml = "a: 1\n---\nb: [1, 2\n---\nc: 3\n";
+        let parser = FyParser::from_string_recovering(yaml).unwrap();
+        let results: Vec<_> = parser.doc_iter().collect();
+
+        assert_eq!(results.len(), 3, "got: {:?}", results);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.at_path("/a").unwrap().scalar_str().unwrap(), "1");
+        assert!(results[1].is_err());
+        let last = results[2].as_ref().unwrap();
+        assert_eq!(last.at_path("/c").unwrap().scalar_str().unwrap(), "3");
+    }
+
+    #[test]
+    fn test_recovering_continues_after_several_errors() {
+        let yaml = "---\nok1: x\n---\nbad: [\n---\nok2: y\n---\nkey: {a: 1\n---\nok3: z\n";
+        let parser = FyParser::from_string_recovering(yaml).unwrap();
+        let (oks, errs): (Vec<_>, Vec<_>) = parser.doc_iter().partition(|r| r.is_ok());
+        assert_eq!(errs.len(), 2);
+
+        let keys: Vec<String> = oks
+            .into_iter()
+            .map(|r| {
+                let doc = r.unwrap();
+                let root = doc.root().unwrap();
+                let (k, _) = root.map_iter().next().unwrap();
+                k.scalar_str().unwrap().to_owned()
+            })
+            .collect();
+        assert_eq!(keys, ["ok1", "ok2", "ok3"]);
+    }
+
+    #[test]
+    fn test_recovering_error_lines_are_absolute() {
+        let yaml = "a: 1\n---\nb: 2\n---\nc: [\n---\nd: 4\n---\ne: [\n";
+        let parser = FyParser::from_string_recovering(yaml).unwrap();
+        let lines: Vec<u32> = parser
+            .doc_iter()
+            .filter_map(|r| r.err())
+            .filter_map(|e| e.as_parse_error().and_then(|pe| pe.line()))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        // The second error is in the last document, which starts at line 8
+        assert!(lines[1] >= 9, "got: {:?}", lines);
+    }
+
+    #[test]
+    fn test_recovering_broken_last_document_ends_stream() {
+        let parser = FyParser::from_string_recovering("a: 1\n---\nb: [").unwrap();
+        let results: Vec<_> = parser.doc_iter().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
     #[test]
     fn test_parse_unclosed_bracket_error() {
         // Clearly invalid YAML: unclosed bracket
@@ -355,4 +764,19 @@ mod tests {
         let has_error = results.iter().any(|r| r.is_err());
         assert!(has_error, "unclosed bracket should produce parse error");
     }
+
+    #[test]
+    fn test_count_documents() {
+        let parser = FyParser::from_string("---\na: 1\n---\n- x\n- y\n---\nscalar\n").unwrap();
+        assert_eq!(parser.count_documents().unwrap(), 3);
+
+        let parser = FyParser::from_string("").unwrap();
+        assert_eq!(parser.count_documents().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_count_documents_surfaces_parse_error() {
+        let parser = FyParser::from_string("---\na: 1\n---\nb: [unclosed\n---\nc: 3\n").unwrap();
+        assert!(parser.count_documents().is_err());
+    }
 }
@@ -1,8 +1,12 @@
 //! Shared scalar parsing utilities for YAML type inference.
 //!
 //! This module provides consistent parsing logic used by both `ValueRef` (zero-copy)
-//! and `Value::from_node_ref()` (owned conversion).
+//! and `Value::from_node_ref()` (owned conversion). The functions operate on the
+//! text of plain scalars; the typed `parse_*` helpers and [`needs_quoting`] are
+//! re-exported at the crate root so callers can apply the same rules to
+//! scalars they read themselves.
 
+use crate::node::ScalarType;
 use crate::value::Number;
 
 /// Checks if a plain scalar represents null.
@@ -32,70 +36,84 @@ pub fn parse_bool(s: &str) -> Option<bool> {
 /// Supports decimal, hexadecimal (`0x`), octal (`0o`), and binary (`0b`) prefixes.
 /// Handles signs correctly, including edge case `i64::MIN`.
 pub fn parse_i64(s: &str) -> Option<i64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
+    parse_i128(s).and_then(|n| i64::try_from(n).ok())
+}
 
-    // Handle sign
-    let (neg, s) = if let Some(rest) = s.strip_prefix('-') {
-        (true, rest)
-    } else if let Some(rest) = s.strip_prefix('+') {
-        (false, rest)
-    } else {
-        (false, s)
-    };
+/// Parses a plain scalar as an unsigned 64-bit integer.
+///
+/// Supports decimal, hexadecimal (`0x`), octal (`0o`), and binary (`0b`) prefixes.
+/// Returns `None` for negative values.
+pub fn parse_u64(s: &str) -> Option<u64> {
+    parse_u128(s).and_then(|n| u64::try_from(n).ok())
+}
 
-    // Parse magnitude as i128 to handle i64::MIN correctly
-    // (i64::MIN's absolute value overflows i64)
-    let magnitude: i128 = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
-        i128::from_str_radix(hex, 16).ok()?
-    } else if let Some(oct) = s.strip_prefix("0o").or_else(|| s.strip_prefix("0O")) {
-        i128::from_str_radix(oct, 8).ok()?
-    } else if let Some(bin) = s.strip_prefix("0b").or_else(|| s.strip_prefix("0B")) {
-        i128::from_str_radix(bin, 2).ok()?
-    } else {
-        s.parse().ok()?
-    };
+/// Parses a plain scalar as a signed 128-bit integer.
+///
+/// Supports decimal, hexadecimal (`0x`), octal (`0o`), and binary (`0b`) prefixes.
+/// Handles signs correctly, including edge case `i128::MIN`.
+pub fn parse_i128(s: &str) -> Option<i128> {
+    let (neg, digits) = split_sign(s)?;
 
-    // Apply sign and check range
-    let value = if neg { -magnitude } else { magnitude };
-    i64::try_from(value).ok()
+    // Parse magnitude as u128 to handle i128::MIN correctly
+    // (i128::MIN's absolute value overflows i128)
+    let magnitude = parse_magnitude(digits)?;
+    if !neg {
+        return i128::try_from(magnitude).ok();
+    }
+    if magnitude <= i128::MAX as u128 {
+        Some(-(magnitude as i128))
+    } else if magnitude == i128::MIN.unsigned_abs() {
+        Some(i128::MIN)
+    } else {
+        None
+    }
 }
 
-/// Parses a plain scalar as an unsigned 64-bit integer.
+/// Parses a plain scalar as an unsigned 128-bit integer.
 ///
 /// Supports decimal, hexadecimal (`0x`), octal (`0o`), and binary (`0b`) prefixes.
 /// Returns `None` for negative values.
-pub fn parse_u64(s: &str) -> Option<u64> {
+pub fn parse_u128(s: &str) -> Option<u128> {
+    match split_sign(s)? {
+        // Negative values cannot be unsigned
+        (true, _) => None,
+        (false, digits) => parse_magnitude(digits),
+    }
+}
+
+/// Trims the scalar and splits off a leading `+` or `-`.
+///
+/// Returns `(negative, rest)`, or `None` for an empty scalar.
+fn split_sign(s: &str) -> Option<(bool, &str)> {
     let s = s.trim();
     if s.is_empty() {
         return None;
     }
-
-    // Handle sign - negative values return None for unsigned
-    let s = if let Some(rest) = s.strip_prefix('-') {
-        // Negative values cannot be unsigned (unless it's just "-" which is invalid anyway)
-        if !rest.is_empty() {
-            return None;
-        }
-        rest
+    Some(if let Some(rest) = s.strip_prefix('-') {
+        (true, rest)
     } else if let Some(rest) = s.strip_prefix('+') {
-        rest
+        (false, rest)
     } else {
-        s
-    };
+        (false, s)
+    })
+}
 
-    // Parse with different bases
-    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
-        u64::from_str_radix(hex, 16).ok()
+/// Parses an unsigned integer magnitude with an optional radix prefix.
+fn parse_magnitude(s: &str) -> Option<u128> {
+    let (digits, radix) = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        (hex, 16)
     } else if let Some(oct) = s.strip_prefix("0o").or_else(|| s.strip_prefix("0O")) {
-        u64::from_str_radix(oct, 8).ok()
+        (oct, 8)
     } else if let Some(bin) = s.strip_prefix("0b").or_else(|| s.strip_prefix("0B")) {
-        u64::from_str_radix(bin, 2).ok()
+        (bin, 2)
     } else {
-        s.parse().ok()
+        (s, 10)
+    };
+    // from_str_radix accepts its own leading `+`; the sign was already taken
+    if digits.starts_with('+') {
+        return None;
     }
+    u128::from_str_radix(digits, radix).ok()
 }
 
 /// Parses a plain scalar as a 64-bit float.
@@ -117,19 +135,82 @@ pub fn parse_f64(s: &str) -> Option<f64> {
     s.parse().ok()
 }
 
-/// Checks if a plain scalar string would be ambiguous with another YAML type.
+/// Checks if a string must be quoted to be written as a YAML scalar.
 ///
-/// Returns `true` if the string content, when emitted as a plain scalar,
-/// could be misinterpreted as null, boolean, or numeric. Such strings
-/// need quoting to roundtrip correctly as `Value::String`.
-#[inline]
+/// Returns `true` if writing `s` unquoted would not read back as the same
+/// string, either because it resolves to another type or because it is
+/// not a valid plain scalar:
+/// - it reads as null, a boolean or a number (`~`, `yes`, `0x1F`, `.inf`)
+/// - it is empty, or has leading or trailing whitespace
+/// - it starts with an indicator (`-`, `?` or `:` followed by a space or
+///   at the end, or any of `,[]{}#&*!|>'"%@` and backtick)
+/// - it contains `: ` or ` #`, ends with `:`, or contains a line break,
+///   tab or other control character
+///
+/// This mirrors the checks libfyaml's emitter makes before choosing the
+/// plain style. A `false` result means `s` can be written as is, in block
+/// context; inside flow collections `,[]{}` also need quoting.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::needs_quoting;
+///
+/// assert!(!needs_quoting("hello world"));
+/// assert!(!needs_quoting("a-b:c"));
+/// assert!(needs_quoting("true"));
+/// assert!(needs_quoting("- item"));
+/// assert!(needs_quoting("key: value"));
+/// assert!(needs_quoting("trailing "));
+/// ```
 pub fn needs_quoting(s: &str) -> bool {
+    resolves_to_non_string(s) || !is_valid_plain(s)
+}
+
+/// Checks if a plain scalar would resolve to null, a boolean or a number.
+#[inline]
+pub(crate) fn resolves_to_non_string(s: &str) -> bool {
     is_null(s) || parse_bool(s).is_some() || parse_number(s).is_some()
 }
 
+/// Checks if a plain scalar is read differently by YAML 1.1 and 1.2
+/// readers: the `yes`/`no`/`on`/`off` booleans and integers with a leading
+/// zero, which YAML 1.1 takes as octal.
+pub(crate) fn is_version_dependent(s: &str) -> bool {
+    if parse_bool(s).is_some() {
+        return !s.eq_ignore_ascii_case("true") && !s.eq_ignore_ascii_case("false");
+    }
+    let digits = s.strip_prefix(&['-', '+'][..]).unwrap_or(s);
+    digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Checks if `s` can be written as a plain scalar in block context.
+fn is_valid_plain(s: &str) -> bool {
+    let first = match s.chars().next() {
+        Some(c) => c,
+        None => return false,
+    };
+    let second = s[first.len_utf8()..].chars().next();
+
+    if first.is_whitespace() || s.ends_with(char::is_whitespace) {
+        return false;
+    }
+    match first {
+        '-' | '?' | ':' if matches!(second, None | Some(' ')) => return false,
+        ',' | '[' | ']' | '{' | '}' | '#' | '&' | '*' | '!' | '|' | '>' | '\'' | '"' | '%'
+        | '@' | '`' => return false,
+        _ => {}
+    }
+    if s.contains(": ") || s.contains(" #") || s.ends_with(':') {
+        return false;
+    }
+    !s.chars().any(|c| c.is_control())
+}
+
 /// Parses a plain scalar as a Number (for Value type inference).
 ///
-/// Tries i64 first, then u64, then f64 (only if contains `.` or exponent).
+/// Tries i64 first, then u64, then the 128-bit integers, then f64 (only if
+/// contains `.` or exponent).
 pub fn parse_number(s: &str) -> Option<Number> {
     let s = s.trim();
     if s.is_empty() {
@@ -150,6 +231,14 @@ pub fn parse_number(s: &str) -> Option<Number> {
         return Some(Number::UInt(n));
     }
 
+    // Integers beyond the 64-bit range
+    if let Some(n) = parse_u128(s) {
+        return Some(Number::U128(n));
+    }
+    if let Some(n) = parse_i128(s) {
+        return Some(Number::I128(n));
+    }
+
     // Try float (special values or decimal/exponent notation)
     // Special values
     if s.eq_ignore_ascii_case(".inf") || s.eq_ignore_ascii_case("+.inf") {
@@ -174,6 +263,23 @@ pub fn parse_number(s: &str) -> Option<Number> {
     None
 }
 
+/// Returns the type a plain scalar is inferred as.
+///
+/// Checks null, then boolean, then number, falling back to string.
+pub fn scalar_type(s: &str) -> ScalarType {
+    if is_null(s) {
+        ScalarType::Null
+    } else if parse_bool(s).is_some() {
+        ScalarType::Bool
+    } else {
+        match parse_number(s) {
+            Some(Number::Float(_)) => ScalarType::Float,
+            Some(_) => ScalarType::Int,
+            None => ScalarType::Str,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,6 +352,47 @@ mod tests {
         assert_eq!(parse_u64(&large.to_string()), Some(large));
     }
 
+    #[test]
+    fn test_parse_i128_boundaries() {
+        assert_eq!(parse_i128(&i128::MAX.to_string()), Some(i128::MAX));
+        assert_eq!(parse_i128(&i128::MIN.to_string()), Some(i128::MIN));
+        assert_eq!(
+            parse_i128("-0x80000000000000000000000000000000"),
+            Some(i128::MIN)
+        );
+        assert_eq!(
+            parse_i128("0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"),
+            Some(i128::MAX)
+        );
+        // Overflow returns None
+        assert_eq!(parse_i128("170141183460469231731687303715884105728"), None); // i128::MAX + 1
+        assert_eq!(parse_i128("-170141183460469231731687303715884105729"), None);
+        // i128::MIN - 1
+    }
+
+    #[test]
+    fn test_parse_i128_radix_and_sign() {
+        assert_eq!(parse_i128("-0b1010"), Some(-10));
+        assert_eq!(parse_i128("0o77"), Some(63));
+        assert_eq!(parse_i128("+42"), Some(42));
+        assert_eq!(parse_i128("-+42"), None);
+        assert_eq!(parse_i128("0x+5"), None);
+        assert_eq!(parse_i128(""), None);
+    }
+
+    #[test]
+    fn test_parse_u128_boundaries() {
+        assert_eq!(parse_u128(&u128::MAX.to_string()), Some(u128::MAX));
+        assert_eq!(
+            parse_u128("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"),
+            Some(u128::MAX)
+        );
+        // Overflow returns None
+        assert_eq!(parse_u128("340282366920938463463374607431768211456"), None); // u128::MAX + 1
+        assert_eq!(parse_u128("-1"), None);
+        assert_eq!(parse_u128("+7"), Some(7));
+    }
+
     #[test]
     fn test_parse_f64() {
         assert_eq!(parse_f64("2.5"), Some(2.5));
@@ -258,6 +405,101 @@ mod tests {
         assert!(parse_f64(".NaN").unwrap().is_nan());
     }
 
+    #[test]
+    fn test_scalar_type() {
+        assert_eq!(scalar_type("~"), ScalarType::Null);
+        assert_eq!(scalar_type("yes"), ScalarType::Bool);
+        assert_eq!(scalar_type("-0x1F"), ScalarType::Int);
+        assert_eq!(scalar_type("1e3"), ScalarType::Float);
+        assert_eq!(scalar_type(".nan"), ScalarType::Float);
+        assert_eq!(scalar_type("hello"), ScalarType::Str);
+    }
+
+    #[test]
+    fn test_needs_quoting_plain_safe() {
+        for s in [
+            "hello",
+            "hello world",
+            "a-b:c",
+            "path/to/file.txt",
+            "user@example.com",
+            "-dash",
+            "key:value",
+            "x?y",
+            "C#",
+            "100%",
+            "東京",
+            "v1.2.3",
+        ] {
+            assert!(!needs_quoting(s), "{:?} should be plain-safe", s);
+        }
+    }
+
+    #[test]
+    fn test_needs_quoting_required() {
+        for s in [
+            // Other types
+            "",
+            "~",
+            "null",
+            "true",
+            "No",
+            "on",
+            "42",
+            "-7",
+            "0x1F",
+            "1e3",
+            ".inf",
+            ".NaN",
+            // Indicators
+            "- item",
+            "-",
+            "? q",
+            ":",
+            ": x",
+            "[a]",
+            "{a}",
+            "]",
+            "}",
+            ",x",
+            "#c",
+            "&a",
+            "*a",
+            "!tag",
+            "|",
+            ">",
+            "'q",
+            "\"q",
+            "%x",
+            "@x",
+            "`x",
+            // Mapping and comment lookalikes
+            "key: value",
+            "a #b",
+            "ends:",
+            // Whitespace and control characters
+            " lead",
+            "trail ",
+            "\tx",
+            "two\nlines",
+            "bell\u{7}",
+        ] {
+            assert!(needs_quoting(s), "{:?} should need quoting", s);
+        }
+    }
+
+    #[test]
+    fn test_is_version_dependent() {
+        for s in ["yes", "No", "ON", "off", "012", "-07", "+00"] {
+            assert!(is_version_dependent(s), "{:?}", s);
+        }
+        for s in [
+            "true", "FALSE", "0", "12", "-1", "0x1F", "0o12", "0.5", "y", "yes!",
+        ] {
+            assert!(!is_version_dependent(s), "{:?}", s);
+        }
+    }
+
     #[test]
     fn test_parse_number() {
         assert_eq!(parse_number("42"), Some(Number::UInt(42)));
@@ -267,4 +509,21 @@ mod tests {
         let large = u64::MAX;
         assert_eq!(parse_number(&large.to_string()), Some(Number::UInt(large)));
     }
+
+    #[test]
+    fn test_parse_number_128_bit() {
+        // Equality ignores the variant, so check it explicitly
+        let big = (u64::MAX as u128 + 1).to_string();
+        assert!(matches!(parse_number(&big), Some(Number::U128(n)) if n == u64::MAX as u128 + 1));
+        let neg = (i64::MIN as i128 - 1).to_string();
+        assert!(matches!(parse_number(&neg), Some(Number::I128(n)) if n == i64::MIN as i128 - 1));
+        assert!(matches!(
+            parse_number(&u128::MAX.to_string()),
+            Some(Number::U128(u128::MAX))
+        ));
+        assert!(matches!(parse_number("42"), Some(Number::UInt(42))));
+        // Past 128 bits without a decimal point stays a string
+        assert_eq!(parse_number(&format!("{}0", u128::MAX)), None);
+        assert_eq!(scalar_type(&big), ScalarType::Int);
+    }
 }
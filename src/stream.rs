@@ -0,0 +1,65 @@
+//! Multi-document stream emission.
+//!
+//! The writing counterpart of [`FyParser::doc_iter`](crate::FyParser::doc_iter):
+//! several values emitted as one YAML stream, one document each.
+
+use crate::config::EmitOptions;
+use crate::error::{Error, Result};
+use crate::ffi_util::emit_to_writer;
+use crate::value::Value;
+use fyaml_sys::*;
+use std::io::Write;
+
+/// Emits each value as its own YAML document in a single stream.
+///
+/// Every document starts with a `---` marker, so the output parses back
+/// into `values.len()` documents with [`FyParser`](crate::FyParser). An
+/// empty slice yields an empty string.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::{emit_documents, FyParser, Value};
+///
+/// let values = vec![Value::from("first"), "{a: 1}".parse::<Value>().unwrap()];
+/// let yaml = emit_documents(&values).unwrap();
+/// assert!(yaml.starts_with("---"));
+///
+/// let parser = FyParser::from_string(&yaml).unwrap();
+/// assert_eq!(parser.doc_iter().count(), 2);
+/// assert_eq!(emit_documents(&[]).unwrap(), "");
+/// ```
+pub fn emit_documents(values: &[Value]) -> Result<String> {
+    let mut out = Vec::new();
+    emit_documents_to_writer(values, &mut out)?;
+    String::from_utf8(out).map_err(|e| Error::Utf8(e.utf8_error()))
+}
+
+/// Emits each value as its own YAML document into `writer`.
+///
+/// Produces the same bytes as [`emit_documents`], streamed to the writer
+/// as libfyaml generates them. The writer is flushed at the end.
+///
+/// # Errors
+///
+/// Returns [`Error::IoError`] with the writer's error if a write or the
+/// final flush fails; output may have been partially written by then.
+pub fn emit_documents_to_writer<W: Write>(values: &[Value], writer: &mut W) -> Result<()> {
+    let docs = values
+        .iter()
+        .map(Value::to_document)
+        .collect::<Result<Vec<_>>>()?;
+    for doc in &docs {
+        doc.check_alias_targets()?;
+    }
+    let flags = EmitOptions::default().emit_flags()? | FYECF_DOC_START_MARK_ON;
+    emit_to_writer(writer, flags, |emitter| {
+        for doc in &docs {
+            let ret = unsafe { fy_emit_document(emitter, doc.as_ptr()) };
+            if ret != 0 {
+                return ret;
+            }
+        }
+        0
+    })
+}
@@ -0,0 +1,478 @@
+//! YAML timestamp scalar type.
+//!
+//! Parses the formats of the YAML 1.1 `!!timestamp` type:
+//!
+//! - date only: `2002-12-14`
+//! - canonical: `2001-12-15T02:59:43.1Z`
+//! - ISO 8601: `2001-12-14t21:59:43.10-05:00`
+//! - space separated: `2001-12-14 21:59:43.10 -5`
+//! - no time zone: `2001-12-15 2:59:43.10`
+
+use crate::error::{Error, Result};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A YAML timestamp (`!!timestamp`).
+///
+/// Holds the calendar fields exactly as written, plus the optional UTC
+/// offset. Implements `Deserialize` from a string, so it can be used as a
+/// struct field when deserializing with [`from_value`](crate::from_value)
+/// or any other serde deserializer. It serializes back to a string.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::Timestamp;
+///
+/// let ts: Timestamp = "2001-12-14t21:59:43.10-05:00".parse().unwrap();
+/// assert_eq!((ts.year(), ts.month(), ts.day()), (2001, 12, 14));
+/// assert_eq!(ts.nanosecond(), 100_000_000);
+/// assert_eq!(ts.offset_seconds(), Some(-5 * 3600));
+/// assert_eq!(ts.to_string(), "2001-12-14T21:59:43.1-05:00");
+/// assert_eq!(ts.unix_seconds(), 1_008_385_183);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    /// Offset east of UTC in seconds; `None` when no zone was written.
+    offset: Option<i32>,
+    date_only: bool,
+}
+
+impl Timestamp {
+    /// Parses a YAML timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] if `s` does not match the `!!timestamp`
+    /// grammar or names an impossible date or time.
+    pub fn parse(s: &str) -> Result<Self> {
+        parse_timestamp(s).ok_or(Error::Parse("invalid timestamp"))
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month (1-12).
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month (1-31).
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the hour (0-23); 0 for date-only timestamps.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute (0-59).
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second (0-59).
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns the fractional second in nanoseconds.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Returns the UTC offset in seconds east of UTC, if one was written.
+    ///
+    /// `Z` yields `Some(0)`.
+    pub fn offset_seconds(&self) -> Option<i32> {
+        self.offset
+    }
+
+    /// Returns `true` if the timestamp was written as a bare date.
+    pub fn is_date_only(&self) -> bool {
+        self.date_only
+    }
+
+    /// Returns the number of seconds since the Unix epoch.
+    ///
+    /// A timestamp without an offset is taken as UTC, as the YAML spec
+    /// prescribes.
+    pub fn unix_seconds(&self) -> i64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let time = i64::from(self.hour) * 3600 + i64::from(self.minute) * 60;
+        days * 86_400 + time + i64::from(self.second) - i64::from(self.offset.unwrap_or(0))
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Timestamp::parse(s)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)?;
+        if self.date_only {
+            return Ok(());
+        }
+        write!(f, "T{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond != 0 {
+            let frac = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", frac.trim_end_matches('0'))?;
+        }
+        match self.offset {
+            None => Ok(()),
+            Some(0) => write!(f, "Z"),
+            Some(off) => {
+                let sign = if off < 0 { '-' } else { '+' };
+                let abs = off.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, abs / 3600, abs % 3600 / 60)
+            }
+        }
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TimestampVisitor;
+
+        impl<'de> Visitor<'de> for TimestampVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a YAML timestamp")
+            }
+
+            fn visit_str<E>(self, v: &str) -> std::result::Result<Timestamp, E>
+            where
+                E: de::Error,
+            {
+                parse_timestamp(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(TimestampVisitor)
+    }
+}
+
+/// Byte cursor over the timestamp text.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_blanks(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b' ') | Some(b'\t')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Reads between `min` and `max` ASCII digits.
+    fn digits(&mut self, min: usize, max: usize) -> Option<u32> {
+        let start = self.pos;
+        let mut value: u32 = 0;
+        while self.pos - start < max {
+            match self.peek() {
+                Some(b @ b'0'..=b'9') => {
+                    value = value * 10 + u32::from(b - b'0');
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        if self.pos - start < min {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+}
+
+fn parse_timestamp(s: &str) -> Option<Timestamp> {
+    let mut c = Cursor {
+        bytes: s.as_bytes(),
+        pos: 0,
+    };
+
+    let year = c.digits(4, 4)? as i32;
+    if !c.eat(b'-') {
+        return None;
+    }
+    let month_start = c.pos;
+    let month = c.digits(1, 2)? as u8;
+    let short_month = c.pos - month_start == 1;
+    if !c.eat(b'-') {
+        return None;
+    }
+    let day_start = c.pos;
+    let day = c.digits(1, 2)? as u8;
+    let short_day = c.pos - day_start == 1;
+    if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let mut ts = Timestamp {
+        year,
+        month,
+        day,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanosecond: 0,
+        offset: None,
+        date_only: true,
+    };
+    if c.at_end() {
+        // The date-only form requires two-digit month and day
+        return if short_month || short_day {
+            None
+        } else {
+            Some(ts)
+        };
+    }
+
+    if !(c.eat(b'T') || c.eat(b't') || c.skip_blanks() > 0) {
+        return None;
+    }
+    ts.date_only = false;
+    ts.hour = c.digits(1, 2)? as u8;
+    if !c.eat(b':') {
+        return None;
+    }
+    ts.minute = c.digits(2, 2)? as u8;
+    if !c.eat(b':') {
+        return None;
+    }
+    ts.second = c.digits(2, 2)? as u8;
+    if ts.hour > 23 || ts.minute > 59 || ts.second > 59 {
+        return None;
+    }
+
+    if c.eat(b'.') {
+        let start = c.pos;
+        let mut nanos: u32 = 0;
+        while let Some(b @ b'0'..=b'9') = c.peek() {
+            // Digits past nanosecond precision are dropped
+            if c.pos - start < 9 {
+                nanos = nanos * 10 + u32::from(b - b'0');
+            }
+            c.pos += 1;
+        }
+        let len = c.pos - start;
+        if len == 0 {
+            return None;
+        }
+        for _ in len..9 {
+            nanos *= 10;
+        }
+        ts.nanosecond = nanos;
+    }
+
+    c.skip_blanks();
+    if c.eat(b'Z') {
+        ts.offset = Some(0);
+    } else if let Some(sign @ (b'+' | b'-')) = c.peek() {
+        c.pos += 1;
+        let hours = c.digits(1, 2)? as i32;
+        let minutes = if c.eat(b':') {
+            c.digits(2, 2)? as i32
+        } else {
+            0
+        };
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        let off = hours * 3600 + minutes * 60;
+        ts.offset = Some(if sign == b'-' { -off } else { off });
+    }
+
+    if c.at_end() {
+        Some(ts)
+    } else {
+        None
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let y = i64::from(year) - i64::from(month <= 2);
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let mp = if m > 2 { m - 3 } else { m + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::{from_value, Value};
+
+    #[test]
+    fn test_parse_spec_examples() {
+        let canonical: Timestamp = "2001-12-15T02:59:43.1Z".parse().unwrap();
+        let iso: Timestamp = "2001-12-14t21:59:43.10-05:00".parse().unwrap();
+        let spaced: Timestamp = "2001-12-14 21:59:43.10 -5".parse().unwrap();
+        let no_zone: Timestamp = "2001-12-15 2:59:43.10".parse().unwrap();
+        let date: Timestamp = "2002-12-14".parse().unwrap();
+
+        assert_eq!(canonical.offset_seconds(), Some(0));
+        assert_eq!(iso.offset_seconds(), Some(-18_000));
+        assert_eq!(spaced.offset_seconds(), Some(-18_000));
+        assert_eq!(no_zone.offset_seconds(), None);
+        assert_eq!(no_zone.hour(), 2);
+        assert!(date.is_date_only());
+
+        // All four describe the same instant
+        let instant = canonical.unix_seconds();
+        assert_eq!(iso.unix_seconds(), instant);
+        assert_eq!(spaced.unix_seconds(), instant);
+        assert_eq!(no_zone.unix_seconds(), instant);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for bad in [
+            "",
+            "2001-1-1",
+            "2001-13-01",
+            "2001-02-29",
+            "2001-12-14T25:00:00",
+            "2001-12-14T10:00",
+            "2001-12-14T10:00:00.",
+            "2001-12-14T10:00:00+",
+            "2001-12-14x",
+            "01-12-14",
+            "hello",
+        ] {
+            assert!(Timestamp::parse(bad).is_err(), "accepted {:?}", bad);
+        }
+        assert!(Timestamp::parse("2000-02-29").is_ok());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for s in [
+            "2002-12-14",
+            "2001-12-15T02:59:43.1Z",
+            "2001-12-14T21:59:43+05:30",
+        ] {
+            let ts: Timestamp = s.parse().unwrap();
+            assert_eq!(ts.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_unix_seconds() {
+        assert_eq!(Timestamp::parse("1970-01-01").unwrap().unix_seconds(), 0);
+        assert_eq!(
+            Timestamp::parse("1969-12-31T23:59:59Z")
+                .unwrap()
+                .unix_seconds(),
+            -1
+        );
+        assert_eq!(
+            Timestamp::parse("2000-03-01").unwrap().unix_seconds(),
+            951_868_800
+        );
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Event {
+        name: String,
+        ts: Timestamp,
+    }
+
+    #[test]
+    fn test_deserialize_struct_field() {
+        let value: Value = "name: launch\nts: 2001-12-14t21:59:43.10-05:00"
+            .parse()
+            .unwrap();
+        let event: Event = from_value(&value).unwrap();
+        assert_eq!(event.name, "launch");
+        assert_eq!(event.ts.day(), 14);
+        assert_eq!(event.ts.offset_seconds(), Some(-18_000));
+    }
+
+    #[test]
+    fn test_deserialize_tagged_timestamp() {
+        let value: Value = "name: d\nts: !!timestamp 2002-12-14".parse().unwrap();
+        let event: Event = from_value(&value).unwrap();
+        assert!(event.ts.is_date_only());
+    }
+
+    #[test]
+    fn test_deserialize_invalid_reports_path() {
+        let value: Value = "name: d\nts: yesterday".parse().unwrap();
+        let err = from_value::<Event>(&value).unwrap_err();
+        assert!(err.to_string().contains("at /ts:"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_serialize_as_string() {
+        let ts = Timestamp::parse("2002-12-14").unwrap();
+        assert_eq!(serde_json::to_string(&ts).unwrap(), "\"2002-12-14\"");
+    }
+}
@@ -0,0 +1,162 @@
+//! Removal of nulls and empty collections from `Value` trees.
+
+use super::Value;
+
+/// Selects what [`Value::compact`] removes.
+///
+/// The default drops nulls, empty mappings and empty sequences.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::value::{CompactOptions, Value};
+///
+/// let mut value: Value = "{a: ~, b: [], c: {}}".parse().unwrap();
+/// value.compact(&CompactOptions {
+///     nulls: false,
+///     ..CompactOptions::default()
+/// });
+/// assert_eq!(value, "{a: ~}".parse::<Value>().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactOptions {
+    /// Remove null values.
+    pub nulls: bool,
+    /// Remove empty mappings.
+    pub empty_mappings: bool,
+    /// Remove empty sequences.
+    pub empty_sequences: bool,
+}
+
+impl Default for CompactOptions {
+    fn default() -> Self {
+        CompactOptions {
+            nulls: true,
+            empty_mappings: true,
+            empty_sequences: true,
+        }
+    }
+}
+
+impl CompactOptions {
+    /// Returns `true` if `value` should be removed from its parent.
+    fn drops(&self, value: &Value) -> bool {
+        match value {
+            Value::Null => self.nulls,
+            Value::Mapping(m) => self.empty_mappings && m.is_empty(),
+            Value::Sequence(s) => self.empty_sequences && s.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    /// Recursively removes nulls and empty collections, as selected by
+    /// `options`.
+    ///
+    /// Children are compacted first, so a collection that only held removed
+    /// entries is itself removed. Mapping entries are dropped by value; keys
+    /// are never inspected. Sequence items are removed, shifting later
+    /// items down. Tagged values are kept even when their content would be
+    /// removed, since the tag carries meaning, but their content is still
+    /// compacted. The value `compact` is called on is never removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::{CompactOptions, Value};
+    ///
+    /// let mut value: Value = "{name: app, debug: ~, env: {}, ports: [80, ~]}".parse().unwrap();
+    /// value.compact(&CompactOptions::default());
+    /// assert_eq!(value, "{name: app, ports: [80]}".parse::<Value>().unwrap());
+    /// ```
+    pub fn compact(&mut self, options: &CompactOptions) {
+        match self {
+            Value::Sequence(items) => {
+                for item in items.iter_mut() {
+                    item.compact(options);
+                }
+                items.retain(|item| !options.drops(item));
+            }
+            Value::Mapping(map) => {
+                for value in map.values_mut() {
+                    value.compact(options);
+                }
+                map.retain(|_, value| !options.drops(value));
+            }
+            Value::Tagged(tagged) => tagged.value.compact(options),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Value {
+        yaml.parse().unwrap()
+    }
+
+    const SAMPLE: &str = "{a: 1, n: ~, m: {}, s: [], nested: {only_null: ~}, list: [~, [], {}, x]}";
+
+    #[test]
+    fn test_compact_default_drops_everything() {
+        let mut value = parse(SAMPLE);
+        value.compact(&CompactOptions::default());
+        assert_eq!(value, parse("{a: 1, list: [x]}"));
+    }
+
+    #[test]
+    fn test_compact_only_nulls() {
+        let mut value = parse(SAMPLE);
+        value.compact(&CompactOptions {
+            nulls: true,
+            empty_mappings: false,
+            empty_sequences: false,
+        });
+        assert_eq!(
+            value,
+            parse("{a: 1, m: {}, s: [], nested: {}, list: [[], {}, x]}")
+        );
+    }
+
+    #[test]
+    fn test_compact_only_empty_mappings() {
+        let mut value = parse(SAMPLE);
+        value.compact(&CompactOptions {
+            nulls: false,
+            empty_mappings: true,
+            empty_sequences: false,
+        });
+        assert_eq!(
+            value,
+            parse("{a: 1, n: ~, s: [], nested: {only_null: ~}, list: [~, [], x]}")
+        );
+    }
+
+    #[test]
+    fn test_compact_only_empty_sequences() {
+        let mut value = parse(SAMPLE);
+        value.compact(&CompactOptions {
+            nulls: false,
+            empty_mappings: false,
+            empty_sequences: true,
+        });
+        assert_eq!(
+            value,
+            parse("{a: 1, n: ~, m: {}, nested: {only_null: ~}, list: [~, {}, x]}")
+        );
+    }
+
+    #[test]
+    fn test_compact_keeps_tagged_and_root() {
+        let mut value = parse("{t: !keep ~, inner: !wrap {x: ~}}");
+        value.compact(&CompactOptions::default());
+        assert_eq!(value, parse("{t: !keep ~, inner: !wrap {}}"));
+
+        let mut root = parse("{a: ~}");
+        root.compact(&CompactOptions::default());
+        assert_eq!(root, parse("{}"));
+    }
+}
@@ -26,15 +26,56 @@ impl Value {
     /// assert!(value.is_mapping());
     /// ```
     pub fn from_node_ref(node: NodeRef<'_>) -> Result<Value> {
-        Self::from_node_ref_inner(node)
+        Self::from_node_ref_inner(node, &|_: &str, _: &str| None)
     }
 
-    fn from_node_ref_inner(node: NodeRef<'_>) -> Result<Value> {
+    /// Creates a Value from a NodeRef, letting `resolver` construct tagged
+    /// scalars.
+    ///
+    /// `resolver` is called with the tag (as returned by
+    /// [`NodeRef::tag_str`]) and the raw scalar text of every tagged scalar,
+    /// keys included. A returned value replaces the scalar, tag and all;
+    /// `None` falls back to [`from_node_ref`](Self::from_node_ref), which
+    /// wraps the scalar in [`Value::Tagged`]. Tagged collections are always
+    /// kept as `Tagged`, with their contents resolved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Value};
+    ///
+    /// let doc = Document::parse_str("home: !env HOME\nref: !Ref bucket").unwrap();
+    /// let value = Value::from_node_ref_with(doc.root().unwrap(), |tag, text| {
+    ///     match (tag, text) {
+    ///         ("!env", "HOME") => Some(Value::from("/home/alice")),
+    ///         _ => None,
+    ///     }
+    /// })
+    /// .unwrap();
+    /// assert_eq!(value["home"], Value::from("/home/alice"));
+    /// assert_eq!(value["ref"].as_tagged().unwrap().tag, "!Ref");
+    /// ```
+    pub fn from_node_ref_with<F>(node: NodeRef<'_>, resolver: F) -> Result<Value>
+    where
+        F: Fn(&str, &str) -> Option<Value>,
+    {
+        Self::from_node_ref_inner(node, &resolver)
+    }
+
+    fn from_node_ref_inner<F>(node: NodeRef<'_>, resolver: &F) -> Result<Value>
+    where
+        F: Fn(&str, &str) -> Option<Value>,
+    {
         let tag = node.tag_str()?;
 
         let value = match node.kind() {
             NodeType::Scalar => {
                 let raw = node.scalar_str()?;
+                if let Some(tag) = tag {
+                    if let Some(resolved) = resolver(tag, raw) {
+                        return Ok(resolved);
+                    }
+                }
                 // Non-plain scalars (quoted, literal, folded) should not be type-inferred
                 if node.is_non_plain() {
                     Value::String(raw.to_string())
@@ -47,7 +88,7 @@ impl Value {
                 let len = node.seq_len().unwrap_or(0);
                 let mut items = Vec::with_capacity(len);
                 for item in node.seq_iter() {
-                    items.push(Self::from_node_ref_inner(item)?);
+                    items.push(Self::from_node_ref_inner(item, resolver)?);
                 }
                 Value::Sequence(items)
             }
@@ -56,8 +97,8 @@ impl Value {
                 let len = node.map_len().unwrap_or(0);
                 let mut map = IndexMap::with_capacity(len);
                 for (key_node, value_node) in node.map_iter() {
-                    let key = Self::from_node_ref_inner(key_node)?;
-                    let value = Self::from_node_ref_inner(value_node)?;
+                    let key = Self::from_node_ref_inner(key_node, resolver)?;
+                    let value = Self::from_node_ref_inner(value_node, resolver)?;
                     map.insert(key, value);
                 }
                 Value::Mapping(map)
@@ -224,6 +265,54 @@ mod tests {
         assert_eq!(value["null"], Value::Null);
     }
 
+    #[test]
+    fn test_from_node_ref_with_resolver() {
+        let doc = Document::parse_str("a: !upper abc\nb: !keep x\nc: !upper [d]\n").unwrap();
+        let value = Value::from_node_ref_with(doc.root().unwrap(), |tag, text| {
+            if tag == "!upper" {
+                Some(Value::String(text.to_uppercase()))
+            } else {
+                None
+            }
+        })
+        .unwrap();
+        assert_eq!(value["a"], Value::String("ABC".into()));
+        let kept = value["b"].as_tagged().unwrap();
+        assert_eq!(
+            (kept.tag.as_str(), &kept.value),
+            ("!keep", &Value::String("x".into()))
+        );
+        // Collections stay tagged; the resolver only sees scalars
+        assert_eq!(value["c"].as_tagged().unwrap().tag, "!upper");
+    }
+
+    #[test]
+    fn test_from_node_ref_with_nested_and_keys() {
+        let doc = Document::parse_str("? !n one\n: [!n two, plain]\n").unwrap();
+        let value = Value::from_node_ref_with(doc.root().unwrap(), |_, text| match text {
+            "one" => Some(Value::from(1)),
+            "two" => Some(Value::from(2)),
+            _ => None,
+        })
+        .unwrap();
+        let expected: Value = "{1: [2, plain]}".parse().unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_from_node_ref_with_core_tag() {
+        let doc = Document::parse_str("when: !!timestamp 2021-01-01").unwrap();
+        let value = Value::from_node_ref_with(doc.root().unwrap(), |tag, text| {
+            if tag.ends_with("timestamp") {
+                Some(Value::String(format!("{text}T00:00:00Z")))
+            } else {
+                None
+            }
+        })
+        .unwrap();
+        assert_eq!(value["when"], Value::String("2021-01-01T00:00:00Z".into()));
+    }
+
     #[test]
     fn test_value_parse() {
         let value: Value = "key: value".parse().unwrap();
@@ -1,8 +1,15 @@
-//! Deserialize implementation for Value.
+//! Deserialize implementation for Value, and deserialization of Rust types
+//! from a Value.
 
 use super::{Number, Value};
+use crate::document::push_pointer_token;
+use crate::error::Error;
 use indexmap::IndexMap;
-use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
+};
 use std::fmt;
 
 impl<'de> Deserialize<'de> for Value {
@@ -107,6 +114,20 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::Number(Number::UInt(v)))
     }
 
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Number(Number::I128(v).narrowed()))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Number(Number::U128(v).narrowed()))
+    }
+
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -232,6 +253,20 @@ impl<'de> Visitor<'de> for NumberVisitor {
         Ok(Number::UInt(v))
     }
 
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Number::I128(v).narrowed())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Number::U128(v).narrowed())
+    }
+
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -247,6 +282,499 @@ impl<'de> Visitor<'de> for NumberVisitor {
     }
 }
 
+/// Deserializes a Rust type from a [`Value`].
+///
+/// Errors carry the path of the offending node, so a type error deep inside
+/// a configuration reads like
+/// `Deserialize error at /database/port: invalid type: string "abc", expected u16`.
+/// Missing fields are reported at the path of the mapping that lacks them.
+///
+/// Tagged values are transparent, except when deserializing an enum, where
+/// the tag (without its leading `!`) names the variant. Enums can also be
+/// written as a plain string (unit variants) or a single-entry mapping.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::value::{from_value, Value};
+/// use std::collections::HashMap;
+///
+/// let value: Value = "{a: 1, b: 2}".parse().unwrap();
+/// let map: HashMap<String, u8> = from_value(&value).unwrap();
+/// assert_eq!(map["b"], 2);
+///
+/// let bad: Value = "{a: 1, b: x}".parse().unwrap();
+/// let err = from_value::<HashMap<String, u8>>(&bad).unwrap_err();
+/// assert!(err.to_string().contains("at /b"));
+/// ```
+pub fn from_value<'de, T>(value: &'de Value) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(ValueDeserializer {
+        value,
+        path: String::new(),
+    })
+}
+
+/// Attaches `path` to a deserialize error that does not have one yet.
+///
+/// Errors bubble up from the innermost node first, so the deepest path wins.
+pub(crate) fn with_path(err: Error, path: &str) -> Error {
+    match err {
+        Error::Deserialize { path: p, message } if p.is_empty() => Error::Deserialize {
+            path: path.to_owned(),
+            message,
+        },
+        other => other,
+    }
+}
+
+/// Hands a number to `visitor`, through the 64-bit methods when it fits.
+///
+/// Most visitors only implement the 64-bit methods, so 128-bit variants
+/// holding small values are narrowed first.
+pub(crate) fn visit_number<'de, V, E>(n: &Number, visitor: V) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    match n.narrowed() {
+        Number::Int(n) => visitor.visit_i64(n),
+        Number::UInt(n) => visitor.visit_u64(n),
+        Number::Float(f) => visitor.visit_f64(f),
+        Number::I128(n) => visitor.visit_i128(n),
+        Number::U128(n) => visitor.visit_u128(n),
+    }
+}
+
+/// Path segment for a mapping key.
+fn key_segment(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "~".to_owned(),
+        Value::Tagged(t) => key_segment(&t.value),
+        Value::Sequence(_) | Value::Mapping(_) => "?".to_owned(),
+    }
+}
+
+/// Returns `path` extended with `segment`, escaped as a JSON pointer token.
+pub(crate) fn child_path(path: &str, segment: &str) -> String {
+    let mut child = String::with_capacity(path.len() + segment.len() + 1);
+    child.push_str(path);
+    child.push('/');
+    push_pointer_token(&mut child, segment);
+    child
+}
+
+fn unexpected(value: &Value) -> Unexpected<'_> {
+    match value {
+        Value::Null => Unexpected::Unit,
+        Value::Bool(b) => Unexpected::Bool(*b),
+        Value::Number(n) => match n.narrowed() {
+            Number::Int(n) => Unexpected::Signed(n),
+            Number::UInt(n) => Unexpected::Unsigned(n),
+            Number::Float(f) => Unexpected::Float(f),
+            Number::I128(_) | Number::U128(_) => Unexpected::Other("128-bit integer"),
+        },
+        Value::String(s) => Unexpected::Str(s),
+        Value::Sequence(_) => Unexpected::Seq,
+        Value::Mapping(_) => Unexpected::Map,
+        Value::Tagged(t) => unexpected(&t.value),
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Deserialize {
+            path: String::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Serde deserializer over a borrowed `Value`, tracking the node path.
+pub(crate) struct ValueDeserializer<'de> {
+    value: &'de Value,
+    path: String,
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => visit_number(n, visitor),
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Sequence(items) => {
+                let mut access = SeqDeserializer::new(items.iter(), items.len(), &self.path);
+                visitor.visit_seq(&mut access).and_then(|v| access.end(v))
+            }
+            Value::Mapping(map) => {
+                visitor.visit_map(MapDeserializer::new(map.iter(), map.len(), &self.path))
+            }
+            Value::Tagged(t) => {
+                return ValueDeserializer {
+                    value: &t.value,
+                    path: self.path,
+                }
+                .deserialize_any(visitor)
+            }
+        };
+        result.map_err(|e| with_path(e, &self.path))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            // `!!null ~` is still null
+            Value::Tagged(t) if t.value.is_null() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let access = match self.value {
+            Value::String(s) => EnumDeserializer::new(s, None, self.path.clone()),
+            Value::Mapping(map) if map.len() == 1 => {
+                let (key, value) = map.iter().next().expect("mapping has one entry");
+                match key.as_str() {
+                    Some(variant) => {
+                        EnumDeserializer::new(variant, Some(value), child_path(&self.path, variant))
+                    }
+                    None => {
+                        return Err(with_path(
+                            de::Error::invalid_type(unexpected(key), &"a string variant name"),
+                            &self.path,
+                        ))
+                    }
+                }
+            }
+            Value::Tagged(t) => EnumDeserializer::new(
+                t.tag.trim_start_matches('!'),
+                Some(&t.value),
+                self.path.clone(),
+            ),
+            other => {
+                return Err(with_path(
+                    de::Error::invalid_type(unexpected(other), &"an enum"),
+                    &self.path,
+                ))
+            }
+        };
+        visitor
+            .visit_enum(access)
+            .map_err(|e| with_path(e, &self.path))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// A node the path-tracking deserializers can walk: a borrowed [`Value`]
+/// here, a document node for [`from_str`](crate::from_str).
+///
+/// The sequence, mapping and enum access types below are shared by both.
+pub(crate) trait PathNode<'de>: Copy {
+    type Deserializer: Deserializer<'de, Error = Error>;
+
+    /// Returns a deserializer for this node, reporting errors at `path`.
+    fn deserializer(self, path: String) -> Self::Deserializer;
+
+    /// Path segment for this node used as a mapping key, unescaped.
+    fn key_segment(self) -> String;
+
+    fn is_null(self) -> bool;
+
+    fn is_sequence(self) -> bool;
+
+    fn is_mapping(self) -> bool;
+
+    fn unexpected(self) -> Unexpected<'de>;
+}
+
+impl<'de> PathNode<'de> for &'de Value {
+    type Deserializer = ValueDeserializer<'de>;
+
+    fn deserializer(self, path: String) -> ValueDeserializer<'de> {
+        ValueDeserializer { value: self, path }
+    }
+
+    fn key_segment(self) -> String {
+        key_segment(self)
+    }
+
+    fn is_null(self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    fn is_sequence(self) -> bool {
+        matches!(self, Value::Sequence(_))
+    }
+
+    fn is_mapping(self) -> bool {
+        matches!(self, Value::Mapping(_))
+    }
+
+    fn unexpected(self) -> Unexpected<'de> {
+        unexpected(self)
+    }
+}
+
+pub(crate) struct SeqDeserializer<'p, I> {
+    iter: I,
+    index: usize,
+    len: usize,
+    path: &'p str,
+}
+
+impl<'p, I> SeqDeserializer<'p, I> {
+    pub(crate) fn new(iter: I, len: usize, path: &'p str) -> Self {
+        SeqDeserializer {
+            iter,
+            index: 0,
+            len,
+            path,
+        }
+    }
+
+    /// Fails if the visitor stopped before consuming every item.
+    pub(crate) fn end<T>(&mut self, value: T) -> Result<T, Error> {
+        if self.index >= self.len {
+            Ok(value)
+        } else {
+            Err(de::Error::invalid_length(
+                self.len,
+                &"fewer elements in sequence",
+            ))
+        }
+    }
+}
+
+impl<'de, 'p, I> SeqAccess<'de> for &mut SeqDeserializer<'p, I>
+where
+    I: Iterator,
+    I::Item: PathNode<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(node) => {
+                let path = child_path(self.path, &self.index.to_string());
+                self.index += 1;
+                seed.deserialize(node.deserializer(path)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len.saturating_sub(self.index))
+    }
+}
+
+pub(crate) struct MapDeserializer<'p, I, N> {
+    iter: I,
+    /// Value and path of the entry whose key was just returned.
+    pending: Option<(N, String)>,
+    remaining: usize,
+    path: &'p str,
+}
+
+impl<'p, I, N> MapDeserializer<'p, I, N> {
+    pub(crate) fn new(iter: I, len: usize, path: &'p str) -> Self {
+        MapDeserializer {
+            iter,
+            pending: None,
+            remaining: len,
+            path,
+        }
+    }
+}
+
+impl<'de, 'p, I, N> MapAccess<'de> for MapDeserializer<'p, I, N>
+where
+    I: Iterator<Item = (N, N)>,
+    N: PathNode<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.remaining = self.remaining.saturating_sub(1);
+                let path = child_path(self.path, &key.key_segment());
+                let key_de = key.deserializer(path.clone());
+                self.pending = Some((value, path));
+                seed.deserialize(key_de).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.pending.take() {
+            Some((value, path)) => seed.deserialize(value.deserializer(path)),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+pub(crate) struct EnumDeserializer<'de, N> {
+    variant: &'de str,
+    value: Option<N>,
+    path: String,
+}
+
+impl<'de, N> EnumDeserializer<'de, N> {
+    /// `value` is the variant's content, `None` for a bare variant name.
+    pub(crate) fn new(variant: &'de str, value: Option<N>, path: String) -> Self {
+        EnumDeserializer {
+            variant,
+            value,
+            path,
+        }
+    }
+}
+
+impl<'de, N: PathNode<'de>> EnumAccess<'de> for EnumDeserializer<'de, N> {
+    type Error = Error;
+    type Variant = VariantDeserializer<N>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                value: self.value,
+                path: self.path,
+            },
+        ))
+    }
+}
+
+pub(crate) struct VariantDeserializer<N> {
+    value: Option<N>,
+    path: String,
+}
+
+impl<'de, N: PathNode<'de>> VariantAccess<'de> for VariantDeserializer<N> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            Some(value) if !value.is_null() => {
+                Err(de::Error::invalid_type(value.unexpected(), &"unit variant"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value.deserializer(self.path)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) if value.is_sequence() => {
+                value.deserializer(self.path).deserialize_any(visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"tuple variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) if value.is_mapping() => {
+                value.deserializer(self.path).deserialize_any(visitor)
+            }
+            Some(other) => Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,4 +828,140 @@ mod tests {
         let restored: Value = serde_json::from_str(&json).unwrap();
         assert!(restored.is_sequence());
     }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        database: Database,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    enum Mode {
+        Fast,
+        Limit(u32),
+        Range { lo: i32, hi: i32 },
+    }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Optional {
+        a: Option<i64>,
+        b: Option<i64>,
+        c: Option<i64>,
+    }
+
+    #[test]
+    fn test_from_value_null_forms_are_none() {
+        let value: Value = "a: ~\nb:\nc: 5".parse().unwrap();
+        let got: Optional = from_value(&value).unwrap();
+        assert_eq!(
+            got,
+            Optional {
+                a: None,
+                b: None,
+                c: Some(5)
+            }
+        );
+
+        let value: Value = "a: null\nb: !!null\nc: !!null ~".parse().unwrap();
+        let got: Optional = from_value(&value).unwrap();
+        assert_eq!(
+            got,
+            Optional {
+                a: None,
+                b: None,
+                c: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_value_struct() {
+        let value: Value = "name: app\ndatabase: {host: db, port: 5432}\ntags: [a, b]"
+            .parse()
+            .unwrap();
+        let config: Config = from_value(&value).unwrap();
+        assert_eq!(config.database.port, 5432);
+        assert_eq!(config.tags, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_from_value_error_has_nested_path() {
+        let value: Value = "name: app\ndatabase: {host: db, port: abc}"
+            .parse()
+            .unwrap();
+        let err = from_value::<Config>(&value).unwrap_err();
+        match &err {
+            Error::Deserialize { path, .. } => assert_eq!(path, "/database/port"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+        let msg = err.to_string();
+        assert!(msg.contains("at /database/port:"), "got: {}", msg);
+        assert!(msg.contains("invalid type"), "got: {}", msg);
+    }
+
+    #[test]
+    fn test_from_value_error_path_in_sequence() {
+        let value: Value = "name: app\ndatabase: {host: db, port: 1}\ntags: [a, [b]]"
+            .parse()
+            .unwrap();
+        let err = from_value::<Config>(&value).unwrap_err();
+        assert!(err.to_string().contains("at /tags/1:"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_from_value_missing_field_reports_parent() {
+        let value: Value = "name: app\ndatabase: {host: db}".parse().unwrap();
+        let err = from_value::<Config>(&value).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("at /database:"), "got: {}", msg);
+        assert!(msg.contains("port"), "got: {}", msg);
+    }
+
+    #[test]
+    fn test_from_value_root_error_has_no_path() {
+        let value = Value::String("x".into());
+        let err = from_value::<u8>(&value).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Deserialize error: "),
+            "got: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_from_value_option_and_tagged() {
+        let value: Value = "[~, !!int 3]".parse().unwrap();
+        let items: Vec<Option<i64>> = from_value(&value).unwrap();
+        assert_eq!(items, vec![None, Some(3)]);
+    }
+
+    #[test]
+    fn test_from_value_enum() {
+        let value: Value = "[Fast, {Limit: 5}, !Range {lo: -1, hi: 1}]"
+            .parse()
+            .unwrap();
+        let modes: Vec<Mode> = from_value(&value).unwrap();
+        assert_eq!(
+            modes,
+            vec![Mode::Fast, Mode::Limit(5), Mode::Range { lo: -1, hi: 1 }]
+        );
+
+        let bad: Value = "[Fast, {Limit: x}]".parse().unwrap();
+        let err = from_value::<Vec<Mode>>(&bad).unwrap_err();
+        assert!(err.to_string().contains("at /1/Limit:"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_from_value_tuple_too_long() {
+        let value: Value = "[1, 2, 3]".parse().unwrap();
+        assert!(from_value::<(u8, u8)>(&value).is_err());
+    }
 }
@@ -0,0 +1,222 @@
+//! Structural comparison of `Value` trees.
+
+use super::Value;
+use crate::document::push_pointer_token;
+
+/// One difference reported by [`Value::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// JSON pointer to the changed node (e.g. `/servers/0/port`); empty for
+    /// the root.
+    pub path: String,
+    /// What changed there.
+    pub kind: ChangeKind,
+}
+
+/// The kind of a [`Change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The node exists only in the new value.
+    Added(Value),
+    /// The node exists only in the old value.
+    Removed(Value),
+    /// The node exists in both values with different content.
+    Modified {
+        /// The old content.
+        from: Value,
+        /// The new content.
+        to: Value,
+    },
+}
+
+impl Value {
+    /// Lists the differences from `self` to `other`.
+    ///
+    /// Mappings are compared key by key, so reordering keys is not a change;
+    /// keys only in `self` are reported first, in their order, then keys
+    /// only in `other`. Sequences are compared index by index: moving an
+    /// item shows up as modifications, and a length difference as items
+    /// removed or added at the end. Anything else that differs, including
+    /// values of different types, is reported as one `Modified` change.
+    /// Tagged values with the same tag are compared through the tag.
+    ///
+    /// Paths are JSON pointers, so they can be passed to
+    /// [`pointer`](Self::pointer). Non-string keys appear in their YAML form,
+    /// which `pointer` does not match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::{ChangeKind, Value};
+    ///
+    /// let old: Value = "{db: {host: a, port: 5432}, tags: [x]}".parse().unwrap();
+    /// let new: Value = "{db: {host: b, port: 5432}, tags: [x, y]}".parse().unwrap();
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes.len(), 2);
+    /// assert_eq!(changes[0].path, "/db/host");
+    /// assert_eq!(
+    ///     changes[0].kind,
+    ///     ChangeKind::Modified { from: Value::from("a"), to: Value::from("b") }
+    /// );
+    /// assert_eq!(changes[1].path, "/tags/1");
+    /// assert_eq!(changes[1].kind, ChangeKind::Added(Value::from("y")));
+    /// ```
+    pub fn diff(&self, other: &Value) -> Vec<Change> {
+        let mut changes = Vec::new();
+        diff_into(self, other, &mut String::new(), &mut changes);
+        changes
+    }
+}
+
+fn diff_into(old: &Value, new: &Value, path: &mut String, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            for (key, value) in a {
+                let len = path.len();
+                push_key(path, key);
+                match b.get(key) {
+                    Some(other) => diff_into(value, other, path, changes),
+                    None => push_change(changes, path, ChangeKind::Removed(value.clone())),
+                }
+                path.truncate(len);
+            }
+            for (key, value) in b {
+                if !a.contains_key(key) {
+                    let len = path.len();
+                    push_key(path, key);
+                    push_change(changes, path, ChangeKind::Added(value.clone()));
+                    path.truncate(len);
+                }
+            }
+        }
+        (Value::Sequence(a), Value::Sequence(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&i.to_string());
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => diff_into(x, y, path, changes),
+                    (Some(x), None) => push_change(changes, path, ChangeKind::Removed(x.clone())),
+                    (None, Some(y)) => push_change(changes, path, ChangeKind::Added(y.clone())),
+                    (None, None) => {}
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Tagged(a), Value::Tagged(b)) if a.tag == b.tag => {
+            diff_into(&a.value, &b.value, path, changes)
+        }
+        (a, b) => {
+            if a != b {
+                let kind = ChangeKind::Modified {
+                    from: a.clone(),
+                    to: b.clone(),
+                };
+                push_change(changes, path, kind);
+            }
+        }
+    }
+}
+
+/// Appends `/` and the pointer token for a mapping key.
+fn push_key(path: &mut String, key: &Value) {
+    path.push('/');
+    match key {
+        Value::String(s) => push_pointer_token(path, s),
+        other => push_pointer_token(path, other.to_string().trim_end()),
+    }
+}
+
+fn push_change(changes: &mut Vec<Change>, path: &str, kind: ChangeKind) {
+    changes.push(Change {
+        path: path.to_owned(),
+        kind,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Value {
+        yaml.parse().unwrap()
+    }
+
+    fn paths(changes: &[Change]) -> Vec<&str> {
+        changes.iter().map(|c| c.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_equal_values_have_no_changes() {
+        let value = parse("{a: [1, {b: 2}], c: !t x}");
+        assert!(value.diff(&value.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_nested_additions_and_deletions() {
+        let old = parse("{a: {x: 1, y: 2}, gone: true}");
+        let new = parse("{a: {x: 1, z: 3}, new: [1]}");
+        let changes = old.diff(&new);
+        assert_eq!(paths(&changes), ["/a/y", "/a/z", "/gone", "/new"]);
+        assert_eq!(changes[0].kind, ChangeKind::Removed(Value::from(2)));
+        assert_eq!(changes[1].kind, ChangeKind::Added(Value::from(3)));
+        assert_eq!(changes[2].kind, ChangeKind::Removed(Value::Bool(true)));
+        assert_eq!(changes[3].kind, ChangeKind::Added(parse("[1]")));
+    }
+
+    #[test]
+    fn test_key_reordering_is_not_a_change() {
+        assert!(parse("{a: 1, b: 2}")
+            .diff(&parse("{b: 2, a: 1}"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sequence_reordering_and_length() {
+        let changes = parse("[a, b, c]").diff(&parse("[b, a]"));
+        assert_eq!(paths(&changes), ["/0", "/1", "/2"]);
+        assert_eq!(
+            changes[0].kind,
+            ChangeKind::Modified {
+                from: Value::from("a"),
+                to: Value::from("b"),
+            }
+        );
+        assert_eq!(changes[2].kind, ChangeKind::Removed(Value::from("c")));
+    }
+
+    #[test]
+    fn test_type_change_is_one_modification() {
+        let old = parse("{a: {b: 1}}");
+        let new = parse("{a: [1]}");
+        let changes = old.diff(&new);
+        assert_eq!(
+            changes,
+            [Change {
+                path: "/a".into(),
+                kind: ChangeKind::Modified {
+                    from: parse("{b: 1}"),
+                    to: parse("[1]"),
+                },
+            }]
+        );
+        assert_eq!(parse("1").diff(&parse("2"))[0].path, "");
+    }
+
+    #[test]
+    fn test_tags() {
+        let changes = parse("{a: !t {x: 1}}").diff(&parse("{a: !t {x: 2}}"));
+        assert_eq!(paths(&changes), ["/a/x"]);
+        let changes = parse("{a: !t 1}").diff(&parse("{a: !u 1}"));
+        assert_eq!(paths(&changes), ["/a"]);
+    }
+
+    #[test]
+    fn test_paths_feed_pointer() {
+        let old = parse("{a/b: {c~d: 1}, 7: x}");
+        let new = parse("{a/b: {c~d: 2}, 7: y}");
+        let changes = old.diff(&new);
+        assert_eq!(paths(&changes), ["/a~1b/c~0d", "/7"]);
+        assert_eq!(new.pointer(&changes[0].path), Some(&Value::from(2)));
+    }
+}
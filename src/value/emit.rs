@@ -3,10 +3,10 @@
 //! Converts owned `Value` trees to YAML strings via the safe `Editor` API.
 //! No direct FFI calls — all node building goes through `Editor` methods.
 
-use super::{Number, TaggedValue, Value};
+use super::{TaggedValue, Value};
 use crate::editor::{Editor, RawNodeHandle};
 use crate::error::Result;
-use crate::Document;
+use crate::{Document, EmitOptions};
 
 impl Value {
     /// Emits this value as a YAML string using libfyaml.
@@ -29,47 +29,82 @@ impl Value {
     /// assert!(yaml.contains("key: value"));
     /// ```
     pub fn to_yaml_string(&self) -> Result<String> {
+        let doc = self.to_document()?;
+        doc.root()
+            .ok_or(crate::error::Error::Ffi("document has no root"))?
+            .emit()
+    }
+
+    /// Emits this value as a YAML string using the given options.
+    ///
+    /// The value is built into a fresh document and emitted through
+    /// [`NodeRef::emit_with`](crate::NodeRef::emit_with), so every
+    /// [`EmitOptions`] field applies, including key sorting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{EmitOptions, Value};
+    ///
+    /// let value: Value = "b: 1\na: 2".parse().unwrap();
+    /// let opts = EmitOptions {
+    ///     sort_keys_from_depth: Some(0),
+    ///     ..EmitOptions::default()
+    /// };
+    /// assert_eq!(value.to_yaml_string_with(&opts).unwrap(), "a: 2\nb: 1");
+    /// ```
+    pub fn to_yaml_string_with(&self, options: &EmitOptions) -> Result<String> {
+        let doc = self.to_document()?;
+        doc.root()
+            .ok_or(crate::error::Error::Ffi("document has no root"))?
+            .emit_with(options)
+    }
+
+    /// Emits this value on a single line, with every collection in flow
+    /// style (`{a: 1, b: [x, y]}`).
+    ///
+    /// Suited to log lines and error messages. Equivalent to
+    /// [`to_yaml_string_with`](Self::to_yaml_string_with) with
+    /// [`EmitOptions::flow_level`] set to `Some(0)`; it is also what `{}`
+    /// formatting prints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value: Value = "a: 1\nb:\n  - x\n  - y".parse().unwrap();
+    /// assert_eq!(value.to_yaml_string_flow().unwrap(), "{a: 1, b: [x, y]}");
+    /// ```
+    pub fn to_yaml_string_flow(&self) -> Result<String> {
+        self.to_yaml_string_with(&EmitOptions {
+            flow_level: Some(0),
+            ..EmitOptions::default()
+        })
+    }
+
+    /// Builds a new document whose root is this value.
+    pub(crate) fn to_document(&self) -> Result<Document> {
         let mut doc = Document::new()?;
         {
             let mut ed = doc.edit();
             let root = self.build_node(&mut ed)?;
             ed.set_root(root)?;
         }
-        doc.root()
-            .ok_or(crate::error::Error::Ffi("document has no root"))?
-            .emit()
+        Ok(doc)
     }
 
     /// Recursively builds a libfyaml node tree from this Value using the Editor API.
-    fn build_node(&self, ed: &mut Editor<'_>) -> Result<RawNodeHandle> {
+    pub(crate) fn build_node(&self, ed: &mut Editor<'_>) -> Result<RawNodeHandle> {
         match self {
             Value::Null => ed.build_null(),
             Value::Bool(b) => {
                 let s = if *b { "true" } else { "false" };
                 ed.build_scalar(s)
             }
-            Value::Number(n) => {
-                let s = match n {
-                    Number::Int(i) => i.to_string(),
-                    Number::UInt(u) => u.to_string(),
-                    Number::Float(f) => {
-                        if f.is_nan() {
-                            ".nan".to_string()
-                        } else if f.is_infinite() {
-                            if f.is_sign_positive() {
-                                ".inf".to_string()
-                            } else {
-                                "-.inf".to_string()
-                            }
-                        } else {
-                            format!("{}", f)
-                        }
-                    }
-                };
-                ed.build_scalar(&s)
-            }
+            Value::Number(n) => ed.build_scalar(&n.to_string()),
             Value::String(s) => {
-                if crate::scalar_parse::needs_quoting(s) {
+                if crate::scalar_parse::resolves_to_non_string(s) {
                     let mut node = ed.build_scalar(s)?;
                     ed.set_style(&mut node, crate::node::NodeStyle::SingleQuoted);
                     Ok(node)
@@ -113,6 +148,7 @@ impl TaggedValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::value::Number;
     use indexmap::IndexMap;
 
     #[test]
@@ -144,6 +180,15 @@ mod tests {
         assert!(yaml.contains("2.5"));
     }
 
+    #[test]
+    fn test_emit_integral_float_stays_float() {
+        let value = Value::Number(Number::Float(1.0));
+        let yaml = value.to_yaml_string().unwrap();
+        assert_eq!(yaml.trim(), "1.0");
+        let parsed: Value = yaml.parse().unwrap();
+        assert_eq!(parsed, value);
+    }
+
     #[test]
     fn test_emit_string() {
         let value = Value::String("hello world".into());
@@ -0,0 +1,229 @@
+//! Entry API for in-place mapping manipulation.
+
+use super::Value;
+use indexmap::map;
+use indexmap::IndexMap;
+
+/// A view into a single mapping entry, which may be vacant or occupied.
+///
+/// Returned by [`Value::entry`]; mirrors
+/// [`std::collections::hash_map::Entry`].
+#[derive(Debug)]
+pub enum Entry<'a> {
+    /// The key is present.
+    Occupied(OccupiedEntry<'a>),
+    /// The key is absent.
+    Vacant(VacantEntry<'a>),
+}
+
+/// An entry whose key is present in the mapping.
+#[derive(Debug)]
+pub struct OccupiedEntry<'a> {
+    inner: map::OccupiedEntry<'a, Value, Value>,
+}
+
+/// An entry whose key is absent from the mapping.
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    inner: map::VacantEntry<'a, Value, Value>,
+}
+
+impl Value {
+    /// Gets the entry for `key` in this mapping, for in-place manipulation.
+    ///
+    /// Tags are looked through, so a tagged mapping is edited in place. Any
+    /// other value, `null` included, is first replaced by an empty mapping:
+    /// `entry` always succeeds and leaves `self` a mapping.
+    ///
+    /// New keys are appended; existing keys keep their position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// for _ in 0..3 {
+    ///     value
+    ///         .entry("count")
+    ///         .and_modify(|v| *v = Value::from(v.as_i64().unwrap() + 1))
+    ///         .or_insert(Value::from(1));
+    /// }
+    /// assert_eq!(value["count"], Value::from(3));
+    /// ```
+    pub fn entry<K: Into<Value>>(&mut self, key: K) -> Entry<'_> {
+        let target = match self {
+            Value::Tagged(t) if t.value.is_mapping() => &mut t.value,
+            other => other,
+        };
+        if !target.is_mapping() {
+            *target = Value::Mapping(IndexMap::new());
+        }
+        match target {
+            Value::Mapping(m) => match m.entry(key.into()) {
+                map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry { inner }),
+                map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry { inner }),
+            },
+            _ => unreachable!("entry target was just made a mapping"),
+        }
+    }
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &Value {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, then returns the value.
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// Inserts the result of `f` if the entry is vacant, then returns the
+    /// value. `f` is only called for vacant entries.
+    pub fn or_insert_with<F: FnOnce() -> Value>(self, f: F) -> &'a mut Value {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(f()),
+        }
+    }
+
+    /// Inserts [`Value::Null`] if the entry is vacant, then returns the
+    /// value.
+    pub fn or_null(self) -> &'a mut Value {
+        self.or_insert(Value::Null)
+    }
+
+    /// Calls `f` on the value if the entry is occupied, then returns the
+    /// entry for further chaining.
+    pub fn and_modify<F: FnOnce(&mut Value)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            vacant => vacant,
+        }
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &Value {
+        self.inner.key()
+    }
+
+    /// Returns the entry's value.
+    pub fn get(&self) -> &Value {
+        self.inner.get()
+    }
+
+    /// Returns the entry's value, mutably.
+    pub fn get_mut(&mut self) -> &mut Value {
+        self.inner.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference bound to the mapping.
+    pub fn into_mut(self) -> &'a mut Value {
+        self.inner.into_mut()
+    }
+
+    /// Replaces the entry's value, returning the old one.
+    pub fn insert(&mut self, value: Value) -> Value {
+        self.inner.insert(value)
+    }
+
+    /// Removes the entry, returning its value.
+    ///
+    /// The entries after it shift up, so the mapping keeps its order.
+    pub fn remove(self) -> Value {
+        self.inner.shift_remove()
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Returns the key that would be inserted.
+    pub fn key(&self) -> &Value {
+        self.inner.key()
+    }
+
+    /// Inserts `value` at the end of the mapping and returns it.
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        self.inner.insert(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_or_insert_appends() {
+        let mut value: Value = "{a: 1, b: 2}".parse().unwrap();
+        *value.entry("c").or_insert(Value::from(0)) = Value::from(3);
+        assert_eq!(*value.entry("a").or_insert(Value::from(9)), Value::from(1));
+        assert_eq!(value.to_yaml_string().unwrap(), "a: 1\nb: 2\nc: 3");
+    }
+
+    #[test]
+    fn test_entry_or_insert_with_is_lazy() {
+        let mut value: Value = "{a: 1}".parse().unwrap();
+        value
+            .entry("a")
+            .or_insert_with(|| panic!("called for an occupied entry"));
+        value
+            .entry("list")
+            .or_insert_with(|| Value::Sequence(Vec::new()));
+        assert!(value["list"].is_sequence());
+    }
+
+    #[test]
+    fn test_entry_and_modify() {
+        let mut value: Value = "{count: 1}".parse().unwrap();
+        value
+            .entry("count")
+            .and_modify(|v| *v = Value::from(v.as_i64().unwrap() * 10))
+            .or_insert(Value::from(0));
+        value
+            .entry("other")
+            .and_modify(|_| panic!("called for a vacant entry"))
+            .or_null();
+        assert_eq!(value, "{count: 10, other: ~}".parse::<Value>().unwrap());
+    }
+
+    #[test]
+    fn test_entry_replaces_non_mapping() {
+        let mut value = Value::from(5);
+        value.entry("k").or_insert(Value::from("v"));
+        assert_eq!(value, "{k: v}".parse::<Value>().unwrap());
+
+        let mut value: Value = "!custom {a: 1}".parse().unwrap();
+        value.entry("b").or_insert(Value::from(2));
+        assert!(value.is_tagged());
+        assert_eq!(
+            value.as_tagged().unwrap().value,
+            "{a: 1, b: 2}".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_occupied_entry_remove_keeps_order() {
+        let mut value: Value = "{a: 1, b: 2, c: 3}".parse().unwrap();
+        match value.entry("b") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), &Value::from("b"));
+                assert_eq!(e.insert(Value::from(20)), Value::from(2));
+                assert_eq!(e.remove(), Value::from(20));
+            }
+            Entry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        assert_eq!(value.to_yaml_string().unwrap(), "a: 1\nc: 3");
+    }
+}
@@ -0,0 +1,367 @@
+//! Conversion between `Value` trees and flat dotted-key maps.
+
+use super::Value;
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::convert::Infallible;
+
+impl Value {
+    /// Flattens the tree into a map from dotted paths to leaf values.
+    ///
+    /// Mapping keys are joined with `sep` and sequence items are written as
+    /// `[n]`, so `{db: {hosts: [a, b]}}` becomes `db.hosts[0]` and
+    /// `db.hosts[1]` with a `.` separator. Entries are in document order.
+    ///
+    /// Leaves are scalars, tagged values (kept whole, tag included) and
+    /// empty mappings and sequences, which are kept as values so their key
+    /// survives. Non-string keys are written in their YAML form (`7`,
+    /// `true`). A root that is not a non-empty collection is returned under
+    /// the empty key `""`; an empty root mapping yields an empty map.
+    ///
+    /// The paths are ambiguous when a key contains `sep`, or ends in
+    /// something like `[0]`: `{a.b: 1}` and `{a: {b: 1}}` both flatten to
+    /// `a.b`. See [`unflatten`](Self::unflatten) for how such paths are
+    /// read back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value: Value = "{db: {host: x, ports: [1, 2]}, tags: []}".parse().unwrap();
+    /// let flat = value.flatten(".");
+    /// let keys: Vec<&str> = flat.keys().map(String::as_str).collect();
+    /// assert_eq!(keys, ["db.host", "db.ports[0]", "db.ports[1]", "tags"]);
+    /// assert_eq!(flat["db.ports[1]"], Value::from(2));
+    /// assert_eq!(Value::unflatten(flat, ".").unwrap(), value);
+    /// ```
+    pub fn flatten(&self, sep: &str) -> IndexMap<String, Value> {
+        let mut out = IndexMap::new();
+        if let Value::Mapping(map) = self {
+            if map.is_empty() {
+                return out;
+            }
+        }
+        let push = |path: &mut String, step: &Step<'_>| match step {
+            Step::Key(key) => {
+                if !path.is_empty() {
+                    path.push_str(sep);
+                }
+                path.push_str(key);
+            }
+            Step::Index(index) => {
+                path.push('[');
+                path.push_str(&index.to_string());
+                path.push(']');
+            }
+        };
+        let walked = walk_leaves(self, &mut String::new(), &push, &mut |path, leaf| {
+            out.insert(path.to_owned(), leaf.clone());
+            Ok(())
+        });
+        match walked {
+            Ok(()) => out,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Rebuilds a tree from a map produced by [`flatten`](Self::flatten).
+    ///
+    /// Each path is split on every occurrence of `sep` into mapping keys,
+    /// and `[n]` suffixes on a part (`items[0][1]`) select sequence items.
+    /// Keys always come back as strings. Sequences are filled with nulls up
+    /// to the highest index given. Where two paths disagree, the later one
+    /// wins: `a = 1` followed by `a.b = 2` leaves `{a: {b: 2}}`. An empty
+    /// `sep` never splits, and an empty map gives an empty mapping.
+    ///
+    /// This inverts `flatten` exactly as long as no key contains `sep` or
+    /// ends in `[n]`, and all keys are strings; a key like `a.b` comes
+    /// back as nested mappings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if an index is not below the
+    /// number of entries in `map`. `flatten` never writes such an index, and
+    /// rejecting it keeps a path like `a[99999999999]` from allocating a huge
+    /// sequence of nulls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut flat = IndexMap::new();
+    /// flat.insert("server.port".to_string(), Value::from(80));
+    /// flat.insert("server.hosts[1]".to_string(), Value::from("b"));
+    /// let value = Value::unflatten(flat, ".").unwrap();
+    /// assert_eq!(value, "{server: {port: 80, hosts: [null, b]}}".parse::<Value>().unwrap());
+    ///
+    /// let mut huge = IndexMap::new();
+    /// huge.insert("hosts[1000]".to_string(), Value::from("z"));
+    /// assert!(Value::unflatten(huge, ".").is_err());
+    /// ```
+    pub fn unflatten(map: IndexMap<String, Value>, sep: &str) -> Result<Value> {
+        let limit = map.len();
+        let mut root = Value::Mapping(IndexMap::new());
+        for (path, leaf) in map {
+            let mut node = &mut root;
+            for step in parse_path(&path, sep) {
+                node = match step {
+                    Step::Key(key) => {
+                        if !node.is_mapping() {
+                            *node = Value::Mapping(IndexMap::new());
+                        }
+                        match node {
+                            Value::Mapping(m) => m
+                                .entry(Value::from(key.into_owned()))
+                                .or_insert(Value::Null),
+                            _ => unreachable!(),
+                        }
+                    }
+                    Step::Index(index) => {
+                        if !node.is_sequence() {
+                            *node = Value::Sequence(Vec::new());
+                        }
+                        match node {
+                            Value::Sequence(items) => {
+                                let needed = index
+                                    .checked_add(1)
+                                    .filter(|&needed| needed <= limit)
+                                    .ok_or(Error::IndexOutOfBounds { index, len: limit })?;
+                                if items.len() < needed {
+                                    items.resize(needed, Value::Null);
+                                }
+                                &mut items[index]
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                };
+            }
+            *node = leaf;
+        }
+        Ok(root)
+    }
+}
+
+/// The children of a collection with their path steps, `None` for a leaf.
+pub(crate) type Children<'a, N> = Option<Vec<(Step<'a>, N)>>;
+
+/// A tree that [`walk_leaves`] can descend into.
+pub(crate) trait FlatNode<'a>: Copy {
+    type Error;
+
+    /// Returns the children of a non-empty collection, or `None` for a
+    /// leaf.
+    fn flat_children(self) -> std::result::Result<Children<'a, Self>, Self::Error>;
+}
+
+impl<'a> FlatNode<'a> for &'a Value {
+    type Error = Infallible;
+
+    fn flat_children(self) -> std::result::Result<Children<'a, Self>, Infallible> {
+        Ok(match self {
+            Value::Mapping(map) if !map.is_empty() => Some(
+                map.iter()
+                    .map(|(key, child)| {
+                        let key = match key {
+                            Value::String(s) => Cow::Borrowed(s.as_str()),
+                            other => Cow::Owned(other.to_string().trim_end().to_owned()),
+                        };
+                        (Step::Key(key), child)
+                    })
+                    .collect(),
+            ),
+            Value::Sequence(items) if !items.is_empty() => Some(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, child)| (Step::Index(i), child))
+                    .collect(),
+            ),
+            _ => None,
+        })
+    }
+}
+
+/// Walks `node` depth first, calling `leaf` with the path of every leaf.
+///
+/// `push` appends one step to the path; leaves are scalars and empty
+/// collections, in document order.
+pub(crate) fn walk_leaves<'a, N, P, F>(
+    node: N,
+    path: &mut String,
+    push: &P,
+    leaf: &mut F,
+) -> std::result::Result<(), N::Error>
+where
+    N: FlatNode<'a>,
+    P: Fn(&mut String, &Step<'_>),
+    F: FnMut(&str, N) -> std::result::Result<(), N::Error>,
+{
+    match node.flat_children()? {
+        Some(children) => {
+            for (step, child) in children {
+                let len = path.len();
+                push(path, &step);
+                walk_leaves(child, path, push, leaf)?;
+                path.truncate(len);
+            }
+            Ok(())
+        }
+        None => leaf(path, node),
+    }
+}
+
+/// One step of a flattened path.
+pub(crate) enum Step<'a> {
+    Key(Cow<'a, str>),
+    Index(usize),
+}
+
+/// Splits a flattened path into mapping keys and sequence indices.
+///
+/// The empty path is the root itself.
+fn parse_path<'a>(path: &'a str, sep: &str) -> Vec<Step<'a>> {
+    let mut steps = Vec::new();
+    if path.is_empty() {
+        return steps;
+    }
+    let parts: Vec<&str> = if sep.is_empty() {
+        vec![path]
+    } else {
+        path.split(sep).collect()
+    };
+    for part in parts {
+        let (key, indices) = split_indices(part);
+        // A leading `[n]` is an index into a root sequence, not a key
+        if !key.is_empty() || indices.is_empty() || !steps.is_empty() {
+            steps.push(Step::Key(Cow::Borrowed(key)));
+        }
+        steps.extend(indices.into_iter().map(Step::Index));
+    }
+    steps
+}
+
+/// Splits trailing `[n]` groups off a path part: `a[0][2]` gives `a` and
+/// `[0, 2]`.
+fn split_indices(part: &str) -> (&str, Vec<usize>) {
+    let mut key = part;
+    let mut indices = Vec::new();
+    while let Some(rest) = key.strip_suffix(']') {
+        let open = match rest.rfind('[') {
+            Some(open) => open,
+            None => break,
+        };
+        match rest[open + 1..].parse::<usize>() {
+            Ok(index) if rest[open + 1..].bytes().all(|b| b.is_ascii_digit()) => {
+                indices.push(index);
+                key = &rest[..open];
+            }
+            _ => break,
+        }
+    }
+    indices.reverse();
+    (key, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Value {
+        yaml.parse().unwrap()
+    }
+
+    fn keys(flat: &IndexMap<String, Value>) -> Vec<&str> {
+        flat.keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_round_trip_nested() {
+        let value = parse("{a: {b: [1, {c: x}, [y, z]]}, d: ~, e: !t {f: 1}}");
+        let flat = value.flatten(".");
+        assert_eq!(
+            keys(&flat),
+            ["a.b[0]", "a.b[1].c", "a.b[2][0]", "a.b[2][1]", "d", "e"]
+        );
+        assert_eq!(flat["d"], Value::Null);
+        assert!(flat["e"].is_tagged());
+        assert_eq!(Value::unflatten(flat, ".").unwrap(), value);
+    }
+
+    #[test]
+    fn test_empty_collections_are_leaves() {
+        let value = parse("{a: {}, b: [], c: {d: []}}");
+        let flat = value.flatten("/");
+        assert_eq!(keys(&flat), ["a", "b", "c/d"]);
+        assert_eq!(flat["a"], parse("{}"));
+        assert_eq!(Value::unflatten(flat, "/").unwrap(), value);
+    }
+
+    #[test]
+    fn test_roots() {
+        assert!(parse("{}").flatten(".").is_empty());
+        assert_eq!(Value::unflatten(IndexMap::new(), ".").unwrap(), parse("{}"));
+
+        let flat = Value::from(5).flatten(".");
+        assert_eq!(keys(&flat), [""]);
+        assert_eq!(Value::unflatten(flat, ".").unwrap(), Value::from(5));
+
+        let value = parse("[a, {b: 1}]");
+        let flat = value.flatten(".");
+        assert_eq!(keys(&flat), ["[0]", "[1].b"]);
+        assert_eq!(Value::unflatten(flat, ".").unwrap(), value);
+    }
+
+    #[test]
+    fn test_keys_containing_separator() {
+        // Both flatten to `a.b`; reading back always nests
+        let dotted = parse("{a.b: 1}");
+        let nested = parse("{a: {b: 1}}");
+        assert_eq!(dotted.flatten("."), nested.flatten("."));
+        assert_eq!(Value::unflatten(dotted.flatten("."), ".").unwrap(), nested);
+        // A separator that no key uses keeps them apart
+        assert_eq!(Value::unflatten(dotted.flatten("/"), "/").unwrap(), dotted);
+    }
+
+    #[test]
+    fn test_unflatten_gaps_and_conflicts() {
+        let mut flat = IndexMap::new();
+        flat.insert("list[2]".to_string(), Value::from("c"));
+        flat.insert("a".to_string(), Value::from(1));
+        flat.insert("a.b".to_string(), Value::from(2));
+        flat.insert("x[k]".to_string(), Value::from(3));
+        assert_eq!(
+            Value::unflatten(flat, ".").unwrap(),
+            parse("{list: [~, ~, c], a: {b: 2}, 'x[k]': 3}")
+        );
+    }
+
+    #[test]
+    fn test_non_string_keys() {
+        let value = parse("{1: a, true: {null: b}}");
+        let flat = value.flatten(".");
+        assert_eq!(keys(&flat), ["1", "true.null"]);
+        assert_eq!(
+            Value::unflatten(flat, ".").unwrap(),
+            parse("{'1': a, 'true': {'null': b}}")
+        );
+    }
+
+    #[test]
+    fn test_unflatten_rejects_index_past_entry_count() {
+        let mut flat = IndexMap::new();
+        flat.insert("a[1]".to_string(), Value::from(1));
+        flat.insert(format!("b[{}]", usize::MAX), Value::from(2));
+        assert_eq!(
+            Value::unflatten(flat, "."),
+            Err(Error::IndexOutOfBounds {
+                index: usize::MAX,
+                len: 2
+            })
+        );
+    }
+}
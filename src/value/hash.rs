@@ -0,0 +1,250 @@
+//! Stable content hashing of `Value` trees.
+//!
+//! Uses 128-bit FNV-1a over a canonical byte encoding, so digests do not
+//! depend on the platform, the process, or the crate's `Hash` impls.
+
+use super::{Number, Value};
+
+const FNV_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// 128-bit FNV-1a hasher.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new() -> Self {
+        Fnv128(FNV_OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u128::from(b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+impl Value {
+    /// Returns a stable hex digest of this value's content.
+    ///
+    /// Suitable as a cache key: the digest is the same across runs,
+    /// platforms and crate versions for the same content. Mapping key order
+    /// is ignored, matching `==`, and numbers that compare equal hash equal
+    /// (`1`, `1.0`). Formatting never matters since only content is hashed.
+    ///
+    /// The digest is 128-bit FNV-1a, rendered as 32 lowercase hex digits.
+    /// It is not cryptographic: do not use it where collisions could be
+    /// forced by an attacker.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let a: Value = "{host: db, port: 5432}".parse().unwrap();
+    /// let b: Value = "port: 5432\nhost: db".parse().unwrap();
+    /// assert_eq!(a.content_hash_hex(), b.content_hash_hex());
+    /// assert_eq!(a.content_hash_hex().len(), 32);
+    /// ```
+    pub fn content_hash_hex(&self) -> String {
+        self.content_hash_hex_with(true)
+    }
+
+    /// Like [`content_hash_hex`](Self::content_hash_hex), choosing whether
+    /// mapping key order affects the digest.
+    ///
+    /// With `ignore_key_order` set to `false`, `{a: 1, b: 2}` and
+    /// `{b: 2, a: 1}` hash differently.
+    pub fn content_hash_hex_with(&self, ignore_key_order: bool) -> String {
+        format!("{:032x}", digest(self, ignore_key_order))
+    }
+}
+
+fn digest(value: &Value, ignore_key_order: bool) -> u128 {
+    let mut h = Fnv128::new();
+    feed(&mut h, value, ignore_key_order);
+    h.0
+}
+
+/// Writes the canonical encoding of `value`: a type marker byte followed by
+/// length-prefixed content.
+fn feed(h: &mut Fnv128, value: &Value, ignore_key_order: bool) {
+    match value {
+        Value::Null => h.write(b"n"),
+        Value::Bool(b) => h.write(if *b { b"t" } else { b"f" }),
+        Value::Number(n) => feed_number(h, n),
+        Value::String(s) => {
+            h.write(b"s");
+            h.write_len(s.len());
+            h.write(s.as_bytes());
+        }
+        Value::Sequence(items) => {
+            h.write(b"[");
+            h.write_len(items.len());
+            for item in items {
+                feed(h, item, ignore_key_order);
+            }
+        }
+        Value::Mapping(map) => {
+            h.write(b"{");
+            h.write_len(map.len());
+            if ignore_key_order {
+                // Hash each entry on its own, then combine in sorted order
+                let mut entries: Vec<u128> = map
+                    .iter()
+                    .map(|(k, v)| {
+                        let mut eh = Fnv128::new();
+                        feed(&mut eh, k, ignore_key_order);
+                        feed(&mut eh, v, ignore_key_order);
+                        eh.0
+                    })
+                    .collect();
+                entries.sort_unstable();
+                for e in entries {
+                    h.write(&e.to_le_bytes());
+                }
+            } else {
+                for (k, v) in map {
+                    feed(h, k, ignore_key_order);
+                    feed(h, v, ignore_key_order);
+                }
+            }
+        }
+        Value::Tagged(t) => {
+            h.write(b"!");
+            h.write_len(t.tag.len());
+            h.write(t.tag.as_bytes());
+            feed(h, &t.value, ignore_key_order);
+        }
+    }
+}
+
+/// Encodes numbers so that values comparing equal encode identically:
+/// integers use the 64-bit encodings whenever they fit, integral floats are
+/// written as integers, and every NaN is the same.
+fn feed_number(h: &mut Fnv128, n: &Number) {
+    match n.narrowed() {
+        Number::Int(i) if i < 0 => {
+            h.write(b"i");
+            h.write(&i.to_le_bytes());
+        }
+        Number::Int(i) => {
+            h.write(b"u");
+            h.write(&(i as u64).to_le_bytes());
+        }
+        Number::UInt(u) => {
+            h.write(b"u");
+            h.write(&u.to_le_bytes());
+        }
+        Number::I128(i) => {
+            h.write(b"I");
+            h.write(&i.to_le_bytes());
+        }
+        Number::U128(u) => {
+            h.write(b"U");
+            h.write(&u.to_le_bytes());
+        }
+        Number::Float(f) => {
+            // -2^127 and 2^128 are exact as f64
+            if f.fract() == 0.0 && f >= -(2f64.powi(127)) && f < 0.0 {
+                feed_number(h, &Number::I128(f as i128));
+            } else if f.fract() == 0.0 && f >= 0.0 && f < 2f64.powi(128) {
+                feed_number(h, &Number::U128(f as u128));
+            } else {
+                let bits = if f.is_nan() {
+                    f64::NAN.to_bits()
+                } else {
+                    f.to_bits()
+                };
+                h.write(b"d");
+                h.write(&bits.to_le_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(yaml: &str) -> String {
+        yaml.parse::<Value>().unwrap().content_hash_hex()
+    }
+
+    #[test]
+    fn test_reordered_mappings_hash_equal() {
+        let a = hash("name: app\ndb: {host: h, port: 5432}\ntags: [x, y]");
+        let b = hash("tags: [x, y]\ndb: {port: 5432, host: h}\nname: app");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_changed_value_changes_hash() {
+        let base = hash("name: app\ndb: {host: h, port: 5432}");
+        assert_ne!(base, hash("name: app\ndb: {host: h, port: 5433}"));
+        assert_ne!(base, hash("name: app\ndb: {host: h, port: '5432'}"));
+        assert_ne!(base, hash("name: app\ndb: {host: h}"));
+        // Sequence order still matters
+        assert_ne!(hash("[a, b]"), hash("[b, a]"));
+    }
+
+    #[test]
+    fn test_key_order_flag() {
+        let a: Value = "{a: 1, b: 2}".parse().unwrap();
+        let b: Value = "{b: 2, a: 1}".parse().unwrap();
+        assert_eq!(a.content_hash_hex_with(true), b.content_hash_hex_with(true));
+        assert_ne!(
+            a.content_hash_hex_with(false),
+            b.content_hash_hex_with(false)
+        );
+    }
+
+    #[test]
+    fn test_structure_is_not_ambiguous() {
+        // Same scalars, different nesting
+        assert_ne!(hash("[[a], b]"), hash("[a, [b]]"));
+        assert_ne!(hash("{a: b}"), hash("[a, b]"));
+        assert_ne!(hash("ab"), hash("[a, b]"));
+        assert_ne!(hash("~"), hash("''"));
+        assert_ne!(hash("!t x"), hash("x"));
+    }
+
+    #[test]
+    fn test_equal_numbers_hash_equal() {
+        let int = Value::Number(Number::Int(3));
+        let uint = Value::Number(Number::UInt(3));
+        let float = Value::Number(Number::Float(3.0));
+        assert_eq!(int.content_hash_hex(), uint.content_hash_hex());
+        assert_eq!(int.content_hash_hex(), float.content_hash_hex());
+        assert_eq!(
+            int.content_hash_hex(),
+            Value::Number(Number::I128(3)).content_hash_hex()
+        );
+        let big = Value::Number(Number::U128(1 << 100));
+        assert_eq!(
+            big.content_hash_hex(),
+            Value::Number(Number::Float(2f64.powi(100))).content_hash_hex()
+        );
+        assert_ne!(
+            float.content_hash_hex(),
+            Value::Number(Number::Float(3.5)).content_hash_hex()
+        );
+    }
+
+    #[test]
+    fn test_stable_across_runs() {
+        // Pinned digests: changing the encoding would invalidate caches
+        assert_eq!(
+            Value::Null.content_hash_hex(),
+            "d228cb69661a8caf78912b704e4a7e51"
+        );
+        assert_eq!(hash("x"), "2d6abb40b811a5db6bdb2c07df6c1421");
+        assert_eq!(hash("x"), hash("'x'"));
+    }
+}
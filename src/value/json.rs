@@ -0,0 +1,549 @@
+//! JSON interop: parsing JSON text into Value, and conversion to
+//! `serde_json::Value` with loss reporting.
+//!
+//! Enabled by the `json` cargo feature.
+
+use super::{Number, Value};
+use crate::document::push_pointer_token;
+use crate::error::{Error, ParseError, Result};
+use std::fmt;
+
+/// Largest integer magnitude that survives a round trip through `f64`
+/// (2^53 - 1), which is how many JSON consumers store numbers.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// What was lost or altered when converting a [`Value`] to JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LossKind {
+    /// A NaN or infinite float was replaced by `null`.
+    NonFiniteFloat(f64),
+    /// An integer is kept exactly, but readers that store JSON numbers as
+    /// `f64` will round it.
+    UnsafeInteger(Number),
+    /// An integer beyond the 64-bit range, which JSON numbers cannot hold
+    /// exactly, was replaced by the nearest float.
+    IntegerOutOfRange(Number),
+    /// A non-string mapping key was converted to its string form.
+    NonStringKey(String),
+    /// Two keys became the same string; the later entry replaced the earlier.
+    DuplicateKey(String),
+}
+
+/// A single lossy coercion reported by [`Value::to_json_value_checked`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LossWarning {
+    /// Path of the affected node (e.g. `/metrics/0`); empty for the root.
+    pub path: String,
+    /// What happened to the node.
+    pub kind: LossKind,
+}
+
+impl fmt::Display for LossWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        match &self.kind {
+            LossKind::NonFiniteFloat(v) => {
+                write!(f, "{}: non-finite float {} replaced by null", path, v)
+            }
+            LossKind::UnsafeInteger(n) => {
+                write!(f, "{}: integer {} exceeds f64 precision", path, n)
+            }
+            LossKind::IntegerOutOfRange(n) => {
+                write!(f, "{}: integer {} rounded to a float", path, n)
+            }
+            LossKind::NonStringKey(k) => {
+                write!(f, "{}: non-string key converted to {:?}", path, k)
+            }
+            LossKind::DuplicateKey(k) => {
+                write!(f, "{}: key {:?} collides with an earlier key", path, k)
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Parses JSON text into a `Value`.
+    ///
+    /// JSON is close to a subset of YAML, but this goes through `serde_json`
+    /// so JSON-specific rules apply (no comments, strict escapes) and object
+    /// member order is preserved. Integers become `Int`/`UInt`, other numbers
+    /// `Float`.
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] with the line and column of the first
+    /// syntax error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value = Value::from_json_str(r#"{"name": "app", "ports": [80, 443]}"#).unwrap();
+    /// assert_eq!(value["ports"][1].as_u64(), Some(443));
+    ///
+    /// let yaml = value.to_yaml_string().unwrap();
+    /// assert!(yaml.starts_with("name: app\nports:"));
+    /// ```
+    pub fn from_json_str(s: &str) -> Result<Value> {
+        serde_json::from_str(s).map_err(|e| {
+            // serde_json appends " at line L column C"; keep the location structured
+            let mut message = e.to_string();
+            if let Some(pos) = message.rfind(" at line ") {
+                message.truncate(pos);
+            }
+            Error::ParseError(ParseError::with_location(
+                message,
+                e.line() as u32,
+                e.column() as u32,
+            ))
+        })
+    }
+
+    /// Converts this value to a `serde_json::Value`, reporting every lossy
+    /// coercion.
+    ///
+    /// Tagged values become single-key objects `{tag: value}`, matching the
+    /// `Serialize` implementation. Conversion never fails; anything JSON
+    /// cannot represent faithfully is coerced and listed in the returned
+    /// warnings:
+    ///
+    /// - NaN and infinities become `null`
+    /// - integers beyond ±2^53 are kept, but flagged as unsafe for `f64` readers
+    /// - non-string keys are stringified (complex keys as compact JSON)
+    /// - keys that stringify to the same text keep only the last entry
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::{LossKind, Value};
+    ///
+    /// let value: Value = "{ratio: .nan, ok: 1}".parse().unwrap();
+    /// let (json, warnings) = value.to_json_value_checked();
+    /// assert!(json["ratio"].is_null());
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].path, "/ratio");
+    /// assert!(matches!(warnings[0].kind, LossKind::NonFiniteFloat(_)));
+    /// ```
+    pub fn to_json_value_checked(&self) -> (serde_json::Value, Vec<LossWarning>) {
+        let mut warnings = Vec::new();
+        let mut path = String::new();
+        let json = to_json(self, &mut path, &mut warnings);
+        (json, warnings)
+    }
+}
+
+impl From<serde_json::Value> for Value {
+    /// Converts a `serde_json::Value` without going through text.
+    ///
+    /// Numbers map the way [`Value::from_json_str`] maps them: non-negative
+    /// integers become `UInt`, negative ones `Int`, everything else `Float`.
+    /// Objects keep their member order as far as `serde_json` preserves it.
+    /// Single-key objects stay mappings; they are not turned back into
+    /// [`Value::Tagged`].
+    ///
+    /// Requires the `json` feature.
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => {
+                let n = if let Some(u) = n.as_u64() {
+                    Number::UInt(u)
+                } else if let Some(i) = n.as_i64() {
+                    Number::Int(i)
+                } else {
+                    Number::Float(n.as_f64().unwrap_or(f64::NAN))
+                };
+                Value::Number(n)
+            }
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(items) => {
+                Value::Sequence(items.into_iter().map(Value::from).collect())
+            }
+            serde_json::Value::Object(map) => Value::Mapping(
+                map.into_iter()
+                    .map(|(k, v)| (Value::String(k), Value::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl TryFrom<Value> for serde_json::Value {
+    type Error = Error;
+
+    /// Converts a `Value` to a `serde_json::Value` without going through
+    /// text, failing if JSON cannot hold it faithfully.
+    ///
+    /// Tagged values become single-key objects `{tag: value}` and
+    /// non-string keys are stringified, as in
+    /// [`Value::to_json_value_checked`]. Integers beyond ±2^53 are kept
+    /// exactly.
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unrepresentable`] for NaN or infinite floats,
+    /// integers outside the 64-bit range, and mapping keys that stringify
+    /// to the same text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value: Value = "{port: 8080, ratio: 0.5}".parse().unwrap();
+    /// let json = serde_json::Value::try_from(value).unwrap();
+    /// assert_eq!(json, serde_json::json!({"port": 8080, "ratio": 0.5}));
+    ///
+    /// let value: Value = "{ratio: .inf}".parse().unwrap();
+    /// assert!(serde_json::Value::try_from(value).is_err());
+    /// ```
+    fn try_from(value: Value) -> Result<Self> {
+        let (json, warnings) = value.to_json_value_checked();
+        for warning in warnings {
+            let message = match warning.kind {
+                LossKind::UnsafeInteger(_) | LossKind::NonStringKey(_) => continue,
+                LossKind::NonFiniteFloat(v) => {
+                    format!("non-finite float {} has no JSON form", v)
+                }
+                LossKind::IntegerOutOfRange(n) => {
+                    format!("integer {} is outside the 64-bit range", n)
+                }
+                LossKind::DuplicateKey(k) => {
+                    format!("key {:?} collides with an earlier key", k)
+                }
+            };
+            return Err(Error::Unrepresentable {
+                path: warning.path,
+                message,
+            });
+        }
+        Ok(json)
+    }
+}
+
+fn warn(warnings: &mut Vec<LossWarning>, path: &str, kind: LossKind) {
+    warnings.push(LossWarning {
+        path: path.to_owned(),
+        kind,
+    });
+}
+
+fn to_json(value: &Value, path: &mut String, warnings: &mut Vec<LossWarning>) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Number(n) => number_to_json(n, path, warnings),
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Sequence(items) => {
+            let mut out = Vec::with_capacity(items.len());
+            for (i, item) in items.iter().enumerate() {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&i.to_string());
+                out.push(to_json(item, path, warnings));
+                path.truncate(len);
+            }
+            serde_json::Value::Array(out)
+        }
+        Value::Mapping(map) => {
+            let mut out = serde_json::Map::new();
+            for (k, v) in map {
+                let key = key_to_string(k);
+                let len = path.len();
+                path.push('/');
+                push_pointer_token(path, &key);
+                if k.as_str().is_none() {
+                    warn(warnings, path, LossKind::NonStringKey(key.clone()));
+                }
+                let converted = to_json(v, path, warnings);
+                if out.insert(key.clone(), converted).is_some() {
+                    warn(warnings, path, LossKind::DuplicateKey(key));
+                }
+                path.truncate(len);
+            }
+            serde_json::Value::Object(out)
+        }
+        Value::Tagged(t) => {
+            let len = path.len();
+            path.push('/');
+            push_pointer_token(path, &t.tag);
+            let inner = to_json(&t.value, path, warnings);
+            path.truncate(len);
+            let mut out = serde_json::Map::new();
+            out.insert(t.tag.clone(), inner);
+            serde_json::Value::Object(out)
+        }
+    }
+}
+
+fn number_to_json(n: &Number, path: &str, warnings: &mut Vec<LossWarning>) -> serde_json::Value {
+    match n.narrowed() {
+        Number::Int(i) => {
+            if i.unsigned_abs() > MAX_SAFE_INTEGER {
+                warn(warnings, path, LossKind::UnsafeInteger(n.clone()));
+            }
+            serde_json::Value::from(i)
+        }
+        Number::UInt(u) => {
+            if u > MAX_SAFE_INTEGER {
+                warn(warnings, path, LossKind::UnsafeInteger(n.clone()));
+            }
+            serde_json::Value::from(u)
+        }
+        Number::I128(_) | Number::U128(_) => {
+            warn(warnings, path, LossKind::IntegerOutOfRange(n.clone()));
+            // Integers up to 2^128 are always finite as f64
+            serde_json::Number::from_f64(n.as_f64())
+                .map_or(serde_json::Value::Null, serde_json::Value::Number)
+        }
+        Number::Float(f) => match serde_json::Number::from_f64(f) {
+            Some(num) => serde_json::Value::Number(num),
+            None => {
+                warn(warnings, path, LossKind::NonFiniteFloat(f));
+                serde_json::Value::Null
+            }
+        },
+    }
+}
+
+/// String form of a mapping key; scalars use their YAML text.
+fn key_to_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Null => "null".to_owned(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        other => {
+            let mut scratch = Vec::new();
+            let json = to_json(other, &mut String::new(), &mut scratch);
+            json.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::TaggedValue;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_nan_becomes_null_with_warning() {
+        let value = Value::Number(Number::Float(f64::NAN));
+        let (json, warnings) = value.to_json_value_checked();
+        assert!(json.is_null());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "");
+        assert!(matches!(warnings[0].kind, LossKind::NonFiniteFloat(f) if f.is_nan()));
+    }
+
+    #[test]
+    fn test_infinity_in_sequence() {
+        let value = Value::Sequence(vec![
+            Value::Number(Number::Float(1.5)),
+            Value::Number(Number::Float(f64::NEG_INFINITY)),
+        ]);
+        let (json, warnings) = value.to_json_value_checked();
+        assert_eq!(json, serde_json::json!([1.5, null]));
+        assert_eq!(
+            warnings,
+            vec![LossWarning {
+                path: "/1".into(),
+                kind: LossKind::NonFiniteFloat(f64::NEG_INFINITY),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_huge_uint_kept_but_flagged() {
+        let mut map = IndexMap::new();
+        map.insert(Value::from("big"), Value::Number(Number::UInt(u64::MAX)));
+        map.insert(Value::from("safe"), Value::Number(Number::UInt(1 << 53)));
+        map.insert(Value::from("neg"), Value::Number(Number::Int(i64::MIN)));
+        let (json, warnings) = Value::Mapping(map).to_json_value_checked();
+
+        assert_eq!(json["big"].as_u64(), Some(u64::MAX));
+        let paths: Vec<&str> = warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["/big", "/safe", "/neg"]);
+        assert_eq!(
+            warnings[0].kind,
+            LossKind::UnsafeInteger(Number::UInt(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_warning_path_escapes_key() {
+        let value: Value = "{a/b: {c~d: .nan}}".parse().unwrap();
+        let (_, warnings) = value.to_json_value_checked();
+        assert_eq!(warnings[0].path, "/a~1b/c~0d");
+    }
+
+    #[test]
+    fn test_128_bit_integers() {
+        let small = Value::Number(Number::I128(-5));
+        let (json, warnings) = small.to_json_value_checked();
+        assert_eq!(json, serde_json::json!(-5));
+        assert!(warnings.is_empty());
+
+        let big = Value::Number(Number::U128(1 << 70));
+        let (json, warnings) = big.to_json_value_checked();
+        assert_eq!(json.as_f64(), Some(2f64.powi(70)));
+        assert_eq!(
+            warnings[0].kind,
+            LossKind::IntegerOutOfRange(Number::U128(1 << 70))
+        );
+    }
+
+    #[test]
+    fn test_max_safe_integer_is_not_flagged() {
+        let value = Value::Number(Number::Int(-((1 << 53) - 1)));
+        let (_, warnings) = value.to_json_value_checked();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_non_string_and_duplicate_keys() {
+        let value: Value = "{1: a, '1': b, true: c}".parse().unwrap();
+        let (json, warnings) = value.to_json_value_checked();
+        assert_eq!(json, serde_json::json!({"1": "b", "true": "c"}));
+        assert_eq!(
+            warnings.iter().map(|w| &w.kind).collect::<Vec<_>>(),
+            vec![
+                &LossKind::NonStringKey("1".into()),
+                &LossKind::DuplicateKey("1".into()),
+                &LossKind::NonStringKey("true".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tagged_matches_serialize() {
+        let value = Value::Tagged(Box::new(TaggedValue {
+            tag: "!t".into(),
+            value: Value::Number(Number::Float(f64::INFINITY)),
+        }));
+        let (json, warnings) = value.to_json_value_checked();
+        assert_eq!(json, serde_json::json!({"!t": null}));
+        assert_eq!(warnings[0].path, "/!t");
+    }
+
+    #[test]
+    fn test_lossless_value_has_no_warnings() {
+        let value: Value = "{a: [1, 2.5, x, ~, true]}".parse().unwrap();
+        let (json, warnings) = value.to_json_value_checked();
+        assert!(warnings.is_empty());
+        assert_eq!(json, serde_json::to_value(&value).unwrap());
+    }
+
+    #[test]
+    fn test_warning_display() {
+        let w = LossWarning {
+            path: "/n".into(),
+            kind: LossKind::NonFiniteFloat(f64::INFINITY),
+        };
+        assert_eq!(w.to_string(), "/n: non-finite float inf replaced by null");
+    }
+
+    #[test]
+    fn test_from_serde_json_value() {
+        let json = serde_json::json!({
+            "name": "app",
+            "replicas": 3,
+            "offset": -2,
+            "ratio": 1.0,
+            "hosts": [{"addr": "a", "tls": true}, null],
+        });
+        let value = Value::from(json);
+        let expected: Value =
+            "{name: app, replicas: 3, offset: -2, ratio: 1.0, hosts: [{addr: a, tls: true}, ~]}"
+                .parse()
+                .unwrap();
+        assert_eq!(value, expected);
+        assert!(matches!(value["replicas"], Value::Number(Number::UInt(3))));
+        assert!(matches!(value["offset"], Value::Number(Number::Int(-2))));
+        assert!(matches!(value["ratio"], Value::Number(Number::Float(_))));
+    }
+
+    #[test]
+    fn test_try_from_value_preserves_integers_and_floats() {
+        let value: Value = "{a: [1, -1, 1.0, 2.5], b: {c: ~}, '7': x}".parse().unwrap();
+        let json = serde_json::Value::try_from(value.clone()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"a": [1, -1, 1.0, 2.5], "b": {"c": null}, "7": "x"})
+        );
+        assert!(json["a"][0].is_u64());
+        assert!(json["a"][1].is_i64());
+        assert!(json["a"][2].is_f64());
+        assert_eq!(Value::from(json), value);
+    }
+
+    #[test]
+    fn test_try_from_value_tagged_and_errors() {
+        let value = Value::Tagged(Box::new(TaggedValue {
+            tag: "!t".into(),
+            value: Value::from(1),
+        }));
+        let json = serde_json::Value::try_from(value).unwrap();
+        assert_eq!(json, serde_json::json!({"!t": 1}));
+
+        let value: Value = "{list: [1, .nan]}".parse().unwrap();
+        match serde_json::Value::try_from(value).unwrap_err() {
+            Error::Unrepresentable { path, .. } => assert_eq!(path, "/list/1"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+        let value = Value::Number(Number::U128(1 << 70));
+        assert!(serde_json::Value::try_from(value).is_err());
+        let value: Value = "{1: a, '1': b}".parse().unwrap();
+        assert!(serde_json::Value::try_from(value).is_err());
+    }
+
+    #[test]
+    fn test_from_json_str_matches_yaml() {
+        let json = r#"{"name": "app", "replicas": 3, "ratio": 0.5, "tags": ["a", "b"], "db": {"host": null, "tls": true}}"#;
+        let from_json = Value::from_json_str(json).unwrap();
+        let from_yaml: Value =
+            "name: app\nreplicas: 3\nratio: 0.5\ntags: [a, b]\ndb: {host: ~, tls: true}"
+                .parse()
+                .unwrap();
+        assert_eq!(from_json, from_yaml);
+
+        let yaml = from_json.to_yaml_string().unwrap();
+        assert!(yaml.starts_with("name: app\n"), "got:\n{}", yaml);
+        assert_eq!(yaml.parse::<Value>().unwrap(), from_json);
+    }
+
+    #[test]
+    fn test_from_json_str_keeps_strings_as_strings() {
+        let value = Value::from_json_str(r#"{"a": "true", "b": "1"}"#).unwrap();
+        assert_eq!(value["a"].as_str(), Some("true"));
+        assert_eq!(value["b"].as_str(), Some("1"));
+        // Emission quotes them so they stay strings
+        let back: Value = value.to_yaml_string().unwrap().parse().unwrap();
+        assert_eq!(back, value);
+    }
+
+    #[test]
+    fn test_from_json_str_error_location() {
+        let err = Value::from_json_str("{\n  \"a\": 1,\n  oops\n}").unwrap_err();
+        match err {
+            Error::ParseError(e) => {
+                assert_eq!(e.line(), Some(3));
+                assert!(!e.message().contains(" at line "), "got: {}", e.message());
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Deep merging of `Value` trees.
+
+use super::Value;
+use std::fmt;
+
+/// One step in a path through a `Value` tree.
+///
+/// Passed to merge resolvers so they can tell where a conflict occurred.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A mapping key.
+    Key(Value),
+    /// A sequence index.
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    /// String keys and indices print bare; other keys use their YAML form.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(Value::String(s)) => f.write_str(s),
+            PathSegment::Key(k) => write!(f, "{}", k),
+            PathSegment::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+/// How [`Value::merge_with_strategy`] combines two sequences at the same path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// The incoming sequence replaces the existing one.
+    #[default]
+    Replace,
+    /// The incoming items are appended to the existing sequence.
+    Append,
+    /// The incoming items are appended unless an equal item is already
+    /// present, so the result holds each item once. Duplicates already in
+    /// the existing sequence are kept.
+    ConcatUnique,
+}
+
+impl Value {
+    /// Deep-merges `other` into `self`, with `other` winning conflicts.
+    ///
+    /// When both sides are mappings they are merged key by key: keys only
+    /// in `other` are appended, keys in both are merged recursively, and
+    /// existing keys keep their position. Anywhere else `other` replaces
+    /// `self`, including sequences, mismatched kinds and a `null` in
+    /// `other`. This is the usual "override file" behavior; see
+    /// [`merge_with_strategy`](Self::merge_with_strategy) to combine
+    /// sequences instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let mut base: Value = "{db: {host: a, port: 5432}, tags: [x]}".parse().unwrap();
+    /// let over: Value = "{db: {host: b}, tags: [y]}".parse().unwrap();
+    /// base.merge(over);
+    /// assert_eq!(base, "{db: {host: b, port: 5432}, tags: [y]}".parse::<Value>().unwrap());
+    /// ```
+    pub fn merge(&mut self, other: Value) {
+        self.merge_with_strategy(other, MergeStrategy::Replace);
+    }
+
+    /// Deep-merges `other` into `self`, combining sequences per `strategy`.
+    ///
+    /// Behaves like [`merge`](Self::merge), except that when both sides
+    /// of a path are sequences they are combined as `strategy` says.
+    /// Sequence items are not merged recursively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{MergeStrategy, Value};
+    ///
+    /// let mut base: Value = "{tags: [a, b]}".parse().unwrap();
+    /// let over: Value = "{tags: [b, c]}".parse().unwrap();
+    /// base.merge_with_strategy(over, MergeStrategy::ConcatUnique);
+    /// assert_eq!(base, "{tags: [a, b, c]}".parse::<Value>().unwrap());
+    /// ```
+    pub fn merge_with_strategy(&mut self, other: Value, strategy: MergeStrategy) {
+        match (self, other) {
+            (Value::Mapping(dst), Value::Mapping(src)) => {
+                for (key, value) in src {
+                    match dst.get_mut(&key) {
+                        Some(existing) => existing.merge_with_strategy(value, strategy),
+                        None => {
+                            dst.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (Value::Sequence(dst), Value::Sequence(src)) => match strategy {
+                MergeStrategy::Replace => *dst = src,
+                MergeStrategy::Append => dst.extend(src),
+                MergeStrategy::ConcatUnique => {
+                    for item in src {
+                        if !dst.contains(&item) {
+                            dst.push(item);
+                        }
+                    }
+                }
+            },
+            (target, other) => *target = other,
+        }
+    }
+
+    /// Deep-merges `other` into `self`, asking `resolver` to settle conflicts.
+    ///
+    /// When both sides are mappings, keys only in `other` are appended and
+    /// shared keys are merged recursively. Anywhere else the two sides
+    /// differ (scalars, sequences, tagged values, or mismatched kinds) is a
+    /// leaf conflict: `resolver` receives the path to it, the current value
+    /// and the incoming one, and its return value is stored. Equal values
+    /// are not conflicts and do not reach the resolver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let mut base: Value = "{limits: {cpu: 2, mem: 512}, name: a}".parse().unwrap();
+    /// let other: Value = "{limits: {cpu: 4, mem: 256}, name: b}".parse().unwrap();
+    ///
+    /// // Keep the larger number, otherwise let `other` win
+    /// base.merge_with_resolver(other, |_path, old, new| match (old.as_i64(), new.as_i64()) {
+    ///     (Some(a), Some(b)) => Value::from(a.max(b)),
+    ///     _ => new.clone(),
+    /// });
+    /// assert_eq!(base, "{limits: {cpu: 4, mem: 512}, name: b}".parse::<Value>().unwrap());
+    /// ```
+    pub fn merge_with_resolver<F>(&mut self, other: Value, mut resolver: F)
+    where
+        F: FnMut(&[PathSegment], &Value, &Value) -> Value,
+    {
+        let mut path = Vec::new();
+        merge_into(self, other, &mut path, &mut resolver);
+    }
+}
+
+fn merge_into<F>(target: &mut Value, other: Value, path: &mut Vec<PathSegment>, resolver: &mut F)
+where
+    F: FnMut(&[PathSegment], &Value, &Value) -> Value,
+{
+    match (target, other) {
+        (Value::Mapping(dst), Value::Mapping(src)) => {
+            for (key, value) in src {
+                match dst.get_mut(&key) {
+                    Some(existing) => {
+                        path.push(PathSegment::Key(key));
+                        merge_into(existing, value, path, resolver);
+                        path.pop();
+                    }
+                    None => {
+                        dst.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, other) => {
+            if *target != other {
+                *target = resolver(path, target, &other);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_int(_: &[PathSegment], old: &Value, new: &Value) -> Value {
+        match (old.as_i64(), new.as_i64()) {
+            (Some(a), Some(b)) => Value::from(a.max(b)),
+            _ => new.clone(),
+        }
+    }
+
+    #[test]
+    fn test_resolver_picks_max() {
+        let mut base: Value = "{a: 1, b: 10, nested: {x: 5, y: 7}, only_base: 0}"
+            .parse()
+            .unwrap();
+        let other: Value = "{a: 3, b: 2, nested: {x: 9, y: 1}, only_other: 4}"
+            .parse()
+            .unwrap();
+        base.merge_with_resolver(other, max_int);
+        let expected: Value = "{a: 3, b: 10, nested: {x: 9, y: 7}, only_base: 0, only_other: 4}"
+            .parse()
+            .unwrap();
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn test_resolver_sees_paths_of_conflicts_only() {
+        let mut base: Value = "{same: 1, db: {port: 80, tags: [a]}}".parse().unwrap();
+        let other: Value = "{same: 1, db: {port: 81, tags: [b]}, new: x}"
+            .parse()
+            .unwrap();
+        let mut seen = Vec::new();
+        base.merge_with_resolver(other, |path, _, new| {
+            let rendered: Vec<String> = path.iter().map(|s| s.to_string()).collect();
+            seen.push(rendered.join("/"));
+            new.clone()
+        });
+        assert_eq!(seen, ["db/port", "db/tags"]);
+        assert_eq!(base["db"]["tags"][0].as_str(), Some("b"));
+        assert_eq!(base["new"].as_str(), Some("x"));
+    }
+
+    #[test]
+    fn test_kind_mismatch_is_a_conflict() {
+        let mut base: Value = "{a: {b: 1}}".parse().unwrap();
+        let other: Value = "{a: 2}".parse().unwrap();
+        let mut calls = 0;
+        base.merge_with_resolver(other, |path, old, _| {
+            calls += 1;
+            assert_eq!(path, [PathSegment::Key(Value::from("a"))]);
+            old.clone()
+        });
+        assert_eq!(calls, 1);
+        assert_eq!(base, "{a: {b: 1}}".parse::<Value>().unwrap());
+    }
+
+    #[test]
+    fn test_merge_nested_maps() {
+        let mut base: Value = "{a: 1, db: {host: h, opts: {ssl: false, pool: 5}}, z: 0}"
+            .parse()
+            .unwrap();
+        let other: Value = "{db: {opts: {ssl: true}, user: u}, b: 2}".parse().unwrap();
+        base.merge(other);
+        let expected: Value =
+            "{a: 1, db: {host: h, opts: {ssl: true, pool: 5}, user: u}, z: 0, b: 2}"
+                .parse()
+                .unwrap();
+        assert_eq!(base, expected);
+
+        // Existing keys keep their position, new keys are appended
+        let keys: Vec<&str> = base
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, ["a", "db", "z", "b"]);
+    }
+
+    #[test]
+    fn test_merge_type_mismatch_replaces() {
+        let mut base: Value = "{a: {b: 1}, c: [1, 2], d: x}".parse().unwrap();
+        let other: Value = "{a: 2, c: {k: v}, d: [y]}".parse().unwrap();
+        base.merge(other);
+        assert_eq!(base, "{a: 2, c: {k: v}, d: [y]}".parse::<Value>().unwrap());
+    }
+
+    #[test]
+    fn test_merge_null_overrides_value() {
+        let mut base: Value = "{a: 1, b: {c: 2}}".parse().unwrap();
+        let other: Value = "{a: null, b: ~}".parse().unwrap();
+        base.merge(other);
+        assert!(base["a"].is_null());
+        assert!(base["b"].is_null());
+        assert_eq!(base.as_mapping().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_sequence_strategies() {
+        let base: Value = "{s: [a, b, a], n: {s: [1]}}".parse().unwrap();
+        let other: Value = "{s: [b, c, c], n: {s: [2]}}".parse().unwrap();
+
+        let mut replaced = base.clone();
+        replaced.merge_with_strategy(other.clone(), MergeStrategy::Replace);
+        assert_eq!(
+            replaced,
+            "{s: [b, c, c], n: {s: [2]}}".parse::<Value>().unwrap()
+        );
+
+        let mut appended = base.clone();
+        appended.merge_with_strategy(other.clone(), MergeStrategy::Append);
+        assert_eq!(
+            appended,
+            "{s: [a, b, a, b, c, c], n: {s: [1, 2]}}"
+                .parse::<Value>()
+                .unwrap()
+        );
+
+        let mut unique = base;
+        unique.merge_with_strategy(other, MergeStrategy::ConcatUnique);
+        assert_eq!(
+            unique,
+            "{s: [a, b, a, c], n: {s: [1, 2]}}"
+                .parse::<Value>()
+                .unwrap()
+        );
+
+        assert_eq!(MergeStrategy::default(), MergeStrategy::Replace);
+    }
+
+    #[test]
+    fn test_root_conflict_has_empty_path() {
+        let mut base = Value::from(1);
+        base.merge_with_resolver(Value::from(2), |path, _, _| {
+            assert!(path.is_empty());
+            Value::from("resolved")
+        });
+        assert_eq!(base.as_str(), Some("resolved"));
+    }
+}
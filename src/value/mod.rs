@@ -35,15 +35,40 @@
 //!
 //! // Access values
 //! assert_eq!(value["foo"], Value::String("bar".into()));
+//! assert_eq!(value["foo"], "bar");
 //!
 //! // Emit back to YAML
 //! let yaml = value.to_yaml_string().unwrap();
 //! ```
 
+mod compact;
 mod convert;
 mod de;
+mod diff;
 mod emit;
+mod entry;
+mod flatten;
+mod hash;
+#[cfg(feature = "json")]
+mod json;
+mod merge;
+mod pointer;
 mod ser;
+#[cfg(feature = "toml")]
+mod toml;
+
+pub use compact::CompactOptions;
+pub use de::from_value;
+pub(crate) use de::{
+    child_path, visit_number, with_path, EnumDeserializer, MapDeserializer, PathNode,
+    SeqDeserializer,
+};
+pub use diff::{Change, ChangeKind};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub(crate) use flatten::{walk_leaves, Children, FlatNode, Step};
+#[cfg(feature = "json")]
+pub use json::{LossKind, LossWarning};
+pub use merge::{MergeStrategy, PathSegment};
 
 use indexmap::IndexMap;
 use std::cmp::Ordering;
@@ -81,6 +106,12 @@ pub enum Value {
 }
 
 /// Numeric value that can be an integer or float.
+///
+/// Parsing picks the 64-bit variants whenever the value fits and only uses
+/// [`I128`](Number::I128) and [`U128`](Number::U128) beyond that. The
+/// variant does not affect comparisons: integers compare, order and hash
+/// by their mathematical value, so `Int(1)`, `U128(1)` and `Float(1.0)`
+/// are all equal.
 #[derive(Clone, Debug)]
 pub enum Number {
     /// Signed 64-bit integer.
@@ -89,6 +120,10 @@ pub enum Number {
     UInt(u64),
     /// 64-bit floating point.
     Float(f64),
+    /// Signed 128-bit integer.
+    I128(i128),
+    /// Unsigned 128-bit integer.
+    U128(u128),
 }
 
 /// A value with an associated YAML tag.
@@ -101,6 +136,122 @@ pub struct TaggedValue {
 }
 
 impl Value {
+    /// Creates an empty `Sequence` with room for `n` items.
+    ///
+    /// Avoids reallocation when the final size is known up front.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let mut seq = Value::sequence_with_capacity(3);
+    /// let items = seq.as_sequence_mut().unwrap();
+    /// items.extend([Value::from(1), Value::from(2), Value::from(3)]);
+    /// assert_eq!(seq.as_sequence().unwrap().len(), 3);
+    /// ```
+    pub fn sequence_with_capacity(n: usize) -> Value {
+        Value::Sequence(Vec::with_capacity(n))
+    }
+
+    /// Creates an empty `Mapping` with room for `n` entries.
+    ///
+    /// Avoids rehashing when the final size is known up front.
+    pub fn mapping_with_capacity(n: usize) -> Value {
+        Value::Mapping(IndexMap::with_capacity(n))
+    }
+
+    /// Builds a `Mapping` from key-value pairs, rejecting duplicate keys.
+    ///
+    /// Unlike collecting into an `IndexMap`, where a repeated key silently
+    /// overwrites the earlier entry, this returns [`Error::DuplicateKey`](crate::Error::DuplicateKey)
+    /// naming the first key seen twice. Entries keep their input order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Error, Value};
+    ///
+    /// let map = Value::try_mapping_from_pairs(vec![
+    ///     (Value::from("a"), Value::from(1)),
+    ///     (Value::from("b"), Value::from(2)),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(map["b"], Value::from(2));
+    ///
+    /// let err = Value::try_mapping_from_pairs(vec![
+    ///     (Value::from("a"), Value::from(1)),
+    ///     (Value::from("a"), Value::from(2)),
+    /// ])
+    /// .unwrap_err();
+    /// assert_eq!(err, Error::DuplicateKey("a".to_string()));
+    /// ```
+    pub fn try_mapping_from_pairs<I>(pairs: I) -> crate::error::Result<Value>
+    where
+        I: IntoIterator<Item = (Value, Value)>,
+    {
+        let pairs = pairs.into_iter();
+        let mut map = IndexMap::with_capacity(pairs.size_hint().0);
+        for (key, value) in pairs {
+            if map.contains_key(&key) {
+                return Err(crate::error::Error::DuplicateKey(
+                    key.to_string().trim_end().to_owned(),
+                ));
+            }
+            map.insert(key, value);
+        }
+        Ok(Value::Mapping(map))
+    }
+
+    /// Builds a table: a `Sequence` of `Mapping`s, one per row, pairing each
+    /// header with the row's cell at the same position.
+    ///
+    /// Handy for turning CSV-like data into YAML. Each mapping keeps the
+    /// header order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RowLengthMismatch`](crate::Error::RowLengthMismatch)
+    /// if a row does not have exactly one cell per header, and
+    /// [`Error::DuplicateKey`](crate::Error::DuplicateKey) if a header repeats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let table = Value::table_from_rows(
+    ///     &["name", "port"],
+    ///     &[
+    ///         vec![Value::from("web"), Value::from(80)],
+    ///         vec![Value::from("db"), Value::from(5432)],
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(table[1]["name"], Value::from("db"));
+    ///
+    /// let expected: Value = "- {name: web, port: 80}\n- {name: db, port: 5432}".parse().unwrap();
+    /// assert_eq!(table, expected);
+    /// ```
+    pub fn table_from_rows(headers: &[&str], rows: &[Vec<Value>]) -> crate::error::Result<Value> {
+        let mut table = Vec::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != headers.len() {
+                return Err(crate::error::Error::RowLengthMismatch {
+                    row: i,
+                    expected: headers.len(),
+                    got: row.len(),
+                });
+            }
+            let pairs = headers
+                .iter()
+                .zip(row)
+                .map(|(&header, cell)| (Value::from(header), cell.clone()));
+            table.push(Value::try_mapping_from_pairs(pairs)?);
+        }
+        Ok(Value::Sequence(table))
+    }
+
     /// Returns `true` if the value is `Null`.
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null)
@@ -146,28 +297,42 @@ impl Value {
 
     /// Returns the value as an `i64`, if it can be represented as one.
     pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|n| n.try_into().ok())
+    }
+
+    /// Returns the value as a `u64`, if it can be represented as one.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_u128().and_then(|n| n.try_into().ok())
+    }
+
+    /// Returns the value as an `i128`, if it is an integer that fits.
+    pub fn as_i128(&self) -> Option<i128> {
         match self {
-            Value::Number(Number::Int(n)) => Some(*n),
-            Value::Number(Number::UInt(n)) => (*n).try_into().ok(),
+            Value::Number(Number::Int(n)) => Some(i128::from(*n)),
+            Value::Number(Number::UInt(n)) => Some(i128::from(*n)),
+            Value::Number(Number::I128(n)) => Some(*n),
+            Value::Number(Number::U128(n)) => (*n).try_into().ok(),
             _ => None,
         }
     }
 
-    /// Returns the value as a `u64`, if it can be represented as one.
-    pub fn as_u64(&self) -> Option<u64> {
+    /// Returns the value as a `u128`, if it is a non-negative integer.
+    pub fn as_u128(&self) -> Option<u128> {
         match self {
-            Value::Number(Number::UInt(n)) => Some(*n),
+            Value::Number(Number::UInt(n)) => Some(u128::from(*n)),
             Value::Number(Number::Int(n)) => (*n).try_into().ok(),
+            Value::Number(Number::I128(n)) => (*n).try_into().ok(),
+            Value::Number(Number::U128(n)) => Some(*n),
             _ => None,
         }
     }
 
     /// Returns the value as an `f64`, if it is a number.
+    ///
+    /// Integers beyond 2^53 in magnitude are rounded to the nearest `f64`.
     pub fn as_f64(&self) -> Option<f64> {
         match self {
-            Value::Number(Number::Float(f)) => Some(*f),
-            Value::Number(Number::Int(n)) => Some(*n as f64),
-            Value::Number(Number::UInt(n)) => Some(*n as f64),
+            Value::Number(n) => Some(n.as_f64()),
             _ => None,
         }
     }
@@ -257,6 +422,237 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Takes this value out, leaving [`Value::Null`] in its place.
+    ///
+    /// Like `serde_json::Value::take`; pairs with [`get_mut`](Self::get_mut)
+    /// to move a sub-value out of a tree without cloning it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let mut value: Value = "{items: [1, 2], name: x}".parse().unwrap();
+    /// let items = value.get_mut("items").unwrap().take();
+    /// assert_eq!(items, "[1, 2]".parse::<Value>().unwrap());
+    /// assert!(value["items"].is_null());
+    /// ```
+    pub fn take(&mut self) -> Value {
+        std::mem::replace(self, Value::Null)
+    }
+
+    /// Replaces this value with `value`, returning the old one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let mut value: Value = "{port: 80}".parse().unwrap();
+    /// let old = value.get_mut("port").unwrap().replace(Value::from(8080));
+    /// assert_eq!(old, Value::from(80));
+    /// assert_eq!(value["port"], Value::from(8080));
+    /// ```
+    pub fn replace(&mut self, value: Value) -> Value {
+        std::mem::replace(self, value)
+    }
+
+    /// Gets a mapping value by key as a string slice.
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type. The other `get_*` helpers work the same way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let value: Value = "{host: db, port: 5432, tls: true}".parse().unwrap();
+    /// assert_eq!(value.get_str("host"), Some("db"));
+    /// assert_eq!(value.get_i64("port"), Some(5432));
+    /// assert_eq!(value.get_bool("tls"), Some(true));
+    /// assert_eq!(value.get_i64("host"), None);
+    /// ```
+    pub fn get_str<Q>(&self, key: &Q) -> Option<&str>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_str()
+    }
+
+    /// Gets a mapping value by key as an `i64` (see [`as_i64`](Self::as_i64)).
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_i64<Q>(&self, key: &Q) -> Option<i64>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_i64()
+    }
+
+    /// Gets a mapping value by key as a `u64` (see [`as_u64`](Self::as_u64)).
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_u64<Q>(&self, key: &Q) -> Option<u64>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_u64()
+    }
+
+    /// Gets a mapping value by key as an `f64` (see [`as_f64`](Self::as_f64)).
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_f64<Q>(&self, key: &Q) -> Option<f64>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_f64()
+    }
+
+    /// Gets a mapping value by key as a boolean.
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_bool<Q>(&self, key: &Q) -> Option<bool>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_bool()
+    }
+
+    /// Gets a mapping value by key as a sequence slice.
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_sequence<Q>(&self, key: &Q) -> Option<&[Value]>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_sequence()
+    }
+
+    /// Gets a mapping value by key as a mapping.
+    ///
+    /// Returns `None` if this is not a mapping, the key is missing, or the
+    /// value has another type.
+    pub fn get_mapping<Q>(&self, key: &Q) -> Option<&IndexMap<Value, Value>>
+    where
+        Q: ?Sized + Hash + Eq + AsValueKey,
+    {
+        self.get(key)?.as_mapping()
+    }
+
+    /// Gets a value from a mapping by string key, following `<<` merge keys.
+    ///
+    /// Explicit keys win. Otherwise the `<<` value is searched: a single
+    /// mapping, or a sequence of mappings where earlier entries override
+    /// later ones. Merge sources may themselves contain `<<`. A `<<` whose
+    /// value is not a mapping (or a sequence item that is not) is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value: Value = "<<: [{a: 1, b: 1}, {b: 2, c: 2}]\nc: 3".parse().unwrap();
+    /// assert_eq!(value.get_merged("a"), Some(&Value::from(1)));
+    /// assert_eq!(value.get_merged("b"), Some(&Value::from(1)));
+    /// assert_eq!(value.get_merged("c"), Some(&Value::from(3)));
+    /// assert_eq!(value.get_merged("d"), None);
+    /// ```
+    pub fn get_merged(&self, key: &str) -> Option<&Value> {
+        let map = self.as_mapping()?;
+        if key != "<<" {
+            if let Some(v) = key.get_from_map(map) {
+                return Some(v);
+            }
+        }
+        match "<<".get_from_map(map)? {
+            source @ Value::Mapping(_) => source.get_merged(key),
+            Value::Sequence(sources) => sources.iter().find_map(|source| source.get_merged(key)),
+            _ => None,
+        }
+    }
+
+    /// Reorders a mapping's entries so the keys listed in `order` come first.
+    ///
+    /// Listed keys are placed in the given order; all other entries follow,
+    /// keeping their relative order. Keys in `order` that are absent from
+    /// the mapping are ignored. Only string keys can be matched. A tagged
+    /// mapping is reordered in place; other values are left untouched.
+    ///
+    /// This only affects this mapping, not nested ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let mut value: Value = "{port: 80, name: web, host: a}".parse().unwrap();
+    /// value.reorder_keys(&["name", "host"]);
+    /// assert_eq!(value.to_yaml_string().unwrap(), "name: web\nhost: a\nport: 80");
+    /// ```
+    pub fn reorder_keys(&mut self, order: &[&str]) {
+        let map = match self {
+            Value::Mapping(m) => m,
+            Value::Tagged(t) => return t.value.reorder_keys(order),
+            _ => return,
+        };
+        let rank = |key: &Value| {
+            key.as_str()
+                .and_then(|k| order.iter().position(|o| *o == k))
+                .unwrap_or(order.len())
+        };
+        // IndexMap's sort is stable, so unlisted keys keep their order
+        map.sort_by(|k1, _, k2, _| rank(k1).cmp(&rank(k2)));
+    }
+
+    /// Compares two values, allowing floats to differ by up to `epsilon`.
+    ///
+    /// Whenever either side of a numeric comparison is a float, both are
+    /// compared as `f64` with an absolute tolerance of `epsilon`. Integers
+    /// compared with integers, and all non-numeric values, must match
+    /// exactly. Sequences are compared item by item; mappings must have the
+    /// same keys (matched exactly, in any order) with approximately equal
+    /// values; tagged values must have the same tag.
+    ///
+    /// As with `==`, NaN is equal to NaN. Infinities are only equal to
+    /// infinities of the same sign.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let a: Value = "{ratio: 0.3, n: 2}".parse().unwrap();
+    /// let b: Value = "{n: 2, ratio: 0.30000000000000004}".parse().unwrap();
+    /// assert_ne!(a, b);
+    /// assert!(a.approx_eq(&b, 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Value, epsilon: f64) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.approx_eq(b, epsilon),
+            (Value::Sequence(a), Value::Sequence(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.approx_eq(y, epsilon))
+            }
+            (Value::Mapping(a), Value::Mapping(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(k, v)| match b.get(k) {
+                        Some(w) => v.approx_eq(w, epsilon),
+                        None => false,
+                    })
+            }
+            (Value::Tagged(a), Value::Tagged(b)) => {
+                a.tag == b.tag && a.value.approx_eq(&b.value, epsilon)
+            }
+            _ => self == other,
+        }
+    }
 }
 
 /// Trait for types that can be used as keys to look up values in a mapping.
@@ -392,30 +788,197 @@ impl Hash for Value {
     }
 }
 
+impl Number {
+    /// Returns the number as an `f64`, rounding integers that do not fit
+    /// exactly.
+    ///
+    /// Use `f64::try_from` to reject integers that would be rounded.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::Int(n) => n as f64,
+            Number::UInt(n) => n as f64,
+            Number::Float(f) => f,
+            Number::I128(n) => n as f64,
+            Number::U128(n) => n as f64,
+        }
+    }
+
+    /// Returns `true` for the integer variants, `false` for
+    /// [`Float`](Number::Float) even when its value is integral.
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, Number::Float(_))
+    }
+
+    /// Adds two numbers without matching on their variants.
+    ///
+    /// Two integers add exactly, across all integer variants; the sum is
+    /// returned in the narrowest variant that holds it, with non-negative
+    /// sums preferring [`UInt`](Number::UInt) over [`Int`](Number::Int) as
+    /// parsing does. A sum outside the 128-bit range returns
+    /// `None` rather than losing precision in a float. If either side is a
+    /// float, both are added as `f64` and the result is a
+    /// [`Float`](Number::Float), which never fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Number;
+    ///
+    /// let sum = Number::Int(i64::MAX).checked_add(&Number::Int(1)).unwrap();
+    /// assert!(matches!(sum, Number::UInt(n) if n == i64::MAX as u64 + 1));
+    /// assert_eq!(Number::Int(1).checked_add(&Number::Float(0.5)), Some(Number::Float(1.5)));
+    /// assert_eq!(Number::U128(u128::MAX).checked_add(&Number::Int(1)), None);
+    /// ```
+    pub fn checked_add(&self, other: &Number) -> Option<Number> {
+        let (a, b) = match (self.wide(), other.wide()) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return Some(Number::Float(self.as_f64() + other.as_f64())),
+        };
+        let sum = match (a, b) {
+            (Wide::Neg(x), Wide::Neg(y)) => Wide::Neg(x.checked_add(y)?),
+            (Wide::NonNeg(x), Wide::NonNeg(y)) => Wide::NonNeg(x.checked_add(y)?),
+            (Wide::Neg(n), Wide::NonNeg(p)) | (Wide::NonNeg(p), Wide::Neg(n)) => {
+                let magnitude = n.unsigned_abs();
+                if p >= magnitude {
+                    Wide::NonNeg(p - magnitude)
+                } else {
+                    // p < |n| <= 2^127, so it fits an i128
+                    Wide::Neg(n + p as i128)
+                }
+            }
+        };
+        Some(Number::from_wide(sum))
+    }
+
+    /// Returns an exact integer in the narrowest variant that holds it.
+    fn from_wide(w: Wide) -> Number {
+        match w {
+            Wide::Neg(n) => i64::try_from(n).map_or(Number::I128(n), Number::Int),
+            Wide::NonNeg(n) => u64::try_from(n).map_or(Number::U128(n), Number::UInt),
+        }
+    }
+
+    /// Returns the number in the narrowest variant that holds it.
+    ///
+    /// 128-bit integers within the 64-bit range become
+    /// [`Int`](Number::Int) or [`UInt`](Number::UInt); everything else is
+    /// returned as is.
+    pub(crate) fn narrowed(&self) -> Number {
+        self.wide().map_or_else(|| self.clone(), Number::from_wide)
+    }
+
+    /// Returns the exact value of an integer variant.
+    fn wide(&self) -> Option<Wide> {
+        match *self {
+            Number::Int(n) if n < 0 => Some(Wide::Neg(i128::from(n))),
+            Number::Int(n) => Some(Wide::NonNeg(n as u128)),
+            Number::UInt(n) => Some(Wide::NonNeg(u128::from(n))),
+            Number::I128(n) if n < 0 => Some(Wide::Neg(n)),
+            Number::I128(n) => Some(Wide::NonNeg(n as u128)),
+            Number::U128(n) => Some(Wide::NonNeg(n)),
+            Number::Float(_) => None,
+        }
+    }
+
+    /// Returns the exact integer value of any variant, floats included.
+    fn exact_integer(&self) -> Option<Wide> {
+        match *self {
+            Number::Float(f) => float_to_wide(f),
+            _ => self.wide(),
+        }
+    }
+
+    /// Compares as `f64` within `epsilon` if either side is a float;
+    /// otherwise exactly. See [`Value::approx_eq`].
+    fn approx_eq(&self, other: &Number, epsilon: f64) -> bool {
+        if !matches!(self, Number::Float(_)) && !matches!(other, Number::Float(_)) {
+            return self == other;
+        }
+        let (a, b) = (self.as_f64(), other.as_f64());
+        if a.is_nan() || b.is_nan() {
+            return a.is_nan() && b.is_nan();
+        }
+        if a.is_infinite() || b.is_infinite() {
+            return a == b;
+        }
+        (a - b).abs() <= epsilon
+    }
+}
+
+/// The exact value of an integer, whatever `Number` variant holds it.
+///
+/// Variant order puts every negative value before every non-negative one,
+/// so the derived `Ord` is numeric order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Wide {
+    Neg(i128),
+    NonNeg(u128),
+}
+
+/// Converts an integral float to its exact integer value.
+///
+/// Returns `None` for fractions, non-finite values, values outside the
+/// 128-bit range and `-0.0`, which floats keep distinct from `0.0`.
+fn float_to_wide(f: f64) -> Option<Wide> {
+    if f.fract() != 0.0 || (f == 0.0 && f.is_sign_negative()) {
+        return None;
+    }
+    // -2^127 and 2^128 are exact as f64
+    if f >= -(2f64.powi(127)) && f < 0.0 {
+        Some(Wide::Neg(f as i128))
+    } else if (0.0..2f64.powi(128)).contains(&f) {
+        Some(Wide::NonNeg(f as u128))
+    } else {
+        None
+    }
+}
+
+/// Orders an integer against a float by exact value.
+///
+/// Matches `f64::total_cmp` at the edges: `-0.0` sorts just below `0`,
+/// and NaN sorts after everything, or before if its sign bit is set.
+fn cmp_wide_float(a: Wide, f: f64) -> Ordering {
+    if f.is_nan() {
+        return if f.is_sign_negative() {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    }
+    if f == 0.0 && f.is_sign_negative() {
+        return if a >= Wide::NonNeg(0) {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    }
+    // Fractions between -1 and 0 truncate to -0.0; compare those against 0
+    let whole = match f.trunc() {
+        w if w == 0.0 => 0.0,
+        w => w,
+    };
+    match float_to_wide(whole) {
+        Some(w) => a
+            .cmp(&w)
+            .then(whole.partial_cmp(&f).unwrap_or(Ordering::Equal)),
+        // Beyond the 128-bit range, infinities included
+        None if f > 0.0 => Ordering::Less,
+        None => Ordering::Greater,
+    }
+}
+
 impl PartialEq for Number {
+    /// Integers are equal when their values are, whatever the variants.
+    /// A float equals an integer only if it is integral with that exact
+    /// value; floats compare to each other by bit pattern, so NaN equals
+    /// NaN and `0.0` differs from `-0.0`.
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Number::Int(a), Number::Int(b)) => a == b,
-            (Number::UInt(a), Number::UInt(b)) => a == b,
             (Number::Float(a), Number::Float(b)) => a.to_bits() == b.to_bits(),
-            (Number::Int(a), Number::UInt(b)) => {
-                if *a >= 0 {
-                    (*a as u64) == *b
-                } else {
-                    false
-                }
-            }
-            (Number::UInt(a), Number::Int(b)) => {
-                if *b >= 0 {
-                    *a == (*b as u64)
-                } else {
-                    false
-                }
-            }
-            (Number::Int(a), Number::Float(b)) => (*a as f64).to_bits() == b.to_bits(),
-            (Number::Float(a), Number::Int(b)) => a.to_bits() == (*b as f64).to_bits(),
-            (Number::UInt(a), Number::Float(b)) => (*a as f64).to_bits() == b.to_bits(),
-            (Number::Float(a), Number::UInt(b)) => a.to_bits() == (*b as f64).to_bits(),
+            _ => match (self.exact_integer(), other.exact_integer()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
         }
     }
 }
@@ -429,42 +992,106 @@ impl PartialOrd for Number {
 }
 
 impl Ord for Number {
+    /// Orders by exact numeric value, with floats placed as by
+    /// `f64::total_cmp`.
     fn cmp(&self, other: &Self) -> Ordering {
-        // Convert to f64 for comparison, using total_cmp for proper NaN handling
-        let a = match self {
-            Number::Int(n) => *n as f64,
-            Number::UInt(n) => *n as f64,
-            Number::Float(f) => *f,
-        };
-        let b = match other {
-            Number::Int(n) => *n as f64,
-            Number::UInt(n) => *n as f64,
-            Number::Float(f) => *f,
-        };
-        a.total_cmp(&b)
+        match (self.wide(), other.wide()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(a), None) => cmp_wide_float(a, other.as_f64()),
+            (None, Some(b)) => cmp_wide_float(b, self.as_f64()).reverse(),
+            (None, None) => self.as_f64().total_cmp(&other.as_f64()),
+        }
     }
 }
 
 impl Hash for Number {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // Hash based on the numeric value, normalized to bits for consistency
-        match self {
-            Number::Int(n) => {
+        // Equal numbers share an exact integer value or a float bit pattern
+        match self.exact_integer() {
+            Some(w) => {
                 0u8.hash(state);
-                n.hash(state);
+                w.hash(state);
             }
-            Number::UInt(n) => {
+            None => {
                 1u8.hash(state);
-                n.hash(state);
+                self.as_f64().to_bits().hash(state);
             }
-            Number::Float(f) => {
-                2u8.hash(state);
-                f.to_bits().hash(state);
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    /// Formats the number as a plain YAML scalar.
+    ///
+    /// Integers use their decimal form. Floats follow YAML conventions:
+    /// `.inf`, `-.inf` and `.nan` for the special values, and otherwise the
+    /// shortest representation that parses back to the same `f64`, always
+    /// containing a `.` or an exponent so it is not re-read as an integer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Int(n) => write!(f, "{}", n),
+            Number::UInt(n) => write!(f, "{}", n),
+            Number::I128(n) => write!(f, "{}", n),
+            Number::U128(n) => write!(f, "{}", n),
+            Number::Float(v) => {
+                if v.is_nan() {
+                    f.write_str(".nan")
+                } else if v.is_infinite() {
+                    if v.is_sign_positive() {
+                        f.write_str(".inf")
+                    } else {
+                        f.write_str("-.inf")
+                    }
+                } else {
+                    // Debug gives the shortest round-trip form and keeps a
+                    // trailing `.0` on integral values (`1.0`, `1e300`)
+                    write!(f, "{:?}", v)
+                }
             }
         }
     }
 }
 
+/// Implements `TryFrom<Number>` via an exact integer conversion.
+///
+/// Floats are refused, even integral ones, as are integers out of range.
+macro_rules! try_from_number {
+    ($ty:ty, $expected:expr) => {
+        impl TryFrom<Number> for $ty {
+            type Error = crate::error::Error;
+
+            fn try_from(n: Number) -> std::result::Result<Self, Self::Error> {
+                let exact = match n.wide() {
+                    Some(Wide::Neg(v)) => <$ty>::try_from(v).ok(),
+                    Some(Wide::NonNeg(v)) => <$ty>::try_from(v).ok(),
+                    None => None,
+                };
+                exact.ok_or(crate::error::Error::TypeMismatch {
+                    expected: $expected,
+                    got: if n.is_integer() {
+                        "out-of-range integer"
+                    } else {
+                        "float"
+                    },
+                })
+            }
+        }
+    };
+}
+
+try_from_number!(i64, "integer");
+try_from_number!(u64, "unsigned integer");
+
+impl TryFrom<Number> for f64 {
+    type Error = crate::error::Error;
+
+    /// Converts like [`Number::as_f64`], rounding integers that `f64`
+    /// cannot hold exactly, so it never fails.
+    fn try_from(n: Number) -> std::result::Result<Self, Self::Error> {
+        Ok(n.as_f64())
+    }
+}
+
 impl PartialOrd for TaggedValue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -490,6 +1117,25 @@ impl std::ops::Index<&str> for Value {
     }
 }
 
+impl std::ops::Index<String> for Value {
+    type Output = Value;
+
+    fn index(&self, key: String) -> &Self::Output {
+        &self[key.as_str()]
+    }
+}
+
+impl std::ops::Index<&Value> for Value {
+    type Output = Value;
+
+    /// Looks up a mapping key of any type, such as `Value::from(7)` for
+    /// `{7: x}`; like `[&str]`, a miss gives `Null`.
+    fn index(&self, key: &Value) -> &Self::Output {
+        static NULL: Value = Value::Null;
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
 impl std::ops::Index<usize> for Value {
     type Output = Value;
 
@@ -502,6 +1148,78 @@ impl std::ops::Index<usize> for Value {
     }
 }
 
+/// Mutable indexing by key auto-vivifies, like `serde_json`: a missing key
+/// is inserted with a `Null` value, and `Null` itself is first replaced by
+/// an empty mapping, so `value["a"]["b"] = x.into()` builds the mappings
+/// it goes through. This changes the value's shape even when the result is
+/// only read: `&mut value["typo"]` leaves a `typo: ~` entry behind (use
+/// [`get_mut`](Value::get_mut) to look up without inserting).
+///
+/// # Panics
+///
+/// Panics if the value is neither a mapping nor `Null`. Tags are not looked
+/// through, so a tagged mapping panics too.
+///
+/// # Example
+///
+/// ```
+/// use fyaml::Value;
+///
+/// let mut value: Value = "{server: {port: 80}}".parse().unwrap();
+/// value["server"]["port"] = 8080.into();
+/// value["server"]["tls"]["cert"] = "c.pem".into();
+/// assert_eq!(value, "{server: {port: 8080, tls: {cert: c.pem}}}".parse::<Value>().unwrap());
+/// ```
+impl std::ops::IndexMut<&str> for Value {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        self.index_or_insert(Value::from(key))
+    }
+}
+
+/// Like `IndexMut<&str>`.
+impl std::ops::IndexMut<String> for Value {
+    fn index_mut(&mut self, key: String) -> &mut Self::Output {
+        self.index_or_insert(Value::from(key))
+    }
+}
+
+/// Like `IndexMut<&str>`, for keys of any type.
+impl std::ops::IndexMut<&Value> for Value {
+    fn index_mut(&mut self, key: &Value) -> &mut Self::Output {
+        self.index_or_insert(key.clone())
+    }
+}
+
+/// Mutable indexing into a sequence.
+///
+/// Unlike shared indexing, which gives `Null` for a miss, nothing is
+/// inserted: a sequence is never extended.
+///
+/// # Panics
+///
+/// Panics if the value is not a sequence or `index` is out of bounds.
+impl std::ops::IndexMut<usize> for Value {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            Value::Sequence(v) => {
+                let len = v.len();
+                match v.get_mut(index) {
+                    Some(item) => item,
+                    None => panic!(
+                        "index {} out of bounds for sequence of length {}",
+                        index, len
+                    ),
+                }
+            }
+            other => panic!(
+                "cannot index into a {} value with index {}",
+                other.index_type_name(),
+                index
+            ),
+        }
+    }
+}
+
 impl FromStr for Value {
     type Err = crate::error::Error;
 
@@ -516,8 +1234,16 @@ impl FromStr for Value {
 }
 
 impl fmt::Display for Value {
+    /// Formats the value as YAML: on one line in flow style with `{}` (see
+    /// [`Value::to_yaml_string_flow`]), in block style with `{:#}` (see
+    /// [`Value::to_yaml_string`]).
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.to_yaml_string() {
+        let yaml = if f.alternate() {
+            self.to_yaml_string()
+        } else {
+            self.to_yaml_string_flow()
+        };
+        match yaml {
             Ok(s) => write!(f, "{}", s),
             Err(e) => write!(f, "<error: {}>", e),
         }
@@ -555,6 +1281,18 @@ impl From<u32> for Value {
     }
 }
 
+impl From<i128> for Value {
+    fn from(n: i128) -> Self {
+        Value::Number(Number::I128(n))
+    }
+}
+
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        Value::Number(Number::U128(n))
+    }
+}
+
 impl From<f64> for Value {
     fn from(f: f64) -> Self {
         Value::Number(Number::Float(f))
@@ -594,6 +1332,251 @@ impl<T: Into<Value>> From<Option<T>> for Value {
     }
 }
 
+// Comparison with primitives, so `value["port"] == 8080` reads naturally
+
+fn eq_str(value: &Value, other: &str) -> bool {
+    matches!(value, Value::String(s) if s == other)
+}
+
+fn eq_bool(value: &Value, other: bool) -> bool {
+    matches!(value, Value::Bool(b) if *b == other)
+}
+
+fn eq_number(value: &Value, other: Number) -> bool {
+    matches!(value, Value::Number(n) if *n == other)
+}
+
+/// Implements `PartialEq` between `Value` (or `&Value`) and a primitive,
+/// in both directions.
+///
+/// A value equals `x` exactly when it equals `Value::from(x)`: numbers
+/// compare across variants like [`Number`] does, and tagged values never
+/// equal a primitive.
+macro_rules! partial_eq_primitive {
+    ($ty:ty, $eq:ident, |$x:ident| $conv:expr) => {
+        impl PartialEq<$ty> for Value {
+            fn eq(&self, other: &$ty) -> bool {
+                let $x = other;
+                $eq(self, $conv)
+            }
+        }
+
+        impl PartialEq<$ty> for &Value {
+            fn eq(&self, other: &$ty) -> bool {
+                let $x = other;
+                $eq(self, $conv)
+            }
+        }
+
+        impl PartialEq<Value> for $ty {
+            fn eq(&self, other: &Value) -> bool {
+                let $x = self;
+                $eq(other, $conv)
+            }
+        }
+    };
+}
+
+// One integer and one float type only: with a single candidate, literals
+// like `30` and `0.5` infer to it. Every extra impl makes comparisons whose
+// right side is inferred, such as `value == x.into()`, ambiguous.
+partial_eq_primitive!(str, eq_str, |s| s);
+partial_eq_primitive!(&str, eq_str, |s| *s);
+partial_eq_primitive!(String, eq_str, |s| s.as_str());
+partial_eq_primitive!(bool, eq_bool, |b| *b);
+partial_eq_primitive!(i64, eq_number, |n| Number::Int(*n));
+partial_eq_primitive!(f64, eq_number, |n| Number::Float(*n));
+
+// Collecting. The item type picks the impl: `Value` items build a sequence,
+// `(Value, Value)` pairs a mapping. A tuple is not a `Value`, so the two never
+// overlap; to collect pairs as a sequence, map each one to a `Value` first.
+
+/// Collects values into a [`Value::Sequence`].
+///
+/// ```
+/// use fyaml::Value;
+///
+/// let seq: Value = (0..3).map(Value::from).collect();
+/// assert_eq!(seq, "[0, 1, 2]".parse::<Value>().unwrap());
+/// ```
+impl FromIterator<Value> for Value {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Value::Sequence(iter.into_iter().collect())
+    }
+}
+
+/// Collects key-value pairs into a [`Value::Mapping`].
+///
+/// Keys keep their first position; a repeated key takes the last value.
+///
+/// ```
+/// use fyaml::Value;
+///
+/// let pairs = vec![(Value::from("a"), Value::from(1)), (Value::from("b"), Value::from(2))];
+/// let map: Value = pairs.into_iter().collect();
+/// assert_eq!(map, "{a: 1, b: 2}".parse::<Value>().unwrap());
+/// ```
+impl FromIterator<(Value, Value)> for Value {
+    fn from_iter<I: IntoIterator<Item = (Value, Value)>>(iter: I) -> Self {
+        Value::Mapping(iter.into_iter().collect())
+    }
+}
+
+/// Appends values to a sequence.
+///
+/// # Panics
+///
+/// Panics if `self` is neither a sequence nor null. Null becomes an empty
+/// sequence first.
+impl Extend<Value> for Value {
+    fn extend<I: IntoIterator<Item = Value>>(&mut self, iter: I) {
+        if self.is_null() {
+            *self = Value::Sequence(Vec::new());
+        }
+        match self {
+            Value::Sequence(seq) => seq.extend(iter),
+            other => panic!("cannot extend a {} with values", other.type_name()),
+        }
+    }
+}
+
+/// Inserts key-value pairs into a mapping, as `IndexMap::extend` does.
+///
+/// # Panics
+///
+/// Panics if `self` is neither a mapping nor null. Null becomes an empty
+/// mapping first.
+impl Extend<(Value, Value)> for Value {
+    fn extend<I: IntoIterator<Item = (Value, Value)>>(&mut self, iter: I) {
+        if self.is_null() {
+            *self = Value::Mapping(IndexMap::new());
+        }
+        match self {
+            Value::Mapping(map) => map.extend(iter),
+            other => panic!("cannot extend a {} with key-value pairs", other.type_name()),
+        }
+    }
+}
+
+impl Value {
+    /// Short name of this value's type, for error messages.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Sequence(_) => "sequence",
+            Value::Mapping(_) => "mapping",
+            Value::Tagged(t) => t.value.type_name(),
+        }
+    }
+
+    /// Returns the value under any tags.
+    fn into_untagged(self) -> Value {
+        match self {
+            Value::Tagged(t) => t.value.into_untagged(),
+            other => other,
+        }
+    }
+
+    /// Returns the mapping entry for `key`, inserting `Null` if missing and
+    /// turning `Null` into a mapping first.
+    fn index_or_insert(&mut self, key: Value) -> &mut Value {
+        if self.is_null() {
+            *self = Value::Mapping(IndexMap::new());
+        }
+        match self {
+            Value::Mapping(m) => m.entry(key).or_insert(Value::Null),
+            other => panic!(
+                "cannot index into a {} value with key `{}`",
+                other.index_type_name(),
+                key
+            ),
+        }
+    }
+
+    /// Describes the value in indexing panics; tagged values are reported
+    /// as such since indexing does not look through tags.
+    fn index_type_name(&self) -> &'static str {
+        match self {
+            Value::Tagged(_) => "tagged",
+            other => other.type_name(),
+        }
+    }
+}
+
+/// Implements `TryFrom<&Value>` via an `as_*` accessor, without cloning.
+///
+/// Behaves exactly like the owned `TryFrom<Value>` impl.
+macro_rules! try_from_value_ref {
+    ($ty:ty, $expected:expr, |$v:ident| $extract:expr) => {
+        impl TryFrom<&Value> for $ty {
+            type Error = crate::error::Error;
+
+            fn try_from(value: &Value) -> std::result::Result<Self, Self::Error> {
+                let mut $v = value;
+                while let Value::Tagged(t) = $v {
+                    $v = &t.value;
+                }
+                match $extract {
+                    Some(out) => Ok(out),
+                    None => Err(crate::error::Error::TypeMismatch {
+                        expected: $expected,
+                        got: $v.type_name(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+try_from_value_ref!(bool, "bool", |v| v.as_bool());
+try_from_value_ref!(i64, "integer", |v| v.as_i64());
+try_from_value_ref!(u64, "unsigned integer", |v| v.as_u64());
+try_from_value_ref!(f64, "number", |v| v.as_f64());
+
+/// Implements `TryFrom<Value>` via an `as_*` accessor.
+///
+/// Tags are looked through; a value of the wrong type yields
+/// [`Error::TypeMismatch`](crate::Error::TypeMismatch).
+macro_rules! try_from_value {
+    ($ty:ty, $expected:expr, |$v:ident| $extract:expr) => {
+        impl TryFrom<Value> for $ty {
+            type Error = crate::error::Error;
+
+            fn try_from(value: Value) -> std::result::Result<Self, Self::Error> {
+                let $v = value.into_untagged();
+                let got = $v.type_name();
+                match $extract {
+                    Some(out) => Ok(out),
+                    None => Err(crate::error::Error::TypeMismatch {
+                        expected: $expected,
+                        got,
+                    }),
+                }
+            }
+        }
+    };
+}
+
+try_from_value!(bool, "bool", |v| v.as_bool());
+try_from_value!(i64, "integer", |v| v.as_i64());
+try_from_value!(u64, "unsigned integer", |v| v.as_u64());
+try_from_value!(f64, "number", |v| v.as_f64());
+try_from_value!(String, "string", |v| match v {
+    Value::String(s) => Some(s),
+    _ => None,
+});
+try_from_value!(Vec<Value>, "sequence", |v| match v {
+    Value::Sequence(items) => Some(items),
+    _ => None,
+});
+try_from_value!(IndexMap<Value, Value>, "mapping", |v| match v {
+    Value::Mapping(map) => Some(map),
+    _ => None,
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -650,6 +1633,167 @@ mod tests {
         assert_eq!(value[10], Value::Null);
     }
 
+    #[test]
+    fn test_typed_getters() {
+        let value: Value = "{s: text, i: -3, u: 7, f: 1.5, b: true, seq: [1, 2], map: {k: v}}"
+            .parse()
+            .unwrap();
+        assert_eq!(value.get_str("s"), Some("text"));
+        assert_eq!(value.get_i64("i"), Some(-3));
+        assert_eq!(value.get_u64("u"), Some(7));
+        assert_eq!(value.get_f64("f"), Some(1.5));
+        assert_eq!(value.get_bool("b"), Some(true));
+        assert_eq!(value.get_sequence("seq").map(|s| s.len()), Some(2));
+        assert_eq!(
+            value
+                .get_mapping("map")
+                .and_then(|m| m.get(&Value::from("k"))),
+            Some(&Value::from("v"))
+        );
+    }
+
+    #[test]
+    fn test_typed_getters_mismatch_and_missing() {
+        let value: Value = "{s: text, i: -3, seq: [1], map: {}}".parse().unwrap();
+        assert_eq!(value.get_i64("s"), None);
+        assert_eq!(value.get_u64("i"), None);
+        assert_eq!(value.get_str("i"), None);
+        assert_eq!(value.get_bool("s"), None);
+        assert_eq!(value.get_f64("seq"), None);
+        assert!(value.get_sequence("map").is_none());
+        assert!(value.get_mapping("seq").is_none());
+        assert_eq!(value.get_str("missing"), None);
+        assert_eq!(Value::from(1).get_str("s"), None);
+    }
+
+    #[test]
+    fn test_as_i128_and_u128() {
+        assert_eq!(Value::from(-5).as_i128(), Some(-5));
+        assert_eq!(Value::from(u64::MAX).as_i128(), Some(u64::MAX as i128));
+        assert_eq!(Value::from(u64::MAX).as_u128(), Some(u64::MAX as u128));
+        assert_eq!(Value::from(-5).as_u128(), None);
+        assert_eq!(Value::from(1.5).as_i128(), None);
+    }
+
+    #[test]
+    fn test_128_bit_accessors_downcast() {
+        let big = Value::Number(Number::U128(u128::MAX));
+        assert_eq!(big.as_u128(), Some(u128::MAX));
+        assert_eq!(big.as_i128(), None);
+        assert_eq!(big.as_u64(), None);
+
+        let small = Value::Number(Number::I128(-7));
+        assert_eq!(small.as_i64(), Some(-7));
+        assert_eq!(small.as_u64(), None);
+        assert_eq!(Value::Number(Number::U128(7)).as_u64(), Some(7));
+        assert_eq!(Value::Number(Number::I128(i128::MIN)).as_i64(), None);
+        assert_eq!(Value::from(1u128 << 100).as_f64(), Some(2f64.powi(100)));
+    }
+
+    #[test]
+    fn test_128_bit_parse_and_display() {
+        let value: Value = "[170141183460469231731687303715884105727, -9223372036854775809]"
+            .parse()
+            .unwrap();
+        assert_eq!(value[0], Value::Number(Number::U128(i128::MAX as u128)));
+        assert_eq!(value[1], Value::Number(Number::I128(i64::MIN as i128 - 1)));
+        assert_eq!(value[0].as_i128(), Some(i128::MAX));
+        assert_eq!(
+            value.to_yaml_string().unwrap(),
+            "- 170141183460469231731687303715884105727\n- -9223372036854775809"
+        );
+        assert_eq!(Number::U128(u128::MAX).to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn test_mixed_variant_number_consistency() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(n: &Number) -> u64 {
+            let mut h = DefaultHasher::new();
+            n.hash(&mut h);
+            h.finish()
+        }
+
+        let ones = [
+            Number::Int(1),
+            Number::UInt(1),
+            Number::I128(1),
+            Number::U128(1),
+            Number::Float(1.0),
+        ];
+        for a in &ones {
+            for b in &ones {
+                assert_eq!(a, b);
+                assert_eq!(a.cmp(b), Ordering::Equal);
+                assert_eq!(hash_of(a), hash_of(b));
+            }
+        }
+
+        // Exact comparison past the f64 mantissa
+        let exact = Number::U128((1 << 53) + 1);
+        assert_ne!(exact, Number::Float(2f64.powi(53)));
+        assert!(exact > Number::Float(2f64.powi(53)));
+        assert!(Number::UInt(1 << 53) < exact);
+        assert_eq!(Number::Float(2f64.powi(100)), Number::U128(1 << 100));
+
+        // Order across variants and signs
+        let mut sorted = vec![
+            Number::U128(u128::MAX),
+            Number::Float(0.5),
+            Number::I128(i128::MIN),
+            Number::Float(-0.5),
+            Number::Int(-1),
+            Number::Float(f64::INFINITY),
+            Number::UInt(0),
+            Number::Float(-0.0),
+        ];
+        sorted.sort();
+        assert_eq!(
+            sorted,
+            [
+                Number::I128(i128::MIN),
+                Number::Int(-1),
+                Number::Float(-0.5),
+                Number::Float(-0.0),
+                Number::UInt(0),
+                Number::Float(0.5),
+                Number::U128(u128::MAX),
+                Number::Float(f64::INFINITY),
+            ]
+        );
+        assert_ne!(Number::Int(0), Number::Float(-0.0));
+    }
+
+    #[test]
+    fn test_number_display_integers() {
+        assert_eq!(Number::Int(-42).to_string(), "-42");
+        assert_eq!(Number::UInt(42).to_string(), "42");
+        assert_eq!(Number::UInt(u64::MAX).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_number_display_floats() {
+        assert_eq!(Number::Float(2.5).to_string(), "2.5");
+        assert_eq!(Number::Float(1.0).to_string(), "1.0");
+        assert_eq!(Number::Float(-0.1).to_string(), "-0.1");
+        assert_eq!(Number::Float(1e300).to_string(), "1e300");
+        for f in [0.1, 1.0 / 3.0, 1e-7, 123456789.125] {
+            let s = Number::Float(f).to_string();
+            assert_eq!(
+                crate::scalar_parse::parse_number(&s),
+                Some(Number::Float(f))
+            );
+        }
+    }
+
+    #[test]
+    fn test_number_display_special_floats() {
+        assert_eq!(Number::Float(f64::INFINITY).to_string(), ".inf");
+        assert_eq!(Number::Float(f64::NEG_INFINITY).to_string(), "-.inf");
+        assert_eq!(Number::Float(f64::NAN).to_string(), ".nan");
+    }
+
     #[test]
     fn test_from_impls() {
         assert_eq!(Value::from(true), Value::Bool(true));
@@ -658,4 +1802,382 @@ mod tests {
         assert_eq!(Value::from(2.5f64), Value::Number(Number::Float(2.5)));
         assert_eq!(Value::from("hello"), Value::String("hello".into()));
     }
+
+    fn keys(value: &Value) -> Vec<&str> {
+        value
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_keys() {
+        let mut value: Value = "{c: 3, a: 1, b: 2}".parse().unwrap();
+        value.reorder_keys(&["a", "b"]);
+        assert_eq!(keys(&value), ["a", "b", "c"]);
+        assert_eq!(value["c"], Value::from(3i64));
+    }
+
+    #[test]
+    fn test_reorder_keys_keeps_rest_in_order() {
+        let mut value: Value = "{z: 1, y: 2, m: 3, x: 4}".parse().unwrap();
+        value.reorder_keys(&["missing", "x", "m", "x"]);
+        assert_eq!(keys(&value), ["x", "m", "z", "y"]);
+    }
+
+    #[test]
+    fn test_reorder_keys_non_mapping_is_noop() {
+        let mut value: Value = "[b, a]".parse().unwrap();
+        let before = value.clone();
+        value.reorder_keys(&["a"]);
+        assert_eq!(value, before);
+
+        let mut tagged: Value = "!cfg {b: 1, a: 2}".parse().unwrap();
+        tagged.reorder_keys(&["a"]);
+        assert_eq!(keys(&tagged.as_tagged().unwrap().value), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_approx_eq_floats() {
+        let a = Value::Number(Number::Float(0.1));
+        let b = Value::Number(Number::Float(0.1 + 1e-12));
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, 1e-9));
+        assert!(!a.approx_eq(&b, 1e-15));
+        assert!(a.approx_eq(&Value::Number(Number::Int(0)), 0.5));
+    }
+
+    #[test]
+    fn test_approx_eq_nested() {
+        let a: Value = "{x: [1.0, {y: 2.5}], tag: !t 3.0, s: str}".parse().unwrap();
+        let b: Value = "{s: str, tag: !t 3.000000000001, x: [1.000000000001, {y: 2.5}]}"
+            .parse()
+            .unwrap();
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, 1e-9));
+
+        let c: Value = "{x: [1.0, {y: 2.5}], tag: !u 3.0, s: str}".parse().unwrap();
+        assert!(!a.approx_eq(&c, 1e-9));
+    }
+
+    #[test]
+    fn test_approx_eq_exact_for_non_floats() {
+        let big = Value::Number(Number::UInt(u64::MAX));
+        let near = Value::Number(Number::UInt(u64::MAX - 1));
+        assert!(!big.approx_eq(&near, 10.0));
+        assert!(!Value::from("a").approx_eq(&Value::from("b"), 1.0));
+        let seq: Value = "[1.0, 2.0]".parse().unwrap();
+        let longer: Value = "[1.0, 2.0, 3.0]".parse().unwrap();
+        assert!(!seq.approx_eq(&longer, 1.0));
+    }
+
+    #[test]
+    fn test_approx_eq_special_floats() {
+        let nan = Value::Number(Number::Float(f64::NAN));
+        let inf = Value::Number(Number::Float(f64::INFINITY));
+        assert!(nan.approx_eq(&nan, 0.0));
+        assert!(!nan.approx_eq(&inf, f64::INFINITY));
+        assert!(inf.approx_eq(&inf, 0.0));
+        assert!(!inf.approx_eq(&Value::Number(Number::Float(1e308)), f64::INFINITY));
+        assert!(!inf.approx_eq(&Value::Number(Number::Float(f64::NEG_INFINITY)), 1.0));
+    }
+
+    #[test]
+    fn test_sequence_with_capacity() {
+        let mut value = Value::sequence_with_capacity(1000);
+        let items = value.as_sequence_mut().unwrap();
+        assert!(items.is_empty());
+        let capacity = items.capacity();
+        assert!(capacity >= 1000);
+        let start = items.as_ptr();
+        for i in 0..1000 {
+            items.push(Value::from(i));
+        }
+        // No reallocation happened
+        assert_eq!(items.capacity(), capacity);
+        assert_eq!(items.as_ptr(), start);
+
+        let items = value.as_sequence().unwrap();
+        assert_eq!(items.len(), 1000);
+        assert_eq!(items[0], Value::from(0));
+        assert_eq!(items[999], Value::from(999));
+    }
+
+    #[test]
+    fn test_mapping_with_capacity() {
+        let mut value = Value::mapping_with_capacity(100);
+        let map = value.as_mapping_mut().unwrap();
+        let capacity = map.capacity();
+        assert!(capacity >= 100);
+        for i in 0..100 {
+            map.insert(Value::from(format!("k{}", i)), Value::from(i));
+        }
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(value.get_i64("k42"), Some(42));
+        assert_eq!(value.as_mapping().unwrap().len(), 100);
+    }
+
+    #[test]
+    fn test_try_mapping_from_pairs() {
+        let value = Value::try_mapping_from_pairs(vec![
+            (Value::from("name"), Value::from("app")),
+            (Value::from(1), Value::from(true)),
+            (Value::from("port"), Value::from(8080)),
+        ])
+        .unwrap();
+        let map = value.as_mapping().unwrap();
+        let keys: Vec<&Value> = map.keys().collect();
+        assert_eq!(
+            keys,
+            [&Value::from("name"), &Value::from(1), &Value::from("port")]
+        );
+        assert_eq!(value.get_i64("port"), Some(8080));
+
+        let empty = Value::try_mapping_from_pairs(Vec::new()).unwrap();
+        assert_eq!(empty, Value::Mapping(IndexMap::new()));
+    }
+
+    #[test]
+    fn test_try_mapping_from_pairs_duplicate() {
+        let err = Value::try_mapping_from_pairs(vec![
+            (Value::from("a"), Value::from(1)),
+            (Value::from("b"), Value::from(2)),
+            (Value::from("a"), Value::from(3)),
+        ])
+        .unwrap_err();
+        assert_eq!(err, crate::error::Error::DuplicateKey("a".to_string()));
+        assert!(err.to_string().contains("a"));
+
+        // Non-string keys are rendered as YAML in the error
+        let err = Value::try_mapping_from_pairs(vec![
+            (Value::from(7), Value::Null),
+            (Value::from(7), Value::Null),
+        ])
+        .unwrap_err();
+        assert_eq!(err, crate::error::Error::DuplicateKey("7".to_string()));
+    }
+
+    #[test]
+    fn test_get_merged() {
+        let value: Value = "<<: {a: 1, b: 1}\nb: 2".parse().unwrap();
+        assert_eq!(value.get_merged("a"), Some(&Value::from(1)));
+        assert_eq!(value.get_merged("b"), Some(&Value::from(2)));
+        assert_eq!(value.get_merged("z"), None);
+        assert_eq!(Value::from(1).get_merged("a"), None);
+    }
+
+    #[test]
+    fn test_get_merged_nested_and_invalid_sources() {
+        let mut deep = IndexMap::new();
+        deep.insert(Value::from("deep"), Value::from(2));
+        let mut first = IndexMap::new();
+        first.insert(Value::from("x"), Value::from(1));
+        first.insert(Value::from("<<"), Value::Mapping(deep));
+        let mut last = IndexMap::new();
+        last.insert(Value::from("deep"), Value::from(3));
+        last.insert(Value::from("y"), Value::from(4));
+        let mut map = IndexMap::new();
+        map.insert(
+            Value::from("<<"),
+            Value::Sequence(vec![
+                Value::Mapping(first),
+                Value::from(5),
+                Value::Mapping(last),
+            ]),
+        );
+        let value = Value::Mapping(map);
+        assert_eq!(value.get_merged("x"), Some(&Value::from(1)));
+        assert_eq!(value.get_merged("deep"), Some(&Value::from(2)));
+        assert_eq!(value.get_merged("y"), Some(&Value::from(4)));
+
+        // A scalar merge target is a no-op
+        let mut map = IndexMap::new();
+        map.insert(Value::from("<<"), Value::from(5));
+        map.insert(Value::from("a"), Value::from(1));
+        let value = Value::Mapping(map);
+        assert_eq!(value.get_merged("a"), Some(&Value::from(1)));
+        assert_eq!(value.get_merged("b"), None);
+    }
+
+    #[test]
+    fn test_table_from_rows() {
+        let table = Value::table_from_rows(
+            &["name", "port"],
+            &[
+                vec![Value::from("web"), Value::from(80)],
+                vec![Value::from("db"), Value::from(5432)],
+                vec![Value::from("cache"), Value::Null],
+            ],
+        )
+        .unwrap();
+        let rows = table.as_sequence().unwrap();
+        assert_eq!(rows.len(), 3);
+        for row in rows {
+            let keys: Vec<&str> = row
+                .as_mapping()
+                .unwrap()
+                .keys()
+                .map(|k| k.as_str().unwrap())
+                .collect();
+            assert_eq!(keys, ["name", "port"]);
+        }
+        assert_eq!(rows[0].get_str("name"), Some("web"));
+        assert_eq!(rows[1].get_i64("port"), Some(5432));
+        assert_eq!(rows[2]["port"], Value::Null);
+
+        let empty = Value::table_from_rows(&["a"], &[]).unwrap();
+        assert_eq!(empty, Value::Sequence(vec![]));
+    }
+
+    #[test]
+    fn test_table_from_rows_errors() {
+        let err = Value::table_from_rows(
+            &["name", "port"],
+            &[
+                vec![Value::from("web"), Value::from(80)],
+                vec![Value::from("db")],
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::Error::RowLengthMismatch {
+                row: 1,
+                expected: 2,
+                got: 1
+            }
+        );
+
+        let err =
+            Value::table_from_rows(&["a", "a"], &[vec![Value::Null, Value::Null]]).unwrap_err();
+        assert_eq!(err, crate::error::Error::DuplicateKey("a".to_string()));
+    }
+
+    #[test]
+    fn test_number_checked_add() {
+        let add = |a: Number, b: Number| a.checked_add(&b);
+
+        assert!(matches!(
+            add(Number::Int(2), Number::UInt(3)),
+            Some(Number::UInt(5))
+        ));
+        assert!(matches!(
+            add(Number::Int(-5), Number::UInt(3)),
+            Some(Number::Int(-2))
+        ));
+        assert!(matches!(
+            add(Number::UInt(u64::MAX), Number::Int(-1)),
+            Some(Number::UInt(n)) if n == u64::MAX - 1
+        ));
+        // Overflowing 64 bits widens instead of failing
+        assert!(matches!(
+            add(Number::Int(i64::MIN), Number::Int(-1)),
+            Some(Number::I128(n)) if n == i64::MIN as i128 - 1
+        ));
+        assert!(matches!(
+            add(Number::UInt(u64::MAX), Number::UInt(1)),
+            Some(Number::U128(n)) if n == u64::MAX as u128 + 1
+        ));
+        assert!(matches!(
+            add(Number::I128(i128::MIN), Number::U128(u128::MAX)),
+            Some(Number::U128(n)) if n == u128::MAX / 2
+        ));
+        // Past 128 bits it fails
+        assert_eq!(add(Number::U128(u128::MAX), Number::Int(1)), None);
+        assert_eq!(add(Number::I128(i128::MIN), Number::Int(-1)), None);
+
+        // Any float makes the sum a float
+        assert!(matches!(
+            add(Number::Int(1), Number::Float(0.5)),
+            Some(Number::Float(f)) if f == 1.5
+        ));
+        assert!(matches!(
+            add(Number::Float(1.0), Number::Float(2.0)),
+            Some(Number::Float(f)) if f == 3.0
+        ));
+        assert!(add(Number::U128(u128::MAX), Number::Float(1.0)).is_some());
+    }
+
+    #[test]
+    fn test_number_conversions() {
+        use crate::error::Error;
+
+        assert!(Number::I128(5).is_integer());
+        assert!(!Number::Float(5.0).is_integer());
+        assert_eq!(Number::U128(1 << 60).as_f64(), 2f64.powi(60));
+
+        assert_eq!(i64::try_from(Number::U128(7)), Ok(7));
+        assert_eq!(u64::try_from(Number::Int(7)), Ok(7));
+        assert_eq!(
+            i64::try_from(Number::UInt(u64::MAX)),
+            Err(Error::TypeMismatch {
+                expected: "integer",
+                got: "out-of-range integer",
+            })
+        );
+        assert!(u64::try_from(Number::Int(-1)).is_err());
+        assert_eq!(
+            i64::try_from(Number::Float(2.0)),
+            Err(Error::TypeMismatch {
+                expected: "integer",
+                got: "float",
+            })
+        );
+        assert_eq!(f64::try_from(Number::Int(-3)), Ok(-3.0));
+        assert_eq!(f64::try_from(Number::Float(0.25)), Ok(0.25));
+    }
+
+    #[test]
+    fn test_try_from_value_ref() {
+        use crate::error::Error;
+
+        let value: Value = "{n: 42, f: 1.5, t: !id 7, s: x, b: true}".parse().unwrap();
+        assert_eq!(i64::try_from(&value["n"]), Ok(42));
+        assert_eq!(u64::try_from(&value["t"]), Ok(7));
+        assert_eq!(f64::try_from(&value["f"]), Ok(1.5));
+        assert_eq!(f64::try_from(&value["n"]), Ok(42.0));
+        assert_eq!(bool::try_from(&value["b"]), Ok(true));
+        assert_eq!(
+            i64::try_from(&value["s"]),
+            Err(Error::TypeMismatch {
+                expected: "integer",
+                got: "string",
+            })
+        );
+        // Same results as the owned conversions
+        assert_eq!(
+            i64::try_from(&value["f"]),
+            i64::try_from(value["f"].clone())
+        );
+    }
+
+    #[test]
+    fn test_partial_eq_primitives() {
+        let value: Value = "{name: Alice, age: 30, big: 18446744073709551615, r: 0.5, ok: true, \
+                            t: !id 7, q: '30'}"
+            .parse()
+            .unwrap();
+        assert_eq!(value["name"], "Alice");
+        assert!(value["name"] == *"Alice");
+        assert_eq!(value["name"], String::from("Alice"));
+        assert_eq!("Alice", value["name"]);
+        assert_eq!(value["age"], 30);
+        assert_eq!(value["age"], 30.0);
+        assert_eq!(30i64, value["age"]);
+        assert_eq!(value["big"], Value::from(u64::MAX));
+        assert_eq!(value["r"], 0.5);
+        assert_eq!(value["ok"], true);
+        assert_eq!(value.get("age").unwrap(), 30);
+
+        assert_ne!(value["age"], 31);
+        assert_ne!(value["age"], "30");
+        assert_ne!(value["q"], 30);
+        assert_ne!(value["r"], 0);
+        assert_ne!(value["ok"], 1);
+        assert_ne!(value["missing"], "");
+        // Tags are part of the value
+        assert_ne!(value["t"], 7);
+    }
 }
@@ -0,0 +1,507 @@
+//! JSON pointer (RFC 6901) access for `Value`.
+//!
+//! Pointers are `/`-separated reference tokens, with `~1` standing for `/`
+//! and `~0` for `~` inside a token. The empty pointer refers to the whole
+//! value. Tagged values are transparent: pointers look through the tag to
+//! the inner value.
+
+use super::Value;
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use std::mem;
+
+impl Value {
+    /// Looks up a value by JSON pointer.
+    ///
+    /// Matches `serde_json::Value::pointer`: the empty pointer `""` returns
+    /// the whole value, any other pointer must start with `/`, and sequence
+    /// tokens must be decimal indices without leading zeros. Mapping tokens
+    /// match string keys only.
+    ///
+    /// Returns `None` if the pointer is malformed or nothing exists there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let value: Value = "users: [{name: ann}]\na/b: {c~d: 1}".parse().unwrap();
+    /// assert_eq!(value.pointer("/users/0/name").unwrap().as_str(), Some("ann"));
+    /// assert_eq!(value.pointer("/a~1b/c~0d"), Some(&Value::from(1)));
+    /// assert_eq!(value.pointer(""), Some(&value));
+    /// assert_eq!(value.pointer("users"), None);
+    /// ```
+    pub fn pointer(&self, ptr: &str) -> Option<&Value> {
+        if ptr.is_empty() {
+            return Some(self);
+        }
+        let rest = ptr.strip_prefix('/')?;
+        rest.split('/')
+            .try_fold(self, |current, token| match untag(current) {
+                Value::Mapping(m) => m.get(&Value::String(unescape_token(token))),
+                Value::Sequence(s) => parse_index(token).ok().and_then(|i| s.get(i)),
+                _ => None,
+            })
+    }
+
+    /// Looks up a value by JSON pointer for modification.
+    ///
+    /// Same rules as [`pointer`](Self::pointer). Unlike
+    /// [`pointer_set`](Self::pointer_set), nothing is created along the way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let mut value: Value = "server: {ports: [80, 443]}".parse().unwrap();
+    /// *value.pointer_mut("/server/ports/0").unwrap() = Value::from(8080);
+    /// assert_eq!(value["server"]["ports"][0], Value::from(8080));
+    /// assert!(value.pointer_mut("/server/host").is_none());
+    /// ```
+    pub fn pointer_mut(&mut self, ptr: &str) -> Option<&mut Value> {
+        if ptr.is_empty() {
+            return Some(self);
+        }
+        let rest = ptr.strip_prefix('/')?;
+        rest.split('/')
+            .try_fold(self, |current, token| match untag_mut(current) {
+                Value::Mapping(m) => m.get_mut(&Value::String(unescape_token(token))),
+                Value::Sequence(s) => parse_index(token).ok().and_then(move |i| s.get_mut(i)),
+                _ => None,
+            })
+    }
+
+    /// Sets the value at a JSON pointer, returning the value it replaced.
+    ///
+    /// Missing intermediate mapping keys are created as empty mappings, and
+    /// a `null` along the way is turned into a mapping. In a sequence, the
+    /// token `-` appends a new element; any other token must be the index of
+    /// an existing element. Sparse sequences are never created.
+    ///
+    /// The empty pointer `""` replaces the whole value.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidPointer`] if the pointer is not empty and does not
+    ///   start with `/`, or a sequence token is not a valid index.
+    /// - [`Error::IndexOutOfBounds`] if a sequence index is past the end.
+    /// - [`Error::TypeMismatch`] if the pointer passes through a scalar.
+    ///
+    /// The value is left unchanged when an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::value::Value;
+    ///
+    /// let mut value: Value = "server: {ports: [80]}".parse().unwrap();
+    /// value.pointer_set("/server/tls/enabled", Value::Bool(true)).unwrap();
+    /// value.pointer_set("/server/ports/-", Value::from(443)).unwrap();
+    ///
+    /// let old = value.pointer_set("/server/ports/0", Value::from(8080)).unwrap();
+    /// assert_eq!(old, Some(Value::from(80)));
+    /// assert_eq!(value["server"]["tls"]["enabled"], Value::Bool(true));
+    /// assert_eq!(value["server"]["ports"][1], Value::from(443));
+    /// ```
+    pub fn pointer_set(&mut self, ptr: &str, value: Value) -> Result<Option<Value>> {
+        if ptr.is_empty() {
+            return Ok(Some(mem::replace(self, value)));
+        }
+        let rest = ptr
+            .strip_prefix('/')
+            .ok_or(Error::InvalidPointer("pointer must start with '/'"))?;
+
+        let mut tokens: Vec<String> = rest.split('/').map(unescape_token).collect();
+        // Non-empty pointer always has at least one token
+        let last = tokens.pop().unwrap_or_default();
+
+        // Check the whole path before touching anything so an error leaves
+        // the value unchanged
+        check_path(self, &tokens, &last)?;
+
+        let mut current = self;
+        for token in &tokens {
+            current = step_creating(current, token)?;
+        }
+
+        let parent = as_container(untag_mut(current));
+        match parent {
+            Value::Mapping(m) => Ok(m.insert(Value::String(last), value)),
+            Value::Sequence(s) => {
+                if last == "-" {
+                    s.push(value);
+                    return Ok(None);
+                }
+                let index = parse_index(&last)?;
+                let len = s.len();
+                match s.get_mut(index) {
+                    Some(slot) => Ok(Some(mem::replace(slot, value))),
+                    None => Err(Error::IndexOutOfBounds { index, len }),
+                }
+            }
+            other => Err(not_a_container(other)),
+        }
+    }
+
+    /// Looks up the value at a JSON pointer and converts it to `T`.
+    ///
+    /// Useful for pulling typed settings out of a config without matching
+    /// on each level by hand. Tags along the path and on the target are
+    /// looked through. Convert to `Value` to take the subtree as is.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::PathNotFound`] if nothing exists at `ptr`, including a
+    ///   sequence token that is not a valid in-bounds index.
+    /// - [`Error::TypeMismatchAt`] if the value exists but has the wrong
+    ///   type for `T`.
+    /// - [`Error::InvalidPointer`] if the pointer is not empty and does not
+    ///   start with `/`.
+    ///
+    /// Both path errors carry `ptr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Error, Value};
+    ///
+    /// let config: Value = "server: {port: 8080, host: localhost}".parse().unwrap();
+    /// let port: u64 = config.require_path("/server/port").unwrap();
+    /// assert_eq!(port, 8080);
+    ///
+    /// let err = config.require_path::<u64>("/server/timeout").unwrap_err();
+    /// assert_eq!(err, Error::PathNotFound("/server/timeout".into()));
+    /// ```
+    pub fn require_path<T>(&self, ptr: &str) -> Result<T>
+    where
+        T: TryFrom<Value>,
+        Error: From<T::Error>,
+    {
+        if !ptr.is_empty() && !ptr.starts_with('/') {
+            return Err(Error::InvalidPointer("pointer must start with '/'"));
+        }
+        let value = self
+            .pointer(ptr)
+            .ok_or_else(|| Error::PathNotFound(ptr.to_owned()))?;
+        T::try_from(value.clone()).map_err(|e| match Error::from(e) {
+            Error::TypeMismatch { expected, got } => Error::TypeMismatchAt {
+                path: ptr.to_owned(),
+                expected,
+                got,
+            },
+            other => other,
+        })
+    }
+}
+
+/// Decodes `~1` and `~0` escapes in a reference token.
+fn unescape_token(token: &str) -> String {
+    if token.contains('~') {
+        token.replace("~1", "/").replace("~0", "~")
+    } else {
+        token.to_owned()
+    }
+}
+
+/// Parses a sequence index token: decimal digits, no leading zeros.
+fn parse_index(token: &str) -> Result<usize> {
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    if !valid {
+        return Err(Error::InvalidPointer("invalid sequence index"));
+    }
+    token
+        .parse()
+        .map_err(|_| Error::InvalidPointer("sequence index too large"))
+}
+
+/// Looks through any tags to the underlying value.
+fn untag_mut(value: &mut Value) -> &mut Value {
+    match value {
+        Value::Tagged(t) => untag_mut(&mut t.value),
+        other => other,
+    }
+}
+
+fn untag(value: &Value) -> &Value {
+    match value {
+        Value::Tagged(t) => untag(&t.value),
+        other => other,
+    }
+}
+
+/// Turns a `null` into an empty mapping so it can receive keys.
+fn as_container(value: &mut Value) -> &mut Value {
+    if value.is_null() {
+        *value = Value::Mapping(IndexMap::new());
+    }
+    value
+}
+
+fn not_a_container(value: &Value) -> Error {
+    let got = match value {
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        _ => "scalar",
+    };
+    Error::TypeMismatch {
+        expected: "mapping or sequence",
+        got,
+    }
+}
+
+/// Validates that `pointer_set` can succeed without mutating anything.
+///
+/// Once a step leaves the existing tree (a missing key or `-`), everything
+/// below it is created fresh and cannot fail.
+fn check_path(root: &Value, tokens: &[String], last: &str) -> Result<()> {
+    let mut current = Some(root);
+    for token in tokens
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(last))
+    {
+        let node = match current {
+            Some(node) => untag(node),
+            None => return Ok(()),
+        };
+        current = match node {
+            Value::Null => None,
+            Value::Mapping(m) => m.get(&Value::String(token.to_owned())),
+            Value::Sequence(s) => {
+                if token == "-" {
+                    None
+                } else {
+                    let index = parse_index(token)?;
+                    let len = s.len();
+                    Some(s.get(index).ok_or(Error::IndexOutOfBounds { index, len })?)
+                }
+            }
+            other => return Err(not_a_container(other)),
+        };
+    }
+    Ok(())
+}
+
+/// Descends one token, creating the child if it is missing.
+fn step_creating<'a>(current: &'a mut Value, token: &str) -> Result<&'a mut Value> {
+    match as_container(untag_mut(current)) {
+        Value::Mapping(m) => Ok(m
+            .entry(Value::String(token.to_owned()))
+            .or_insert(Value::Null)),
+        Value::Sequence(s) => {
+            if token == "-" {
+                s.push(Value::Null);
+                let last = s.len() - 1;
+                return Ok(&mut s[last]);
+            }
+            let index = parse_index(token)?;
+            let len = s.len();
+            s.get_mut(index)
+                .ok_or(Error::IndexOutOfBounds { index, len })
+        }
+        other => Err(not_a_container(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Value {
+        yaml.parse().unwrap()
+    }
+
+    #[test]
+    fn test_pointer_set_deep_new_key() {
+        let mut value = parse("a: 1");
+        let old = value
+            .pointer_set("/b/c/d", Value::String("deep".into()))
+            .unwrap();
+        assert_eq!(old, None);
+        assert_eq!(value["b"]["c"]["d"].as_str(), Some("deep"));
+        assert_eq!(value["a"], Value::from(1));
+    }
+
+    #[test]
+    fn test_pointer_set_replaces_existing() {
+        let mut value = parse("a: {b: old}");
+        let old = value.pointer_set("/a/b", Value::from("new")).unwrap();
+        assert_eq!(old, Some(Value::from("old")));
+        assert_eq!(value["a"]["b"].as_str(), Some("new"));
+    }
+
+    #[test]
+    fn test_pointer_set_append_with_dash() {
+        let mut value = parse("items: [1, 2]");
+        let old = value.pointer_set("/items/-", Value::from(3)).unwrap();
+        assert_eq!(old, None);
+        assert_eq!(value["items"].as_sequence().unwrap().len(), 3);
+        assert_eq!(value["items"][2], Value::from(3));
+
+        value
+            .pointer_set("/items/-/name", Value::from("x"))
+            .unwrap();
+        assert_eq!(value["items"][3]["name"].as_str(), Some("x"));
+    }
+
+    #[test]
+    fn test_pointer_set_sparse_index_errors() {
+        let mut value = parse("items: [1, 2]");
+        let before = value.clone();
+        let err = value.pointer_set("/items/5", Value::from(3)).unwrap_err();
+        assert_eq!(err, Error::IndexOutOfBounds { index: 5, len: 2 });
+        let err = value.pointer_set("/items/5/x", Value::from(3)).unwrap_err();
+        assert_eq!(err, Error::IndexOutOfBounds { index: 5, len: 2 });
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn test_pointer_set_through_scalar_errors() {
+        let mut value = parse("a: 1");
+        let err = value.pointer_set("/a/b", Value::Null).unwrap_err();
+        assert!(matches!(err, Error::TypeMismatch { .. }));
+        assert_eq!(value, parse("a: 1"));
+    }
+
+    #[test]
+    fn test_pointer_set_invalid_pointer() {
+        let mut value = parse("items: [1]");
+        assert!(matches!(
+            value.pointer_set("a", Value::Null),
+            Err(Error::InvalidPointer(_))
+        ));
+        assert!(matches!(
+            value.pointer_set("/items/01", Value::Null),
+            Err(Error::InvalidPointer(_))
+        ));
+    }
+
+    #[test]
+    fn test_pointer_set_escapes_and_root() {
+        let mut value = parse("{}");
+        value.pointer_set("/a~1b/c~0d", Value::from(1)).unwrap();
+        assert_eq!(value["a/b"]["c~d"], Value::from(1));
+
+        let old = value.pointer_set("", Value::from("root")).unwrap();
+        assert!(old.unwrap().is_mapping());
+        assert_eq!(value, Value::from("root"));
+    }
+
+    #[test]
+    fn test_pointer_set_through_tag() {
+        let mut value = parse("cfg: !custom {a: 1}");
+        value.pointer_set("/cfg/b", Value::from(2)).unwrap();
+        let tagged = value["cfg"].as_tagged().unwrap();
+        assert_eq!(tagged.tag, "!custom");
+        assert_eq!(tagged.value["b"], Value::from(2));
+    }
+
+    #[test]
+    fn test_require_path_typed() {
+        let value = parse("server: {port: 8080, name: !env api, tags: [a, b]}");
+        assert_eq!(value.require_path::<u64>("/server/port").unwrap(), 8080);
+        assert_eq!(value.require_path::<i64>("/server/port").unwrap(), 8080);
+        assert_eq!(value.require_path::<String>("/server/name").unwrap(), "api");
+        assert_eq!(value.require_path::<String>("/server/tags/1").unwrap(), "b");
+        let tags: Vec<Value> = value.require_path("/server/tags").unwrap();
+        assert_eq!(tags.len(), 2);
+        let whole: Value = value.require_path("").unwrap();
+        assert_eq!(whole, value);
+    }
+
+    #[test]
+    fn test_require_path_not_found() {
+        let value = parse("server: {port: 8080, tags: [a]}");
+        for ptr in [
+            "/server/host",
+            "/server/tags/3",
+            "/server/port/x",
+            "/missing/deep",
+        ] {
+            assert_eq!(
+                value.require_path::<String>(ptr).unwrap_err(),
+                Error::PathNotFound(ptr.to_owned())
+            );
+        }
+        assert!(matches!(
+            value.require_path::<String>("server"),
+            Err(Error::InvalidPointer(_))
+        ));
+    }
+
+    #[test]
+    fn test_require_path_type_mismatch() {
+        let value = parse("server: {port: 8080, host: localhost}");
+        let err = value.require_path::<bool>("/server/port").unwrap_err();
+        assert_eq!(
+            err,
+            Error::TypeMismatchAt {
+                path: "/server/port".into(),
+                expected: "bool",
+                got: "number",
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Type mismatch at /server/port: expected bool, got number"
+        );
+        assert!(matches!(
+            value.require_path::<u64>("/server/host"),
+            Err(Error::TypeMismatchAt { ref path, .. }) if path == "/server/host"
+        ));
+    }
+
+    #[test]
+    fn test_pointer_lookup() {
+        let value =
+            parse("users:\n  - name: ann\n    roles: [admin]\na/b: {c~d: 1}\ncfg: !t {k: v}");
+        assert_eq!(
+            value.pointer("/users/0/name").unwrap().as_str(),
+            Some("ann")
+        );
+        assert_eq!(
+            value.pointer("/users/0/roles/0").unwrap().as_str(),
+            Some("admin")
+        );
+        assert_eq!(value.pointer("/a~1b/c~0d"), Some(&Value::from(1)));
+        assert_eq!(value.pointer("/cfg/k").unwrap().as_str(), Some("v"));
+        assert_eq!(value.pointer(""), Some(&value));
+    }
+
+    #[test]
+    fn test_pointer_misses() {
+        let value = parse("users: [{name: ann}]\nn: 1");
+        for ptr in [
+            "users",
+            "/missing",
+            "/users/1",
+            "/users/01",
+            "/users/+0",
+            "/users/-",
+            "/users/name",
+            "/n/x",
+            "/users/0/name/",
+        ] {
+            assert_eq!(value.pointer(ptr), None, "{}", ptr);
+        }
+    }
+
+    #[test]
+    fn test_pointer_mut() {
+        let mut value = parse("a: {b: [1, 2]}");
+        *value.pointer_mut("/a/b/1").unwrap() = Value::from("two");
+        assert_eq!(value["a"]["b"][1].as_str(), Some("two"));
+        value
+            .pointer_mut("")
+            .unwrap()
+            .as_mapping_mut()
+            .unwrap()
+            .clear();
+        assert_eq!(value, parse("{}"));
+        assert!(value.pointer_mut("/a").is_none());
+    }
+}
@@ -38,10 +38,14 @@ impl Serialize for Number {
     where
         S: Serializer,
     {
-        match self {
-            Number::Int(n) => serializer.serialize_i64(*n),
-            Number::UInt(n) => serializer.serialize_u64(*n),
-            Number::Float(f) => serializer.serialize_f64(*f),
+        // Not every serializer supports 128-bit integers; use them only
+        // for values that need them
+        match self.narrowed() {
+            Number::Int(n) => serializer.serialize_i64(n),
+            Number::UInt(n) => serializer.serialize_u64(n),
+            Number::Float(f) => serializer.serialize_f64(f),
+            Number::I128(n) => serializer.serialize_i128(n),
+            Number::U128(n) => serializer.serialize_u128(n),
         }
     }
 }
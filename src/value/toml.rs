@@ -0,0 +1,123 @@
+//! TOML interop: parsing TOML text into Value.
+//!
+//! Enabled by the `toml` cargo feature.
+
+use super::{Number, Value};
+use crate::error::{Error, ParseError, Result};
+use indexmap::IndexMap;
+
+impl Value {
+    /// Parses a TOML document into a `Value`.
+    ///
+    /// The top-level table becomes a `Mapping`, keeping key order. TOML
+    /// dates and times have no `Value` counterpart and become strings in
+    /// their RFC 3339 form, which YAML readers resolve as timestamps.
+    ///
+    /// Requires the `toml` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] with the line and column of the first
+    /// syntax error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Value;
+    ///
+    /// let value = Value::from_toml_str("name = \"app\"\n[db]\nport = 5432\n").unwrap();
+    /// assert_eq!(value["db"]["port"].as_i64(), Some(5432));
+    /// ```
+    pub fn from_toml_str(s: &str) -> Result<Value> {
+        let table: ::toml::Table = s.parse().map_err(|e: ::toml::de::Error| {
+            let message = e.message().to_owned();
+            match e.span() {
+                Some(span) => {
+                    let (line, column) = line_column(s, span.start);
+                    Error::ParseError(ParseError::with_location(message, line, column))
+                }
+                None => Error::ParseError(ParseError::new(message)),
+            }
+        })?;
+        Ok(from_table(table))
+    }
+}
+
+fn from_table(table: ::toml::Table) -> Value {
+    let mut map = IndexMap::with_capacity(table.len());
+    for (k, v) in table {
+        map.insert(Value::String(k), from_toml(v));
+    }
+    Value::Mapping(map)
+}
+
+fn from_toml(value: ::toml::Value) -> Value {
+    match value {
+        ::toml::Value::String(s) => Value::String(s),
+        ::toml::Value::Integer(i) => Value::Number(Number::Int(i)),
+        ::toml::Value::Float(f) => Value::Number(Number::Float(f)),
+        ::toml::Value::Boolean(b) => Value::Bool(b),
+        ::toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        ::toml::Value::Array(items) => Value::Sequence(items.into_iter().map(from_toml).collect()),
+        ::toml::Value::Table(table) => from_table(table),
+    }
+}
+
+/// 1-based line and column of byte offset `pos` in `s`.
+fn line_column(s: &str, pos: usize) -> (u32, u32) {
+    let before = &s[..pos.min(s.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line as u32, column as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_str_matches_yaml() {
+        let toml = r#"
+name = "app"
+replicas = 3
+ratio = 0.5
+tags = ["a", "b"]
+
+[db]
+host = "localhost"
+tls = true
+"#;
+        let from_toml = Value::from_toml_str(toml).unwrap();
+        let from_yaml: Value =
+            "name: app\nreplicas: 3\nratio: 0.5\ntags: [a, b]\ndb: {host: localhost, tls: true}"
+                .parse()
+                .unwrap();
+        assert_eq!(from_toml, from_yaml);
+
+        let yaml = from_toml.to_yaml_string().unwrap();
+        assert!(yaml.starts_with("name: app\n"), "got:\n{}", yaml);
+        assert_eq!(yaml.parse::<Value>().unwrap(), from_toml);
+    }
+
+    #[test]
+    fn test_from_toml_str_datetime_as_string() {
+        let value = Value::from_toml_str("released = 1979-05-27T07:32:00Z").unwrap();
+        assert_eq!(value["released"].as_str(), Some("1979-05-27T07:32:00Z"));
+    }
+
+    #[test]
+    fn test_from_toml_str_error_location() {
+        let err = Value::from_toml_str("a = 1\nb = = 2\n").unwrap_err();
+        match err {
+            Error::ParseError(e) => assert_eq!(e.line(), Some(2)),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_line_column() {
+        assert_eq!(line_column("ab\ncd", 0), (1, 1));
+        assert_eq!(line_column("ab\ncd", 4), (2, 2));
+    }
+}
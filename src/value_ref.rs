@@ -37,8 +37,12 @@
 //! assert_eq!(root.get("active").unwrap().as_bool(), Some(true));
 //! ```
 
+use crate::document::push_pointer_token;
+use crate::error::{Error, Result};
+use crate::node::ScalarType;
 use crate::node_ref::NodeRef;
 use crate::scalar_parse;
+use std::borrow::Cow;
 use std::fmt;
 
 /// A zero-copy typed view of a YAML node.
@@ -158,6 +162,39 @@ impl<'doc> ValueRef<'doc> {
         self.node.scalar_str().ok()
     }
 
+    /// Returns the scalar value, borrowed only if it is verbatim input text.
+    ///
+    /// The content is the same as [`as_str`](Self::as_str) returns (see
+    /// [`NodeRef::scalar_bytes`] for what each style yields). It is
+    /// `Cow::Borrowed` when it is an unmodified slice of the parsed input,
+    /// as for most single-line plain and quoted scalars, and `Cow::Owned`
+    /// when libfyaml had to decode it: escapes, `''`, folding, block scalar
+    /// indentation, or nodes built with the [`Editor`](crate::Editor).
+    ///
+    /// Returns `None` if this is not a scalar or if the content is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    /// use std::borrow::Cow;
+    ///
+    /// let doc = Document::parse_str("plain: abc\nescaped: \"a\\tb\"").unwrap();
+    /// let root = doc.root_value().unwrap();
+    /// assert!(matches!(root.get("plain").unwrap().as_cow_str(), Some(Cow::Borrowed("abc"))));
+    /// let escaped = root.get("escaped").unwrap().as_cow_str().unwrap();
+    /// assert!(matches!(escaped, Cow::Owned(_)));
+    /// assert_eq!(escaped, "a\tb");
+    /// ```
+    pub fn as_cow_str(&self) -> Option<Cow<'doc, str>> {
+        let s = self.as_str()?;
+        if self.node.document().input_contains(s.as_ptr(), s.len()) {
+            Some(Cow::Borrowed(s))
+        } else {
+            Some(Cow::Owned(s.to_owned()))
+        }
+    }
+
     /// Returns the scalar value as a byte slice (zero-copy).
     ///
     /// Returns `None` if this is not a scalar.
@@ -165,6 +202,58 @@ impl<'doc> ValueRef<'doc> {
         self.node.scalar_bytes().ok()
     }
 
+    /// Returns the scalar value if it equals one of the `allowed` strings.
+    ///
+    /// This is a small validation helper for enum-like fields. The comparison
+    /// is exact (case-sensitive) and uses the raw scalar content, so quoted
+    /// scalars match as well.
+    ///
+    /// Returns `None` if this is not a scalar or the value is not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("level: debug").unwrap();
+    /// let root = doc.root_value().unwrap();
+    /// let level = root.get("level").unwrap();
+    /// assert_eq!(level.as_one_of(&["debug", "info", "warn"]), Some("debug"));
+    /// assert_eq!(level.as_one_of(&["info", "warn"]), None);
+    /// ```
+    pub fn as_one_of<'a>(&self, allowed: &'a [&'a str]) -> Option<&'doc str> {
+        let s = self.as_str()?;
+        if allowed.contains(&s) {
+            Some(s)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if this is a scalar whose content matches `re`.
+    ///
+    /// Useful for schema-style checks. The pattern may match anywhere in
+    /// the value; anchor it with `^...$` to check the whole value. Returns
+    /// `false` for sequences and mappings.
+    ///
+    /// Requires the `regex` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    /// use regex::Regex;
+    ///
+    /// let semver = Regex::new(r"^\d+\.\d+\.\d+$").unwrap();
+    /// let doc = Document::parse_str("version: 1.2.3").unwrap();
+    /// let root = doc.root_value().unwrap();
+    /// assert!(root.get("version").unwrap().scalar_matches(&semver));
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn scalar_matches(&self, re: &regex::Regex) -> bool {
+        self.node.scalar_matches(re)
+    }
+
     // ==================== Type Interpretation ====================
 
     /// Interprets the scalar as a boolean.
@@ -258,6 +347,36 @@ impl<'doc> ValueRef<'doc> {
         scalar_parse::parse_u64(s)
     }
 
+    /// Interprets the scalar as a signed 128-bit integer.
+    ///
+    /// Accepts the same forms as [`as_i64`](Self::as_i64), for values that
+    /// do not fit in 64 bits.
+    ///
+    /// Returns `None` if not a scalar, non-plain, not a valid integer,
+    /// or overflows `i128`.
+    pub fn as_i128(&self) -> Option<i128> {
+        if !self.node.is_scalar() || self.node.is_non_plain() {
+            return None;
+        }
+        let s = self.node.scalar_str().ok()?;
+        scalar_parse::parse_i128(s)
+    }
+
+    /// Interprets the scalar as an unsigned 128-bit integer.
+    ///
+    /// Accepts the same forms as [`as_u64`](Self::as_u64), for values that
+    /// do not fit in 64 bits.
+    ///
+    /// Returns `None` if not a scalar, non-plain, negative, not a valid integer,
+    /// or overflows `u128`.
+    pub fn as_u128(&self) -> Option<u128> {
+        if !self.node.is_scalar() || self.node.is_non_plain() {
+            return None;
+        }
+        let s = self.node.scalar_str().ok()?;
+        scalar_parse::parse_u128(s)
+    }
+
     /// Interprets the scalar as a 64-bit floating point number.
     ///
     /// Recognizes:
@@ -319,6 +438,40 @@ impl<'doc> ValueRef<'doc> {
         self.node.map_get(key).map(ValueRef::new)
     }
 
+    /// Gets a value from a mapping by string key, following `<<` merge keys.
+    ///
+    /// Explicit keys win. Otherwise the `<<` value is searched: a single
+    /// mapping, or a sequence of mappings where earlier entries override
+    /// later ones. Merge sources may themselves contain `<<`. A `<<` whose
+    /// value is not a mapping (or a sequence item that is not) is ignored.
+    ///
+    /// Returns `None` if this is not a mapping or the key is not found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let yaml = "base: &base {image: alpine, retries: 2}\njob:\n  <<: *base\n  retries: 5";
+    /// let doc = Document::parse_str(yaml).unwrap();
+    /// let job = doc.root_value().unwrap().get("job").unwrap();
+    /// assert_eq!(job.get_merged("image").unwrap().as_str(), Some("alpine"));
+    /// assert_eq!(job.get_merged("retries").unwrap().as_i64(), Some(5));
+    /// ```
+    pub fn get_merged(&self, key: &str) -> Option<ValueRef<'doc>> {
+        if !self.is_mapping() {
+            return None;
+        }
+        if key != MERGE_KEY {
+            if let Some(v) = self.get(key) {
+                return Some(v);
+            }
+        }
+        merge_sources(self.get(MERGE_KEY)?)
+            .into_iter()
+            .find_map(|source| source.get_merged(key))
+    }
+
     /// Gets a sequence item by index.
     ///
     /// Negative indices count from the end (-1 is the last element).
@@ -328,6 +481,122 @@ impl<'doc> ValueRef<'doc> {
         self.node.seq_get(i).map(ValueRef::new)
     }
 
+    /// Returns the items of a sequence as an array, if it has exactly `N`.
+    ///
+    /// Returns `None` if this is not a sequence or its length is not `N`.
+    /// Handy for fixed-shape data such as coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("pos: [1.5, -2, 0.25]").unwrap();
+    /// let pos = doc.root_value().unwrap().get("pos").unwrap();
+    ///
+    /// let [x, y, z] = pos.as_array::<3>().unwrap();
+    /// assert_eq!((x.as_f64(), y.as_f64(), z.as_f64()), (Some(1.5), Some(-2.0), Some(0.25)));
+    /// assert!(pos.as_array::<2>().is_none());
+    /// ```
+    pub fn as_array<const N: usize>(&self) -> Option<[ValueRef<'doc>; N]> {
+        if self.seq_len()? != N {
+            return None;
+        }
+        let items: Vec<ValueRef<'doc>> = self.seq_iter().collect();
+        items.try_into().ok()
+    }
+
+    // ==================== Typed Lookup ====================
+
+    /// Gets a mapping value by key as a string slice, with a descriptive error.
+    ///
+    /// Any scalar is accepted, as with [`as_str`](Self::as_str). The other
+    /// `get_*` helpers work the same way, each accepting what its `as_*`
+    /// counterpart does.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::TypeMismatch`] if this is not a mapping.
+    /// - [`Error::PathNotFound`] holding the key if it is missing.
+    /// - [`Error::TypeMismatchAt`] holding the key, the expected type and the
+    ///   kind of node found (`"mapping"`, `"string"`, `"null"`, ...) if the
+    ///   value does not convert.
+    ///
+    /// The key is reported as a path relative to this mapping, escaped like
+    /// any other path segment (`/tls`, `/a~1b`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::{Document, Error};
+    ///
+    /// let doc = Document::parse_str("server: {host: db, port: http}").unwrap();
+    /// let server = doc.root_value().unwrap().get("server").unwrap();
+    /// assert_eq!(server.get_str("host").unwrap(), "db");
+    ///
+    /// let err = server.get_i64("port").unwrap_err();
+    /// assert_eq!(err.to_string(), "Type mismatch at /port: expected integer, got string");
+    /// assert!(matches!(server.get_bool("tls"), Err(Error::PathNotFound(key)) if key == "/tls"));
+    /// ```
+    pub fn get_str(&self, key: &str) -> Result<&'doc str> {
+        self.get_typed(key, "string", |v| v.as_str())
+    }
+
+    /// Gets a mapping value by key as an `i64` (see [`get_str`](Self::get_str)).
+    pub fn get_i64(&self, key: &str) -> Result<i64> {
+        self.get_typed(key, "integer", |v| v.as_i64())
+    }
+
+    /// Gets a mapping value by key as a `bool` (see [`get_str`](Self::get_str)).
+    pub fn get_bool(&self, key: &str) -> Result<bool> {
+        self.get_typed(key, "bool", |v| v.as_bool())
+    }
+
+    /// Gets a mapping value by key as an `f64` (see [`get_str`](Self::get_str)).
+    pub fn get_f64(&self, key: &str) -> Result<f64> {
+        self.get_typed(key, "number", |v| v.as_f64())
+    }
+
+    /// Looks up `key` and converts its value, describing any failure.
+    fn get_typed<T>(
+        &self,
+        key: &str,
+        expected: &'static str,
+        convert: impl FnOnce(&ValueRef<'doc>) -> Option<T>,
+    ) -> Result<T> {
+        if !self.is_mapping() {
+            return Err(Error::TypeMismatch {
+                expected: "mapping",
+                got: self.kind_name(),
+            });
+        }
+        let path = || {
+            let mut path = String::new();
+            push_pointer_token(&mut path, key);
+            path
+        };
+        let value = self.get(key).ok_or_else(|| Error::PathNotFound(path()))?;
+        convert(&value).ok_or_else(|| Error::TypeMismatchAt {
+            path: path(),
+            expected,
+            got: value.kind_name(),
+        })
+    }
+
+    /// Short name of this node's kind, for error messages.
+    fn kind_name(&self) -> &'static str {
+        match self.node.inferred_scalar_type() {
+            None if self.is_sequence() => "sequence",
+            None if self.is_mapping() => "mapping",
+            None => "scalar",
+            Some(ScalarType::Null) => "null",
+            Some(ScalarType::Bool) => "bool",
+            Some(ScalarType::Int) => "integer",
+            Some(ScalarType::Float) => "float",
+            Some(ScalarType::Str) => "string",
+        }
+    }
+
     // ==================== Length ====================
 
     /// Returns the number of items in a sequence.
@@ -389,6 +658,56 @@ impl<'doc> ValueRef<'doc> {
             .map(|(k, v)| (ValueRef::new(k), ValueRef::new(v)))
     }
 
+    /// Returns an iterator over the mapping's keys as borrowed strings.
+    ///
+    /// Keys are zero-copy slices into the document. Keys that are not
+    /// scalars (or are not valid UTF-8) are skipped. If this is not a
+    /// mapping, the iterator will be empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let doc = Document::parse_str("name: app\nport: 80").unwrap();
+    /// let root = doc.root_value().unwrap();
+    /// assert!(root.map_key_strs().any(|k| k == "port"));
+    /// ```
+    pub fn map_key_strs(&self) -> impl Iterator<Item = &'doc str> {
+        self.node
+            .map_iter()
+            .filter_map(|(k, _)| k.scalar_str().ok())
+    }
+
+    /// Returns an iterator over the mapping's effective entries after
+    /// resolving `<<` merge keys.
+    ///
+    /// Explicit entries come first, in document order, followed by inherited
+    /// entries whose key was not already seen. `<<` entries themselves are
+    /// not yielded. Each key maps to the value [`get_merged`](Self::get_merged)
+    /// would return. If this is not a mapping, the iterator will be empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyaml::Document;
+    ///
+    /// let yaml = "base: &base {a: 1, b: 2}\nchild:\n  <<: *base\n  b: 3";
+    /// let doc = Document::parse_str(yaml).unwrap();
+    /// let child = doc.root_value().unwrap().get("child").unwrap();
+    /// let entries: Vec<(&str, i64)> = child
+    ///     .merged_map_iter()
+    ///     .map(|(k, v)| (k.as_str().unwrap(), v.as_i64().unwrap()))
+    ///     .collect();
+    /// assert_eq!(entries, [("b", 3), ("a", 1)]);
+    /// ```
+    pub fn merged_map_iter(&self) -> impl Iterator<Item = (ValueRef<'doc>, ValueRef<'doc>)> {
+        let mut entries = Vec::new();
+        let mut seen = Vec::new();
+        collect_merged(*self, &mut entries, &mut seen);
+        entries.into_iter()
+    }
+
     // ==================== Tag Access ====================
 
     /// Returns the YAML tag as a string slice (zero-copy).
@@ -399,6 +718,45 @@ impl<'doc> ValueRef<'doc> {
     }
 }
 
+/// The YAML merge key.
+const MERGE_KEY: &str = "<<";
+
+/// Returns the mappings a `<<` value merges in, highest priority first.
+fn merge_sources(merge: ValueRef<'_>) -> Vec<ValueRef<'_>> {
+    if merge.is_mapping() {
+        vec![merge]
+    } else {
+        merge.seq_iter().filter(|v| v.is_mapping()).collect()
+    }
+}
+
+/// Appends `map`'s effective entries to `entries`, skipping keys in `seen`.
+///
+/// Keys that are not scalars cannot be shadowed and are always kept.
+fn collect_merged<'doc>(
+    map: ValueRef<'doc>,
+    entries: &mut Vec<(ValueRef<'doc>, ValueRef<'doc>)>,
+    seen: &mut Vec<&'doc str>,
+) {
+    let mut merge = None;
+    for (k, v) in map.map_iter() {
+        match k.as_str() {
+            Some(MERGE_KEY) => merge = Some(v),
+            Some(key) if seen.contains(&key) => {}
+            Some(key) => {
+                seen.push(key);
+                entries.push((k, v));
+            }
+            None => entries.push((k, v)),
+        }
+    }
+    if let Some(merge) = merge {
+        for source in merge_sources(merge) {
+            collect_merged(source, entries, seen);
+        }
+    }
+}
+
 impl fmt::Debug for ValueRef<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_null() {
@@ -429,7 +787,7 @@ impl fmt::Display for ValueRef<'_> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Document;
+    use crate::{Document, Error};
 
     // ==================== Basic Access ====================
 
@@ -450,6 +808,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_as_one_of() {
+        let doc = Document::parse_str("mode: fast\nother: slow\nlist: [fast]").unwrap();
+        let root = doc.root_value().unwrap();
+        let allowed = ["fast", "safe"];
+        assert_eq!(root.get("mode").unwrap().as_one_of(&allowed), Some("fast"));
+        assert_eq!(root.get("other").unwrap().as_one_of(&allowed), None);
+        assert_eq!(root.get("list").unwrap().as_one_of(&allowed), None);
+    }
+
     // ==================== Boolean Tests ====================
 
     #[test]
@@ -523,6 +891,25 @@ mod tests {
         assert_eq!(root.get("pos").unwrap().as_u64(), Some(42));
     }
 
+    #[test]
+    fn test_as_i128_and_u128() {
+        let doc = Document::parse_str(
+            "big: 170141183460469231731687303715884105727\n\
+             huge: 340282366920938463463374607431768211455\n\
+             neg: -0x10\n\
+             quoted: '42'",
+        )
+        .unwrap();
+        let root = doc.root_value().unwrap();
+        assert_eq!(root.get("big").unwrap().as_i128(), Some(i128::MAX));
+        assert_eq!(root.get("big").unwrap().as_i64(), None);
+        assert_eq!(root.get("huge").unwrap().as_u128(), Some(u128::MAX));
+        assert_eq!(root.get("huge").unwrap().as_i128(), None);
+        assert_eq!(root.get("neg").unwrap().as_i128(), Some(-16));
+        assert_eq!(root.get("neg").unwrap().as_u128(), None);
+        assert_eq!(root.get("quoted").unwrap().as_i128(), None);
+    }
+
     // ==================== Float Tests ====================
 
     #[test]
@@ -646,6 +1033,26 @@ mod tests {
         assert_eq!(value.as_i64(), Some(42));
     }
 
+    #[test]
+    fn test_as_array_exact_length() {
+        let doc = Document::parse_str("[1.0, 2.5, -3]").unwrap();
+        let root = doc.root_value().unwrap();
+        let coords = root.as_array::<3>().unwrap();
+        let floats: Vec<f64> = coords.iter().filter_map(|v| v.as_f64()).collect();
+        assert_eq!(floats, vec![1.0, 2.5, -3.0]);
+    }
+
+    #[test]
+    fn test_as_array_wrong_length_or_type() {
+        let doc = Document::parse_str("seq: [1, 2, 3]\nmap: {a: 1}\nempty: []").unwrap();
+        let root = doc.root_value().unwrap();
+        let seq = root.get("seq").unwrap();
+        assert!(seq.as_array::<2>().is_none());
+        assert!(seq.as_array::<4>().is_none());
+        assert!(root.get("map").unwrap().as_array::<1>().is_none());
+        assert!(root.get("empty").unwrap().as_array::<0>().is_some());
+    }
+
     // ==================== Type Checking Tests ====================
 
     #[test]
@@ -664,6 +1071,90 @@ mod tests {
         assert!(!root.get("map").unwrap().is_scalar());
     }
 
+    #[test]
+    fn test_map_key_strs_borrowed() {
+        let doc = Document::parse_str("a: 1\n? [x]\n: complex\nb: 2\n'c': 3").unwrap();
+        let keys: Vec<&str> = doc.root_value().unwrap().map_key_strs().collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+
+        // Same slices as the key nodes, not copies
+        let root = doc.root().unwrap();
+        let node_keys = root.map_iter().filter_map(|(k, _)| k.scalar_str().ok());
+        for (key, node_key) in keys.iter().zip(node_keys) {
+            assert!(std::ptr::eq(key.as_ptr(), node_key.as_ptr()));
+        }
+    }
+
+    #[test]
+    fn test_map_key_strs_non_mapping() {
+        let doc = Document::parse_str("[a, b]").unwrap();
+        assert_eq!(doc.root_value().unwrap().map_key_strs().count(), 0);
+    }
+
+    // ==================== Typed Lookup Tests ====================
+
+    #[test]
+    fn test_get_typed_success() {
+        let doc = Document::parse_str("port: 8080\nratio: 0.5\ntls: yes\nname: app").unwrap();
+        let root = doc.root_value().unwrap();
+        assert_eq!(root.get_i64("port").unwrap(), 8080);
+        assert_eq!(root.get_f64("ratio").unwrap(), 0.5);
+        assert!(root.get_bool("tls").unwrap());
+        assert_eq!(root.get_str("name").unwrap(), "app");
+    }
+
+    #[test]
+    fn test_get_typed_reports_key_and_kind() {
+        let doc = Document::parse_str("port: '80'\ntls: [x]\nratio: ~\nmap: {}").unwrap();
+        let root = doc.root_value().unwrap();
+        let mismatch = |err: Error| match err {
+            Error::TypeMismatchAt {
+                path,
+                expected,
+                got,
+            } => (path, expected, got),
+            other => panic!("unexpected error: {other:?}"),
+        };
+        assert_eq!(
+            mismatch(root.get_i64("port").unwrap_err()),
+            ("/port".to_string(), "integer", "string")
+        );
+        assert_eq!(
+            mismatch(root.get_bool("tls").unwrap_err()),
+            ("/tls".to_string(), "bool", "sequence")
+        );
+        assert_eq!(
+            mismatch(root.get_f64("ratio").unwrap_err()),
+            ("/ratio".to_string(), "number", "null")
+        );
+        assert_eq!(
+            mismatch(root.get_str("map").unwrap_err()),
+            ("/map".to_string(), "string", "mapping")
+        );
+    }
+
+    #[test]
+    fn test_get_typed_missing_key_and_non_mapping() {
+        let doc = Document::parse_str("list: [1]").unwrap();
+        let root = doc.root_value().unwrap();
+        assert_eq!(
+            root.get_i64("port").unwrap_err(),
+            Error::PathNotFound("/port".into())
+        );
+        assert_eq!(
+            root.get_i64("a/b~c").unwrap_err(),
+            Error::PathNotFound("/a~1b~0c".into())
+        );
+        let list = root.get("list").unwrap();
+        assert_eq!(
+            list.get_str("x").unwrap_err(),
+            Error::TypeMismatch {
+                expected: "mapping",
+                got: "sequence",
+            }
+        );
+    }
+
     // ==================== Tag Tests ====================
 
     #[test]
@@ -680,4 +1171,95 @@ mod tests {
         let root = doc.root_value().unwrap();
         assert!(root.tag().is_none());
     }
+
+    // ==================== Merge Key Tests ====================
+
+    const CI: &str = "\
+defaults: &defaults
+  image: alpine
+  retries: 2
+extra: &extra
+  retries: 9
+  timeout: 60
+build:
+  <<: [*defaults, *extra]
+  script: make
+  retries: 5
+";
+
+    #[test]
+    fn test_get_merged() {
+        let doc = Document::parse_str(CI).unwrap();
+        let build = doc.root_value().unwrap().get("build").unwrap();
+        assert_eq!(build.get_merged("script").unwrap().as_str(), Some("make"));
+        // Explicit key wins over every merge source
+        assert_eq!(build.get_merged("retries").unwrap().as_i64(), Some(5));
+        assert_eq!(build.get_merged("image").unwrap().as_str(), Some("alpine"));
+        assert_eq!(build.get_merged("timeout").unwrap().as_i64(), Some(60));
+        assert!(build.get_merged("missing").is_none());
+    }
+
+    #[test]
+    fn test_get_merged_sequence_order() {
+        let doc = Document::parse_str("<<: [{a: 1}, {a: 2, b: 2}]").unwrap();
+        let root = doc.root_value().unwrap();
+        // Earlier sources override later ones
+        assert_eq!(root.get_merged("a").unwrap().as_i64(), Some(1));
+        assert_eq!(root.get_merged("b").unwrap().as_i64(), Some(2));
+    }
+
+    #[test]
+    fn test_get_merged_nested() {
+        let doc = Document::parse_str("<<:\n  <<: {deep: 1}\n  mid: 2\ntop: 3").unwrap();
+        let root = doc.root_value().unwrap();
+        assert_eq!(root.get_merged("deep").unwrap().as_i64(), Some(1));
+        assert_eq!(root.get_merged("mid").unwrap().as_i64(), Some(2));
+        assert_eq!(root.get_merged("top").unwrap().as_i64(), Some(3));
+    }
+
+    #[test]
+    fn test_get_merged_not_a_mapping() {
+        let doc = Document::parse_str("a: 1\nlist: [{a: 1}]").unwrap();
+        let root = doc.root_value().unwrap();
+        assert!(root.get("a").unwrap().get_merged("a").is_none());
+        assert!(root.get("list").unwrap().get_merged("a").is_none());
+        assert_eq!(root.get("list").unwrap().merged_map_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_merged_map_iter() {
+        let doc = Document::parse_str(CI).unwrap();
+        let build = doc.root_value().unwrap().get("build").unwrap();
+        let keys: Vec<&str> = build
+            .merged_map_iter()
+            .map(|(k, _)| k.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, ["script", "retries", "image", "timeout"]);
+        for (k, v) in build.merged_map_iter() {
+            let expected = build.get_merged(k.as_str().unwrap()).unwrap();
+            assert_eq!(v.as_str(), expected.as_str());
+        }
+    }
+
+    // ==================== Regex Tests ====================
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_scalar_matches() {
+        let semver = regex::Regex::new(r"^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?$").unwrap();
+        let doc = Document::parse_str("good: 1.20.3\npre: '2.0.0-rc.1'\nbad: 1.2\nlist: [1.2.3]")
+            .unwrap();
+        let root = doc.root_value().unwrap();
+        assert!(root.get("good").unwrap().scalar_matches(&semver));
+        assert!(root.get("pre").unwrap().scalar_matches(&semver));
+        assert!(!root.get("bad").unwrap().scalar_matches(&semver));
+        // Non-scalars never match, even if an item would
+        assert!(!root.get("list").unwrap().scalar_matches(&semver));
+        assert!(root
+            .get("list")
+            .unwrap()
+            .index(0)
+            .unwrap()
+            .scalar_matches(&semver));
+    }
 }
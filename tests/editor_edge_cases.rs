@@ -2,8 +2,8 @@
 //!
 //! Tests for editor operations at boundaries and error conditions.
 
-use fyaml::Document;
 use fyaml::NodeStyle;
+use fyaml::{Document, Error};
 
 // =============================================================================
 // Root Operations
@@ -152,6 +152,73 @@ fn editor_seq_append_at_root_sequence() {
     assert_eq!(root.seq_len().unwrap(), 3);
 }
 
+fn seq_items(doc: &Document, path: &str) -> Vec<String> {
+    doc.at_path(path)
+        .unwrap()
+        .seq_iter()
+        .map(|n| n.scalar_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn editor_insert_at_shifts_later_items() {
+    let mut doc = Document::parse_str("items:\n  - a\n  - c\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.insert_at("/items", 1, "b").unwrap();
+        ed.insert_at("/items", 0, "start").unwrap();
+    }
+    assert_eq!(seq_items(&doc, "/items"), ["start", "a", "b", "c"]);
+}
+
+#[test]
+fn editor_insert_at_end_and_negative_index() {
+    let mut doc = Document::parse_str("[a, b]").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.insert_at("", 2, "end").unwrap();
+        ed.insert_at("", -2, "before_end").unwrap();
+        ed.insert_at("", -5, "front").unwrap();
+    }
+    assert_eq!(
+        seq_items(&doc, ""),
+        ["front", "a", "b", "before_end", "end"]
+    );
+}
+
+#[test]
+fn editor_insert_at_minus_one_appends() {
+    let mut doc = Document::parse_str("[a, b]").unwrap();
+    doc.edit().insert_at("", -1, "last").unwrap();
+    assert_eq!(seq_items(&doc, ""), ["a", "b", "last"]);
+}
+
+#[test]
+fn editor_insert_at_empty_sequence_and_collection_item() {
+    let mut doc = Document::parse_str("items: []").unwrap();
+    doc.edit().insert_at("/items", 0, "{name: x}").unwrap();
+    assert_eq!(
+        doc.at_path("/items/0/name").unwrap().scalar_str().unwrap(),
+        "x"
+    );
+}
+
+#[test]
+fn editor_insert_at_errors() {
+    let mut doc = Document::parse_str("items: [a, b]\nmap: {k: v}").unwrap();
+    let mut ed = doc.edit();
+    assert!(ed.insert_at("/items", 3, "x").is_err());
+    assert!(ed.insert_at("/items", -4, "x").is_err());
+    assert!(matches!(
+        ed.insert_at("/map", 0, "x"),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(ed.insert_at("/missing", 0, "x").is_err());
+    assert!(ed.insert_at("/items", 0, "[unclosed").is_err());
+    drop(ed);
+    assert_eq!(seq_items(&doc, "/items"), ["a", "b"]);
+}
+
 // =============================================================================
 // Error Paths
 // =============================================================================
@@ -594,3 +661,428 @@ fn editor_copy_then_tag_and_insert() {
     assert_eq!(root.tag_str().unwrap().unwrap(), "!copied");
     assert_eq!(root.at_path("/x").unwrap().scalar_str().unwrap(), "1");
 }
+
+// =============================================================================
+// Position Preservation
+// =============================================================================
+
+fn key_order(doc: &Document) -> Vec<String> {
+    doc.root()
+        .unwrap()
+        .map_iter()
+        .map(|(k, _)| k.scalar_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn editor_set_yaml_at_replace_keeps_middle_key_position() {
+    let mut doc = Document::parse_str("a: 1\nb: 2\nc: 3\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.set_yaml_at("/b", "changed").unwrap();
+    }
+    assert_eq!(key_order(&doc), ["a", "b", "c"]);
+
+    let emitted = doc.emit().unwrap();
+    assert_eq!(emitted, "a: 1\nb: changed\nc: 3\n");
+}
+
+#[test]
+fn editor_replace_value_at_type_change_keeps_position() {
+    let mut doc = Document::parse_str("a: 1\nb: 2\nc: 3\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.replace_value_at("/b", "{x: 1, y: 2}").unwrap();
+    }
+    let emitted = doc.emit().unwrap();
+    let reparsed = Document::parse_str(&emitted).unwrap();
+    assert_eq!(
+        key_order(&reparsed),
+        ["a", "b", "c"],
+        "emitted:\n{}",
+        emitted
+    );
+    assert!(reparsed.at_path("/b").unwrap().is_mapping());
+
+    let b_line = emitted.find("b:").unwrap();
+    assert!(emitted.find("a:").unwrap() < b_line);
+    assert!(b_line < emitted.find("c:").unwrap());
+}
+
+#[test]
+fn editor_replace_value_at_sequence_keeps_index() {
+    let mut doc = Document::parse_str("[a, b, c]").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.replace_value_at("/1", "[nested]").unwrap();
+    }
+    let root = doc.root().unwrap();
+    assert_eq!(root.seq_len().unwrap(), 3);
+    assert!(root.seq_get(1).unwrap().is_sequence());
+    assert_eq!(root.seq_get(2).unwrap().scalar_str().unwrap(), "c");
+}
+
+#[test]
+fn editor_replace_value_at_missing_key_fails() {
+    let mut doc = Document::parse_str("a: 1").unwrap();
+    {
+        let mut ed = doc.edit();
+        assert!(ed.replace_value_at("/b", "2").is_err());
+        assert!(ed.replace_value_at("/a/deeper", "2").is_err());
+    }
+    assert_eq!(doc.emit().unwrap(), "a: 1\n");
+}
+
+// =============================================================================
+// Located Insertion
+// =============================================================================
+
+#[test]
+fn editor_seq_append_at_located_returns_new_index() {
+    let mut doc = Document::parse_str("jobs:\n  - build\n  - test\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        let job = ed.build_from_yaml("{name: deploy, needs: [test]}").unwrap();
+        let path = ed.seq_append_at_located("/jobs", job).unwrap();
+        assert_eq!(path, "/jobs/2");
+
+        // The returned path addresses the new item for further edits
+        let needs = format!("{}/needs", path);
+        ed.set_yaml_at(&needs, "[build, test]").unwrap();
+        assert_eq!(
+            ed.at_path(&path)
+                .unwrap()
+                .at_path("/name")
+                .unwrap()
+                .scalar_str()
+                .unwrap(),
+            "deploy"
+        );
+    }
+    assert_eq!(doc.at_path("/jobs/2/needs").unwrap().seq_len().unwrap(), 2);
+}
+
+#[test]
+fn editor_set_yaml_at_located_paths() {
+    let mut doc = Document::parse_str("a: {b: 1}\nlist: [x, y]\n").unwrap();
+    let mut ed = doc.edit();
+    assert_eq!(ed.set_yaml_at_located("/a/b", "2").unwrap(), "/a/b");
+    assert_eq!(ed.set_yaml_at_located("/a/c", "3").unwrap(), "/a/c");
+    assert_eq!(ed.set_yaml_at_located("/list/-1", "z").unwrap(), "/list/1");
+    assert_eq!(ed.at_path("/list/1").unwrap().scalar_str().unwrap(), "z");
+    assert!(ed.set_yaml_at_located("/missing/key", "1").is_err());
+}
+
+// =============================================================================
+// Key Renaming
+// =============================================================================
+
+#[test]
+fn editor_rename_key_keeps_position_and_value() {
+    let mut doc = Document::parse_str("a: 1\nhostname: \"web\"\nc: 3\n").unwrap();
+    assert!(doc.edit().rename_key("", "hostname", "host").unwrap());
+    assert_eq!(key_order(&doc), ["a", "host", "c"]);
+    assert_eq!(doc.emit().unwrap(), "a: 1\nhost: \"web\"\nc: 3\n");
+}
+
+#[test]
+fn editor_rename_key_nested_collection_value() {
+    let mut doc = Document::parse_str("server:\n  opts: &o {x: 1}\n  port: 80\n").unwrap();
+    assert!(doc.edit().rename_key("/server", "opts", "options").unwrap());
+    let value = doc.at_path("/server/options").unwrap();
+    assert_eq!(value.anchor(), Some("o"));
+    assert_eq!(value.style(), NodeStyle::Flow);
+    assert!(doc.at_path("/server/opts").is_none());
+}
+
+#[test]
+fn editor_rename_key_missing_and_same() {
+    let mut doc = Document::parse_str("a: 1\n").unwrap();
+    let mut ed = doc.edit();
+    assert!(!ed.rename_key("", "missing", "b").unwrap());
+    assert!(ed.rename_key("", "a", "a").unwrap());
+    drop(ed);
+    assert_eq!(doc.emit().unwrap(), "a: 1\n");
+}
+
+#[test]
+fn editor_rename_key_quotes_ambiguous_key() {
+    let mut doc = Document::parse_str("a: 1\n").unwrap();
+    doc.edit().rename_key("", "a", "true").unwrap();
+    let root = doc.root().unwrap();
+    let (key, _) = root.map_iter().next().unwrap();
+    assert!(key.is_quoted());
+    assert_eq!(key.scalar_str().unwrap(), "true");
+}
+
+#[test]
+fn editor_rename_key_errors() {
+    let mut doc = Document::parse_str("a: 1\nb: 2\nlist: [x]\n").unwrap();
+    let mut ed = doc.edit();
+    assert_eq!(
+        ed.rename_key("", "a", "b").unwrap_err(),
+        Error::DuplicateKey("b".into())
+    );
+    assert!(matches!(
+        ed.rename_key("/list", "0", "k"),
+        Err(Error::TypeMismatch { .. })
+    ));
+    assert!(ed.rename_key("/missing", "a", "k").is_err());
+
+    ed.lock_subtree("/a").unwrap();
+    assert_eq!(
+        ed.rename_key("", "a", "z").unwrap_err(),
+        Error::Locked { path: "/a".into() }
+    );
+    assert!(ed.rename_key("", "b", "c").unwrap());
+}
+
+// =============================================================================
+// Moving Nodes
+// =============================================================================
+
+#[test]
+fn editor_move_node_between_mappings() {
+    let yaml = "old:\n  db: &db {host: h, port: 1}\n  x: 1\nnew: {a: 0}\n";
+    let mut doc = Document::parse_str(yaml).unwrap();
+    doc.edit().move_node("/old/db", "/new/db").unwrap();
+    assert!(doc.at_path("/old/db").is_none());
+    let db = doc.at_path("/new/db").unwrap();
+    assert_eq!(db.anchor(), Some("db"));
+    assert_eq!(db.style(), NodeStyle::Flow);
+    assert_eq!(
+        doc.at_path("/new/db/port").unwrap().scalar_str().unwrap(),
+        "1"
+    );
+    assert_eq!(doc.at_path("/old/x").unwrap().scalar_str().unwrap(), "1");
+}
+
+#[test]
+fn editor_move_node_replaces_existing_value() {
+    let mut doc = Document::parse_str("a: {inner: keep}\nb: 2\n").unwrap();
+    doc.edit().move_node("/a/inner", "/b").unwrap();
+    assert_eq!(key_order(&doc), ["a", "b"]);
+    assert_eq!(doc.at_path("/b").unwrap().scalar_str().unwrap(), "keep");
+
+    // Hoisting a child over its own parent
+    let mut doc = Document::parse_str("a: {inner: [1, 2]}\n").unwrap();
+    doc.edit().move_node("/a/inner", "/a").unwrap();
+    assert_eq!(doc.at_path("/a").unwrap().seq_len().unwrap(), 2);
+}
+
+#[test]
+fn editor_move_node_within_sequence() {
+    let mut doc = Document::parse_str("[a, b, c, d]").unwrap();
+    doc.edit().move_node("/0", "/2").unwrap();
+    assert_eq!(seq_items(&doc, ""), ["b", "c", "a", "d"]);
+    doc.edit().move_node("/3", "/0").unwrap();
+    assert_eq!(seq_items(&doc, ""), ["d", "b", "c", "a"]);
+    doc.edit().move_node("/0", "/-1").unwrap();
+    assert_eq!(seq_items(&doc, ""), ["b", "c", "a", "d"]);
+    doc.edit().move_node("/1", "/1").unwrap();
+    assert_eq!(seq_items(&doc, ""), ["b", "c", "a", "d"]);
+}
+
+#[test]
+fn editor_move_node_minus_one_moves_last() {
+    let mut doc = Document::parse_str("src: [x, y]\ndst: [a, b]\n").unwrap();
+    doc.edit().move_node("/src/0", "/dst/-1").unwrap();
+    assert_eq!(seq_items(&doc, "/dst"), ["a", "b", "x"]);
+    assert_eq!(seq_items(&doc, "/src"), ["y"]);
+}
+
+#[test]
+fn editor_move_node_between_sequences_and_mappings() {
+    let mut doc = Document::parse_str("src: [x, y]\ndst: [z]\nmap: {}\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.move_node("/src/1", "/dst/0").unwrap();
+        ed.move_node("/src/0", "/dst/2").unwrap();
+        ed.move_node("/dst/1", "/map/key").unwrap();
+    }
+    assert_eq!(seq_items(&doc, "/dst"), ["y", "x"]);
+    assert_eq!(doc.at_path("/src").unwrap().seq_len().unwrap(), 0);
+    assert_eq!(doc.at_path("/map/key").unwrap().scalar_str().unwrap(), "z");
+}
+
+#[test]
+fn editor_move_node_errors_leave_document_unchanged() {
+    let yaml = "a: {b: {c: 1}}\nlist: [x]\ns: scalar\n";
+    let mut doc = Document::parse_str(yaml).unwrap();
+    {
+        let mut ed = doc.edit();
+        assert_eq!(
+            ed.move_node("/a", "/a/b/a").unwrap_err(),
+            Error::InvalidOption("cannot move a node inside itself")
+        );
+        assert_eq!(
+            ed.move_node("/a", "").unwrap_err(),
+            Error::InvalidOption("cannot move a node to the root")
+        );
+        assert_eq!(
+            ed.move_node("", "/x").unwrap_err(),
+            Error::InvalidOption("cannot move the root")
+        );
+        assert!(ed.move_node("/missing", "/x").is_err());
+        assert!(ed.move_node("/a/b", "/missing/b").is_err());
+        assert_eq!(
+            ed.move_node("/a/b", "/list/2").unwrap_err(),
+            Error::IndexOutOfBounds { index: 2, len: 1 }
+        );
+        assert_eq!(
+            ed.move_node("/a/b", "/list/-3").unwrap_err(),
+            Error::IndexOutOfBounds { index: 3, len: 1 }
+        );
+        assert_eq!(
+            ed.move_node("/a/b", "/list/name").unwrap_err(),
+            Error::InvalidOption("invalid sequence index")
+        );
+        assert!(matches!(
+            ed.move_node("/a/b", "/s/x"),
+            Err(Error::TypeMismatch { .. })
+        ));
+        ed.lock_subtree("/a/b/c").unwrap();
+        assert_eq!(
+            ed.move_node("/a/b", "/list/0").unwrap_err(),
+            Error::Locked {
+                path: "/a/b/c".into()
+            }
+        );
+    }
+    assert_eq!(
+        doc.emit().unwrap(),
+        Document::parse_str(yaml).unwrap().emit().unwrap()
+    );
+}
+
+#[test]
+fn editor_swap_nodes() {
+    let mut doc = Document::parse_str("a: {k: 'v'}\nb: [1, 2]\nlist: [x, y, z]\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.swap("/a", "/b").unwrap();
+        ed.swap("/list/0", "/list/2").unwrap();
+        ed.swap("/list/1", "/list/1").unwrap();
+    }
+    assert_eq!(key_order(&doc), ["a", "b", "list"]);
+    assert!(doc.at_path("/a").unwrap().is_sequence());
+    assert!(doc.at_path("/b/k").unwrap().is_quoted());
+    assert_eq!(seq_items(&doc, "/list"), ["z", "y", "x"]);
+}
+
+#[test]
+fn editor_swap_errors() {
+    let mut doc = Document::parse_str("a: {b: 1}\nc: 2\n").unwrap();
+    let mut ed = doc.edit();
+    assert!(ed.swap("/a", "/a/b").is_err());
+    assert!(ed.swap("", "/c").is_err());
+    assert!(ed.swap("/a", "/missing").is_err());
+    ed.lock_subtree("/a/b").unwrap();
+    assert_eq!(
+        ed.swap("/a", "/c").unwrap_err(),
+        Error::Locked {
+            path: "/a/b".into()
+        }
+    );
+}
+
+// =============================================================================
+// Anchors and Aliases
+// =============================================================================
+
+#[test]
+fn editor_anchor_and_alias_round_trip() {
+    let mut doc = Document::parse_str("defaults:\n  retries: 3\njobs:\n- a\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.set_anchor("/defaults", "defaults").unwrap();
+        let alias = ed.build_alias("defaults").unwrap();
+        ed.seq_append_at("/jobs", alias).unwrap();
+    }
+    let yaml = doc.emit().unwrap();
+    assert!(yaml.contains("defaults: &defaults"), "{}", yaml);
+    assert!(yaml.contains("- *defaults"), "{}", yaml);
+
+    let reparsed = Document::parse_str(&yaml).unwrap();
+    assert_eq!(
+        reparsed.at_path("/defaults").unwrap().anchor(),
+        Some("defaults")
+    );
+    assert_eq!(
+        reparsed.at_path("/jobs/1").unwrap().style(),
+        NodeStyle::Alias
+    );
+    // Copies keep the anchor and the alias
+    assert_eq!(doc.deep_copy().unwrap().emit().unwrap(), yaml);
+}
+
+#[test]
+fn editor_set_anchor_duplicate_name() {
+    let mut doc = Document::parse_str("a: &x 1\nb: 2\n").unwrap();
+    let mut ed = doc.edit();
+    assert_eq!(
+        ed.set_anchor("/b", "x").unwrap_err(),
+        Error::DuplicateAnchor("x".into())
+    );
+    // Re-setting a node's own anchor is fine
+    ed.set_anchor("/a", "x").unwrap();
+    ed.set_anchor("/b", "y").unwrap();
+    drop(ed);
+    assert_eq!(doc.at_path("/b").unwrap().anchor(), Some("y"));
+}
+
+#[test]
+fn editor_set_anchor_errors() {
+    let mut doc = Document::parse_str("a: 1\n").unwrap();
+    let mut ed = doc.edit();
+    assert!(ed.set_anchor("/missing", "x").is_err());
+    assert!(ed.set_anchor("/a", "").is_err());
+    assert!(ed.set_anchor("/a", "two words").is_err());
+    assert!(ed.set_anchor("/a", "x,y").is_err());
+    assert!(matches!(
+        ed.build_alias("[x]"),
+        Err(Error::InvalidOption(_))
+    ));
+    ed.lock_subtree("/a").unwrap();
+    assert_eq!(
+        ed.set_anchor("/a", "x").unwrap_err(),
+        Error::Locked { path: "/a".into() }
+    );
+}
+
+#[test]
+fn editor_alias_to_undefined_anchor_fails_at_emit() {
+    let mut doc = Document::parse_str("items: [1]\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        let alias = ed.build_alias("nowhere").unwrap();
+        ed.seq_append_at("/items", alias).unwrap();
+    }
+    let err = doc.emit().unwrap_err();
+    assert_eq!(err, Error::UndefinedAlias("nowhere".into()));
+    assert!(err.to_string().contains("*nowhere"));
+    assert!(doc.emit_stable().is_err());
+    assert!(doc.emit_to_writer(&mut Vec::<u8>::new()).is_err());
+    let items = doc.at_path("/items").unwrap();
+    assert_eq!(items.emit().unwrap_err(), err);
+    assert!(items.emit_to_writer(&mut Vec::<u8>::new()).is_err());
+
+    // Defining the anchor afterwards makes the document emittable
+    doc.edit().set_anchor("/items/0", "nowhere").unwrap();
+    assert!(doc.emit().unwrap().contains("*nowhere"));
+}
+
+#[test]
+fn editor_alias_before_its_anchor_fails_at_emit() {
+    let mut doc = Document::parse_str("first: [1]\nsecond: 2\n").unwrap();
+    {
+        let mut ed = doc.edit();
+        let alias = ed.build_alias("late").unwrap();
+        ed.seq_append_at("/first", alias).unwrap();
+        ed.set_anchor("/second", "late").unwrap();
+    }
+    assert_eq!(
+        doc.emit().unwrap_err(),
+        Error::UndefinedAlias("late".into())
+    );
+}
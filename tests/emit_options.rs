@@ -0,0 +1,414 @@
+//! Tests for `EmitOptions` and `Document::emit_with`.
+
+use fyaml::value::Value;
+use fyaml::{Document, EmitOptions};
+
+fn escaped() -> EmitOptions {
+    EmitOptions {
+        escape_unicode: true,
+        ..EmitOptions::default()
+    }
+}
+
+// =============================================================================
+// Unicode Escaping
+// =============================================================================
+
+#[test]
+fn emit_cjk_raw_by_default() {
+    let doc = Document::parse_str("city: 東京\nlist: [北京, ソウル]").unwrap();
+    let yaml = doc.emit_with(&EmitOptions::default()).unwrap();
+    assert!(yaml.contains("東京"), "expected raw UTF-8:\n{yaml}");
+    assert!(yaml.contains("ソウル"), "expected raw UTF-8:\n{yaml}");
+    assert!(!yaml.contains("\\u"));
+}
+
+#[test]
+fn emit_cjk_escaped() {
+    let doc = Document::parse_str("city: 東京\nlist: [北京, ソウル]").unwrap();
+    let yaml = doc.emit_with(&escaped()).unwrap();
+    assert!(yaml.is_ascii(), "expected only ASCII:\n{yaml}");
+    assert!(yaml.contains("\\u6771\\u4EAC"), "got:\n{yaml}");
+}
+
+#[test]
+fn emit_cjk_raw_and_escaped_reparse_equal() {
+    let doc = Document::parse_str("名前: 東京\nemoji: 😀\nplain: ascii").unwrap();
+    let raw = doc.emit_with(&EmitOptions::default()).unwrap();
+    let esc = doc.emit_with(&escaped()).unwrap();
+    assert_ne!(raw, esc);
+
+    let raw_value: Value = raw.parse().unwrap();
+    let esc_value: Value = esc.parse().unwrap();
+    assert_eq!(raw_value, esc_value);
+    assert_eq!(esc_value["名前"].as_str(), Some("東京"));
+    assert_eq!(esc_value["emoji"].as_str(), Some("😀"));
+}
+
+#[test]
+fn emit_escaped_leaves_ascii_scalars_alone() {
+    let doc = Document::parse_str("plain: ascii\nquoted: 'kept'").unwrap();
+    let yaml = doc.emit_with(&escaped()).unwrap();
+    assert_eq!(yaml, doc.emit().unwrap());
+}
+
+#[test]
+fn emit_escaped_does_not_modify_document() {
+    let doc = Document::parse_str("city: 東京").unwrap();
+    let _ = doc.emit_with(&escaped()).unwrap();
+    assert!(doc.emit().unwrap().contains("東京"));
+    assert!(!doc.at_path("/city").unwrap().is_quoted());
+}
+
+#[test]
+fn emit_escaped_only_touches_double_quoted_scalars() {
+    let doc = Document::parse_str("# réglages\nbase: &clé café # noté\ncopy: *clé\n").unwrap();
+    let yaml = doc.emit_with(&escaped()).unwrap();
+    // Anchors, aliases and comments have no escapes and stay verbatim
+    assert!(!yaml.contains("r\\u00E9glages"), "got:\n{yaml}");
+    assert!(!yaml.contains("not\\u00E9"), "got:\n{yaml}");
+    assert!(yaml.contains("&clé \"caf\\u00E9\""), "got:\n{yaml}");
+    assert!(yaml.contains("copy: *clé"), "got:\n{yaml}");
+
+    let reparsed = Document::parse_str(&yaml).unwrap();
+    assert_eq!(reparsed.at_path("/base").unwrap().anchor(), Some("clé"));
+    assert_eq!(
+        reparsed.at_path("/base").unwrap().scalar_str().unwrap(),
+        "café"
+    );
+}
+
+// =============================================================================
+// Base Indentation
+// =============================================================================
+
+#[test]
+fn emit_base_indent_prefixes_every_line() {
+    let doc = Document::parse_str("name: app\nport: 80\n").unwrap();
+    let opts = EmitOptions {
+        base_indent: 4,
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert_eq!(yaml, "    name: app\n    port: 80\n");
+}
+
+#[test]
+fn emit_base_indent_embeds_as_valid_block() {
+    let doc = Document::parse_str("name: app\nscript: |\n  echo hi\n  exit 0\nports: [80, 443]\n")
+        .unwrap();
+    let opts = EmitOptions {
+        base_indent: 2,
+        ..EmitOptions::default()
+    };
+    let embedded = format!("service:\n{}", doc.emit_with(&opts).unwrap());
+
+    let outer: Value = embedded.parse().unwrap();
+    let inner: Value = doc.emit().unwrap().parse().unwrap();
+    assert_eq!(outer["service"], inner, "embedded:\n{embedded}");
+}
+
+// =============================================================================
+// Indentation, Width and Flow Level
+// =============================================================================
+
+const NESTED: &str = "server:\n  host: h\n  ports:\n    - 80\n    - 443\n  tls:\n    cert: c\n";
+
+#[test]
+fn emit_indent_four() {
+    let doc = Document::parse_str(NESTED).unwrap();
+    let opts = EmitOptions {
+        indent: 4,
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.contains("server:\n    host: h\n"), "got:\n{yaml}");
+    assert!(yaml.contains("\n    tls:\n        cert: c"), "got:\n{yaml}");
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(reparsed, NESTED.parse::<Value>().unwrap());
+}
+
+#[test]
+fn emit_indent_zero_rejected() {
+    let doc = Document::parse_str(NESTED).unwrap();
+    let opts = EmitOptions {
+        indent: 0,
+        ..EmitOptions::default()
+    };
+    assert!(matches!(
+        doc.emit_with(&opts),
+        Err(fyaml::Error::InvalidOption(_))
+    ));
+    let node = doc.at_path("/server").unwrap();
+    assert!(node.emit_with(&opts).is_err());
+}
+
+#[test]
+fn emit_default_options_match_emit() {
+    let doc = Document::parse_str(NESTED).unwrap();
+    let default = doc.emit_with(&EmitOptions::default()).unwrap();
+    assert_eq!(default, doc.emit().unwrap());
+    assert!(default.contains("\n  host: h\n"));
+}
+
+#[test]
+fn emit_width_below_indent_still_round_trips() {
+    let doc = Document::parse_str("text: alpha beta gamma delta\nnested: {k: v}\n").unwrap();
+    let opts = EmitOptions {
+        indent: 4,
+        width: Some(1),
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(
+        reparsed["text"].as_str(),
+        Some("alpha beta gamma delta"),
+        "got:\n{yaml}"
+    );
+    assert_eq!(reparsed["nested"]["k"].as_str(), Some("v"));
+}
+
+#[test]
+fn emit_flow_level_keeps_top_level_block() {
+    let doc = Document::parse_str(NESTED).unwrap();
+    let opts = EmitOptions {
+        flow_level: Some(1),
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.starts_with("server: {"), "got:\n{yaml}");
+    assert!(yaml.contains("[80, 443]"), "got:\n{yaml}");
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(reparsed, NESTED.parse::<Value>().unwrap());
+
+    // The source document keeps its block style
+    assert!(doc.emit().unwrap().contains("\n    - 80\n"));
+}
+
+#[test]
+fn noderef_emit_with_flow_level_zero() {
+    let doc = Document::parse_str(NESTED).unwrap();
+    let node = doc.at_path("/server").unwrap();
+    let opts = EmitOptions {
+        flow_level: Some(0),
+        ..EmitOptions::default()
+    };
+    let yaml = node.emit_with(&opts).unwrap();
+    assert!(yaml.starts_with('{'), "got:\n{yaml}");
+    assert!(!yaml.trim_end().contains('\n'), "got:\n{yaml}");
+    assert_eq!(
+        node.emit_with(&EmitOptions::default()).unwrap(),
+        node.emit().unwrap()
+    );
+}
+
+// =============================================================================
+// Key Sorting
+// =============================================================================
+
+const UNSORTED: &str = "zeta:\n  c: 3\n  a: 1\n  b: 2\nalpha:\n  y: [2, 1]\n  x: {q: 1, p: 2}\n";
+
+#[test]
+fn emit_sort_keys_from_depth_one_keeps_top_level_order() {
+    let doc = Document::parse_str(UNSORTED).unwrap();
+    let opts = EmitOptions {
+        sort_keys_from_depth: Some(1),
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    let zeta = yaml.find("zeta:").unwrap();
+    let alpha = yaml.find("alpha:").unwrap();
+    assert!(zeta < alpha, "got:\n{yaml}");
+    assert!(yaml.contains("  a: 1\n  b: 2\n  c: 3\n"), "got:\n{yaml}");
+    assert!(
+        yaml.find("x:").unwrap() < yaml.find("y:").unwrap(),
+        "got:\n{yaml}"
+    );
+    assert!(yaml.contains("{p: 2, q: 1}"), "got:\n{yaml}");
+    // Sequences keep their item order
+    assert!(yaml.contains("[2, 1]"), "got:\n{yaml}");
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(reparsed, UNSORTED.parse::<Value>().unwrap());
+
+    // The source document keeps its key order
+    assert!(doc.emit().unwrap().starts_with("zeta:\n  c: 3\n"));
+}
+
+#[test]
+fn emit_sort_keys_from_depth_zero_sorts_everything() {
+    let doc = Document::parse_str(UNSORTED).unwrap();
+    let opts = EmitOptions {
+        sort_keys_from_depth: Some(0),
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.starts_with("alpha:\n"), "got:\n{yaml}");
+    assert!(
+        yaml.contains("zeta:\n  a: 1\n  b: 2\n  c: 3\n"),
+        "got:\n{yaml}"
+    );
+}
+
+#[test]
+fn emit_sort_keys_beyond_nesting_changes_nothing() {
+    let doc = Document::parse_str(UNSORTED).unwrap();
+    let opts = EmitOptions {
+        sort_keys_from_depth: Some(5),
+        ..EmitOptions::default()
+    };
+    assert_eq!(doc.emit_with(&opts).unwrap(), doc.emit().unwrap());
+}
+
+#[test]
+fn value_to_yaml_string_with_sorts_nested_keys() {
+    let value: Value = UNSORTED.parse().unwrap();
+    let opts = EmitOptions {
+        sort_keys_from_depth: Some(1),
+        ..EmitOptions::default()
+    };
+    let yaml = value.to_yaml_string_with(&opts).unwrap();
+    assert!(
+        yaml.starts_with("zeta:\n  a: 1\n  b: 2\n  c: 3\n"),
+        "got:\n{yaml}"
+    );
+    assert_eq!(
+        value.to_yaml_string_with(&EmitOptions::default()).unwrap(),
+        value.to_yaml_string().unwrap()
+    );
+}
+
+#[test]
+fn emit_sort_keys_recurses_into_sequences() {
+    let doc = Document::parse_str("b: 1\na:\n  - z: 1\n    y: 2\n  - [{d: 1, c: 2}]\n").unwrap();
+    let opts = EmitOptions {
+        sort_keys: true,
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.starts_with("a:\n"), "got:\n{yaml}");
+    assert!(
+        yaml.find("y: 2").unwrap() < yaml.find("z: 1").unwrap(),
+        "got:\n{yaml}"
+    );
+    assert!(yaml.contains("{c: 2, d: 1}"), "got:\n{yaml}");
+    assert!(yaml.trim_end().ends_with("b: 1"), "got:\n{yaml}");
+}
+
+#[test]
+fn emit_sort_keys_overrides_sort_depth() {
+    let doc = Document::parse_str(UNSORTED).unwrap();
+    let opts = EmitOptions {
+        sort_keys: true,
+        sort_keys_from_depth: Some(1),
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.starts_with("alpha:\n"), "got:\n{yaml}");
+}
+
+#[test]
+fn emit_sort_keys_is_stable() {
+    // Complex keys compare equal, so they keep their authored order
+    let yaml = "? [3]\n: c\n? [1]\n: a\nb: 1\n? [2]\n: b\n";
+    let doc = Document::parse_str(yaml).unwrap();
+    let opts = EmitOptions {
+        sort_keys: true,
+        ..EmitOptions::default()
+    };
+    let sorted = doc.emit_with(&opts).unwrap();
+    let positions: Vec<usize> = ["b: 1", ": c", ": a", ": b"]
+        .iter()
+        .map(|needle| sorted.find(needle).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "got:\n{sorted}");
+}
+
+#[test]
+fn value_to_yaml_string_with_sort_keys() {
+    let value: Value = "b: [{y: 1, x: 2}]\na: 1\n".parse().unwrap();
+    let opts = EmitOptions {
+        sort_keys: true,
+        ..EmitOptions::default()
+    };
+    let yaml = value.to_yaml_string_with(&opts).unwrap();
+    assert!(yaml.starts_with("a: 1\n"), "got:\n{yaml}");
+    assert!(
+        yaml.find("x: 2").unwrap() < yaml.find("y: 1").unwrap(),
+        "got:\n{yaml}"
+    );
+}
+
+// =============================================================================
+// Ambiguous Strings
+// =============================================================================
+
+#[test]
+fn emit_quote_ambiguous_strings_double_quotes_value_strings() {
+    let opts = EmitOptions {
+        quote_ambiguous_strings: true,
+        ..EmitOptions::default()
+    };
+    for text in ["yes", "null", "~", "", "0x1F", "1.5", "False"] {
+        let value = Value::String(text.into());
+        let yaml = value.to_yaml_string_with(&opts).unwrap();
+        assert!(yaml.starts_with('"'), "{text:?} got: {yaml}");
+        assert_eq!(
+            yaml.parse::<Value>().unwrap(),
+            value,
+            "{text:?} got: {yaml}"
+        );
+    }
+    let plain = Value::String("hello".into());
+    assert_eq!(plain.to_yaml_string_with(&opts).unwrap(), "hello");
+}
+
+#[test]
+fn emit_quote_ambiguous_strings_leaves_typed_scalars_plain() {
+    let doc = Document::parse_str("flag: true\nname: 'no'\ncount: 3\nlit: |\n  true\n").unwrap();
+    let opts = EmitOptions {
+        quote_ambiguous_strings: true,
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert!(yaml.contains("flag: true\n"), "got:\n{yaml}");
+    assert!(yaml.contains("name: \"no\"\n"), "got:\n{yaml}");
+    assert!(yaml.contains("count: 3\n"), "got:\n{yaml}");
+    // Block scalars keep their trailing newline, which makes them unambiguous
+    assert!(yaml.contains("lit: |"), "got:\n{yaml}");
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(reparsed["flag"], Value::Bool(true));
+    assert_eq!(reparsed["name"], Value::String("no".into()));
+}
+
+#[test]
+fn emit_quote_ambiguous_strings_quotes_version_dependent_plain_scalars() {
+    let doc = Document::parse_str("a: yes\nb: on\nc: 012\nd: !!bool off\n").unwrap();
+    let opts = EmitOptions {
+        quote_ambiguous_strings: true,
+        ..EmitOptions::default()
+    };
+    let yaml = doc.emit_with(&opts).unwrap();
+    assert_ne!(yaml, doc.emit().unwrap());
+    assert!(yaml.contains("a: \"yes\"\n"), "got:\n{yaml}");
+    assert!(yaml.contains("b: \"on\"\n"), "got:\n{yaml}");
+    assert!(yaml.contains("c: \"012\"\n"), "got:\n{yaml}");
+    // An explicit tag already settles the type
+    assert!(yaml.contains("d: !!bool off\n"), "got:\n{yaml}");
+    let reparsed: Value = yaml.parse().unwrap();
+    assert_eq!(reparsed["c"], Value::String("012".into()));
+}
+
+#[test]
+fn emit_quote_ambiguous_strings_applies_to_keys() {
+    let mut map = indexmap::IndexMap::new();
+    map.insert(Value::String("true".into()), Value::from(1));
+    let value = Value::Mapping(map);
+    let opts = EmitOptions {
+        quote_ambiguous_strings: true,
+        ..EmitOptions::default()
+    };
+    let yaml = value.to_yaml_string_with(&opts).unwrap();
+    assert_eq!(yaml, "\"true\": 1");
+    assert_eq!(yaml.parse::<Value>().unwrap(), value);
+}
@@ -585,3 +585,215 @@ fn value_emit_deeply_nested() {
     }
     assert_eq!(current.as_str(), Some("deep"));
 }
+
+// =============================================================================
+// Document::emit_stable() tests
+// =============================================================================
+
+fn assert_emit_stable(yaml: &str) {
+    let first = Document::parse_str(yaml).unwrap().emit_stable().unwrap();
+    let second = Document::parse_str(&first).unwrap().emit_stable().unwrap();
+    let third = Document::parse_str(&second).unwrap().emit_stable().unwrap();
+    assert_eq!(first, second, "not stable for input:\n{}", yaml);
+    assert_eq!(second, third, "not stable for input:\n{}", yaml);
+
+    // Content survives the cycle
+    let original: Value = yaml.parse().unwrap();
+    let emitted: Value = first.parse().unwrap();
+    assert_eq!(original, emitted, "content changed for input:\n{}", yaml);
+}
+
+#[test]
+fn emit_stable_block_mapping() {
+    assert_emit_stable("name: app\nport:   8080\nnested:\n    deep:\n        key: value\n");
+}
+
+#[test]
+fn emit_stable_flow_collections() {
+    assert_emit_stable("list: [1, 2, 3]\nmap: {a: 1, b: [x, y]}\nempty: []\n");
+}
+
+#[test]
+fn emit_stable_scalar_styles() {
+    assert_emit_stable(
+        "plain: text\nsingle: 'quoted'\ndouble: \"esc\\tape\"\nliteral: |\n  line 1\n  line 2\nfolded: >-\n  folded\n  text\n",
+    );
+}
+
+#[test]
+fn emit_stable_sequences_of_mappings() {
+    assert_emit_stable("- name: a\n  tags: [x]\n-   name: b\n    tags:\n    - y\n    - z\n");
+}
+
+#[test]
+fn emit_stable_comments_and_tags() {
+    assert_emit_stable("# header\nkey: value  # trailing\ntagged: !custom data\nlong: !!str 123\n");
+}
+
+#[test]
+fn emit_stable_keeps_styles() {
+    let doc = Document::parse_str("s: 'single'\nd: \"double\"\nf: [1, 2]\n").unwrap();
+    let out = doc.emit_stable().unwrap();
+    assert!(out.contains("'single'"), "got:\n{}", out);
+    assert!(out.contains("\"double\""), "got:\n{}", out);
+    assert!(out.contains("[1, 2]"), "got:\n{}", out);
+}
+
+#[test]
+fn emit_stable_pins_document_markers() {
+    let implicit = Document::parse_str("a: 1\n").unwrap();
+    let explicit = Document::parse_str("---\na: 1\n...\n").unwrap();
+    let stable = implicit.emit_stable().unwrap();
+    assert_ne!(stable, implicit.emit().unwrap());
+    assert!(stable.starts_with("---"), "got:\n{}", stable);
+    assert!(!stable.contains("..."), "got:\n{}", stable);
+    assert_eq!(explicit.emit_stable().unwrap(), stable);
+}
+
+// =============================================================================
+// emit_to_writer() tests
+// =============================================================================
+
+/// Accepts `limit` bytes, then fails every write.
+struct FailingWriter {
+    written: Vec<u8>,
+    limit: usize,
+}
+
+impl std::io::Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let room = self.limit - self.written.len();
+        if room == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "sink closed",
+            ));
+        }
+        // Short writes exercise write_all's retry loop
+        let n = buf.len().min(room).min(3);
+        self.written.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn emit_to_writer_matches_emit() {
+    for yaml in [
+        "name: app\nports: [80, 443]\n",
+        "# header\nkey: value  # trailing\ntagged: !custom data\n",
+        "literal: |\n  line 1\n  line 2\ncity: 東京\n",
+        "- a\n- {b: c}\n",
+    ] {
+        let doc = Document::parse_str(yaml).unwrap();
+        let mut out = Vec::new();
+        doc.emit_to_writer(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), doc.emit().unwrap());
+    }
+}
+
+#[test]
+fn emit_to_writer_large_document() {
+    let mut yaml = String::new();
+    for i in 0..5000 {
+        yaml.push_str(&format!("key{}: [value{}, {}]\n", i, i, i));
+    }
+    let doc = Document::parse_str(&yaml).unwrap();
+    let mut out = std::io::BufWriter::new(Vec::new());
+    doc.emit_to_writer(&mut out).unwrap();
+    let out = out.into_inner().unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), doc.emit().unwrap());
+}
+
+#[test]
+fn noderef_emit_to_writer_matches_emit() {
+    let doc = Document::parse_str("db:\n  host: h\n  ports: [1, 2]\nname: x\n").unwrap();
+    for path in ["/db", "/db/ports", "/name"] {
+        let node = doc.at_path(path).unwrap();
+        let mut out = Vec::new();
+        node.emit_to_writer(&mut out).unwrap();
+        let streamed = String::from_utf8(out).unwrap();
+        assert_eq!(streamed.trim_end(), node.emit().unwrap().trim_end());
+        assert!(streamed.ends_with('\n'));
+    }
+}
+
+#[test]
+fn emit_to_writer_surfaces_io_error() {
+    let doc = Document::parse_str("a: 1\nb: [2, 3]\nc: {d: 4}\n").unwrap();
+    let mut writer = FailingWriter {
+        written: Vec::new(),
+        limit: 7,
+    };
+    let err = doc.emit_to_writer(&mut writer).unwrap_err();
+    match err {
+        fyaml::Error::IoError { kind, ref message } => {
+            assert_eq!(kind, std::io::ErrorKind::BrokenPipe);
+            assert_eq!(message, "sink closed");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(writer.written, b"a: 1\nb:");
+    assert!(err.to_string().contains("sink closed"));
+}
+
+// =============================================================================
+// emit_documents() tests
+// =============================================================================
+
+#[test]
+fn emit_documents_round_trips_through_parser() {
+    let values: Vec<Value> = ["name: app\nport: 80", "[1, two, 3.5]", "plain", "~", "{}"]
+        .iter()
+        .map(|yaml| yaml.parse().unwrap())
+        .collect();
+    let yaml = fyaml::emit_documents(&values).unwrap();
+    assert_eq!(yaml.matches("---").count(), values.len(), "{}", yaml);
+
+    let parser = fyaml::FyParser::from_string(&yaml).unwrap();
+    let parsed: Vec<Value> = parser
+        .doc_iter()
+        .map(|doc| {
+            let doc = doc.unwrap();
+            Value::from_node_ref(doc.root().unwrap()).unwrap()
+        })
+        .collect();
+    assert_eq!(parsed, values);
+}
+
+#[test]
+fn emit_documents_single_has_start_marker() {
+    let yaml = fyaml::emit_documents(&["a: 1".parse::<Value>().unwrap()]).unwrap();
+    assert!(yaml.starts_with("---"), "{}", yaml);
+    let parser = fyaml::FyParser::from_string(&yaml).unwrap();
+    assert_eq!(parser.doc_iter().count(), 1);
+}
+
+#[test]
+fn emit_documents_empty_slice() {
+    assert_eq!(fyaml::emit_documents(&[]).unwrap(), "");
+    let mut out = Vec::new();
+    fyaml::emit_documents_to_writer(&[], &mut out).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn emit_documents_to_writer_matches_string() {
+    let values = vec![Value::from("x"), Value::from(vec![1, 2])];
+    let mut out = Vec::new();
+    fyaml::emit_documents_to_writer(&values, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        fyaml::emit_documents(&values).unwrap()
+    );
+
+    let mut failing = FailingWriter {
+        written: Vec::new(),
+        limit: 2,
+    };
+    let err = fyaml::emit_documents_to_writer(&values, &mut failing).unwrap_err();
+    assert!(matches!(err, fyaml::Error::IoError { .. }));
+}
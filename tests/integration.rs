@@ -2,7 +2,7 @@
 //!
 //! These tests cover the lifetime-based API with NodeRef and Editor.
 
-use fyaml::{Document, FyParser, NodeStyle, NodeType};
+use fyaml::{Document, FyParser, NodeStyle, NodeType, ParseOptions};
 
 // =============================================================================
 // Document Parsing and Root Node Access
@@ -821,6 +821,76 @@ fn copy_scalar_between_documents() {
     assert_eq!(dest_root.scalar_str().unwrap(), "just a scalar");
 }
 
+#[test]
+fn deep_copy_is_independent() {
+    let template = Document::parse_str("name: app\nports: [80]\n").unwrap();
+    let mut copy = template.deep_copy().unwrap();
+    {
+        let mut ed = copy.edit();
+        ed.set_yaml_at("/name", "web").unwrap();
+        ed.insert_at("/ports", 1, "443").unwrap();
+    }
+    assert_eq!(template.emit().unwrap(), "name: app\nports: [80]\n");
+    assert_eq!(copy.at_path("/name").unwrap().scalar_str().unwrap(), "web");
+    assert_eq!(copy.at_path("/ports").unwrap().seq_len().unwrap(), 2);
+
+    template
+        .deep_copy()
+        .unwrap()
+        .edit()
+        .delete_at("/ports")
+        .unwrap();
+    assert!(template.at_path("/ports").is_some());
+}
+
+#[test]
+fn deep_copy_preserves_styles_tags_and_anchors() {
+    let yaml = "base: &b {x: 'q', y: \"d\"}\nref: *b\ntagged: !custom value\ntext: |\n  line\n";
+    let src = Document::parse_str(yaml).unwrap();
+    let copy = src.deep_copy().unwrap();
+
+    let base = copy.at_path("/base").unwrap();
+    assert_eq!(base.anchor(), Some("b"));
+    assert_eq!(base.style(), NodeStyle::Flow);
+    assert_eq!(
+        copy.at_path("/base/x").unwrap().style(),
+        NodeStyle::SingleQuoted
+    );
+    assert_eq!(
+        copy.at_path("/base/y").unwrap().style(),
+        NodeStyle::DoubleQuoted
+    );
+    assert_eq!(copy.at_path("/ref").unwrap().style(), NodeStyle::Alias);
+    assert_eq!(
+        copy.at_path("/tagged").unwrap().tag_str().unwrap(),
+        Some("!custom")
+    );
+    assert_eq!(copy.at_path("/text").unwrap().style(), NodeStyle::Literal);
+    assert_eq!(copy.emit().unwrap(), src.emit().unwrap());
+}
+
+#[test]
+fn deep_copy_outlives_original_input() {
+    let copy = {
+        let src = Document::from_string("owned: text\nlist: [a, b]\n".to_string()).unwrap();
+        src.deep_copy().unwrap()
+    };
+    assert_eq!(
+        copy.at_path("/owned").unwrap().scalar_str().unwrap(),
+        "text"
+    );
+
+    let copy = {
+        let parser = FyParser::from_string("---\nfirst: 1\n---\nsecond: 2\n").unwrap();
+        let doc = parser.doc_iter().nth(1).unwrap().unwrap();
+        doc.deep_copy().unwrap()
+    };
+    assert_eq!(copy.at_path("/second").unwrap().scalar_str().unwrap(), "2");
+
+    let empty = Document::new().unwrap().deep_copy().unwrap();
+    assert!(empty.root().is_none());
+}
+
 #[test]
 fn copy_sequence_between_documents() {
     let src = Document::parse_str("[a, b, c]").unwrap();
@@ -1645,9 +1715,6 @@ fn value_ref_type_checking() {
     assert!(!root.get("map").unwrap().is_scalar());
 }
 
-// Note: Comment preservation requires FYPCF_PARSE_COMMENTS and FYECF_OUTPUT_COMMENTS
-// flags which are not yet exposed in the Rust API. libfyaml supports it, but we don't yet.
-
 #[test]
 fn test_quote_style_preservation() {
     let yaml = "single: 'quoted value'
@@ -1760,6 +1827,49 @@ age: 30
     );
 }
 
+#[test]
+fn test_comment_preservation_with_options() {
+    let yaml = "# Default values for chart
+replicaCount: 1  # scale here
+image:
+  # where to pull from
+  repository: nginx
+  tag: stable  # pinned
+";
+
+    let mut doc = Document::parse_str_with(yaml, &ParseOptions::default()).unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.set_yaml_at("/image/repository", "registry.local/nginx")
+            .unwrap();
+    }
+
+    let output = doc.emit().unwrap();
+    assert!(output.contains("# Default values for chart"), "{}", output);
+    assert!(output.contains("# scale here"), "{}", output);
+    assert!(output.contains("# where to pull from"), "{}", output);
+    assert!(output.contains("# pinned"), "{}", output);
+    assert!(output.contains("registry.local/nginx"), "{}", output);
+}
+
+#[test]
+fn test_comments_dropped_when_disabled() {
+    let yaml = "# Top comment\nname: Alice  # inline comment\n";
+    let opts = ParseOptions {
+        parse_comments: false,
+        ..ParseOptions::default()
+    };
+
+    let mut doc = Document::parse_str_with(yaml, &opts).unwrap();
+    doc.edit()
+        .set_yaml_at("/name", "Bob  # new comment")
+        .unwrap();
+
+    let output = doc.emit().unwrap();
+    assert!(!output.contains('#'), "{}", output);
+    assert_eq!(doc.at_path("/name").unwrap().scalar_str().unwrap(), "Bob");
+}
+
 /// Regression test for the libfyaml finite-width emitter bug: wrapping a
 /// long single-quoted scalar inserts a `\` line-continuation, which is a
 /// LITERAL character in single-quoted style, corrupting the round-trip.
@@ -1955,6 +2065,50 @@ database:
     );
 }
 
+#[test]
+fn from_bytes_detect_strips_utf8_bom() {
+    let mut bytes = vec![0xEF, 0xBB, 0xBF];
+    bytes.extend_from_slice("name: café\nport: 80".as_bytes());
+    let doc = Document::from_bytes_detect(bytes).unwrap();
+    let root = doc.root().unwrap();
+    let (first_key, _) = root.map_iter().next().unwrap();
+    assert_eq!(first_key.scalar_str().unwrap(), "name");
+    assert_eq!(root.at_path("/name").unwrap().scalar_str().unwrap(), "café");
+}
+
+#[test]
+fn from_bytes_detect_transcodes_utf16le_bom() {
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(
+        "city: 東京\nlist: [a, b]"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes),
+    );
+    let doc = Document::from_bytes_detect(bytes).unwrap();
+    assert_eq!(doc.at_path("/city").unwrap().scalar_str().unwrap(), "東京");
+    assert_eq!(doc.at_path("/list/1").unwrap().scalar_str().unwrap(), "b");
+}
+
+#[test]
+fn from_bytes_detect_transcodes_utf16be_without_bom() {
+    let bytes: Vec<u8> = "k: v".encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let doc = Document::from_bytes_detect(bytes).unwrap();
+    assert_eq!(doc.at_path("/k").unwrap().scalar_str().unwrap(), "v");
+}
+
+#[test]
+fn from_bytes_detect_rejects_utf32() {
+    let bytes = vec![0xFF, 0xFE, 0x00, 0x00, b'k', 0, 0, 0];
+    let err = Document::from_bytes_detect(bytes).unwrap_err();
+    assert_eq!(err, fyaml::Error::UnsupportedEncoding("UTF-32"));
+    assert_eq!(err.to_string(), "Unsupported encoding: UTF-32");
+}
+
+#[test]
+fn from_bytes_detect_bom_only_is_empty() {
+    assert!(Document::from_bytes_detect(vec![0xEF, 0xBB, 0xBF]).is_err());
+}
+
 /// Stress test for Document::from_bytes destruction.
 #[test]
 fn destructor_stress_from_bytes() {
@@ -1966,3 +2120,252 @@ fn destructor_stress_from_bytes() {
         // Doc dropped with OwnedBytes input
     }
 }
+
+// =============================================================================
+// Minimal-Diff Emission Tests
+// =============================================================================
+
+#[test]
+fn emit_preserving_unchanged_only_edited_line_differs() {
+    let original = "\
+# Service configuration
+service:
+  name:   api      # public name
+
+  # Network settings
+  port: 8080
+  host:    0.0.0.0
+
+limits:
+    cpu: 2   # cores
+    memory: 512Mi
+";
+    let mut doc = Document::parse_str(original).unwrap();
+    {
+        let mut ed = doc.edit();
+        ed.set_yaml_at("/service/port", "9090").unwrap();
+    }
+
+    let output = doc.emit_preserving_unchanged(original).unwrap();
+    let before: Vec<&str> = original.lines().collect();
+    let after: Vec<&str> = output.lines().collect();
+    assert_eq!(before.len(), after.len(), "output:\n{}", output);
+
+    let changed: Vec<usize> = (0..before.len())
+        .filter(|&i| before[i] != after[i])
+        .collect();
+    assert_eq!(changed, [5], "output:\n{}", output);
+    assert_eq!(after[5].trim(), "port: 9090");
+    assert!(output.ends_with('\n'));
+}
+
+#[test]
+fn emit_preserving_unchanged_without_edits_returns_original() {
+    let original = "a:    1   # one\n\n\nb:   [x,   y]\n";
+    let doc = Document::parse_str(original).unwrap();
+    assert_eq!(doc.emit_preserving_unchanged(original).unwrap(), original);
+}
+
+#[test]
+fn emit_preserving_without_edits_returns_input_for_every_constructor() {
+    let original = "# header\n\na:    1   # one\n\n\nb:   [x,   y]\n\n# trailer\n";
+    let docs = [
+        Document::parse_str(original).unwrap(),
+        Document::from_string(original.to_string()).unwrap(),
+        Document::from_bytes(original.as_bytes().to_vec()).unwrap(),
+        Document::from_reader(original.as_bytes()).unwrap(),
+    ];
+    for doc in &docs {
+        assert_eq!(doc.emit_preserving().unwrap(), original);
+        assert_eq!(
+            doc.deep_copy().unwrap().emit_preserving().unwrap(),
+            original
+        );
+    }
+}
+
+#[test]
+fn emit_preserving_keeps_untouched_lines_after_edit() {
+    let original = "\
+service:
+  name:   api      # public name
+  port: 8080
+
+limits:
+    cpu: 2   # cores
+";
+    let mut doc = Document::parse_str(original).unwrap();
+    doc.edit().set_yaml_at("/service/port", "9090").unwrap();
+    let output = doc.emit_preserving().unwrap();
+    assert_eq!(output, doc.emit_preserving_unchanged(original).unwrap());
+    assert!(
+        output.starts_with("service:\n  name:   api      # public name\n"),
+        "{}",
+        output
+    );
+    assert!(output.contains("port: 9090"), "{}", output);
+    assert!(
+        output.ends_with("\n\nlimits:\n    cpu: 2   # cores\n"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn emit_preserving_without_input_falls_back_to_emit() {
+    let mut doc = Document::new().unwrap();
+    {
+        let mut ed = doc.edit();
+        let root = ed.build_from_yaml("{a: 1}").unwrap();
+        ed.set_root(root).unwrap();
+    }
+    assert_eq!(doc.emit_preserving().unwrap(), doc.emit().unwrap());
+}
+
+// =============================================================================
+// Parse Limits
+// =============================================================================
+
+fn limits(max_depth: Option<usize>, max_nodes: Option<usize>) -> ParseOptions {
+    ParseOptions {
+        max_depth,
+        max_nodes,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn parse_limits_accept_documents_at_the_bound() {
+    let yaml = "a: {b: [1, 2]}\nc: 3";
+    // depth 3; root, a, its mapping, b, the sequence, 1, 2, c, 3
+    let doc = Document::parse_str_with(yaml, &limits(Some(3), Some(9))).unwrap();
+    assert_eq!(doc.root().unwrap().depth(), 3);
+    assert_eq!(doc.root().unwrap().node_count(), 9);
+    let bytes = yaml.as_bytes().to_vec();
+    assert!(Document::from_bytes_with(bytes, &limits(Some(3), Some(9))).is_ok());
+    assert!(Document::parse_str_with("scalar", &limits(Some(0), Some(1))).is_ok());
+}
+
+#[test]
+fn parse_limits_reject_deep_documents() {
+    let yaml = format!("{}1{}", "[".repeat(10_000), "]".repeat(10_000));
+    let err = Document::parse_str_with(&yaml, &limits(Some(64), None)).unwrap_err();
+    assert_eq!(
+        err,
+        fyaml::Error::LimitExceeded {
+            limit: 64,
+            kind: "depth"
+        }
+    );
+    assert_eq!(err.to_string(), "Document exceeds the depth limit of 64");
+    let err = Document::from_bytes_with(yaml.into_bytes(), &limits(Some(64), None)).unwrap_err();
+    assert!(matches!(
+        err,
+        fyaml::Error::LimitExceeded { kind: "depth", .. }
+    ));
+}
+
+#[test]
+fn parse_limits_reject_large_documents() {
+    let yaml = format!("[{}]", vec!["x"; 1000].join(", "));
+    let err = Document::parse_str_with(&yaml, &limits(None, Some(1000))).unwrap_err();
+    assert_eq!(
+        err,
+        fyaml::Error::LimitExceeded {
+            limit: 1000,
+            kind: "nodes"
+        }
+    );
+    assert!(Document::parse_str_with(&yaml, &limits(None, Some(1001))).is_ok());
+}
+
+#[test]
+fn parse_limits_count_aliases_once() {
+    let yaml = "a: &x [1, 2, 3]\nb: *x\nc: *x";
+    // root, 3 keys, the sequence and its 3 items, 2 aliases
+    assert!(Document::parse_str_with(yaml, &limits(Some(2), Some(10))).is_ok());
+    assert!(Document::parse_str_with(yaml, &limits(None, Some(9))).is_err());
+}
+
+// =============================================================================
+// Alias Expansion
+// =============================================================================
+
+/// Nine levels of anchors, each a sequence of ten aliases to the previous one.
+fn billion_laughs() -> String {
+    let mut yaml = format!("l0: &l0 [{}]\n", vec!["lol"; 10].join(", "));
+    for level in 1..9 {
+        let aliases = vec![format!("*l{}", level - 1); 10].join(", ");
+        yaml.push_str(&format!("l{}: &l{} [{}]\n", level, level, aliases));
+    }
+    yaml
+}
+
+#[test]
+fn expanded_node_count_follows_nested_aliases() {
+    let doc = Document::parse_str(&billion_laughs()).unwrap();
+    // root, 9 keys, 9 sequences of 10 items
+    assert_eq!(doc.root().unwrap().node_count(), 109);
+    assert!(doc.check_aliases().is_ok());
+    // level n expands to 11...1 (n + 2 ones)
+    let levels: usize = (2..=10)
+        .map(|ones| "1".repeat(ones).parse::<usize>().unwrap())
+        .sum();
+    assert_eq!(doc.expanded_node_count().unwrap(), 1 + 9 + levels);
+}
+
+#[test]
+fn expanded_node_count_uses_closest_earlier_anchor() {
+    let doc = Document::parse_str("a: &x 1\nb: *x\nc: &x [1, 2]\nd: *x").unwrap();
+    // root, 4 keys, 1, *x as 1, [1, 2], *x as [1, 2]
+    assert_eq!(doc.expanded_node_count().unwrap(), 13);
+    assert_eq!(
+        Document::parse_str("{}")
+            .unwrap()
+            .expanded_node_count()
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn check_aliases_reports_cycles() {
+    let doc = Document::parse_str("a: &a [*a, *a]").unwrap();
+    let err = doc.check_aliases().unwrap_err();
+    assert_eq!(err, fyaml::Error::AliasCycle("a".into()));
+    assert_eq!(
+        err.to_string(),
+        "Alias *a refers to an anchor that contains it"
+    );
+
+    // The cycle goes through a second anchor
+    let doc = Document::parse_str("a: &a {b: &b [1, {c: *a}]}\nd: *b").unwrap();
+    assert_eq!(
+        doc.check_aliases(),
+        Err(fyaml::Error::AliasCycle("a".into()))
+    );
+    assert!(doc.expanded_node_count().is_err());
+}
+
+#[test]
+fn parse_limit_rejects_alias_expansion() {
+    let opts = ParseOptions {
+        max_alias_expansion: Some(1_000_000),
+        ..ParseOptions::default()
+    };
+    let err = Document::parse_str_with(&billion_laughs(), &opts).unwrap_err();
+    assert_eq!(
+        err,
+        fyaml::Error::LimitExceeded {
+            limit: 1_000_000,
+            kind: "alias expansion"
+        }
+    );
+    assert!(Document::parse_str_with("a: &x [1, 2]\nb: *x", &opts).is_ok());
+    // Without the option, neither the expansion nor a cycle is checked
+    assert!(Document::parse_str("a: &a [*a]").is_ok());
+    assert_eq!(
+        Document::parse_str_with("a: &a [*a]", &opts).unwrap_err(),
+        fyaml::Error::AliasCycle("a".into())
+    );
+}
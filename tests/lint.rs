@@ -0,0 +1,96 @@
+//! Tests for `Document::lint()`.
+
+use fyaml::{Document, LintKind};
+
+// =============================================================================
+// Clean Documents
+// =============================================================================
+
+#[test]
+fn lint_clean_document_has_no_findings() {
+    let yaml = "\
+server:
+  host: localhost
+  enabled: true
+  ports:
+  - 80
+  - 443
+  tls:
+    cert: a.pem
+tags: []
+";
+    assert!(Document::parse_str(yaml).unwrap().lint().is_empty());
+    assert!(Document::from_string(yaml.to_string())
+        .unwrap()
+        .lint()
+        .is_empty());
+}
+
+#[test]
+fn lint_quoted_and_tagged_booleans_are_strings() {
+    let doc = Document::parse_str("a: 'yes'\nb: \"off\"\nc: !!str on").unwrap();
+    assert!(doc.lint().is_empty());
+}
+
+// =============================================================================
+// Booleans
+// =============================================================================
+
+#[test]
+fn lint_reports_mixed_boolean_forms() {
+    let doc = Document::parse_str("a: true\nb: yes\nc: Off\nd: [TRUE, false]").unwrap();
+    let findings = doc.lint();
+    let paths: Vec<&str> = findings.iter().map(|f| f.path()).collect();
+    assert_eq!(paths, ["/b", "/c", "/d/0"]);
+    assert!(findings
+        .iter()
+        .all(|f| f.kind() == LintKind::NonCanonicalBool));
+    assert_eq!(findings[0].line(), Some(2));
+    assert!(findings[0].message().contains("`yes`"));
+    assert!(findings[1].message().contains("`false`"));
+    assert_eq!(
+        findings[0].to_string(),
+        "line 2 (/b): non-canonical boolean `yes`, use `true`"
+    );
+}
+
+// =============================================================================
+// Layout
+// =============================================================================
+
+#[test]
+fn lint_reports_inconsistent_indentation() {
+    let doc = Document::parse_str("a:\n  x: 1\nb:\n    y: 2\n").unwrap();
+    let findings = doc.lint();
+    assert_eq!(findings.len(), 1, "{findings:?}");
+    assert_eq!(findings[0].kind(), LintKind::InconsistentIndent);
+    assert_eq!(findings[0].path(), "/b");
+    assert_eq!(findings[0].line(), Some(4));
+}
+
+#[test]
+fn lint_reports_mixed_flow_and_block() {
+    let doc = Document::parse_str("a: {x: 1}\nb:\n  y: 2\n").unwrap();
+    let findings = doc.lint();
+    assert_eq!(findings.len(), 1, "{findings:?}");
+    assert_eq!(findings[0].kind(), LintKind::MixedFlowBlock);
+    assert_eq!(findings[0].path(), "");
+}
+
+#[test]
+fn lint_reports_trailing_whitespace_from_source() {
+    let yaml = "name: app  \nport: 80\nhosts:\n  - a\t\n";
+    let doc = Document::from_string(yaml.to_string()).unwrap();
+    let findings = doc.lint();
+    assert_eq!(findings.len(), 2, "{findings:?}");
+    assert!(findings
+        .iter()
+        .all(|f| f.kind() == LintKind::TrailingWhitespace));
+    assert_eq!((findings[0].path(), findings[0].line()), ("/name", Some(1)));
+    assert_eq!(
+        (findings[1].path(), findings[1].line()),
+        ("/hosts/0", Some(4))
+    );
+
+    assert_eq!(Document::parse_str(yaml).unwrap().lint(), findings);
+}
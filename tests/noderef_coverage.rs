@@ -7,7 +7,7 @@
 //! - `map_get()` on non-mapping
 //! - Debug and Display formatting
 
-use fyaml::{Document, NodeStyle, NodeType};
+use fyaml::{Document, Error, Location, NodeRef, NodeStyle, NodeType, Value};
 
 // =============================================================================
 // document() method tests
@@ -462,3 +462,393 @@ fn noderef_is_scalar_is_mapping_is_sequence() {
     assert!(map.is_mapping());
     assert!(!map.is_sequence());
 }
+
+// =============================================================================
+// map_iter_matching() tests
+// =============================================================================
+
+#[test]
+fn noderef_map_iter_matching_prefix() {
+    let yaml = "x_one: 1\nname: n\n? [complex]\n: c\nx_two: 2\n42: answer\nx_three: 3";
+    let doc = Document::parse_str(yaml).unwrap();
+    let root = doc.root().unwrap();
+
+    let pairs: Vec<(&str, &str)> = root
+        .map_iter_matching(|k| k.starts_with("x_"))
+        .map(|(k, v)| (k.scalar_str().unwrap(), v.scalar_str().unwrap()))
+        .collect();
+    assert_eq!(pairs, [("x_one", "1"), ("x_two", "2"), ("x_three", "3")]);
+}
+
+#[test]
+fn noderef_map_iter_matching_skips_non_scalar_keys() {
+    let doc = Document::parse_str("? [complex]\n: c\nplain: p").unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(root.map_iter_matching(|_| true).count(), 1);
+}
+
+#[test]
+fn noderef_map_iter_matching_skips_non_string_keys() {
+    let yaml = "&k 42: int\ntrue: bool\n~: null\n'7': quoted\n*k : alias\nname: n";
+    let doc = Document::parse_str(yaml).unwrap();
+    let root = doc.root().unwrap();
+    let keys: Vec<&str> = root
+        .map_iter_matching(|_| true)
+        .map(|(k, _)| k.scalar_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["7", "name"]);
+}
+
+#[test]
+fn noderef_map_iter_matching_on_non_mapping_is_empty() {
+    let doc = Document::parse_str("[x_a, x_b]").unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(root.map_iter_matching(|_| true).count(), 0);
+}
+
+// =============================================================================
+// seq_iter_indexed() tests
+// =============================================================================
+
+#[test]
+fn noderef_seq_iter_indexed_matches_positions() {
+    let doc = Document::parse_str("[zero, one, two, three]").unwrap();
+    let root = doc.root().unwrap();
+
+    let indexed: Vec<(usize, NodeRef)> = root.seq_iter_indexed().collect();
+    assert_eq!(indexed.len(), 4);
+    for (expected, (i, item)) in indexed.into_iter().enumerate() {
+        assert_eq!(i, expected);
+        assert_eq!(
+            item.scalar_str().unwrap(),
+            root.seq_get(i as i32).unwrap().scalar_str().unwrap()
+        );
+    }
+}
+
+#[test]
+fn noderef_seq_iter_indexed_on_non_sequence_is_empty() {
+    let doc = Document::parse_str("a: 1").unwrap();
+    assert_eq!(doc.root().unwrap().seq_iter_indexed().count(), 0);
+}
+
+// =============================================================================
+// build_key_index() tests
+// =============================================================================
+
+#[test]
+fn noderef_key_index_matches_map_get() {
+    let mut yaml = String::new();
+    for i in 0..2000 {
+        yaml.push_str(&format!("key{}: value{}\n", i, i));
+    }
+    let doc = Document::parse_str(&yaml).unwrap();
+    let root = doc.root().unwrap();
+    let index = root.build_key_index();
+    assert_eq!(index.len(), 2000);
+
+    // Probe hits and misses; both lookups must agree on every key
+    for i in (0..2500).step_by(7) {
+        let key = format!("key{}", i);
+        let indexed = index.get(&key).map(|n| n.scalar_str().unwrap());
+        let scanned = root.map_get(&key).map(|n| n.scalar_str().unwrap());
+        assert_eq!(indexed, scanned, "mismatch for {}", key);
+        assert_eq!(indexed.is_some(), i < 2000);
+    }
+}
+
+#[test]
+fn noderef_key_index_returns_same_nodes() {
+    let doc = Document::parse_str("a: [1, 2]\nb: {c: d}").unwrap();
+    let root = doc.root().unwrap();
+    let index = root.build_key_index();
+    for key in ["a", "b"] {
+        let indexed = index.get(key).unwrap();
+        let scanned = root.map_get(key).unwrap();
+        assert_eq!(indexed.kind(), scanned.kind());
+        assert_eq!(indexed.emit().unwrap(), scanned.emit().unwrap());
+    }
+}
+
+// =============================================================================
+// query() tests
+// =============================================================================
+
+fn scalars(nodes: &[NodeRef<'_>]) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|n| n.scalar_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn noderef_query_wildcard_over_sequence() {
+    let doc = Document::parse_str(
+        "servers:\n  - host: a\n    port: 80\n  - host: b\n  - name: no-host\n",
+    )
+    .unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(scalars(&root.query("/servers/*/host")), ["a", "b"]);
+    assert_eq!(root.query("/servers/*").len(), 3);
+    assert_eq!(scalars(&root.query("/servers/1/host")), ["b"]);
+    assert!(root.query("/servers/*/missing").is_empty());
+    assert!(root.query("/nope/*").is_empty());
+}
+
+#[test]
+fn noderef_query_wildcard_over_mapping_values() {
+    let doc = Document::parse_str("a: {v: 1}\nb: {v: 2}\nc: 3").unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(scalars(&root.query("/*/v")), ["1", "2"]);
+}
+
+#[test]
+fn noderef_query_recursive_descent() {
+    let doc = Document::parse_str(
+        "name: top\nchild:\n  name: mid\n  items:\n    - name: leaf\n    - other: x\n",
+    )
+    .unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(scalars(&root.query("/**/name")), ["top", "mid", "leaf"]);
+    // Repeated `**` does not yield duplicates
+    assert_eq!(scalars(&root.query("/**/**/name")), ["top", "mid", "leaf"]);
+    assert_eq!(scalars(&root.query("/child/**/other")), ["x"]);
+}
+
+#[test]
+fn noderef_select_services() {
+    let doc = Document::parse_str(
+        "services:\n  web:\n    host: w\n    port: 80\n  db:\n    host: d\n    replica: {port: 5433}\n",
+    )
+    .unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(scalars(&root.select("/services/*/host")), ["w", "d"]);
+    assert_eq!(scalars(&root.select("/**/port")), ["80", "5433"]);
+    assert!(root.select("/services/*/nothing").is_empty());
+    assert!(root.select("/services/web/host/*").is_empty());
+}
+
+#[test]
+fn noderef_query_empty_expr_is_self() {
+    let doc = Document::parse_str("a: 1").unwrap();
+    let root = doc.root().unwrap();
+    let matches = root.query("");
+    assert_eq!(matches.len(), 1);
+    assert!(matches[0].is_mapping());
+}
+
+// =============================================================================
+// Anchor tests
+// =============================================================================
+
+#[test]
+fn noderef_anchor_label() {
+    let doc = Document::parse_str("base: &defaults\n  a: 1\nlist: &items [x]\nplain: 2").unwrap();
+    assert_eq!(doc.at_path("/base").unwrap().anchor(), Some("defaults"));
+    assert_eq!(doc.at_path("/list").unwrap().anchor(), Some("items"));
+    assert_eq!(doc.at_path("/plain").unwrap().anchor(), None);
+    assert_eq!(doc.at_path("/base/a").unwrap().anchor(), None);
+    assert_eq!(doc.root().unwrap().anchor(), None);
+}
+
+#[test]
+fn document_node_by_anchor() {
+    let doc = Document::parse_str("base: &defaults\n  a: 1\nport: &p 8080").unwrap();
+    let base = doc.node_by_anchor("defaults").unwrap();
+    assert!(base.is_mapping());
+    assert_eq!(base.at_path("/a").unwrap().scalar_str().unwrap(), "1");
+    assert_eq!(
+        doc.node_by_anchor("p").unwrap().scalar_str().unwrap(),
+        "8080"
+    );
+    assert!(doc.node_by_anchor("missing").is_none());
+    assert!(doc.node_by_anchor("").is_none());
+}
+
+// =============================================================================
+// Location tests
+// =============================================================================
+
+#[test]
+fn noderef_location_of_keys_and_values() {
+    let doc = Document::parse_str("name: app\nserver:\n  host: local\n  port: 80\n").unwrap();
+    let root = doc.root().unwrap();
+    let (key, value) = root.map_iter().nth(1).unwrap();
+    assert_eq!(
+        key.location(),
+        Some(Location {
+            line: 2,
+            column: 1,
+            offset: 10
+        })
+    );
+    // A block mapping starts at its first key
+    assert_eq!(
+        value.location(),
+        Some(Location {
+            line: 3,
+            column: 3,
+            offset: 20
+        })
+    );
+    let port = doc.at_path("/server/port").unwrap().location().unwrap();
+    assert_eq!((port.line, port.column, port.offset), (4, 9, 40));
+}
+
+#[test]
+fn noderef_location_in_flow_sequence() {
+    let doc = Document::parse_str("items: [a, bb, c]").unwrap();
+    let item = doc.at_path("/items/2").unwrap().location().unwrap();
+    assert_eq!((item.line, item.column, item.offset), (1, 16, 15));
+}
+
+#[test]
+fn noderef_location_matches_span_start() {
+    let doc = Document::parse_str("a:\n  - x\n  - {k: v}\n").unwrap();
+    for path in ["/a", "/a/0", "/a/1", "/a/1/k"] {
+        let node = doc.at_path(path).unwrap();
+        assert_eq!(node.location().unwrap().offset, node.span().unwrap().start);
+    }
+}
+
+// =============================================================================
+// Parent navigation tests
+// =============================================================================
+
+#[test]
+fn noderef_parent_walks_to_root() {
+    let doc = Document::parse_str("a:\n  b:\n    - {c: deep}\n").unwrap();
+    let root = doc.root().unwrap();
+    let mut node = doc.at_path("/a/b/0/c").unwrap();
+    let mut depth = 0;
+    while let Some(parent) = node.parent() {
+        node = parent;
+        depth += 1;
+    }
+    assert_eq!(depth, 4);
+    assert_eq!(node.span(), root.span());
+    assert!(root.parent().is_none());
+}
+
+#[test]
+fn noderef_parent_of_key_is_mapping() {
+    let doc = Document::parse_str("outer: {inner: 1}").unwrap();
+    let outer = doc.at_path("/outer").unwrap();
+    let (key, _) = outer.map_iter().next().unwrap();
+    assert!(key.parent().unwrap().is_mapping());
+    assert!(key.key().is_none());
+}
+
+#[test]
+fn noderef_key_reports_enclosing_key() {
+    let doc = Document::parse_str("db:\n  port: bad\nitems: [x]\n").unwrap();
+    let bad = doc.at_path("/db/port").unwrap();
+    assert_eq!(bad.key().unwrap().scalar_str().unwrap(), "port");
+    let db = bad.parent().unwrap();
+    assert_eq!(db.key().unwrap().scalar_str().unwrap(), "db");
+    assert!(doc.root().unwrap().key().is_none());
+    assert!(doc.at_path("/items/0").unwrap().key().is_none());
+}
+
+// =============================================================================
+// Owned conversion tests
+// =============================================================================
+
+#[test]
+fn noderef_to_value_outlives_document() {
+    let value = {
+        let doc = Document::parse_str("a: [1, {b: 'yes'}]").unwrap();
+        doc.at_path("/a").unwrap().to_value().unwrap()
+    };
+    assert_eq!(value[1]["b"], Value::from("yes"));
+}
+
+#[test]
+fn noderef_to_owned_pairs_keeps_order_and_types() {
+    let doc = Document::parse_str("z: 1\n2: two\na: {n: ~}\n").unwrap();
+    let pairs = doc.root().unwrap().to_owned_pairs().unwrap();
+    let keys: Vec<&str> = pairs.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(keys, ["z", "2", "a"]);
+    assert_eq!(pairs[0].1, Value::from(1u64));
+    assert_eq!(pairs[2].1["n"], Value::Null);
+
+    let empty = Document::parse_str("{}").unwrap();
+    assert!(empty.root().unwrap().to_owned_pairs().unwrap().is_empty());
+}
+
+#[test]
+fn noderef_to_owned_pairs_errors() {
+    let doc = Document::parse_str("seq: [1]\nscalar: x\n? [complex]\n: v\n").unwrap();
+    for path in ["/seq", "/scalar"] {
+        let err = doc.at_path(path).unwrap().to_owned_pairs().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::TypeMismatch {
+                    expected: "mapping",
+                    ..
+                }
+            ),
+            "{path}: {err:?}"
+        );
+    }
+    let err = doc.root().unwrap().to_owned_pairs().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::TypeMismatch {
+            expected: "scalar key",
+            ..
+        }
+    ));
+}
+
+// =============================================================================
+// Size tests
+// =============================================================================
+
+#[test]
+fn noderef_depth() {
+    let doc = Document::parse_str("a: {b: [1, [2]]}\nc: 3\nd: {}\n").unwrap();
+    let root = doc.root().unwrap();
+    assert_eq!(root.depth(), 4);
+    assert_eq!(doc.at_path("/a/b").unwrap().depth(), 2);
+    assert_eq!(doc.at_path("/c").unwrap().depth(), 0);
+    assert_eq!(doc.at_path("/d").unwrap().depth(), 1);
+
+    // Complex keys count like values
+    let doc = Document::parse_str("? [[x]]\n: 1\n").unwrap();
+    assert_eq!(doc.root().unwrap().depth(), 3);
+}
+
+#[test]
+fn noderef_node_count() {
+    let doc = Document::parse_str("a: [1, 2]\nb: {c: d}\n").unwrap();
+    let root = doc.root().unwrap();
+    // root, a, [1, 2], 1, 2, b, {c: d}, c, d
+    assert_eq!(root.node_count(), 9);
+    assert_eq!(doc.at_path("/a").unwrap().node_count(), 3);
+    assert_eq!(doc.at_path("/a/0").unwrap().node_count(), 1);
+}
+
+#[test]
+fn noderef_size_does_not_expand_aliases() {
+    let doc = Document::parse_str("a: &a [x, y]\nb: &b [*a, *a]\nc: [*b, *b]\n").unwrap();
+    let root = doc.root().unwrap();
+    // Every alias is one node, however much it would expand to
+    assert_eq!(root.node_count(), 13);
+    assert_eq!(root.depth(), 2);
+}
+
+#[test]
+fn noderef_size_of_deep_nesting() {
+    let depth = 10_000;
+    let yaml = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    let doc = match Document::parse_str(&yaml) {
+        Ok(doc) => doc,
+        // libfyaml may cap nesting itself; nothing to walk then
+        Err(_) => return,
+    };
+    let root = doc.root().unwrap();
+    assert_eq!(root.depth(), depth);
+    assert_eq!(root.node_count(), depth);
+}
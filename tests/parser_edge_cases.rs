@@ -6,8 +6,11 @@
 //! - Whitespace-only streams
 //! - Comment-only documents
 //! - Iterator exhaustion behavior
+//! - Reading from `io::Read` sources
+//! - Pull-parser events
 
-use fyaml::FyParser;
+use fyaml::{Event, FyParser, NodeStyle};
+use std::borrow::Cow;
 
 // =============================================================================
 // Document end marker tests
@@ -481,3 +484,172 @@ fn parser_sequence_only_document() {
     assert!(root.is_sequence());
     assert_eq!(root.seq_len().unwrap(), 3);
 }
+
+// =============================================================================
+// Reader input tests
+// =============================================================================
+
+/// A reader that fails after yielding its first chunk.
+struct FailingReader {
+    first: Option<&'static [u8]>,
+}
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.first.take() {
+            Some(chunk) => {
+                buf[..chunk.len()].copy_from_slice(chunk);
+                Ok(chunk.len())
+            }
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "peer went away",
+            )),
+        }
+    }
+}
+
+#[test]
+fn parser_from_reader_streams_documents() {
+    let input = std::io::Cursor::new(b"---\nname: a\n---\nname: b\n".to_vec());
+    let parser = FyParser::from_reader(input).unwrap();
+    let names: Vec<String> = parser
+        .doc_iter()
+        .map(|doc| {
+            doc.unwrap()
+                .at_path("/name")
+                .unwrap()
+                .scalar_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn parser_from_reader_documents_outlive_parser() {
+    let docs: Vec<fyaml::Document> = {
+        let parser = FyParser::from_reader(&b"a: 1\n---\nb: 2\n"[..]).unwrap();
+        parser.doc_iter().map(Result::unwrap).collect()
+    };
+    assert_eq!(docs[1].at_path("/b").unwrap().scalar_str().unwrap(), "2");
+}
+
+#[test]
+fn document_from_reader_parses_buffered_input() {
+    let doc = fyaml::Document::from_reader(&b"key: value\nlist: [1, 2]\n"[..]).unwrap();
+    assert_eq!(doc.at_path("/key").unwrap().scalar_str().unwrap(), "value");
+    assert_eq!(doc.at_path("/list").unwrap().seq_len().unwrap(), 2);
+}
+
+#[test]
+fn from_reader_propagates_io_errors() {
+    let err = fyaml::Document::from_reader(FailingReader {
+        first: Some(b"a: 1\n"),
+    })
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        fyaml::Error::IoError {
+            kind: std::io::ErrorKind::ConnectionReset,
+            ..
+        }
+    ));
+
+    let err = FyParser::from_reader(FailingReader { first: None })
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("peer went away"), "got: {err}");
+}
+
+// =============================================================================
+// Event stream tests
+// =============================================================================
+
+#[test]
+fn parser_events_full_sequence() {
+    let parser = FyParser::from_string("---\nk: &a [v, 'q']\nr: *a\n").unwrap();
+    let events: Vec<Event<'_>> = parser.events().map(|e| e.unwrap()).collect();
+    let scalar = |value: &str, style| Event::Scalar {
+        value: value.into(),
+        style,
+        tag: None,
+        anchor: None,
+    };
+    assert_eq!(
+        events,
+        [
+            Event::StreamStart,
+            Event::DocumentStart { implicit: false },
+            Event::MappingStart {
+                anchor: None,
+                tag: None,
+                style: NodeStyle::Block,
+            },
+            scalar("k", NodeStyle::Plain),
+            Event::SequenceStart {
+                anchor: Some("a".into()),
+                tag: None,
+                style: NodeStyle::Flow,
+            },
+            scalar("v", NodeStyle::Plain),
+            scalar("q", NodeStyle::SingleQuoted),
+            Event::SequenceEnd,
+            scalar("r", NodeStyle::Plain),
+            Event::Alias { anchor: "a".into() },
+            Event::MappingEnd,
+            Event::DocumentEnd { implicit: true },
+            Event::StreamEnd,
+        ]
+    );
+}
+
+#[test]
+fn parser_events_borrow_plain_scalars() {
+    let parser = FyParser::from_string("key: plain\nesc: \"a\\tb\"\n").unwrap();
+    let scalars: Vec<Cow<'_, str>> = parser
+        .events()
+        .filter_map(|e| match e.unwrap() {
+            Event::Scalar { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(scalars[1], Cow::Borrowed("plain")));
+    // Escapes are processed, so that text cannot point into the input
+    assert_eq!(scalars[3], "a\tb");
+}
+
+#[test]
+fn parser_events_tags_and_multiple_documents() {
+    let parser = FyParser::from_string("--- !t x\n--- y\n").unwrap();
+    let events: Vec<Event<'_>> = parser.events().map(|e| e.unwrap()).collect();
+    let starts = events
+        .iter()
+        .filter(|e| matches!(e, Event::DocumentStart { .. }))
+        .count();
+    assert_eq!(starts, 2);
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::Scalar { value, tag: Some(tag), .. } if value == "x" && tag == "!t"
+    )));
+}
+
+#[test]
+fn parser_events_stop_at_error() {
+    let parser = FyParser::from_string("a: [1, 2\n").unwrap();
+    let results: Vec<_> = parser.events().collect();
+    assert!(results.last().unwrap().is_err());
+    assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+}
+
+#[test]
+fn parser_events_from_reader() {
+    let input: &[u8] = b"- 1\n- 2\n";
+    let parser = FyParser::from_reader(input).unwrap();
+    let count = parser
+        .events()
+        .filter(|e| matches!(e, Ok(Event::Scalar { .. })))
+        .count();
+    assert_eq!(count, 2);
+}
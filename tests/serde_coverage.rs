@@ -5,9 +5,13 @@
 //! - Float deserialization
 //! - Negative integer deserialization
 //! - Various Value serialization scenarios
+//! - Direct deserialization with `from_str` / `from_slice` / `from_reader`
 
 use fyaml::value::{Number, TaggedValue, Value};
+use fyaml::Error;
 use indexmap::IndexMap;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 
 // =============================================================================
 // TaggedValue serde roundtrip tests
@@ -387,6 +391,35 @@ fn serde_number_float_preserved() {
     assert!((f - std::f64::consts::PI).abs() < 0.0001);
 }
 
+#[test]
+fn serde_number_128_bit_narrowed_when_it_fits() {
+    // Small 128-bit values serialize through the 64-bit methods
+    let json = serde_json::to_string(&Value::Number(Number::I128(-3))).unwrap();
+    assert_eq!(json, "-3");
+
+    let value: Value = "big: 340282366920938463463374607431768211455\nsmall: 7"
+        .parse()
+        .unwrap();
+    let big: u128 = fyaml::from_value(&value["big"]).unwrap();
+    assert_eq!(big, u128::MAX);
+    let small: u8 = fyaml::from_value(&value["small"]).unwrap();
+    assert_eq!(small, 7);
+}
+
+#[test]
+fn from_str_128_bit_integers() {
+    #[derive(Debug, Deserialize)]
+    struct Ids {
+        mask: u128,
+        offset: i128,
+    }
+    let yaml = "mask: 0xffffffffffffffffffffffffffffffff\n\
+                offset: -170141183460469231731687303715884105728";
+    let ids: Ids = fyaml::from_str(yaml).unwrap();
+    assert_eq!(ids.mask, u128::MAX);
+    assert_eq!(ids.offset, i128::MIN);
+}
+
 // =============================================================================
 // Empty collection tests
 // =============================================================================
@@ -471,3 +504,199 @@ fn value_from_yaml_with_types() {
     assert_eq!(value.get("bool").unwrap().as_bool(), Some(true));
     assert!(value.get("null_val").unwrap().is_null());
 }
+
+// =============================================================================
+// from_str() tests
+// =============================================================================
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    version: String,
+    mask: u8,
+    debug: bool,
+    ratio: f64,
+    database: Database,
+    #[serde(default)]
+    tags: Vec<String>,
+    owner: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Mode {
+    Fast,
+    Limit(u32),
+    Range { lo: i32, hi: i32 },
+}
+
+const CONFIG: &str = "\
+name: app
+version: '1.10'
+mask: 0xFF
+debug: yes
+ratio: .5
+database:
+  host: db.local
+  port: 5432
+tags: [a, b]
+owner: ~
+";
+
+fn deserialize_path(err: &Error) -> &str {
+    match err {
+        Error::Deserialize { path, .. } => path,
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn from_str_struct() {
+    let config: Config = fyaml::from_str(CONFIG).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "app".into(),
+            version: "1.10".into(),
+            mask: 255,
+            debug: true,
+            ratio: 0.5,
+            database: Database {
+                host: "db.local".into(),
+                port: 5432,
+            },
+            tags: vec!["a".into(), "b".into()],
+            owner: None,
+        }
+    );
+}
+
+#[test]
+fn from_str_matches_from_value() {
+    let value: Value = CONFIG.parse().unwrap();
+    let via_value: Config = fyaml::from_value(&value).unwrap();
+    let direct: Config = fyaml::from_str(CONFIG).unwrap();
+    assert_eq!(direct, via_value);
+}
+
+#[test]
+fn from_str_quoted_scalars_stay_strings() {
+    let map: BTreeMap<String, String> =
+        fyaml::from_str("a: '42'\nb: \"true\"\nc: |\n  0x10\n").unwrap();
+    assert_eq!(map["a"], "42");
+    assert_eq!(map["b"], "true");
+    assert_eq!(map["c"], "0x10\n");
+
+    // Plain scalars are typed, so a string target rejects them
+    let err = fyaml::from_str::<BTreeMap<String, String>>("a: 42").unwrap_err();
+    assert_eq!(deserialize_path(&err), "/a");
+}
+
+#[test]
+fn from_str_str_tag_keeps_plain_scalars_strings() {
+    let map: BTreeMap<String, String> =
+        fyaml::from_str("a: !!str 42\nb: !!str true\nc: !!str ~\n").unwrap();
+    assert_eq!(map["a"], "42");
+    assert_eq!(map["b"], "true");
+    assert_eq!(map["c"], "~");
+
+    let err = fyaml::from_str::<BTreeMap<String, u8>>("a: !!str 42").unwrap_err();
+    assert_eq!(deserialize_path(&err), "/a");
+}
+
+#[test]
+fn from_str_error_path_escapes_keys() {
+    let yaml = "a/b: {c~d: x}";
+    let err = fyaml::from_str::<BTreeMap<String, BTreeMap<String, u8>>>(yaml).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/a~1b/c~0d");
+
+    let value: Value = yaml.parse().unwrap();
+    let err = fyaml::from_value::<BTreeMap<String, BTreeMap<String, u8>>>(&value).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/a~1b/c~0d");
+}
+
+#[test]
+fn from_str_type_error_has_path() {
+    let yaml = CONFIG.replace("port: 5432", "port: abc");
+    let err = fyaml::from_str::<Config>(&yaml).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/database/port");
+    assert!(err.to_string().contains("invalid type"), "got: {}", err);
+
+    let yaml = CONFIG.replace("tags: [a, b]", "tags: [a, [b]]");
+    let err = fyaml::from_str::<Config>(&yaml).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/tags/1");
+}
+
+#[test]
+fn from_str_missing_field_has_path() {
+    let yaml = CONFIG.replace("  port: 5432\n", "");
+    let err = fyaml::from_str::<Config>(&yaml).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/database");
+    assert!(err.to_string().contains("port"), "got: {}", err);
+}
+
+#[test]
+fn from_str_unknown_field_has_path() {
+    let yaml = CONFIG.replace("  port: 5432\n", "  port: 5432\n  pool: 4\n");
+    let err = fyaml::from_str::<Config>(&yaml).unwrap_err();
+    assert_eq!(deserialize_path(&err), "/database/pool");
+    assert!(err.to_string().contains("unknown field"), "got: {}", err);
+}
+
+#[test]
+fn from_str_enums() {
+    let modes: Vec<Mode> = fyaml::from_str("[Fast, {Limit: 5}, !Range {lo: -1, hi: 1}]").unwrap();
+    assert_eq!(
+        modes,
+        vec![Mode::Fast, Mode::Limit(5), Mode::Range { lo: -1, hi: 1 }]
+    );
+
+    let err = fyaml::from_str::<Vec<Mode>>("[Fast, {Limit: x}]").unwrap_err();
+    assert_eq!(deserialize_path(&err), "/1/Limit");
+}
+
+#[test]
+fn from_str_options() {
+    let items: Vec<Option<i64>> = fyaml::from_str("[~, null, !!null, !!int 3]").unwrap();
+    assert_eq!(items, vec![None, None, None, Some(3)]);
+
+    // A quoted empty string is a value, not null
+    let items: Vec<Option<String>> = fyaml::from_str("['', ~]").unwrap();
+    assert_eq!(items, vec![Some(String::new()), None]);
+
+    let nothing: Option<Config> = fyaml::from_str("--- ~\n").unwrap();
+    assert_eq!(nothing, None);
+    assert!(fyaml::from_str::<Config>("[unclosed").is_err());
+}
+
+#[test]
+fn from_slice_and_reader() {
+    let from_slice: Config = fyaml::from_slice(CONFIG.as_bytes()).unwrap();
+    let from_reader: Config = fyaml::from_reader(std::io::Cursor::new(CONFIG)).unwrap();
+    assert_eq!(from_slice, from_reader);
+    assert_eq!(from_reader.database.port, 5432);
+}
+
+#[test]
+fn from_reader_io_error() {
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "boom"))
+        }
+    }
+    let err = fyaml::from_reader::<_, Config>(Broken).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::IoError {
+            kind: std::io::ErrorKind::Other,
+            ..
+        }
+    ));
+}
@@ -106,6 +106,31 @@ fn value_get_mut_on_non_mapping() {
     assert!(value.get_mut("key").is_none());
 }
 
+#[test]
+fn value_take_moves_out_of_mapping() {
+    let mut value: Value = "{items: [a, b], keep: 1}".parse().unwrap();
+    let items = value.get_mut("items").unwrap().take();
+    assert_eq!(items, Value::Sequence(vec!["a".into(), "b".into()]));
+    // The entry stays, now null
+    assert_eq!(value.as_mapping().unwrap().len(), 2);
+    assert!(value["items"].is_null());
+    assert_eq!(value["keep"], Value::from(1));
+
+    let mut whole = Value::from("x");
+    assert_eq!(whole.take(), Value::from("x"));
+    assert!(whole.is_null());
+}
+
+#[test]
+fn value_replace_returns_old() {
+    let mut value: Value = "{tags: [a]}".parse().unwrap();
+    let tags = value.get_mut("tags").unwrap();
+    let mut old = tags.replace(Value::Sequence(Vec::new()));
+    old.as_sequence_mut().unwrap().push("b".into());
+    *tags = old;
+    assert_eq!(value, "{tags: [a, b]}".parse::<Value>().unwrap());
+}
+
 // =============================================================================
 // Ordering Tests
 // =============================================================================
@@ -321,6 +346,108 @@ fn value_index_on_non_container() {
     assert_eq!(value[0], Value::Null);
 }
 
+#[test]
+fn value_index_mapping_miss_then_index() {
+    let value: Value = "a: {b: [1, 2]}".parse().unwrap();
+    assert_eq!(value["missing"][0], Value::Null);
+    assert_eq!(value["missing"][0]["x"], Value::Null);
+    assert_eq!(value["a"]["missing"][3], Value::Null);
+}
+
+#[test]
+fn value_index_into_scalar() {
+    let value: Value = "s: text\nn: 5\nb: true\nz: ~".parse().unwrap();
+    for key in ["s", "n", "b", "z"] {
+        assert_eq!(value[key][0], Value::Null, "{}[0]", key);
+        assert_eq!(value[key]["x"], Value::Null, "{}[x]", key);
+        assert_eq!(value[key][usize::MAX]["x"][0], Value::Null, "{} chain", key);
+    }
+    // Key lookup on a sequence and index lookup on a mapping miss too
+    let value: Value = "seq: [a]\nmap: {0: zero}".parse().unwrap();
+    assert_eq!(value["seq"]["0"], Value::Null);
+    assert_eq!(value["map"][0], Value::Null);
+}
+
+#[test]
+fn value_index_deep_safe_chains() {
+    let value: Value = "a: {b: [{c: {d: found}}]}".parse().unwrap();
+    assert_eq!(value["a"]["b"][0]["c"]["d"].as_str(), Some("found"));
+    assert_eq!(value["a"]["b"][1]["c"]["d"], Value::Null);
+    assert_eq!(value["a"]["b"][0]["c"]["d"]["e"][0]["f"], Value::Null);
+    assert_eq!(value["x"]["y"]["z"][0][1][2]["w"], Value::Null);
+    assert_eq!(Value::Null["a"][0], Value::Null);
+    assert_eq!(Value::Sequence(vec![])[0]["a"], Value::Null);
+}
+
+#[test]
+fn value_index_by_string_and_value_key() {
+    let value: Value = "{name: app, 7: seven}".parse().unwrap();
+    let key = String::from("name");
+    assert_eq!(value[key.clone()], Value::from("app"));
+    assert_eq!(value[&Value::from(7)], Value::from("seven"));
+    assert_eq!(value[&Value::from("name")], value[key]);
+    assert_eq!(value[&Value::from(8)], Value::Null);
+}
+
+#[test]
+fn value_index_mut_auto_vivifies() {
+    let mut value: Value = "{a: 1}".parse().unwrap();
+    value["a"] = Value::from(2);
+    value["b"]["c"] = Value::from(3);
+    value[String::from("d")] = Value::from(true);
+    value[&Value::from(7)] = Value::from("seven");
+    assert_eq!(
+        value,
+        "{a: 2, b: {c: 3}, d: true, 7: seven}"
+            .parse::<Value>()
+            .unwrap()
+    );
+
+    // Merely borrowing a missing key inserts it
+    let _ = &mut value["typo"];
+    assert_eq!(value.get("typo"), Some(&Value::Null));
+
+    let mut null = Value::Null;
+    null["x"] = Value::from(1);
+    assert_eq!(null, "{x: 1}".parse::<Value>().unwrap());
+}
+
+#[test]
+fn value_index_mut_sequence() {
+    let mut value: Value = "{items: [a, {b: 1}]}".parse().unwrap();
+    value["items"][0] = Value::from("z");
+    value["items"][1]["b"] = Value::from(2);
+    assert_eq!(value, "{items: [z, {b: 2}]}".parse::<Value>().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "index 2 out of bounds for sequence of length 2")]
+fn value_index_mut_sequence_out_of_bounds() {
+    let mut value: Value = "[a, b]".parse().unwrap();
+    value[2] = Value::Null;
+}
+
+#[test]
+#[should_panic(expected = "cannot index into a string value with key `k`")]
+fn value_index_mut_on_scalar_panics() {
+    let mut value = Value::from("text");
+    value["k"] = Value::Null;
+}
+
+#[test]
+#[should_panic(expected = "cannot index into a tagged value with key `k`")]
+fn value_index_mut_on_tagged_panics() {
+    let mut value: Value = "!t {k: v}".parse().unwrap();
+    value["k"] = Value::Null;
+}
+
+#[test]
+#[should_panic(expected = "cannot index into a mapping value with index 0")]
+fn value_index_mut_usize_on_mapping_panics() {
+    let mut value: Value = "{0: zero}".parse().unwrap();
+    value[0] = Value::Null;
+}
+
 // =============================================================================
 // Display
 // =============================================================================
@@ -332,6 +459,30 @@ fn value_display() {
     assert!(display.contains("hello"));
 }
 
+#[test]
+fn value_display_flow_and_alternate_block() {
+    let value: Value = "name: app\nports:\n  - 80\n  - 443\nenv: {}"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        format!("{}", value),
+        "{name: app, ports: [80, 443], env: {}}"
+    );
+    assert_eq!(value.to_string(), value.to_yaml_string_flow().unwrap());
+
+    let block = format!("{:#}", value);
+    assert_eq!(block, value.to_yaml_string().unwrap());
+    assert!(
+        block.contains("ports:\n- 80") || block.contains("ports:\n  - 80"),
+        "{}",
+        block
+    );
+
+    // Scalars print the same either way
+    let scalar = Value::from("hello");
+    assert_eq!(format!("{}", scalar), format!("{:#}", scalar));
+}
+
 #[test]
 fn tagged_value_equality() {
     let t1 = TaggedValue {
@@ -371,3 +522,81 @@ fn tagged_value_ordering() {
     // Different tag (tag compared first)
     assert!(t1 < t3);
 }
+
+// =============================================================================
+// Collecting and Extending
+// =============================================================================
+
+#[test]
+fn value_collect_sequence() {
+    let seq: Value = (0..3).map(Value::from).collect();
+    assert_eq!(seq, Value::Sequence(vec![0.into(), 1.into(), 2.into()]));
+
+    let empty: Value = std::iter::empty::<Value>().collect();
+    assert_eq!(empty, Value::Sequence(Vec::new()));
+}
+
+#[test]
+fn value_collect_mapping() {
+    let pairs = vec![
+        (Value::from("b"), Value::from(1)),
+        (Value::from("a"), Value::from(2)),
+        (Value::from("b"), Value::from(3)),
+    ];
+    let map: Value = pairs.into_iter().collect();
+    let entries: Vec<_> = map.as_mapping().unwrap().iter().collect();
+    assert_eq!(
+        entries,
+        vec![
+            (&Value::from("b"), &Value::from(3)),
+            (&Value::from("a"), &Value::from(2)),
+        ]
+    );
+
+    let empty: Value = std::iter::empty::<(Value, Value)>().collect();
+    assert_eq!(empty, Value::Mapping(IndexMap::new()));
+}
+
+#[test]
+fn value_collect_pairs_as_sequence() {
+    // Mapping each pair to a Value picks the sequence impl
+    let seq: Value = vec![("a", 1), ("b", 2)]
+        .into_iter()
+        .map(|(k, v)| Value::Sequence(vec![k.into(), v.into()]))
+        .collect();
+    assert_eq!(seq, "[[a, 1], [b, 2]]".parse::<Value>().unwrap());
+}
+
+#[test]
+fn value_extend_sequence() {
+    let mut seq: Value = "[1]".parse().unwrap();
+    seq.extend(vec![Value::from(2), Value::from(3)]);
+    assert_eq!(seq, "[1, 2, 3]".parse::<Value>().unwrap());
+    seq.extend(std::iter::empty::<Value>());
+    assert_eq!(seq.as_sequence().unwrap().len(), 3);
+
+    let mut null = Value::Null;
+    null.extend(std::iter::once(Value::from("x")));
+    assert_eq!(null, Value::Sequence(vec!["x".into()]));
+}
+
+#[test]
+fn value_extend_mapping() {
+    let mut map: Value = "{a: 1}".parse().unwrap();
+    map.extend(vec![
+        (Value::from("b"), Value::from(2)),
+        (Value::from("a"), Value::from(9)),
+    ]);
+    assert_eq!(map, "{a: 9, b: 2}".parse::<Value>().unwrap());
+
+    let mut null = Value::Null;
+    null.extend(std::iter::empty::<(Value, Value)>());
+    assert_eq!(null, Value::Mapping(IndexMap::new()));
+}
+
+#[test]
+#[should_panic(expected = "cannot extend a string")]
+fn value_extend_wrong_type_panics() {
+    let mut value = Value::from("text");
+    value.extend(std::iter::once(Value::from(1)));
+}
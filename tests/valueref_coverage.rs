@@ -5,8 +5,10 @@
 //! - `as_bytes()` method
 //! - Debug formatting for different value types
 //! - Index out of bounds scenarios
+//! - Borrowed vs. decoded scalars from `as_cow_str()`
 
 use fyaml::Document;
+use std::borrow::Cow;
 
 // =============================================================================
 // seq_len() and map_len() on wrong types
@@ -542,3 +544,58 @@ fn valueref_as_node_returns_underlying_noderef() {
     let key_node = node.at_path("/key").unwrap();
     assert_eq!(key_node.scalar_str().unwrap(), "value");
 }
+
+// =============================================================================
+// as_cow_str() tests
+// =============================================================================
+
+/// Returns whether the value at `key` was borrowed, and its text.
+fn cow_at(doc: &Document, key: &str) -> (bool, String) {
+    let value = doc
+        .root_value()
+        .unwrap()
+        .get(key)
+        .unwrap()
+        .as_cow_str()
+        .unwrap();
+    (matches!(value, Cow::Borrowed(_)), value.into_owned())
+}
+
+#[test]
+fn valueref_as_cow_str_plain_and_quoted_borrow() {
+    let doc = Document::parse_str("plain: hello world\nsingle: 'x y'\ndouble: \"x y\"").unwrap();
+    assert_eq!(cow_at(&doc, "plain"), (true, "hello world".to_string()));
+    assert_eq!(cow_at(&doc, "single"), (true, "x y".to_string()));
+    assert_eq!(cow_at(&doc, "double"), (true, "x y".to_string()));
+}
+
+#[test]
+fn valueref_as_cow_str_decoded_content_is_owned() {
+    let yaml = "esc: \"a\\tb\\u00e9\"\nquote: 'it''s'\nfolded_plain: one\n  two\n";
+    let doc = Document::parse_str(yaml).unwrap();
+    assert_eq!(cow_at(&doc, "esc"), (false, "a\tbé".to_string()));
+    assert_eq!(cow_at(&doc, "quote"), (false, "it's".to_string()));
+    assert_eq!(cow_at(&doc, "folded_plain"), (false, "one two".to_string()));
+}
+
+#[test]
+fn valueref_as_cow_str_block_scalars() {
+    let doc = Document::parse_str("lit: |\n  line1\n  line2\nfold: >\n  a\n  b\n").unwrap();
+    assert_eq!(cow_at(&doc, "lit"), (false, "line1\nline2\n".to_string()));
+    assert_eq!(cow_at(&doc, "fold"), (false, "a b\n".to_string()));
+}
+
+#[test]
+fn valueref_as_cow_str_other_inputs() {
+    // Owned-string input borrows as well
+    let doc = Document::from_string("k: v".to_string()).unwrap();
+    assert_eq!(cow_at(&doc, "k"), (true, "v".to_string()));
+
+    // Built nodes are never input text
+    let mut doc = Document::parse_str("k: v").unwrap();
+    doc.edit().set_yaml_at("/k", "w").unwrap();
+    assert_eq!(cow_at(&doc, "k"), (false, "w".to_string()));
+
+    let doc = Document::parse_str("[a]").unwrap();
+    assert!(doc.root_value().unwrap().as_cow_str().is_none());
+}
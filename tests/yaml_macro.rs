@@ -0,0 +1,137 @@
+//! Tests for the `yaml!` macro.
+
+use fyaml::value::{Number, TaggedValue, Value};
+use fyaml::yaml;
+use indexmap::IndexMap;
+
+fn parse(s: &str) -> Value {
+    s.parse().unwrap()
+}
+
+// =============================================================================
+// Literals
+// =============================================================================
+
+#[test]
+fn yaml_macro_literals() {
+    assert_eq!(yaml!(null), Value::Null);
+    assert_eq!(yaml!(true), Value::Bool(true));
+    assert_eq!(yaml!(false), Value::Bool(false));
+    assert_eq!(yaml!("text"), Value::String("text".into()));
+    assert_eq!(yaml!(-7), Value::Number(Number::Int(-7)));
+    assert_eq!(yaml!(2.5), Value::Number(Number::Float(2.5)));
+    assert_eq!(yaml!([]), Value::Sequence(Vec::new()));
+    assert_eq!(yaml!({}), Value::Mapping(IndexMap::new()));
+}
+
+// =============================================================================
+// Collections
+// =============================================================================
+
+#[test]
+fn yaml_macro_nested_collections() {
+    let value = yaml!({
+        "name": "Alice",
+        "tags": ["a", "b"],
+        "age": 30,
+        "address": {"city": "Paris", "zip": null},
+        "matrix": [[1, 2], [], [{"x": true}]],
+        "empty": {},
+    });
+    let expected = parse(
+        "name: Alice\ntags: [a, b]\nage: 30\naddress: {city: Paris, zip: ~}\n\
+         matrix: [[1, 2], [], [{x: true}]]\nempty: {}",
+    );
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn yaml_macro_keeps_key_order() {
+    let value = yaml!({"z": 1, "a": 2, "m": 3});
+    let keys: Vec<&str> = value
+        .as_mapping()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["z", "a", "m"]);
+}
+
+#[test]
+fn yaml_macro_repeated_key_takes_last_value() {
+    let value = yaml!({"a": 1, "b": 2, "a": 3});
+    assert_eq!(value.to_yaml_string().unwrap(), "a: 3\nb: 2");
+}
+
+#[test]
+fn yaml_macro_trailing_commas() {
+    assert_eq!(yaml!([1, 2,]), yaml!([1, 2]));
+    assert_eq!(yaml!({"a": [true,], "b": {},}), parse("{a: [true], b: {}}"));
+}
+
+// =============================================================================
+// Interpolation
+// =============================================================================
+
+#[test]
+fn yaml_macro_interpolates_expressions() {
+    let n: i64 = 42;
+    let name = String::from("svc");
+    let port: u32 = 8080;
+    let items = vec!["x", "y"];
+    let value = yaml!({
+        "n": n,
+        "name": name.clone(),
+        "port": port + 1,
+        "ratio": 1.0 / 4.0,
+        "items": items,
+        "maybe": None::<i64>,
+        "nested": [n, name.as_str(), -n],
+    });
+    let expected = parse(
+        "{n: 42, name: svc, port: 8081, ratio: 0.25, items: [x, y], maybe: ~, \
+         nested: [42, svc, -42]}",
+    );
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn yaml_macro_embeds_values() {
+    let inner = yaml!({"k": "v"});
+    let tagged = Value::Tagged(Box::new(TaggedValue {
+        tag: "!t".into(),
+        value: Value::from(1),
+    }));
+    let value = yaml!([inner.clone(), tagged.clone()]);
+    assert_eq!(value[0], inner);
+    assert_eq!(value[1], tagged);
+}
+
+#[test]
+fn yaml_macro_expression_keys() {
+    let key = "dynamic";
+    let value = yaml!({
+        key: 1,
+        1: "one",
+        (format!("{}-{}", "a", "b")): 2,
+        ("with: colon"): 3,
+        true: "yes",
+    });
+    assert_eq!(value["dynamic"], Value::from(1));
+    assert_eq!(value["a-b"], Value::from(2));
+    assert_eq!(value["with: colon"], Value::from(3));
+    let map = value.as_mapping().unwrap();
+    assert_eq!(map[&Value::from(1)], Value::from("one"));
+    assert_eq!(map[&Value::Bool(true)], Value::from("yes"));
+}
+
+#[test]
+fn yaml_macro_does_not_capture_caller_names() {
+    // The macro's own bindings must not shadow these
+    let value = 1;
+    let map = 2;
+    assert_eq!(
+        yaml!({"value": value, "map": map}),
+        parse("{value: 1, map: 2}")
+    );
+}